@@ -1,20 +1,43 @@
 use std::fmt::Debug;
 
-use tb_core::types::{AssemblyGenerator, DataItemCollection, Definition};
+use tb_core::{error::TBError, interpreter::{Execution, Interpreter, InterpreterError}, ir::{self, ParseError}, syntax::Syntax, target::Target, types::{AssemblyGenerator, DataItemCollection, Definition, ExecutableGenerator, ObjectGenerator}};
 
 use super::{BuilderGenerate, FunctionType};
 
+#[cfg(unix)]
+use tb_core::jit::{Jit, JitError, JitGenerator};
+
 #[derive(Debug, Clone, Default)]
 pub struct ApplicationType {
     definitions: Vec<Definition>,
     datas: DataItemCollection,
+    target: Target
 }
 
 impl ApplicationType {
+    /// Application from the textual IR
+    pub fn from_ir(source: &str) -> Result<Self, ParseError> {
+        Ok(Self { definitions: ir::parse(source)?, ..Default::default() })
+    }
+
+    /// Platform of the generated code, default one is the running system
+    pub fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
     pub fn add_function(&mut self, func: FunctionType) {
         self.definitions.push(func.convert());
     }
 
+    pub fn add_definition(&mut self, definition: Definition) {
+        self.definitions.push(definition);
+    }
+
+    /// Textual IR of the definitions
+    pub fn to_ir(&self) -> String {
+        ir::print(&self.definitions)
+    }
+
     pub fn add_string_data<L: AsRef<str>, D: AsRef<str>>(&mut self, label: L, data: D) {
         self.datas.add_string_data(label, data)
     }
@@ -23,10 +46,47 @@ impl ApplicationType {
         self.datas.add_byte_data(label, data)
     }
 
-    pub fn build<A: AssemblyGenerator>(self) -> String {
-        let Self { definitions, datas } = self;
+    pub fn build<A: AssemblyGenerator>(self) -> Result<String, TBError> {
+        self.build_with_syntax::<A>(Syntax::default())
+    }
+
+    /// Assembly with the selected syntax, AT&T is the default
+    pub fn build_with_syntax<A: AssemblyGenerator>(self, syntax: Syntax) -> Result<String, TBError> {
+        let Self { definitions, datas, target } = self;
 
+        let application = A::new(target);
+        application.generate_with_syntax(definitions, datas, syntax)
+    }
+
+    /// Relocatable object file, it is linked without the assembler
+    pub fn build_object<A: ObjectGenerator>(self) -> Result<Vec<u8>, TBError> {
+        let Self { definitions, datas, target } = self;
+
+        let application = A::new(target);
+        application.generate_object(definitions, datas)
+    }
+
+    /// Static executable that runs without the C library, print is written with the system calls
+    pub fn build_static_executable<A: ExecutableGenerator>(self) -> Result<Vec<u8>, TBError> {
+        let Self { definitions, datas, target } = self;
+
+        let application = A::new(target);
+        application.generate_executable(definitions, datas)
+    }
+
+    /// Machine code of the definitions in the process memory, the functions are called without an external compiler
+    #[cfg(unix)]
+    pub fn jit<A: JitGenerator>(self) -> Result<Jit, JitError> {
+        let Self { definitions, datas, .. } = self;
+
+        // Code runs in this process, so the target is always the running system
         let application = A::default();
-        application.generate(definitions, datas)
+        application.jit(definitions, datas)
+    }
+
+    /// Run the main function with the interpreter instead of the native code
+    pub fn interpret(self) -> Result<Execution, InterpreterError> {
+        let Self { definitions, datas, .. } = self;
+        Interpreter::run(definitions, datas)
     }
 }
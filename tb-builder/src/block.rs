@@ -1,6 +1,6 @@
-use tb_core::types::{Block, Number, Statement, Value};
+use tb_core::types::{Block, Number, NumberType, Statement, Value};
 
-use crate::if_block::IfBlockType;
+use crate::{if_block::IfBlockType, LoopBlockType, WhileBlockType};
 
 use super::{expression::ExpressionType, BuilderGenerate};
 
@@ -18,6 +18,14 @@ impl BlockType {
         })
     }
 
+    pub fn add_declare(&mut self, name: &str, ty: NumberType, init: Option<ExpressionType>) {
+        self.items.push(Statement::Declare {
+            name: name.to_owned(),
+            ty,
+            init: init.map(|item| item.convert())
+        })
+    }
+
     pub fn add_if(&mut self, if_block: IfBlockType) {
         self.items.push(Statement::If {
             condition: if_block.condition.convert(),
@@ -26,6 +34,22 @@ impl BlockType {
         })
     }
 
+    pub fn add_while(&mut self, while_block: WhileBlockType) {
+        self.items.push(while_block.convert())
+    }
+
+    pub fn add_loop(&mut self, loop_block: LoopBlockType) {
+        self.items.push(loop_block.convert())
+    }
+
+    pub fn add_break(&mut self) {
+        self.items.push(Statement::Break)
+    }
+
+    pub fn add_continue(&mut self) {
+        self.items.push(Statement::Continue)
+    }
+
     pub fn add_print(&mut self, format: String, arguments: Vec<Value>) {
         self.items.push(Statement::Print {
             format,
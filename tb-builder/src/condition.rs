@@ -1,67 +1,94 @@
-use tb_core::types::{ConditionDiscriminant, Condition, Value};
+use tb_core::types::{ConditionDiscriminant, Condition};
+
+use crate::ExpressionType;
 
 use super::BuilderGenerate;
 
 #[derive(Debug, Clone)]
 pub struct ConditionType {
     pub condition_type: ConditionDiscriminant,
-    pub left: Option<Box<Value>>,
-    pub right: Option<Box<Value>>
+    pub left: Option<Box<ExpressionType>>,
+    pub right: Option<Box<ExpressionType>>,
+
+    // Operands of the logical conditions
+    pub left_condition: Option<Box<ConditionType>>,
+    pub right_condition: Option<Box<ConditionType>>
 }
 
 impl ConditionType {
-    pub fn eq(left: Value, right: Value) -> Self {
+    fn comparison<L: Into<ExpressionType>, R: Into<ExpressionType>>(condition_type: ConditionDiscriminant, left: L, right: R) -> Self {
         Self {
-            condition_type: ConditionDiscriminant::Eq,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
+            condition_type,
+            right: Some(Box::new(right.into())),
+            left: Some(Box::new(left.into())),
+            left_condition: None,
+            right_condition: None
         }
     }
 
-    pub fn ne(left: Value, right: Value) -> Self {
-        Self {
-            condition_type: ConditionDiscriminant::Ne,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
-        }
+    pub fn eq<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Eq, left, right)
     }
 
-    pub fn gr(left: Value, right: Value) -> Self {
-        Self {
-            condition_type: ConditionDiscriminant::Gr,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
-        }
+    pub fn ne<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Ne, left, right)
+    }
+
+    pub fn gr<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Gr, left, right)
+    }
+
+    pub fn ge<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Ge, left, right)
     }
 
-    pub fn ge(left: Value, right: Value) -> Self {
+    pub fn ls<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Ls, left, right)
+    }
+
+    pub fn le<L: Into<ExpressionType>, R: Into<ExpressionType>>(left: L, right: R) -> Self {
+        Self::comparison(ConditionDiscriminant::Le, left, right)
+    }
+
+    pub fn and(left: ConditionType, right: ConditionType) -> Self {
         Self {
-            condition_type: ConditionDiscriminant::Ge,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
+            condition_type: ConditionDiscriminant::And,
+            left: None,
+            right: None,
+            left_condition: Some(Box::new(left)),
+            right_condition: Some(Box::new(right))
         }
     }
 
-    pub fn ls(left: Value, right: Value) -> Self {
+    pub fn or(left: ConditionType, right: ConditionType) -> Self {
         Self {
-            condition_type: ConditionDiscriminant::Ls,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
+            condition_type: ConditionDiscriminant::Or,
+            left: None,
+            right: None,
+            left_condition: Some(Box::new(left)),
+            right_condition: Some(Box::new(right))
         }
     }
+}
 
-    pub fn le(left: Value, right: Value) -> Self {
+/// Negated condition, `!condition`
+impl std::ops::Not for ConditionType {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
         Self {
-            condition_type: ConditionDiscriminant::Le,
-            right: Some(Box::new(right)),
-            left: Some(Box::new(left))
+            condition_type: ConditionDiscriminant::Not,
+            left: None,
+            right: None,
+            left_condition: Some(Box::new(self)),
+            right_condition: None
         }
     }
 }
 
 impl Default for ConditionType {
     fn default() -> Self {
-        Self { condition_type: ConditionDiscriminant::Eq, left: None, right: None }
+        Self { condition_type: ConditionDiscriminant::Eq, left: None, right: None, left_condition: None, right_condition: None }
     }
 }
 
@@ -71,28 +98,39 @@ impl BuilderGenerate for ConditionType {
     fn convert(self) -> Self::Output {
         match self.condition_type {
             ConditionDiscriminant::Eq => Condition::Eq {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
             },
             ConditionDiscriminant::Ne => Condition::Ne {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
             },
             ConditionDiscriminant::Gr => Condition::Gr {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
             },
             ConditionDiscriminant::Ge => Condition::Ge {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
             },
             ConditionDiscriminant::Ls => Condition::Ls {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
             },
             ConditionDiscriminant::Le => Condition::Le {
-                left: *self.left.unwrap(),
-                right: *self.right.unwrap()
+                left: self.left.unwrap().convert(),
+                right: self.right.unwrap().convert()
+            },
+            ConditionDiscriminant::And => Condition::And {
+                left: Box::new(self.left_condition.unwrap().convert()),
+                right: Box::new(self.right_condition.unwrap().convert())
+            },
+            ConditionDiscriminant::Or => Condition::Or {
+                left: Box::new(self.left_condition.unwrap().convert()),
+                right: Box::new(self.right_condition.unwrap().convert())
+            },
+            ConditionDiscriminant::Not => Condition::Not {
+                source: Box::new(self.left_condition.unwrap().convert())
             }
         }
     }
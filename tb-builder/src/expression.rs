@@ -1,139 +1,196 @@
 use tb_core::types::{Expression, ExpressionDiscriminant, Value};
 
+use crate::ConditionType;
+
 use super::BuilderGenerate;
 
 #[derive(Debug, Clone)]
 pub struct ExpressionType {
     expression_type: ExpressionDiscriminant,
-    target: Option<Box<Value>>,
-    source: Option<Box<Value>>
+    target: Option<Box<ExpressionType>>,
+    source: Option<Box<ExpressionType>>,
+    value: Option<Value>,
+    condition: Option<Box<ConditionType>>
 }
 
 impl ExpressionType {
-    pub fn add(source: Value, target: Value) -> Self {
+    pub fn add<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Add,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
 
-    pub fn sub(source: Value, target: Value) -> Self {
+    pub fn sub<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Sub,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
 
-    pub fn div(divided: Value, divider: Value) -> Self {
+    pub fn div<S: Into<ExpressionType>, T: Into<ExpressionType>>(divided: S, divider: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Div,
-            source: Some(Box::new(divided)),
-            target: Some(Box::new(divider))
+            source: Some(Box::new(divided.into())),
+            target: Some(Box::new(divider.into())),
+            value: None,
+            condition: None
         }
     }
 
-    pub fn mul(source: Value, target: Value) -> Self {
+    pub fn mul<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Mul,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
 
-    pub fn modulo(divided: Value, divider: Value) -> Self {
+    pub fn modulo<S: Into<ExpressionType>, T: Into<ExpressionType>>(divided: S, divider: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Modulo,
-            source: Some(Box::new(divided)),
-            target: Some(Box::new(divider))
+            source: Some(Box::new(divided.into())),
+            target: Some(Box::new(divider.into())),
+            value: None,
+            condition: None
         }
     }
     
     pub fn dec(source: String) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Dec,
-            source: Some(Box::new(Value::Variable(source))),
-            target: None
+            source: Some(Box::new(Value::Variable(source).into())),
+            target: None,
+            value: None,
+            condition: None
         }
     }
     
     pub fn inc(source: String) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Inc,
-            source: Some(Box::new(Value::Variable(source))),
-            target: None
+            source: Some(Box::new(Value::Variable(source).into())),
+            target: None,
+            value: None,
+            condition: None
         }
     }
     
-    pub fn bitwise_not(source: Value) -> Self {
+    pub fn bitwise_not<S: Into<ExpressionType>>(source: S) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::BitwiseNot,
-            source: Some(Box::new(source)),
-            target: None
+            source: Some(Box::new(source.into())),
+            target: None,
+            value: None,
+            condition: None
         }
     }
     
-    pub fn bitwise_and(source: Value, target: Value) -> Self {
+    pub fn bitwise_and<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::BitwiseAnd,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
     
-    pub fn bitwise_or(source: Value, target: Value) -> Self {
+    pub fn bitwise_or<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::BitwiseOr,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
     
-    pub fn bitwise_xor(source: Value, target: Value) -> Self {
+    pub fn bitwise_xor<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::BitwiseXor,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
     
-    pub fn bitwise_neg(source: Value) -> Self {
+    pub fn bitwise_neg<S: Into<ExpressionType>>(source: S) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::BitwiseNeg,
-            source: Some(Box::new(source)),
-            target: None
+            source: Some(Box::new(source.into())),
+            target: None,
+            value: None,
+            condition: None
         }
     }
     
-    pub fn shift_left(source: Value, target: Value) -> Self {
+    pub fn shift_left<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::ShiftLeft,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
     
-    pub fn shift_right(source: Value, target: Value) -> Self {
+    pub fn shift_right<S: Into<ExpressionType>, T: Into<ExpressionType>>(source: S, target: T) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::ShiftRight,
-            source: Some(Box::new(source)),
-            target: Some(Box::new(target))
+            source: Some(Box::new(source.into())),
+            target: Some(Box::new(target.into())),
+            value: None,
+            condition: None
         }
     }
     
     pub fn value(source: Value) -> Self {
         Self {
             expression_type: ExpressionDiscriminant::Value,
-            source: Some(Box::new(source)),
-            target: None
+            source: None,
+            target: None,
+            value: Some(source),
+            condition: None
+        }
+    }
+
+    /// Boolean value of the condition, 1 if it is true and 0 if it is false
+    pub fn condition(condition: ConditionType) -> Self {
+        Self {
+            expression_type: ExpressionDiscriminant::Condition,
+            source: None,
+            target: None,
+            value: None,
+            condition: Some(Box::new(condition))
         }
     }
 }
 
+impl From<Value> for ExpressionType {
+    fn from(value: Value) -> Self {
+        ExpressionType::value(value)
+    }
+}
+
+impl From<ConditionType> for ExpressionType {
+    fn from(condition: ConditionType) -> Self {
+        ExpressionType::condition(condition)
+    }
+}
+
 impl Default for ExpressionType {
     fn default() -> Self {
-        Self { expression_type: ExpressionDiscriminant::Add, target: None, source: None }
+        Self { expression_type: ExpressionDiscriminant::Add, target: None, source: None, value: None, condition: None }
     }
 }
 
@@ -143,58 +200,59 @@ impl BuilderGenerate for ExpressionType {
     fn convert(self) -> Self::Output {
         match self.expression_type {
             ExpressionDiscriminant::Add => Expression::Add {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::Sub => Expression::Sub {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::Div => Expression::Div {
-                divider: *self.source.unwrap(),
-                divided: *self.target.unwrap()
+                divider: Box::new(self.source.unwrap().convert()),
+                divided: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::Mul => Expression::Mul {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::Modulo => Expression::Modulo {
-                divider: *self.source.unwrap(),
-                divided: *self.target.unwrap()
+                divider: Box::new(self.source.unwrap().convert()),
+                divided: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::ShiftLeft => Expression::ShiftLeft {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::ShiftRight => Expression::ShiftRight {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::BitwiseAnd => Expression::BitwiseAnd {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::BitwiseOr => Expression::BitwiseOr {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::BitwiseXor => Expression::BitwiseXor {
-                source: *self.source.unwrap(),
-                target: *self.target.unwrap()
+                source: Box::new(self.source.unwrap().convert()),
+                target: Box::new(self.target.unwrap().convert())
             },
             ExpressionDiscriminant::BitwiseNot => Expression::BitwiseNot {
-                source: *self.source.unwrap()
+                source: Box::new(self.source.unwrap().convert())
             },
             ExpressionDiscriminant::BitwiseNeg => Expression::BitwiseNeg {
-                source: *self.source.unwrap()
+                source: Box::new(self.source.unwrap().convert())
             },
             ExpressionDiscriminant::Inc => Expression::Inc {
-                source: *self.source.unwrap()
+                source: Box::new(self.source.unwrap().convert())
             },
             ExpressionDiscriminant::Dec => Expression::Dec {
-                source: *self.source.unwrap()
+                source: Box::new(self.source.unwrap().convert())
             },
-            ExpressionDiscriminant::Value => Expression::Value(*self.source.unwrap())
+            ExpressionDiscriminant::Condition => Expression::Condition(Box::new(self.condition.unwrap().convert())),
+            ExpressionDiscriminant::Value => Expression::Value(self.value.unwrap())
         }
     }
 }
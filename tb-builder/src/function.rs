@@ -1,4 +1,4 @@
-use tb_core::{tool::os_defs, types::{Definition, NumberType, Parameter}};
+use tb_core::{types::{Definition, NumberType, Parameter}};
 
 use crate::BlockType;
 
@@ -13,10 +13,10 @@ pub struct FunctionType {
 }
 
 impl FunctionType {
+    /// Main function is renamed for the target when it is compiled
     pub fn main() -> Self {
-        let defs = os_defs();
         Self {
-            name: defs.main_function_name().to_owned(),
+            name: "main".to_owned(),
             parameters: Default::default(),
             block: Default::default()
         }
@@ -5,6 +5,8 @@ mod block;
 mod condition;
 mod if_block;
 mod call;
+mod while_block;
+mod loop_block;
 
 pub use function::FunctionType;
 pub use expression::ExpressionType;
@@ -13,6 +15,8 @@ pub use block::BlockType;
 pub use condition::ConditionType;
 pub use if_block::IfBlockType;
 pub use call::CallType;
+pub use while_block::WhileBlockType;
+pub use loop_block::LoopBlockType;
 
 pub trait BuilderGenerate {
     type Output;
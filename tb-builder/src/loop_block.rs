@@ -0,0 +1,26 @@
+use tb_core::types::Statement;
+
+use crate::BlockType;
+
+use super::BuilderGenerate;
+
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct LoopBlockType {
+    pub block: BlockType
+}
+
+impl LoopBlockType {
+    pub fn set_block(&mut self, block: BlockType) {
+        self.block = block;
+    }
+}
+
+impl BuilderGenerate for LoopBlockType {
+    type Output = Statement;
+
+    fn convert(self) -> Self::Output {
+        let Self { block } = self;
+        Statement::Loop { block: block.convert() }
+    }
+}
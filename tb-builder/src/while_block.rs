@@ -0,0 +1,31 @@
+use tb_core::types::Statement;
+
+use crate::{BlockType, ConditionType};
+
+use super::BuilderGenerate;
+
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct WhileBlockType {
+    pub condition: ConditionType,
+    pub block: BlockType
+}
+
+impl WhileBlockType {
+    pub fn set_condition(&mut self, condition: ConditionType) {
+        self.condition = condition;
+    }
+
+    pub fn set_block(&mut self, block: BlockType) {
+        self.block = block;
+    }
+}
+
+impl BuilderGenerate for WhileBlockType {
+    type Output = Statement;
+
+    fn convert(self) -> Self::Output {
+        let Self { condition, block } = self;
+        Statement::While { condition: condition.convert(), block: block.convert() }
+    }
+}
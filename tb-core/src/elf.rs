@@ -0,0 +1,260 @@
+//! ELF64 relocatable object files and static executables. Targets give the machine code, the read only data and
+//! the relocations, object files link with the system linker like the output of the assembler.
+
+use std::collections::HashMap;
+
+pub const EM_X86_64: u16 = 62;
+
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_PLT32: u32 = 4;
+
+const ET_REL: u16 = 1;
+const ET_EXEC: u16 = 2;
+
+const PT_LOAD: u32 = 1;
+const PF_X: u32 = 0x1;
+const PF_R: u32 = 0x4;
+
+/// Executable is loaded to the usual address of the static executables, the image is after the headers page
+const EXECUTABLE_ADDRESS: u64 = 0x400000;
+const PAGE_SIZE: usize = 0x1000;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+
+const HEADER_SIZE: usize = 64;
+const PROGRAM_HEADER_SIZE: usize = 56;
+const SECTION_HEADER_SIZE: usize = 64;
+const SYMBOL_SIZE: usize = 24;
+const RELOCATION_SIZE: usize = 24;
+
+// Section indexes of the written file
+const TEXT_INDEX: u16 = 1;
+const RODATA_INDEX: u16 = 2;
+const SYMTAB_INDEX: u32 = 5;
+const STRTAB_INDEX: u32 = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfRelocation {
+    pub offset: usize,
+    pub symbol: String,
+    pub kind: u32,
+    pub addend: i64
+}
+
+#[derive(Debug, Clone)]
+struct ElfSymbol {
+    name: String,
+    info: u8,
+    section: u16,
+    value: usize,
+    size: usize
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ElfObject {
+    machine: u16,
+    text: Vec<u8>,
+    rodata: Vec<u8>,
+    functions: Vec<ElfSymbol>,
+    datas: Vec<ElfSymbol>,
+    relocations: Vec<ElfRelocation>
+}
+
+/// Names are written one after another with the terminating zero
+struct StringTable {
+    bytes: Vec<u8>,
+    offsets: HashMap<String, u32>
+}
+
+impl StringTable {
+    fn new() -> Self {
+        Self { bytes: vec![0], offsets: HashMap::new() }
+    }
+
+    fn add(&mut self, name: &str) -> u32 {
+        if let Some(offset) = self.offsets.get(name) {
+            return *offset;
+        }
+
+        let offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(name.as_bytes());
+        self.bytes.push(0);
+        self.offsets.insert(name.to_owned(), offset);
+        offset
+    }
+}
+
+struct Section {
+    name: &'static str,
+    kind: u32,
+    flags: u64,
+    bytes: Vec<u8>,
+    link: u32,
+    info: u32,
+    align: u64,
+    entry_size: u64
+}
+
+impl ElfObject {
+    pub fn new(machine: u16, text: Vec<u8>, rodata: Vec<u8>) -> Self {
+        Self { machine, text, rodata, ..Default::default() }
+    }
+
+    /// Functions are visible to the other objects
+    pub fn add_function(&mut self, name: &str, offset: usize, size: usize) {
+        self.functions.push(ElfSymbol { name: name.to_owned(), info: STB_GLOBAL << 4 | STT_FUNC, section: TEXT_INDEX, value: offset, size });
+    }
+
+    /// Data labels are only used in this object
+    pub fn add_data(&mut self, label: &str, offset: usize) {
+        self.datas.push(ElfSymbol { name: label.to_owned(), info: STB_LOCAL << 4 | STT_OBJECT, section: RODATA_INDEX, value: offset, size: 0 });
+    }
+
+    /// Symbols that are not added before are undefined, the linker finds them in the other objects
+    pub fn add_relocation(&mut self, relocation: ElfRelocation) {
+        self.relocations.push(relocation);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut names = StringTable::new();
+
+        // Local symbols should be before the global ones
+        let mut symbols = vec![ElfSymbol { name: String::new(), info: 0, section: 0, value: 0, size: 0 }];
+        symbols.extend(self.datas.iter().cloned());
+        let first_global = symbols.len();
+        symbols.extend(self.functions.iter().cloned());
+
+        for relocation in self.relocations.iter() {
+            if !symbols.iter().any(|symbol| symbol.name == relocation.symbol) {
+                symbols.push(ElfSymbol { name: relocation.symbol.clone(), info: STB_GLOBAL << 4 | STT_NOTYPE, section: 0, value: 0, size: 0 });
+            }
+        }
+
+        let mut symtab = Vec::with_capacity(symbols.len() * SYMBOL_SIZE);
+        for symbol in symbols.iter() {
+            let name = match symbol.name.is_empty() {
+                true => 0,
+                false => names.add(&symbol.name)
+            };
+
+            symtab.extend_from_slice(&name.to_le_bytes());
+            symtab.push(symbol.info);
+            symtab.push(0);
+            symtab.extend_from_slice(&symbol.section.to_le_bytes());
+            symtab.extend_from_slice(&(symbol.value as u64).to_le_bytes());
+            symtab.extend_from_slice(&(symbol.size as u64).to_le_bytes());
+        }
+
+        let mut rela = Vec::with_capacity(self.relocations.len() * RELOCATION_SIZE);
+        for relocation in self.relocations.iter() {
+            let index = symbols.iter().position(|symbol| symbol.name == relocation.symbol).unwrap() as u64;
+
+            rela.extend_from_slice(&(relocation.offset as u64).to_le_bytes());
+            rela.extend_from_slice(&(index << 32 | relocation.kind as u64).to_le_bytes());
+            rela.extend_from_slice(&relocation.addend.to_le_bytes());
+        }
+
+        let sections = [
+            Section { name: ".text", kind: SHT_PROGBITS, flags: SHF_ALLOC | SHF_EXECINSTR, bytes: self.text.clone(), link: 0, info: 0, align: 16, entry_size: 0 },
+            Section { name: ".rodata", kind: SHT_PROGBITS, flags: SHF_ALLOC, bytes: self.rodata.clone(), link: 0, info: 0, align: 8, entry_size: 0 },
+            Section { name: ".rela.text", kind: SHT_RELA, flags: SHF_INFO_LINK, bytes: rela, link: SYMTAB_INDEX, info: TEXT_INDEX as u32, align: 8, entry_size: RELOCATION_SIZE as u64 },
+
+            // Stack is not executable
+            Section { name: ".note.GNU-stack", kind: SHT_PROGBITS, flags: 0, bytes: Vec::new(), link: 0, info: 0, align: 1, entry_size: 0 },
+            Section { name: ".symtab", kind: SHT_SYMTAB, flags: 0, bytes: symtab, link: STRTAB_INDEX, info: first_global as u32, align: 8, entry_size: SYMBOL_SIZE as u64 },
+            Section { name: ".strtab", kind: SHT_STRTAB, flags: 0, bytes: names.bytes, link: 0, info: 0, align: 1, entry_size: 0 },
+            Section { name: ".shstrtab", kind: SHT_STRTAB, flags: 0, bytes: Vec::new(), link: 0, info: 0, align: 1, entry_size: 0 }
+        ];
+
+        let mut section_names = StringTable::new();
+        let name_offsets = sections.iter().map(|section| section_names.add(section.name)).collect::<Vec<_>>();
+
+        // Section contents are after the header, section headers are at the end
+        let mut bytes = vec![0; HEADER_SIZE];
+        let mut headers = vec![0; SECTION_HEADER_SIZE];
+
+        for (section, name) in sections.iter().zip(name_offsets) {
+            let content = match section.name {
+                ".shstrtab" => &section_names.bytes,
+                _ => &section.bytes
+            };
+
+            bytes.resize(bytes.len().next_multiple_of(section.align as usize), 0);
+            let offset = bytes.len() as u64;
+            bytes.extend_from_slice(content);
+
+            headers.extend_from_slice(&name.to_le_bytes());
+            headers.extend_from_slice(&section.kind.to_le_bytes());
+            headers.extend_from_slice(&section.flags.to_le_bytes());
+            headers.extend_from_slice(&0u64.to_le_bytes());
+            headers.extend_from_slice(&offset.to_le_bytes());
+            headers.extend_from_slice(&(content.len() as u64).to_le_bytes());
+            headers.extend_from_slice(&section.link.to_le_bytes());
+            headers.extend_from_slice(&section.info.to_le_bytes());
+            headers.extend_from_slice(&section.align.to_le_bytes());
+            headers.extend_from_slice(&section.entry_size.to_le_bytes());
+        }
+
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        let section_header_offset = bytes.len() as u64;
+        bytes.extend_from_slice(&headers);
+
+        let header = &mut bytes[..HEADER_SIZE];
+        write_header(header, ET_REL, self.machine);
+        header[40..48].copy_from_slice(&section_header_offset.to_le_bytes());
+        header[58..60].copy_from_slice(&(SECTION_HEADER_SIZE as u16).to_le_bytes());
+        header[60..62].copy_from_slice(&(sections.len() as u16 + 1).to_le_bytes());
+        header[62..64].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+
+        bytes
+    }
+}
+
+/// Little endian 64bit file with the current version
+fn write_header(header: &mut [u8], kind: u16, machine: u16) {
+    header[..8].copy_from_slice(&[0x7F, b'E', b'L', b'F', 2, 1, 1, 0]);
+    header[16..18].copy_from_slice(&kind.to_le_bytes());
+    header[18..20].copy_from_slice(&machine.to_le_bytes());
+    header[20..24].copy_from_slice(&1u32.to_le_bytes());
+    header[52..54].copy_from_slice(&(HEADER_SIZE as u16).to_le_bytes());
+}
+
+/// Static executable with a single read only and executable segment, the image should not need any relocation
+pub fn executable(machine: u16, image: &[u8], entry: usize) -> Vec<u8> {
+    let mut bytes = vec![0; PAGE_SIZE];
+    bytes.extend_from_slice(image);
+
+    let address = EXECUTABLE_ADDRESS + PAGE_SIZE as u64;
+
+    let header = &mut bytes[..HEADER_SIZE];
+    write_header(header, ET_EXEC, machine);
+    header[24..32].copy_from_slice(&(address + entry as u64).to_le_bytes());
+    header[32..40].copy_from_slice(&(HEADER_SIZE as u64).to_le_bytes());
+    header[54..56].copy_from_slice(&(PROGRAM_HEADER_SIZE as u16).to_le_bytes());
+    header[56..58].copy_from_slice(&1u16.to_le_bytes());
+
+    let program_header = &mut bytes[HEADER_SIZE..HEADER_SIZE + PROGRAM_HEADER_SIZE];
+    program_header[..4].copy_from_slice(&PT_LOAD.to_le_bytes());
+    program_header[4..8].copy_from_slice(&(PF_R | PF_X).to_le_bytes());
+    program_header[8..16].copy_from_slice(&(PAGE_SIZE as u64).to_le_bytes());
+    program_header[16..24].copy_from_slice(&address.to_le_bytes());
+    program_header[24..32].copy_from_slice(&address.to_le_bytes());
+    program_header[32..40].copy_from_slice(&(image.len() as u64).to_le_bytes());
+    program_header[40..48].copy_from_slice(&(image.len() as u64).to_le_bytes());
+    program_header[48..56].copy_from_slice(&(PAGE_SIZE as u64).to_le_bytes());
+
+    bytes
+}
@@ -1,8 +1,11 @@
-use std::string::FromUtf8Error;
+use std::{error::Error, string::FromUtf8Error};
 
 use thiserror::Error;
 
-use crate::compiler::CompilerType;
+use crate::{compiler::CompilerType, interpreter::InterpreterError, ir::ParseError};
+
+#[cfg(unix)]
+use crate::jit::JitError;
 
 #[derive(Error, Debug)]
 pub enum TBError {
@@ -16,5 +19,65 @@ pub enum TBError {
     UnsupportedStringFormat(#[from] FromUtf8Error),
 
     #[error("Compile failed ({0})")]
-    CompileFailed(String)
+    CompileFailed(String),
+
+    #[error("Parse failed ({0})")]
+    ParseFailed(#[from] ParseError),
+
+    #[error("Interpreter failed ({0})")]
+    InterpreterFailed(#[from] InterpreterError),
+
+    #[error("Code generation failed ({0})")]
+    CodeGenerationFailed(#[from] CodeGenerationError),
+
+    #[error("Encode failed ({0})")]
+    EncodeFailed(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("Link failed ({0})")]
+    LinkFailed(String),
+
+    #[error("Unknown target ({0})")]
+    UnknownTarget(String),
+
+    #[error("Unsupported target ({0})")]
+    UnsupportedTarget(String),
+
+    #[cfg(unix)]
+    #[error("JIT failed ({0})")]
+    JitFailed(#[from] JitError)
+}
+
+/// Errors of the targets that generate source code with named variables (C, LLVM IR and WebAssembly text)
+#[derive(Error, Debug)]
+pub enum SourceError {
+    #[error("Variable not found ({0})")]
+    VariableNotFound(String),
+
+    #[error("Function does not return a value ({0})")]
+    MissingReturnValue(String),
+
+    #[error("Break or continue is outside of a loop")]
+    OutsideOfLoop,
+
+    #[error("Unsupported float operation")]
+    UnsupportedFloatOperation
+}
+
+/// Error of a target with the place of the statement that caused it, the error of the target is the source
+#[derive(Error, Debug)]
+#[error("{function}:{statement}: {error}")]
+pub struct CodeGenerationError {
+    pub function: String,
+
+    /// Index of the statement in the function body, errors of the nested statements have the index of the outer one
+    pub statement: usize,
+
+    #[source]
+    pub error: Box<dyn Error + Send + Sync>
+}
+
+impl CodeGenerationError {
+    pub fn new<E: Error + Send + Sync + 'static>(function: &str, statement: usize, error: E) -> Self {
+        Self { function: function.to_owned(), statement, error: Box::new(error) }
+    }
 }
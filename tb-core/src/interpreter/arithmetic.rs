@@ -0,0 +1,153 @@
+use crate::types::{ConditionDiscriminant, ExpressionDiscriminant, Number, NumberType};
+
+use super::InterpreterError;
+
+/// Integer value with the sign of its type, every integer type fits into it
+pub fn to_integer(number: Number) -> i128 {
+    match number {
+        Number::I8(num) => num as i128,
+        Number::U8(num) => num as i128,
+        Number::I16(num) => num as i128,
+        Number::U16(num) => num as i128,
+        Number::I32(num) => num as i128,
+        Number::U32(num) => num as i128,
+        Number::I64(num) => num as i128,
+        Number::U64(num) => num as i128,
+        Number::Float(num) => num as i128,
+        Number::Double(num) => num as i128,
+        Number::Bool(num) => num as i128
+    }
+}
+
+/// Keeps the lower bits that fit into the type, like the registers do
+pub fn from_integer(value: i128, number_type: NumberType) -> Number {
+    match number_type {
+        NumberType::I8 => Number::I8(value as i8),
+        NumberType::U8 => Number::U8(value as u8),
+        NumberType::I16 => Number::I16(value as i16),
+        NumberType::U16 => Number::U16(value as u16),
+        NumberType::I32 => Number::I32(value as i32),
+        NumberType::U32 => Number::U32(value as u32),
+        NumberType::I64 => Number::I64(value as i64),
+        NumberType::U64 => Number::U64(value as u64),
+        NumberType::Float => Number::Float(value as f32),
+        NumberType::Double => Number::Double(value as f64),
+        NumberType::Bool => Number::Bool(value != 0)
+    }
+}
+
+fn to_float(number: Number) -> f64 {
+    match number {
+        Number::Float(num) => num as f64,
+        Number::Double(num) => num,
+        number => to_integer(number) as f64
+    }
+}
+
+/// Both operands are already converted to the number type
+pub fn binary(operation: ExpressionDiscriminant, number_type: NumberType, left: Number, right: Number) -> Result<Number, InterpreterError> {
+    match number_type {
+        NumberType::Float => {
+            let (left, right) = (to_float(left) as f32, to_float(right) as f32);
+            Ok(Number::Float(match operation {
+                ExpressionDiscriminant::Add => left + right,
+                ExpressionDiscriminant::Sub => left - right,
+                ExpressionDiscriminant::Mul => left * right,
+                ExpressionDiscriminant::Div => left / right,
+                _ => return Err(InterpreterError::UnsupportedFloatOperation)
+            }))
+        },
+        NumberType::Double => {
+            let (left, right) = (to_float(left), to_float(right));
+            Ok(Number::Double(match operation {
+                ExpressionDiscriminant::Add => left + right,
+                ExpressionDiscriminant::Sub => left - right,
+                ExpressionDiscriminant::Mul => left * right,
+                ExpressionDiscriminant::Div => left / right,
+                _ => return Err(InterpreterError::UnsupportedFloatOperation)
+            }))
+        },
+        number_type => {
+            let (left, right) = (to_integer(left), to_integer(right));
+            let bits = number_type.size() as u32 * 8;
+
+            let value = match operation {
+                ExpressionDiscriminant::Add => left.wrapping_add(right),
+                ExpressionDiscriminant::Sub => left.wrapping_sub(right),
+                ExpressionDiscriminant::Mul => left.wrapping_mul(right),
+                ExpressionDiscriminant::BitwiseAnd => left & right,
+                ExpressionDiscriminant::BitwiseOr => left | right,
+                ExpressionDiscriminant::BitwiseXor => left ^ right,
+
+                // Processor uses only the lower bits of the shift count
+                ExpressionDiscriminant::ShiftLeft => left.wrapping_shl(right as u32 & (bits - 1)),
+                ExpressionDiscriminant::ShiftRight => left >> (right as u32 & (bits - 1)),
+                ExpressionDiscriminant::Div | ExpressionDiscriminant::Modulo => {
+                    // Both of them raise a division error on the processor
+                    if right == 0 {
+                        return Err(InterpreterError::DivisionByZero);
+                    }
+
+                    if number_type.is_signed() && right == -1 && left == -(1 << (bits - 1)) {
+                        return Err(InterpreterError::DivisionOverflow);
+                    }
+
+                    match operation {
+                        ExpressionDiscriminant::Div => left / right,
+                        _ => left % right
+                    }
+                },
+                _ => return Err(InterpreterError::UnexpectedExpression)
+            };
+
+            Ok(from_integer(value, number_type))
+        }
+    }
+}
+
+pub fn unary(operation: ExpressionDiscriminant, number_type: NumberType, source: Number) -> Result<Number, InterpreterError> {
+    match number_type {
+        // There is no negate instruction for SSE, the value is subtracted from zero
+        NumberType::Float | NumberType::Double => match operation {
+            ExpressionDiscriminant::BitwiseNeg => binary(ExpressionDiscriminant::Sub, number_type, Number::I32(0).convert(number_type), source),
+            ExpressionDiscriminant::Inc => binary(ExpressionDiscriminant::Add, number_type, source, Number::I32(1).convert(number_type)),
+            ExpressionDiscriminant::Dec => binary(ExpressionDiscriminant::Sub, number_type, source, Number::I32(1).convert(number_type)),
+            _ => Err(InterpreterError::UnsupportedFloatOperation)
+        },
+        number_type => {
+            let source = to_integer(source);
+            let value = match operation {
+                ExpressionDiscriminant::BitwiseNeg => source.wrapping_neg(),
+                ExpressionDiscriminant::BitwiseNot => !source,
+                ExpressionDiscriminant::Inc => source.wrapping_add(1),
+                ExpressionDiscriminant::Dec => source.wrapping_sub(1),
+                _ => return Err(InterpreterError::UnexpectedExpression)
+            };
+
+            Ok(from_integer(value, number_type))
+        }
+    }
+}
+
+/// Unordered floating point comparison (NaN) is false for all conditions except not equal
+pub fn compare(condition: ConditionDiscriminant, number_type: NumberType, left: Number, right: Number) -> Result<bool, InterpreterError> {
+    let ordering = match number_type.is_float() {
+        true => to_float(left).partial_cmp(&to_float(right)),
+        false => Some(to_integer(left).cmp(&to_integer(right)))
+    };
+
+    let ordering = match ordering {
+        Some(ordering) => ordering,
+        None => return Ok(condition == ConditionDiscriminant::Ne)
+    };
+
+    Ok(match condition {
+        ConditionDiscriminant::Eq => ordering.is_eq(),
+        ConditionDiscriminant::Ne => ordering.is_ne(),
+        ConditionDiscriminant::Gr => ordering.is_gt(),
+        ConditionDiscriminant::Ge => ordering.is_ge(),
+        ConditionDiscriminant::Ls => ordering.is_lt(),
+        ConditionDiscriminant::Le => ordering.is_le(),
+        _ => return Err(InterpreterError::UnexpectedExpression)
+    })
+}
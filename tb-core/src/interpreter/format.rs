@@ -0,0 +1,281 @@
+use super::InterpreterError;
+
+/// Variadic argument as the callee sees it. Integers are extended to the full register and floats are promoted to double.
+#[derive(Debug, Clone)]
+pub enum FormatArgument {
+    Integer(u64),
+    Float(f64),
+    String(String)
+}
+
+#[derive(Debug, Default)]
+struct Specification {
+    left: bool,
+    plus: bool,
+    space: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    length: String
+}
+
+/// printf compatible formatting for the conversions that the generated code could use
+pub fn format(format: &str, arguments: &[FormatArgument]) -> Result<String, InterpreterError> {
+    let mut output = String::new();
+    let mut arguments = arguments.iter();
+    let mut chars = format.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            output.push(char);
+            continue;
+        }
+
+        let mut specification = Specification::default();
+        while let Some(flag) = chars.next_if(|char| "-+ #0".contains(*char)) {
+            match flag {
+                '-' => specification.left = true,
+                '+' => specification.plus = true,
+                ' ' => specification.space = true,
+                '#' => specification.alternate = true,
+                _ => specification.zero = true
+            };
+        }
+
+        while let Some(digit) = chars.next_if(|char| char.is_ascii_digit()) {
+            specification.width = specification.width * 10 + digit.to_digit(10).unwrap_or_default() as usize;
+        }
+
+        if chars.next_if_eq(&'.').is_some() {
+            let mut precision = 0;
+            while let Some(digit) = chars.next_if(|char| char.is_ascii_digit()) {
+                precision = precision * 10 + digit.to_digit(10).unwrap_or_default() as usize;
+            }
+            specification.precision = Some(precision);
+        }
+
+        while let Some(length) = chars.next_if(|char| "hlLqjzt".contains(*char)) {
+            specification.length.push(length);
+        }
+
+        let conversion = chars.next().ok_or_else(|| InterpreterError::InvalidFormat(format.to_owned()))?;
+        if conversion == '%' {
+            output.push('%');
+            continue;
+        }
+
+        let argument = arguments.next().ok_or_else(|| InterpreterError::InvalidFormat(format!("missing argument for %{}", conversion)))?;
+        let (sign, body) = match (conversion, argument) {
+            ('d' | 'i', FormatArgument::Integer(value)) => {
+                let value = signed(*value, &specification.length);
+                let sign = match (value < 0, specification.plus, specification.space) {
+                    (true, _, _) => "-",
+                    (false, true, _) => "+",
+                    (false, false, true) => " ",
+                    _ => ""
+                };
+                (sign, integer_digits(value.unsigned_abs(), 10, false, &specification))
+            },
+            ('u' | 'o' | 'x' | 'X', FormatArgument::Integer(value)) => {
+                let value = unsigned(*value, &specification.length);
+                let (radix, upper) = match conversion {
+                    'o' => (8, false),
+                    'x' => (16, false),
+                    'X' => (16, true),
+                    _ => (10, false)
+                };
+
+                let mut digits = integer_digits(value, radix, upper, &specification);
+                let prefix = match (specification.alternate, conversion) {
+                    (true, 'o') if !digits.starts_with('0') => "0",
+                    (true, 'x') if value != 0 => "0x",
+                    (true, 'X') if value != 0 => "0X",
+                    _ => ""
+                };
+                digits.insert_str(0, prefix);
+                ("", digits)
+            },
+            ('c', FormatArgument::Integer(value)) => {
+                specification.zero = false;
+                ("", ((*value as u8) as char).to_string())
+            },
+            ('s', FormatArgument::String(text)) => {
+                specification.zero = false;
+                let text = match specification.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text.clone()
+                };
+                ("", text)
+            },
+            ('f' | 'F' | 'e' | 'E' | 'g' | 'G', FormatArgument::Float(value)) => {
+                let sign = match (value.is_sign_negative(), specification.plus, specification.space) {
+                    (true, _, _) => "-",
+                    (false, true, _) => "+",
+                    (false, false, true) => " ",
+                    _ => ""
+                };
+
+                let body = match value.is_finite() {
+                    true => float_digits(value.abs(), conversion, &specification),
+                    false => {
+                        // Padding with zeros is not used for inf and nan
+                        specification.zero = false;
+                        let text = match value.is_nan() {
+                            true => "nan",
+                            false => "inf"
+                        };
+
+                        match conversion.is_uppercase() {
+                            true => text.to_uppercase(),
+                            false => text.to_owned()
+                        }
+                    }
+                };
+                (sign, body)
+            },
+            (conversion, argument) => return Err(InterpreterError::InvalidFormat(format!("%{} can not print {:?}", conversion, argument)))
+        };
+
+        output.push_str(&pad(sign, body, &specification, conversion));
+    }
+
+    Ok(output)
+}
+
+fn signed(value: u64, length: &str) -> i64 {
+    match length {
+        "hh" => value as i8 as i64,
+        "h" => value as i16 as i64,
+        "" => value as i32 as i64,
+        _ => value as i64
+    }
+}
+
+fn unsigned(value: u64, length: &str) -> u64 {
+    match length {
+        "hh" => value as u8 as u64,
+        "h" => value as u16 as u64,
+        "" => value as u32 as u64,
+        _ => value
+    }
+}
+
+/// Precision is the minimum number of digits, zero with zero precision has no digits
+fn integer_digits(value: u64, radix: u32, upper: bool, specification: &Specification) -> String {
+    let mut digits = match radix {
+        8 => format!("{:o}", value),
+        16 if upper => format!("{:X}", value),
+        16 => format!("{:x}", value),
+        _ => value.to_string()
+    };
+
+    if let Some(precision) = specification.precision {
+        if precision == 0 && value == 0 {
+            digits.clear();
+        }
+
+        if digits.len() < precision {
+            digits.insert_str(0, &"0".repeat(precision - digits.len()));
+        }
+    }
+
+    digits
+}
+
+fn float_digits(value: f64, conversion: char, specification: &Specification) -> String {
+    let precision = specification.precision.unwrap_or(6);
+
+    let text = match conversion {
+        'f' | 'F' => format!("{:.*}", precision, value),
+        'e' | 'E' => exponent_digits(value, precision),
+        _ => {
+            // Shortest of the fixed and the exponent formats, trailing zeros are removed
+            let precision = std::cmp::max(precision, 1);
+            let exponent = exponent_of(value, precision - 1);
+
+            let text = match exponent < -4 || exponent >= precision as i32 {
+                true => exponent_digits(value, precision - 1),
+                false => format!("{:.*}", (precision as i32 - 1 - exponent) as usize, value)
+            };
+
+            match specification.alternate {
+                true => text,
+                false => remove_trailing_zeros(text)
+            }
+        }
+    };
+
+    let text = match specification.alternate && !text.contains('.') {
+        true => match text.find('e') {
+            Some(position) => format!("{}.{}", &text[..position], &text[position..]),
+            None => format!("{}.", text)
+        },
+        false => text
+    };
+
+    match conversion.is_uppercase() {
+        true => text.to_uppercase(),
+        false => text
+    }
+}
+
+/// Decimal exponent after rounding to the precision
+fn exponent_of(value: f64, precision: usize) -> i32 {
+    let text = format!("{:.*e}", precision, value);
+    text[text.find('e').unwrap_or_default() + 1..].parse().unwrap_or_default()
+}
+
+/// C writes the exponent with a sign and at least two digits
+fn exponent_digits(value: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, value);
+    let position = text.find('e').unwrap_or(text.len());
+    let exponent: i32 = text[position + 1..].parse().unwrap_or_default();
+
+    let sign = match exponent < 0 {
+        true => '-',
+        false => '+'
+    };
+
+    format!("{}e{}{:02}", &text[..position], sign, exponent.abs())
+}
+
+fn remove_trailing_zeros(text: String) -> String {
+    let (mantissa, exponent) = match text.find('e') {
+        Some(position) => (text[..position].to_owned(), text[position..].to_owned()),
+        None => (text, String::new())
+    };
+
+    let mantissa = match mantissa.contains('.') {
+        true => mantissa.trim_end_matches('0').trim_end_matches('.').to_owned(),
+        false => mantissa
+    };
+
+    format!("{}{}", mantissa, exponent)
+}
+
+fn pad(sign: &str, body: String, specification: &Specification, conversion: char) -> String {
+    let length = sign.len() + body.chars().count();
+    if length >= specification.width {
+        return format!("{}{}", sign, body);
+    }
+
+    let padding = specification.width - length;
+
+    // Zero flag is ignored with the left alignment and with the integer precision
+    let integer = "diouxX".contains(conversion);
+    let zero = specification.zero && !specification.left && !(integer && specification.precision.is_some());
+
+    match (specification.left, zero) {
+        (true, _) => format!("{}{}{}", sign, body, " ".repeat(padding)),
+        (false, true) => {
+            // Zeros are placed after the sign and the hex prefix
+            let prefix_length = match body.starts_with("0x") || body.starts_with("0X") {
+                true => 2,
+                false => 0
+            };
+            format!("{}{}{}{}", sign, &body[..prefix_length], "0".repeat(padding), &body[prefix_length..])
+        },
+        (false, false) => format!("{}{}{}", " ".repeat(padding), sign, body)
+    }
+}
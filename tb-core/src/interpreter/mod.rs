@@ -0,0 +1,397 @@
+//! Reference interpreter of the definitions. It follows the semantics of the generated code:
+//! arithmetic is calculated with the common type of the operands and wraps like the registers,
+//! variables live until the end of the function and the last declaration shadows the previous ones.
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{syntax::Data, types::{Block, Condition, ConditionDiscriminant, DataItemCollection, Definition, Expression, ExpressionDiscriminant, Number, NumberType, Parameter, Statement, Value}};
+
+use format::FormatArgument;
+
+mod arithmetic;
+mod format;
+
+/// Recursion deeper than this is reported as an error instead of overflowing the interpreter stack
+const MAX_CALL_DEPTH: usize = 256;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum InterpreterError {
+    #[error("Variable not found ({0})")]
+    VariableNotFound(String),
+
+    #[error("Variable is used before it has a value ({0})")]
+    UninitializedVariable(String),
+
+    #[error("Function not found ({0})")]
+    FunctionNotFound(String),
+
+    #[error("Function {name} expects {expected} arguments, {found} given")]
+    ArgumentCount { name: String, expected: usize, found: usize },
+
+    #[error("Function does not return a value ({0})")]
+    MissingReturnValue(String),
+
+    #[error("String can only be used as a call argument")]
+    StringInExpression,
+
+    #[error("Division by zero")]
+    DivisionByZero,
+
+    #[error("Division overflow")]
+    DivisionOverflow,
+
+    #[error("Unsupported float operation")]
+    UnsupportedFloatOperation,
+
+    #[error("Unexpected expression")]
+    UnexpectedExpression,
+
+    #[error("Break or continue outside of a loop")]
+    OutsideOfLoop,
+
+    #[error("Call depth exceeded")]
+    CallDepthExceeded,
+
+    #[error("Step limit exceeded")]
+    StepLimitExceeded,
+
+    #[error("Invalid format ({0})")]
+    InvalidFormat(String)
+}
+
+/// Result of the main function and everything that is printed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Execution {
+    pub exit_code: i32,
+    pub output: String
+}
+
+#[derive(Debug, Clone)]
+struct Variable {
+    name: String,
+    var_type: NumberType,
+    value: Option<Number>
+}
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Number>)
+}
+
+pub struct Interpreter {
+    functions: HashMap<String, (Vec<Parameter>, Block)>,
+    datas: DataItemCollection,
+    output: String,
+    depth: usize,
+
+    // Remaining statements and blocks to execute, programs that do not finish are stopped with an error
+    steps: Option<usize>
+}
+
+impl Interpreter {
+    pub fn new(definitions: Vec<Definition>, datas: DataItemCollection) -> Self {
+        let functions = definitions.into_iter().map(|definition| match definition {
+            Definition::Function { name, parameters, block } => (name, (parameters, block))
+        }).collect();
+
+        Self { functions, datas, output: String::new(), depth: 0, steps: None }
+    }
+
+    /// Execute the main function, the exit code is the lowest byte of its return value like the process exit code
+    pub fn run(definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Execution, InterpreterError> {
+        Self::new(definitions, datas).execute()
+    }
+
+    pub fn execute(mut self) -> Result<Execution, InterpreterError> {
+        let result = self.call("main", Vec::new())?;
+
+        let exit_code = result.map(|number| arithmetic::to_integer(number.convert(NumberType::U8)) as i32).unwrap_or_default();
+        Ok(Execution { exit_code, output: self.output })
+    }
+
+    pub fn set_step_limit(&mut self, steps: usize) {
+        self.steps = Some(steps);
+    }
+
+    /// Everything that is printed so far
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Call the function with the arguments, they are converted to the parameter types
+    pub fn call(&mut self, name: &str, arguments: Vec<Number>) -> Result<Option<Number>, InterpreterError> {
+        let (parameters, block) = self.functions.get(name).cloned().ok_or_else(|| InterpreterError::FunctionNotFound(name.to_owned()))?;
+
+        if parameters.len() != arguments.len() {
+            return Err(InterpreterError::ArgumentCount { name: name.to_owned(), expected: parameters.len(), found: arguments.len() });
+        }
+
+        if self.depth == MAX_CALL_DEPTH {
+            return Err(InterpreterError::CallDepthExceeded);
+        }
+
+        let mut variables = parameters.into_iter().zip(arguments).map(|(parameter, argument)| Variable {
+            name: parameter.name,
+            var_type: parameter.param_type,
+            value: Some(argument.convert(parameter.param_type))
+        }).collect::<Vec<_>>();
+
+        self.depth += 1;
+        let flow = self.block(&block, &mut variables);
+        self.depth -= 1;
+
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            Flow::Next => Ok(None),
+            Flow::Break | Flow::Continue => Err(InterpreterError::OutsideOfLoop)
+        }
+    }
+
+    fn block(&mut self, block: &Block, variables: &mut Vec<Variable>) -> Result<Flow, InterpreterError> {
+        // Block is also a step, so the loops without a statement are stopped
+        self.step()?;
+
+        for statement in block.items.iter() {
+            match self.statement(statement, variables)? {
+                Flow::Next => (),
+                flow => return Ok(flow)
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn step(&mut self) -> Result<(), InterpreterError> {
+        if let Some(steps) = self.steps.as_mut() {
+            *steps = steps.checked_sub(1).ok_or(InterpreterError::StepLimitExceeded)?;
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &Statement, variables: &mut Vec<Variable>) -> Result<Flow, InterpreterError> {
+        self.step()?;
+
+        match statement {
+            Statement::Declare { name, ty, init } => {
+                // Initial value could use the shadowed variable
+                let value = match init {
+                    Some(init) => Some(Self::expression(init, variables)?.convert(*ty)),
+                    None => None
+                };
+                variables.push(Variable { name: name.clone(), var_type: *ty, value });
+            },
+            Statement::Assign { name, assigne } => {
+                let value = Self::expression(assigne, variables)?;
+
+                // Undeclared variable takes the type of the expression
+                match Self::find_variable(variables, name) {
+                    Some(variable) => variable.value = Some(value.convert(variable.var_type)),
+                    None => variables.push(Variable { name: name.clone(), var_type: value.number_type(), value: Some(value) })
+                };
+            },
+            Statement::If { condition, true_block, false_block } => {
+                match (Self::condition(condition, variables)?, false_block) {
+                    (true, _) => return self.block(true_block, variables),
+                    (false, Some(false_block)) => return self.block(false_block, variables),
+                    (false, None) => ()
+                };
+            },
+            // Declarations of the previous iteration are dropped, the names are resolved like the native code does
+            Statement::While { condition, block } => {
+                let declared = variables.len();
+                while Self::condition(condition, variables)? {
+                    variables.truncate(declared);
+                    match self.block(block, variables)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Next | Flow::Continue => ()
+                    }
+                }
+            },
+            Statement::Loop { block } => {
+                let declared = variables.len();
+                loop {
+                    variables.truncate(declared);
+                    match self.block(block, variables)? {
+                        Flow::Break => break,
+                        Flow::Return(value) => return Ok(Flow::Return(value)),
+                        Flow::Next | Flow::Continue => ()
+                    }
+                }
+            },
+            Statement::Break => return Ok(Flow::Break),
+            Statement::Continue => return Ok(Flow::Continue),
+            Statement::Print { format, arguments } => {
+                let arguments = arguments.iter().map(|argument| self.format_argument(argument, variables)).collect::<Result<Vec<_>, _>>()?;
+                let text = format::format(format, &arguments)?;
+                self.output.push_str(&text);
+            },
+            Statement::Call { name, arguments, assign, is_variadic } => {
+                let result = match self.functions.contains_key(name) {
+                    true => {
+                        let arguments = arguments.iter().map(|argument| self.value(argument, variables)).collect::<Result<Vec<_>, _>>()?;
+                        self.call(name, arguments)?
+                    },
+
+                    // printf is the only external function, it returns the number of the printed bytes
+                    false if *is_variadic && matches!(name.trim_start_matches('_'), "printf") && !arguments.is_empty() => {
+                        let format = match self.format_argument(&arguments[0], variables)? {
+                            FormatArgument::String(format) => format,
+                            argument => return Err(InterpreterError::InvalidFormat(format!("{:?}", argument)))
+                        };
+                        let arguments = arguments[1..].iter().map(|argument| self.format_argument(argument, variables)).collect::<Result<Vec<_>, _>>()?;
+
+                        let text = format::format(&format, &arguments)?;
+                        self.output.push_str(&text);
+                        Some(Number::I32(text.len() as i32))
+                    },
+                    false => return Err(InterpreterError::FunctionNotFound(name.clone()))
+                };
+
+                if let Some(assign) = assign {
+                    let value = result.ok_or_else(|| InterpreterError::MissingReturnValue(name.clone()))?;
+
+                    // Undeclared variable gets the full register
+                    match Self::find_variable(variables, assign) {
+                        Some(variable) => variable.value = Some(value.convert(variable.var_type)),
+                        None => variables.push(Variable { name: assign.clone(), var_type: NumberType::I64, value: Some(value.convert(NumberType::I64)) })
+                    };
+                }
+            },
+            Statement::Return(value) => {
+                let value = match value {
+                    Some(value) => Some(self.value(value, variables)?),
+                    None => None
+                };
+                return Ok(Flow::Return(value));
+            }
+        };
+
+        Ok(Flow::Next)
+    }
+
+    fn find_variable<'a>(variables: &'a mut [Variable], name: &str) -> Option<&'a mut Variable> {
+        // The last declaration shadows the previous ones
+        variables.iter_mut().rev().find(|variable| variable.name == name)
+    }
+
+    fn variable(variables: &[Variable], name: &str) -> Result<Number, InterpreterError> {
+        let variable = variables.iter().rev().find(|variable| variable.name == name).ok_or_else(|| InterpreterError::VariableNotFound(name.to_owned()))?;
+        variable.value.ok_or_else(|| InterpreterError::UninitializedVariable(name.to_owned()))
+    }
+
+    fn value(&self, value: &Value, variables: &[Variable]) -> Result<Number, InterpreterError> {
+        match value {
+            Value::Variable(name) => Self::variable(variables, name),
+            Value::Number(number) => Ok(*number),
+            Value::String(_) => Err(InterpreterError::StringInExpression)
+        }
+    }
+
+    /// Data labels could be used as the string arguments
+    fn format_argument(&self, value: &Value, variables: &[Variable]) -> Result<FormatArgument, InterpreterError> {
+        let number = match value {
+            Value::String(text) => return Ok(FormatArgument::String(text.clone())),
+            Value::Variable(name) if !variables.iter().any(|variable| variable.name == *name) => match self.datas.items.iter().find(|item| item.label == *name) {
+                Some(item) => return Ok(FormatArgument::String(item.values.iter().map(|data| match data {
+                    Data::String(text) => text.clone(),
+                    Data::Byte(byte) => (*byte as char).to_string(),
+                    Data::Float(_) | Data::Double(_) => String::new()
+                }).collect())),
+                None => return Err(InterpreterError::VariableNotFound(name.clone()))
+            },
+            value => self.value(value, variables)?
+        };
+
+        Ok(match number {
+            Number::Float(number) => FormatArgument::Float(number as f64),
+            Number::Double(number) => FormatArgument::Float(number),
+            number => FormatArgument::Integer(arithmetic::to_integer(number) as u64)
+        })
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    fn expression_type(expression: &Expression, variables: &[Variable]) -> Result<NumberType, InterpreterError> {
+        match expression {
+            Expression::Value(Value::Variable(name)) => variables.iter().rev().find(|variable| variable.name == *name).map(|variable| variable.var_type).ok_or_else(|| InterpreterError::VariableNotFound(name.to_owned())),
+            Expression::Value(Value::Number(number)) => Ok(number.number_type()),
+            Expression::Value(Value::String(_)) => Err(InterpreterError::StringInExpression),
+            Expression::Condition(_) => Ok(NumberType::Bool),
+            Expression::ShiftLeft { target, .. } |
+            Expression::ShiftRight { target, .. } => Ok(Self::expression_type(target, variables)?.promote()),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Ok(Self::expression_type(source, variables)?.promote()),
+            Expression::Div { divider: left, divided: right } |
+            Expression::Modulo { divider: left, divided: right } |
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } => Ok(Self::expression_type(left, variables)?.common(Self::expression_type(right, variables)?))
+        }
+    }
+
+    /// Value of the expression with the type of the expression
+    fn expression(expression: &Expression, variables: &[Variable]) -> Result<Number, InterpreterError> {
+        let number_type = Self::expression_type(expression, variables)?;
+
+        match expression {
+            Expression::Value(Value::Variable(name)) => Self::variable(variables, name),
+            Expression::Value(Value::Number(number)) => Ok(*number),
+            Expression::Value(Value::String(_)) => Err(InterpreterError::StringInExpression),
+            Expression::Condition(condition) => Ok(Number::Bool(Self::condition(condition, variables)?)),
+            Expression::ShiftLeft { target, source } |
+            Expression::ShiftRight { target, source } => {
+                let target = Self::expression(target, variables)?.convert(number_type);
+                let source = Self::expression(source, variables)?;
+                arithmetic::binary(ExpressionDiscriminant::from(expression), number_type, target, source)
+            },
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => {
+                let source = Self::expression(source, variables)?.convert(number_type);
+                arithmetic::unary(ExpressionDiscriminant::from(expression), number_type, source)
+            },
+            Expression::Div { divider: right, divided: left } |
+            Expression::Modulo { divider: right, divided: left } |
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } => {
+                let left = Self::expression(left, variables)?.convert(number_type);
+                let right = Self::expression(right, variables)?.convert(number_type);
+                arithmetic::binary(ExpressionDiscriminant::from(expression), number_type, left, right)
+            }
+        }
+    }
+
+    /// Right side of And and Or is evaluated only if the left side does not decide the result
+    fn condition(condition: &Condition, variables: &[Variable]) -> Result<bool, InterpreterError> {
+        match condition {
+            Condition::And { left, right } => Ok(Self::condition(left, variables)? && Self::condition(right, variables)?),
+            Condition::Or { left, right } => Ok(Self::condition(left, variables)? || Self::condition(right, variables)?),
+            Condition::Not { source } => Ok(!Self::condition(source, variables)?),
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => {
+                let number_type = Self::expression_type(left, variables)?.common(Self::expression_type(right, variables)?);
+                let left = Self::expression(left, variables)?.convert(number_type);
+                let right = Self::expression(right, variables)?.convert(number_type);
+                arithmetic::compare(ConditionDiscriminant::from(condition), number_type, left, right)
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use super::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Identifier(String),
+
+    // Digits are kept as text, the parser decides the type and the sign
+    Number { text: String, suffix: Option<String> },
+    String(String),
+    Symbol(&'static str),
+    End
+}
+
+#[derive(Debug, Clone)]
+pub struct PositionedToken {
+    pub token: Token,
+    pub line: usize,
+    pub column: usize
+}
+
+/// Longer symbols are placed first, so they are matched before their prefixes
+const SYMBOLS: [&str; 29] = [
+    "...", "<<", ">>", "&&", "||", "==", "!=", "<=", ">=",
+    "(", ")", "{", "}", ",", ";", ":", "=", "+", "-", "*", "/", "%", "&", "|", "^", "~", "!", "<", ">"
+];
+
+pub struct Lexer {
+    chars: Vec<char>,
+    position: usize,
+    line: usize,
+    column: usize
+}
+
+impl Lexer {
+    pub fn tokenize(source: &str) -> Result<Vec<PositionedToken>, ParseError> {
+        let mut lexer = Self { chars: source.chars().collect(), position: 0, line: 1, column: 1 };
+        let mut tokens = Vec::new();
+
+        loop {
+            lexer.skip_whitespace();
+            let (line, column) = (lexer.line, lexer.column);
+
+            let token = match lexer.peek() {
+                None => Token::End,
+                Some(char) if char.is_ascii_digit() => lexer.number(),
+                Some(char) if char.is_alphabetic() || char == '_' => Token::Identifier(lexer.identifier()),
+                Some('"') => Token::String(lexer.string()?),
+                Some(_) => Token::Symbol(lexer.symbol()?)
+            };
+
+            let end = token == Token::End;
+            tokens.push(PositionedToken { token, line, column });
+
+            if end {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).cloned()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).cloned()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let char = self.peek()?;
+        self.position += 1;
+
+        match char {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            },
+            _ => self.column += 1
+        };
+
+        Some(char)
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError::new(self.line, self.column, message)
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match (self.peek(), self.peek_at(1)) {
+                (Some(char), _) if char.is_whitespace() => { self.next(); },
+
+                // Line comment
+                (Some('/'), Some('/')) => {
+                    while !matches!(self.peek(), Some('\n') | None) {
+                        self.next();
+                    }
+                },
+                _ => return
+            }
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let mut text = String::new();
+        while let Some(char) = self.peek().filter(|char| char.is_alphanumeric() || *char == '_') {
+            text.push(char);
+            self.next();
+        }
+        text
+    }
+
+    fn digits(&mut self, text: &mut String) {
+        while let Some(char) = self.peek().filter(|char| char.is_ascii_digit()) {
+            text.push(char);
+            self.next();
+        }
+    }
+
+    fn number(&mut self) -> Token {
+        let mut text = String::new();
+        self.digits(&mut text);
+
+        // Fraction needs a digit after the dot, "..." is not a part of the number
+        if self.peek() == Some('.') && self.peek_at(1).map(|char| char.is_ascii_digit()).unwrap_or_default() {
+            text.push('.');
+            self.next();
+            self.digits(&mut text);
+        }
+
+        let exponent = match (self.peek(), self.peek_at(1), self.peek_at(2)) {
+            (Some('e' | 'E'), Some(digit), _) if digit.is_ascii_digit() => true,
+            (Some('e' | 'E'), Some('+' | '-'), Some(digit)) if digit.is_ascii_digit() => true,
+            _ => false
+        };
+
+        if exponent {
+            text.push(self.next().unwrap_or_default());
+            if let Some(sign) = self.peek().filter(|char| *char == '+' || *char == '-') {
+                text.push(sign);
+                self.next();
+            }
+            self.digits(&mut text);
+        }
+
+        let suffix = match self.peek() {
+            Some(char) if char.is_alphabetic() || char == '_' => Some(self.identifier()),
+            _ => None
+        };
+
+        Token::Number { text, suffix }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        let mut text = String::new();
+        let start = ParseError::new(self.line, self.column, "unterminated string");
+        self.next();
+
+        loop {
+            let char = match self.next() {
+                Some(char) => char,
+                None => return Err(start)
+            };
+
+            match char {
+                '"' => return Ok(text),
+                '\\' => {
+                    let escaped = match self.next() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('0') => '\0',
+                        Some('\\') => '\\',
+                        Some('"') => '"',
+                        Some('x') => {
+                            let digits = [self.next(), self.next()].into_iter().flatten().collect::<String>();
+                            match u8::from_str_radix(&digits, 16) {
+                                Ok(value) if digits.len() == 2 && value.is_ascii() => value as char,
+                                _ => return Err(self.error("invalid hex escape"))
+                            }
+                        },
+                        _ => return Err(self.error("invalid escape sequence"))
+                    };
+                    text.push(escaped);
+                },
+                char => text.push(char)
+            }
+        }
+    }
+
+    fn symbol(&mut self) -> Result<&'static str, ParseError> {
+        for symbol in SYMBOLS.iter() {
+            let matched = symbol.chars().enumerate().all(|(index, char)| self.peek_at(index) == Some(char));
+
+            if matched {
+                for _ in 0..symbol.len() {
+                    self.next();
+                }
+                return Ok(symbol);
+            }
+        }
+
+        Err(self.error(&format!("unexpected character '{}'", self.peek().unwrap_or_default())))
+    }
+}
@@ -0,0 +1,77 @@
+//! Human readable text format of the definitions
+//!
+//! ```text
+//! fn sum(a: i64, b: i64) {
+//!     let total: i64 = a + b;
+//!     if (total > 10i64) && !(a == b) {
+//!         print "Sum: %lld\n", total;
+//!     }
+//!     return total;
+//! }
+//! ```
+//!
+//! Integer literals are I32 and fractions are Double, other types need a suffix like `10u8` or `1.5f32`.
+//! Variadic calls end with `...`, like `call printf("%d", x, ...);`
+
+use thiserror::Error;
+
+use crate::types::{Definition, NumberType};
+
+mod lexer;
+mod parser;
+mod printer;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("{line}:{column}: {message}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, message: &str) -> Self {
+        Self { line, column, message: message.to_owned() }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Vec<Definition>, ParseError> {
+    parser::Parser::parse(source)
+}
+
+pub fn print(definitions: &[Definition]) -> String {
+    printer::Printer::print(definitions)
+}
+
+pub fn type_name(number_type: NumberType) -> &'static str {
+    match number_type {
+        NumberType::I8 => "i8",
+        NumberType::U8 => "u8",
+        NumberType::I16 => "i16",
+        NumberType::U16 => "u16",
+        NumberType::I32 => "i32",
+        NumberType::U32 => "u32",
+        NumberType::I64 => "i64",
+        NumberType::U64 => "u64",
+        NumberType::Float => "f32",
+        NumberType::Double => "f64",
+        NumberType::Bool => "bool"
+    }
+}
+
+pub fn parse_type_name(name: &str) -> Option<NumberType> {
+    match name {
+        "i8" => Some(NumberType::I8),
+        "u8" => Some(NumberType::U8),
+        "i16" => Some(NumberType::I16),
+        "u16" => Some(NumberType::U16),
+        "i32" => Some(NumberType::I32),
+        "u32" => Some(NumberType::U32),
+        "i64" => Some(NumberType::I64),
+        "u64" => Some(NumberType::U64),
+        "f32" => Some(NumberType::Float),
+        "f64" => Some(NumberType::Double),
+        "bool" => Some(NumberType::Bool),
+        _ => None
+    }
+}
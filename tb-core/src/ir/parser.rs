@@ -0,0 +1,530 @@
+use crate::types::{Block, Condition, Definition, Expression, Number, NumberType, Parameter, Statement, Value};
+
+use super::{lexer::{Lexer, PositionedToken, Token}, parse_type_name, ParseError};
+
+/// Operators and comparisons are parsed with the same precedence table, the result is converted when the other one is expected
+enum Node {
+    Expression(Expression),
+    Condition(Condition)
+}
+
+/// Binary operators from the lowest to the highest precedence
+const BINARY_OPERATORS: [&[&str]; 10] = [
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"]
+];
+
+pub struct Parser {
+    tokens: Vec<PositionedToken>,
+    position: usize
+}
+
+impl Parser {
+    pub fn parse(source: &str) -> Result<Vec<Definition>, ParseError> {
+        let mut parser = Self { tokens: Lexer::tokenize(source)?, position: 0 };
+        let mut definitions = Vec::new();
+
+        while parser.peek() != &Token::End {
+            definitions.push(parser.function()?);
+        }
+
+        Ok(definitions)
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let position = std::cmp::min(self.position + offset, self.tokens.len() - 1);
+        &self.tokens[position].token
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].token.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let token = &self.tokens[self.position];
+        ParseError::new(token.line, token.column, message)
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let found = match self.peek() {
+            Token::Identifier(name) => format!("'{}'", name),
+            Token::Number { text, suffix } => format!("'{}{}'", text, suffix.as_deref().unwrap_or_default()),
+            Token::String(_) => "string".to_owned(),
+            Token::Symbol(symbol) => format!("'{}'", symbol),
+            Token::End => "end of input".to_owned()
+        };
+
+        self.error(&format!("expected {}, found {}", expected, found))
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Token::Symbol(item) if *item == symbol)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Identifier(item) if item == keyword)
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        match self.is_symbol(symbol) {
+            true => {
+                self.next();
+                Ok(())
+            },
+            false => Err(self.unexpected(&format!("'{}'", symbol)))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        match self.is_keyword(keyword) {
+            true => {
+                self.next();
+                Ok(())
+            },
+            false => Err(self.unexpected(&format!("'{}'", keyword)))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String, ParseError> {
+        match self.peek().clone() {
+            Token::Identifier(name) => {
+                self.next();
+                Ok(name)
+            },
+            _ => Err(self.unexpected("identifier"))
+        }
+    }
+
+    fn number_type(&mut self) -> Result<NumberType, ParseError> {
+        match self.peek().clone() {
+            Token::Identifier(name) => match parse_type_name(&name) {
+                Some(number_type) => {
+                    self.next();
+                    Ok(number_type)
+                },
+                None => Err(self.error(&format!("unknown type '{}'", name)))
+            },
+            _ => Err(self.unexpected("type"))
+        }
+    }
+
+    /// fn name(a: i64, b: f64) { ... }
+    fn function(&mut self) -> Result<Definition, ParseError> {
+        self.expect_keyword("fn")?;
+        let name = self.identifier()?;
+        self.expect_symbol("(")?;
+
+        let mut parameters = Vec::new();
+        while !self.is_symbol(")") {
+            if !parameters.is_empty() {
+                self.expect_symbol(",")?;
+            }
+
+            let name = self.identifier()?;
+            self.expect_symbol(":")?;
+            parameters.push(Parameter { name, param_type: self.number_type()? });
+        }
+
+        self.expect_symbol(")")?;
+        let block = self.block()?;
+        Ok(Definition::Function { name, parameters, block })
+    }
+
+    fn block(&mut self) -> Result<Block, ParseError> {
+        self.expect_symbol("{")?;
+
+        let mut items = Vec::new();
+        while !self.is_symbol("}") {
+            if self.peek() == &Token::End {
+                return Err(self.unexpected("'}'"));
+            }
+            items.push(self.statement()?);
+        }
+
+        self.expect_symbol("}")?;
+        Ok(Block { items })
+    }
+
+    fn statement(&mut self) -> Result<Statement, ParseError> {
+        let keyword = match self.peek() {
+            Token::Identifier(name) => name.clone(),
+            _ => return Err(self.unexpected("statement"))
+        };
+
+        let statement = match keyword.as_str() {
+            "let" => {
+                self.next();
+                let name = self.identifier()?;
+                self.expect_symbol(":")?;
+                let ty = self.number_type()?;
+
+                let init = match self.is_symbol("=") {
+                    true => {
+                        self.next();
+                        Some(self.expression()?)
+                    },
+                    false => None
+                };
+                Statement::Declare { name, ty, init }
+            },
+            "if" => return self.if_statement(),
+            "while" => {
+                self.next();
+                let condition = self.condition()?;
+                return Ok(Statement::While { condition, block: self.block()? });
+            },
+            "loop" => {
+                self.next();
+                return Ok(Statement::Loop { block: self.block()? });
+            },
+            "break" => {
+                self.next();
+                Statement::Break
+            },
+            "continue" => {
+                self.next();
+                Statement::Continue
+            },
+            "print" => {
+                self.next();
+                let format = match self.peek().clone() {
+                    Token::String(format) => {
+                        self.next();
+                        format
+                    },
+                    _ => return Err(self.unexpected("format string"))
+                };
+
+                let mut arguments = Vec::new();
+                while self.is_symbol(",") {
+                    self.next();
+                    arguments.push(self.value()?);
+                }
+                Statement::Print { format, arguments }
+            },
+            "call" => self.call(None)?,
+            "return" => {
+                self.next();
+                match self.is_symbol(";") {
+                    true => Statement::Return(None),
+                    false => Statement::Return(Some(self.value()?))
+                }
+            },
+            _ => {
+                let name = self.identifier()?;
+                self.expect_symbol("=")?;
+
+                match self.is_keyword("call") {
+                    true => self.call(Some(name))?,
+                    false => Statement::Assign { name, assigne: self.expression()? }
+                }
+            }
+        };
+
+        self.expect_symbol(";")?;
+        Ok(statement)
+    }
+
+    /// if condition { ... } else if condition { ... } else { ... }
+    fn if_statement(&mut self) -> Result<Statement, ParseError> {
+        self.expect_keyword("if")?;
+        let condition = self.condition()?;
+        let true_block = self.block()?;
+
+        let false_block = match self.is_keyword("else") {
+            true => {
+                self.next();
+                match self.is_keyword("if") {
+                    true => Some(Block { items: vec![self.if_statement()?] }),
+                    false => Some(self.block()?)
+                }
+            },
+            false => None
+        };
+
+        Ok(Statement::If { condition, true_block, false_block })
+    }
+
+    /// call name(a, b) or call name(format, a, ...) for the variadic functions
+    fn call(&mut self, assign: Option<String>) -> Result<Statement, ParseError> {
+        self.expect_keyword("call")?;
+        let name = self.identifier()?;
+        self.expect_symbol("(")?;
+
+        let mut arguments = Vec::new();
+        let mut is_variadic = false;
+
+        while !self.is_symbol(")") {
+            if !arguments.is_empty() || is_variadic {
+                self.expect_symbol(",")?;
+            }
+
+            if is_variadic {
+                return Err(self.unexpected("')'"));
+            }
+
+            match self.is_symbol("...") {
+                true => {
+                    self.next();
+                    is_variadic = true;
+                },
+                false => arguments.push(self.value()?)
+            }
+        }
+
+        self.expect_symbol(")")?;
+        Ok(Statement::Call { name, arguments, assign, is_variadic })
+    }
+
+    /// Variable, number or string
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek().clone() {
+            Token::String(text) => {
+                self.next();
+                Ok(Value::String(text))
+            },
+            Token::Symbol("-") => {
+                self.next();
+                Ok(Value::Number(self.number(true)?))
+            },
+            Token::Number { .. } => Ok(Value::Number(self.number(false)?)),
+            Token::Identifier(name) if Self::special_number(&name).is_some() => Ok(Value::Number(self.number(false)?)),
+            Token::Identifier(name) => {
+                self.next();
+                Ok(Value::Variable(name))
+            },
+            _ => Err(self.unexpected("value"))
+        }
+    }
+
+    fn special_number(name: &str) -> Option<Number> {
+        match name {
+            "true" => Some(Number::Bool(true)),
+            "false" => Some(Number::Bool(false)),
+            "nan" => Some(Number::Double(f64::NAN)),
+            "inf" => Some(Number::Double(f64::INFINITY)),
+            "nanf32" => Some(Number::Float(f32::NAN)),
+            "inff32" => Some(Number::Float(f32::INFINITY)),
+            _ => None
+        }
+    }
+
+    /// Number literal, the type comes from the suffix. Integers are I32 and fractions are Double without a suffix.
+    fn number(&mut self, negative: bool) -> Result<Number, ParseError> {
+        let (text, suffix) = match self.peek().clone() {
+            Token::Number { text, suffix } => (text, suffix),
+            Token::Identifier(name) => match Self::special_number(&name) {
+                Some(Number::Bool(_)) if negative => return Err(self.error("boolean can not be negative")),
+                Some(Number::Double(number)) if negative => {
+                    self.next();
+                    return Ok(Number::Double(-number));
+                },
+                Some(Number::Float(number)) if negative => {
+                    self.next();
+                    return Ok(Number::Float(-number));
+                },
+                Some(number) => {
+                    self.next();
+                    return Ok(number);
+                },
+                None => return Err(self.unexpected("number"))
+            },
+            _ => return Err(self.unexpected("number"))
+        };
+
+        let text = match negative {
+            true => format!("-{}", text),
+            false => text
+        };
+
+        let is_fraction = text.contains(['.', 'e', 'E']);
+        let number_type = match suffix.as_deref() {
+            Some(suffix) => parse_type_name(suffix).ok_or_else(|| self.error(&format!("unknown number suffix '{}'", suffix)))?,
+            None if is_fraction => NumberType::Double,
+            None => match text.parse::<i64>() {
+                Ok(number) if i32::try_from(number).is_ok() => NumberType::I32,
+                Ok(_) => NumberType::I64,
+                Err(_) => NumberType::U64
+            }
+        };
+
+        if is_fraction && !number_type.is_float() {
+            return Err(self.error(&format!("fraction can not be {}", super::type_name(number_type))));
+        }
+
+        let out_of_range = || self.error(&format!("number '{}' is out of range", text));
+        let number = match number_type {
+            NumberType::I8 => Number::I8(text.parse().map_err(|_| out_of_range())?),
+            NumberType::U8 => Number::U8(text.parse().map_err(|_| out_of_range())?),
+            NumberType::I16 => Number::I16(text.parse().map_err(|_| out_of_range())?),
+            NumberType::U16 => Number::U16(text.parse().map_err(|_| out_of_range())?),
+            NumberType::I32 => Number::I32(text.parse().map_err(|_| out_of_range())?),
+            NumberType::U32 => Number::U32(text.parse().map_err(|_| out_of_range())?),
+            NumberType::I64 => Number::I64(text.parse().map_err(|_| out_of_range())?),
+            NumberType::U64 => Number::U64(text.parse().map_err(|_| out_of_range())?),
+            NumberType::Float => Number::Float(text.parse().map_err(|_| out_of_range())?),
+            NumberType::Double => Number::Double(text.parse().map_err(|_| out_of_range())?),
+            NumberType::Bool => return Err(self.error("use true or false for the boolean numbers"))
+        };
+
+        self.next();
+        Ok(number)
+    }
+
+    fn expression(&mut self) -> Result<Expression, ParseError> {
+        let node = self.binary(0)?;
+        Ok(Self::as_expression(node))
+    }
+
+    fn condition(&mut self) -> Result<Condition, ParseError> {
+        let position = self.position;
+        let node = self.binary(0)?;
+        self.as_condition(node, position)
+    }
+
+    fn as_expression(node: Node) -> Expression {
+        match node {
+            Node::Expression(expression) => expression,
+            Node::Condition(condition) => Expression::Condition(Box::new(condition))
+        }
+    }
+
+    fn as_condition(&self, node: Node, position: usize) -> Result<Condition, ParseError> {
+        match node {
+            Node::Condition(condition) => Ok(condition),
+            Node::Expression(_) => {
+                let token = &self.tokens[position];
+                Err(ParseError::new(token.line, token.column, "expected a condition"))
+            }
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Node, ParseError> {
+        if level == BINARY_OPERATORS.len() {
+            return self.unary();
+        }
+
+        let left_position = self.position;
+        let mut left = self.binary(level + 1)?;
+
+        loop {
+            let operator = match self.peek() {
+                Token::Symbol(symbol) if BINARY_OPERATORS[level].contains(symbol) => *symbol,
+                _ => return Ok(left)
+            };
+
+            self.next();
+            let right_position = self.position;
+            let right = self.binary(level + 1)?;
+
+            left = match operator {
+                "||" => Node::Condition(Condition::Or { left: Box::new(self.as_condition(left, left_position)?), right: Box::new(self.as_condition(right, right_position)?) }),
+                "&&" => Node::Condition(Condition::And { left: Box::new(self.as_condition(left, left_position)?), right: Box::new(self.as_condition(right, right_position)?) }),
+                operator => {
+                    let left = Self::as_expression(left);
+                    let right = Self::as_expression(right);
+
+                    match operator {
+                        "==" => Node::Condition(Condition::Eq { left, right }),
+                        "!=" => Node::Condition(Condition::Ne { left, right }),
+                        "<" => Node::Condition(Condition::Ls { left, right }),
+                        "<=" => Node::Condition(Condition::Le { left, right }),
+                        ">" => Node::Condition(Condition::Gr { left, right }),
+                        ">=" => Node::Condition(Condition::Ge { left, right }),
+                        operator => {
+                            let (target, source) = (Box::new(left), Box::new(right));
+                            Node::Expression(match operator {
+                                "|" => Expression::BitwiseOr { target, source },
+                                "^" => Expression::BitwiseXor { target, source },
+                                "&" => Expression::BitwiseAnd { target, source },
+                                "<<" => Expression::ShiftLeft { target, source },
+                                ">>" => Expression::ShiftRight { target, source },
+                                "+" => Expression::Add { target, source },
+                                "-" => Expression::Sub { target, source },
+                                "*" => Expression::Mul { target, source },
+                                "/" => Expression::Div { divided: target, divider: source },
+                                _ => Expression::Modulo { divided: target, divider: source }
+                            })
+                        }
+                    }
+                }
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Node, ParseError> {
+        match self.peek().clone() {
+            Token::Symbol("-") => {
+                self.next();
+
+                // Minus sign of a literal belongs to the number
+                let literal = match self.peek() {
+                    Token::Number { .. } => true,
+                    Token::Identifier(name) => matches!(Self::special_number(name), Some(Number::Double(_) | Number::Float(_))),
+                    _ => false
+                };
+
+                match literal {
+                    true => Ok(Node::Expression(Expression::Value(Value::Number(self.number(true)?)))),
+                    false => Ok(Node::Expression(Expression::BitwiseNeg { source: Box::new(self.unary_expression()?) }))
+                }
+            },
+            Token::Symbol("~") => {
+                self.next();
+                Ok(Node::Expression(Expression::BitwiseNot { source: Box::new(self.unary_expression()?) }))
+            },
+            Token::Symbol("!") => {
+                self.next();
+                let position = self.position;
+                let node = self.unary()?;
+                Ok(Node::Condition(Condition::Not { source: Box::new(self.as_condition(node, position)?) }))
+            },
+            _ => self.atom()
+        }
+    }
+
+    fn unary_expression(&mut self) -> Result<Expression, ParseError> {
+        let node = self.unary()?;
+        Ok(Self::as_expression(node))
+    }
+
+    fn atom(&mut self) -> Result<Node, ParseError> {
+        match self.peek().clone() {
+            Token::Symbol("(") => {
+                self.next();
+                let node = self.binary(0)?;
+                self.expect_symbol(")")?;
+                Ok(node)
+            },
+            Token::Identifier(name) if (name == "inc" || name == "dec") && self.peek_at(1) == &Token::Symbol("(") => {
+                self.next();
+                self.next();
+                let source = Box::new(self.expression()?);
+                self.expect_symbol(")")?;
+
+                Ok(Node::Expression(match name.as_str() {
+                    "inc" => Expression::Inc { source },
+                    _ => Expression::Dec { source }
+                }))
+            },
+            Token::Number { .. } | Token::String(_) | Token::Identifier(_) => Ok(Node::Expression(Expression::Value(self.value()?))),
+            _ => Err(self.unexpected("expression"))
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt::Write;
+
+use crate::types::{Block, Condition, Definition, Expression, Number, Statement, Value};
+
+use super::type_name;
+
+const INDENT: &str = "    ";
+
+pub struct Printer {
+    buffer: String
+}
+
+impl Printer {
+    pub fn print(definitions: &[Definition]) -> String {
+        let mut printer = Self { buffer: String::new() };
+
+        for (index, definition) in definitions.iter().enumerate() {
+            if index > 0 {
+                printer.buffer.push('\n');
+            }
+            printer.definition(definition);
+        }
+
+        printer.buffer
+    }
+
+    fn definition(&mut self, definition: &Definition) {
+        match definition {
+            Definition::Function { name, parameters, block } => {
+                let parameters = parameters.iter().map(|parameter| format!("{}: {}", parameter.name, type_name(parameter.param_type))).collect::<Vec<_>>();
+                let _ = write!(self.buffer, "fn {}({}) ", name, parameters.join(", "));
+                self.block(block, 0);
+                self.buffer.push('\n');
+            }
+        }
+    }
+
+    fn block(&mut self, block: &Block, depth: usize) {
+        self.buffer.push_str("{\n");
+
+        for statement in block.items.iter() {
+            self.buffer.push_str(&INDENT.repeat(depth + 1));
+            self.statement(statement, depth + 1);
+            self.buffer.push('\n');
+        }
+
+        self.buffer.push_str(&INDENT.repeat(depth));
+        self.buffer.push('}');
+    }
+
+    fn statement(&mut self, statement: &Statement, depth: usize) {
+        match statement {
+            Statement::Assign { name, assigne } => { let _ = write!(self.buffer, "{} = {};", name, Self::expression(assigne, false)); },
+            Statement::Declare { name, ty, init: Some(init) } => { let _ = write!(self.buffer, "let {}: {} = {};", name, type_name(*ty), Self::expression(init, false)); },
+            Statement::Declare { name, ty, init: None } => { let _ = write!(self.buffer, "let {}: {};", name, type_name(*ty)); },
+            Statement::If { condition, true_block, false_block } => {
+                let _ = write!(self.buffer, "if {} ", Self::condition(condition, false));
+                self.block(true_block, depth);
+
+                match false_block {
+                    // Single if in the else block is written as "else if"
+                    Some(Block { items }) if items.len() == 1 && matches!(items[0], Statement::If { .. }) => {
+                        self.buffer.push_str(" else ");
+                        self.statement(&items[0], depth);
+                    },
+                    Some(false_block) => {
+                        self.buffer.push_str(" else ");
+                        self.block(false_block, depth);
+                    },
+                    None => ()
+                }
+            },
+            Statement::While { condition, block } => {
+                let _ = write!(self.buffer, "while {} ", Self::condition(condition, false));
+                self.block(block, depth);
+            },
+            Statement::Loop { block } => {
+                self.buffer.push_str("loop ");
+                self.block(block, depth);
+            },
+            Statement::Break => self.buffer.push_str("break;"),
+            Statement::Continue => self.buffer.push_str("continue;"),
+            Statement::Print { format, arguments } => {
+                let _ = write!(self.buffer, "print {}", Self::string(format));
+                for argument in arguments.iter() {
+                    let _ = write!(self.buffer, ", {}", Self::value(argument));
+                }
+                self.buffer.push(';');
+            },
+            Statement::Call { name, arguments, assign, is_variadic } => {
+                let mut arguments = arguments.iter().map(Self::value).collect::<Vec<_>>();
+                if *is_variadic {
+                    arguments.push("...".to_owned());
+                }
+
+                if let Some(assign) = assign {
+                    let _ = write!(self.buffer, "{} = ", assign);
+                }
+                let _ = write!(self.buffer, "call {}({});", name, arguments.join(", "));
+            },
+            Statement::Return(Some(value)) => { let _ = write!(self.buffer, "return {};", Self::value(value)); },
+            Statement::Return(None) => self.buffer.push_str("return;")
+        }
+    }
+
+    /// Nested binary operations are always in parentheses, so the precedence never changes the meaning
+    fn expression(expression: &Expression, nested: bool) -> String {
+        let (operator, left, right) = match expression {
+            Expression::Add { target, source } => ("+", target, source),
+            Expression::Sub { target, source } => ("-", target, source),
+            Expression::Mul { target, source } => ("*", target, source),
+            Expression::Div { divider, divided } => ("/", divided, divider),
+            Expression::Modulo { divider, divided } => ("%", divided, divider),
+            Expression::ShiftLeft { target, source } => ("<<", target, source),
+            Expression::ShiftRight { target, source } => (">>", target, source),
+            Expression::BitwiseAnd { target, source } => ("&", target, source),
+            Expression::BitwiseOr { target, source } => ("|", target, source),
+            Expression::BitwiseXor { target, source } => ("^", target, source),
+
+            // Minus sign of a literal would be a part of the number
+            Expression::BitwiseNeg { source } => return match source.as_ref() {
+                Expression::Value(Value::Number(_)) => format!("-({})", Self::expression(source, false)),
+                source => format!("-{}", Self::expression(source, true))
+            },
+            Expression::BitwiseNot { source } => return format!("~{}", Self::expression(source, true)),
+            Expression::Inc { source } => return format!("inc({})", Self::expression(source, false)),
+            Expression::Dec { source } => return format!("dec({})", Self::expression(source, false)),
+            Expression::Condition(condition) => return Self::condition(condition, nested),
+            Expression::Value(value) => return Self::value(value)
+        };
+
+        Self::binary(operator, Self::expression(left, true), Self::expression(right, true), nested)
+    }
+
+    fn condition(condition: &Condition, nested: bool) -> String {
+        let (operator, left, right) = match condition {
+            Condition::Eq { left, right } => ("==", left, right),
+            Condition::Ne { left, right } => ("!=", left, right),
+            Condition::Gr { left, right } => (">", left, right),
+            Condition::Ge { left, right } => (">=", left, right),
+            Condition::Ls { left, right } => ("<", left, right),
+            Condition::Le { left, right } => ("<=", left, right),
+            Condition::And { left, right } => return Self::binary("&&", Self::condition(left, true), Self::condition(right, true), nested),
+            Condition::Or { left, right } => return Self::binary("||", Self::condition(left, true), Self::condition(right, true), nested),
+            Condition::Not { source } => return format!("!{}", Self::condition(source, true))
+        };
+
+        Self::binary(operator, Self::expression(left, true), Self::expression(right, true), nested)
+    }
+
+    fn binary(operator: &str, left: String, right: String, nested: bool) -> String {
+        match nested {
+            true => format!("({} {} {})", left, operator, right),
+            false => format!("{} {} {}", left, operator, right)
+        }
+    }
+
+    fn value(value: &Value) -> String {
+        match value {
+            Value::Variable(name) => name.clone(),
+            Value::Number(number) => Self::number(number),
+            Value::String(text) => Self::string(text)
+        }
+    }
+
+    /// I32 and Double literals do not need a suffix, others are written with the type name
+    fn number(number: &Number) -> String {
+        match number {
+            Number::I32(number) => number.to_string(),
+            Number::Double(number) => Self::fraction(*number, ""),
+            Number::Float(number) => Self::fraction(*number as f64, "f32"),
+            Number::Bool(value) => value.to_string(),
+            number => format!("{}{}", number, type_name(number.number_type()))
+        }
+    }
+
+    fn fraction(number: f64, suffix: &str) -> String {
+        match number {
+            number if number.is_nan() => format!("nan{}", suffix),
+            number if number.is_infinite() && number.is_sign_negative() => format!("-inf{}", suffix),
+            number if number.is_infinite() => format!("inf{}", suffix),
+
+            // Debug format is the shortest text that parses back to the same number
+            number if suffix.is_empty() => format!("{:?}", number),
+            number => format!("{:?}{}", number as f32, suffix)
+        }
+    }
+
+    fn string(text: &str) -> String {
+        let mut buffer = String::from("\"");
+
+        for char in text.chars() {
+            match char {
+                '\n' => buffer.push_str("\\n"),
+                '\r' => buffer.push_str("\\r"),
+                '\t' => buffer.push_str("\\t"),
+                '\0' => buffer.push_str("\\0"),
+                '\\' => buffer.push_str("\\\\"),
+                '"' => buffer.push_str("\\\""),
+                char if char.is_ascii_control() => { let _ = write!(buffer, "\\x{:02x}", char as u32); },
+                char => buffer.push(char)
+            }
+        }
+
+        buffer.push('"');
+        buffer
+    }
+}
@@ -0,0 +1,122 @@
+//! Executable memory for the machine code that is generated in the process, targets write and link their
+//! code into it and the functions are called through the returned pointers.
+
+use std::{collections::HashMap, error::Error, ffi::CString, mem, ptr, slice};
+
+use thiserror::Error;
+
+use crate::{error::CodeGenerationError, types::{AssemblyGenerator, DataItemCollection, Definition}};
+
+#[derive(Error, Debug)]
+pub enum JitError {
+    #[error("Memory could not be allocated ({0} bytes)")]
+    AllocationFailed(usize),
+
+    #[error("Memory protection could not be changed")]
+    ProtectionFailed,
+
+    #[error("Symbol not found ({0})")]
+    SymbolNotFound(String),
+
+    #[error("Relocation out of range ({0})")]
+    RelocationOutOfRange(String),
+
+    #[error("Code generation failed ({0})")]
+    CodeGenerationFailed(#[from] CodeGenerationError),
+
+    #[error("Encode failed ({0})")]
+    EncodeFailed(#[source] Box<dyn Error + Send + Sync>)
+}
+
+pub trait JitGenerator: AssemblyGenerator {
+    fn jit(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Jit, JitError>;
+}
+
+/// Page aligned memory, it is writable until it is made executable
+#[derive(Debug)]
+pub struct ExecutableMemory {
+    pointer: *mut u8,
+    size: usize
+}
+
+impl ExecutableMemory {
+    pub fn new(size: usize) -> Result<Self, JitError> {
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let size = size.max(1).next_multiple_of(page_size);
+
+        let pointer = unsafe { libc::mmap(ptr::null_mut(), size, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_PRIVATE | libc::MAP_ANONYMOUS, -1, 0) };
+        match pointer == libc::MAP_FAILED {
+            true => Err(JitError::AllocationFailed(size)),
+            false => Ok(Self { pointer: pointer as *mut u8, size })
+        }
+    }
+
+    pub fn address(&self) -> usize {
+        self.pointer as usize
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.pointer, self.size) }
+    }
+
+    /// Memory is never writable and executable at the same time
+    pub fn make_executable(&mut self) -> Result<(), JitError> {
+        match unsafe { libc::mprotect(self.pointer as *mut libc::c_void, self.size, libc::PROT_READ | libc::PROT_EXEC) } {
+            0 => Ok(()),
+            _ => Err(JitError::ProtectionFailed)
+        }
+    }
+}
+
+impl Drop for ExecutableMemory {
+    fn drop(&mut self) {
+        unsafe { libc::munmap(self.pointer as *mut libc::c_void, self.size) };
+    }
+}
+
+/// Compiled functions, they are valid as long as this is alive
+#[derive(Debug)]
+pub struct Jit {
+    // Code is unmapped when it is dropped
+    _memory: ExecutableMemory,
+    functions: HashMap<String, usize>
+}
+
+impl Jit {
+    /// Function addresses are absolute addresses in the memory
+    pub fn new(memory: ExecutableMemory, functions: HashMap<String, usize>) -> Self {
+        Self { _memory: memory, functions }
+    }
+
+    pub fn address(&self, name: &str) -> Option<usize> {
+        self.functions.get(name).copied()
+    }
+
+    /// Function pointer of the compiled function, like `jit.get::<extern "C" fn(i64, i64) -> i64>("sum")`
+    ///
+    /// # Safety
+    /// `F` should be an `extern "C"` function pointer that matches the parameters and the return type of the
+    /// function, and it should not be called after the `Jit` is dropped.
+    pub unsafe fn get<F: Copy>(&self, name: &str) -> Option<F> {
+        assert_eq!(mem::size_of::<F>(), mem::size_of::<usize>(), "only function pointers could be returned");
+
+        self.address(name).map(|address| mem::transmute_copy::<usize, F>(&address))
+    }
+}
+
+/// Address of a function in the process, like `printf` from the C library
+pub fn resolve_symbol(name: &str) -> Option<usize> {
+    // Mach-O symbols in the assembly have an underscore prefix, dlsym adds it by itself
+    let name = match cfg!(target_os = "macos") {
+        true => name.strip_prefix('_').unwrap_or(name),
+        false => name
+    };
+
+    let name = CString::new(name).ok()?;
+    let address = unsafe { libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr()) };
+
+    match address.is_null() {
+        true => None,
+        false => Some(address as usize)
+    }
+}
@@ -1,9 +1,17 @@
 pub mod types;
 pub mod tool;
 pub mod store;
+pub mod scope;
 pub mod compiler;
 pub mod syntax;
 pub mod error;
 pub mod addressing_mode;
 pub mod location;
 pub mod instruction;
+pub mod register_allocator;
+pub mod ir;
+pub mod interpreter;
+pub mod elf;
+pub mod target;
+#[cfg(unix)]
+pub mod jit;
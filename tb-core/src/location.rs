@@ -1,5 +1,6 @@
 use std::fmt::Debug;
 
+use crate::register_allocator::VirtualRegister;
 use crate::types::{Number, RegisterTrait};
 use crate::addressing_mode::AddressingMode;
 
@@ -8,7 +9,8 @@ pub enum Location<R: RegisterTrait> {
     Memory(i64),
     Register(AddressingMode<R>),
     Imm(Number),
-    Label(String)
+    Label(String),
+    Virtual(VirtualRegister)
 }
 
 impl<R> Default for Location<R> where R: RegisterTrait {
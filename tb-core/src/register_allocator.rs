@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use crate::types::{RegisterSize, RegisterTrait};
+
+/// Register that is not bound to a physical register yet. The allocator decides the real register or the stack slot.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VirtualRegister {
+    pub index: usize,
+    pub size: RegisterSize
+}
+
+impl VirtualRegister {
+    pub fn new(index: usize, size: RegisterSize) -> Self {
+        Self { index, size }
+    }
+
+    /// Sized view of the same register, vector registers have only one size
+    pub fn get_sized(self, size: RegisterSize) -> Self {
+        match self.is_vector() {
+            true => self,
+            false => Self { index: self.index, size }
+        }
+    }
+
+    pub fn is_vector(&self) -> bool {
+        self.size == RegisterSize::_128Bit
+    }
+}
+
+/// First and last instruction position that the virtual register is alive
+#[derive(Debug, Clone)]
+pub struct LiveInterval {
+    pub register: usize,
+    pub vector: bool,
+    pub start: usize,
+    pub end: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Allocation<R: RegisterTrait> {
+    Register(R),
+    Spill
+}
+
+/// Positions that physical registers are used directly by the instructions (fixed operands, calls, etc.)
+/// Ranges of the same register do not overlap, they are kept sorted to find the conflicts fast.
+#[derive(Debug, Clone)]
+pub struct FixedRanges<R: RegisterTrait> {
+    ranges: Vec<(R, Vec<(usize, usize)>)>
+}
+
+impl<R> Default for FixedRanges<R> where R: RegisterTrait {
+    fn default() -> Self {
+        Self { ranges: Default::default() }
+    }
+}
+
+impl<R> FixedRanges<R> where R: RegisterTrait {
+    pub fn add(&mut self, register: R, start: usize, end: usize) {
+        let register = register.get_sized(RegisterSize::_64Bit);
+
+        let index = match self.ranges.iter().position(|(item, _)| *item == register) {
+            Some(index) => index,
+            None => {
+                self.ranges.push((register, Vec::new()));
+                self.ranges.len() - 1
+            }
+        };
+
+        let ranges = &mut self.ranges[index].1;
+        let position = ranges.partition_point(|(range_start, _)| *range_start < start);
+        ranges.insert(position, (start, end));
+    }
+
+    /// The interval can not use the register if the register is written or read inside of it.
+    /// Touching the edges is allowed, the instruction reads the operands before writing the result.
+    pub fn conflicts(&self, register: &R, start: usize, end: usize) -> bool {
+        let register = register.clone().get_sized(RegisterSize::_64Bit);
+
+        match self.ranges.iter().find(|(item, _)| *item == register) {
+            Some((_, ranges)) => {
+                // First range that ends after the interval start
+                let position = ranges.partition_point(|(_, range_end)| *range_end <= start);
+                ranges.get(position).map(|(range_start, _)| *range_start < end).unwrap_or_default()
+            },
+            None => false
+        }
+    }
+}
+
+/// Classic linear scan allocator. Intervals are processed by the start position and
+/// the interval that lives longest is spilled when there is no free register.
+#[derive(Debug, Clone)]
+pub struct LinearScanAllocator<R: RegisterTrait> {
+    registers: Vec<R>
+}
+
+impl<R> LinearScanAllocator<R> where R: RegisterTrait {
+    pub fn new(registers: Vec<R>) -> Self {
+        Self { registers }
+    }
+
+    pub fn allocate(&self, mut intervals: Vec<LiveInterval>, fixed_ranges: &FixedRanges<R>) -> HashMap<usize, Allocation<R>> {
+        let mut allocations = HashMap::new();
+        let mut active: Vec<(LiveInterval, R)> = Vec::new();
+
+        intervals.sort_by_key(|interval| (interval.start, interval.end));
+
+        for interval in intervals.into_iter() {
+            // Registers of the finished intervals could be used again
+            active.retain(|(item, _)| item.end >= interval.start);
+
+            let free_register = self.registers.iter()
+                .filter(|register| Self::is_vector(register) == interval.vector)
+                .filter(|register| !fixed_ranges.conflicts(register, interval.start, interval.end))
+                .find(|register| !active.iter().any(|(_, used)| used == *register))
+                .cloned();
+
+            match free_register {
+                Some(register) => {
+                    allocations.insert(interval.register, Allocation::Register(register.clone()));
+                    active.push((interval, register));
+                },
+                None => {
+                    let candidate = active.iter()
+                        .enumerate()
+                        .filter(|(_, (item, register))| item.vector == interval.vector && !fixed_ranges.conflicts(register, interval.start, interval.end))
+                        .max_by_key(|(_, (item, _))| item.end)
+                        .map(|(index, (item, _))| (index, item.end));
+
+                    match candidate {
+                        Some((index, end)) if end > interval.end => {
+                            let (spilled, register) = active.remove(index);
+                            allocations.insert(spilled.register, Allocation::Spill);
+                            allocations.insert(interval.register, Allocation::Register(register.clone()));
+                            active.push((interval, register));
+                        },
+                        _ => {
+                            allocations.insert(interval.register, Allocation::Spill);
+                        }
+                    }
+                }
+            }
+        }
+
+        allocations
+    }
+
+    fn is_vector(register: &R) -> bool {
+        register.get_register_size() == RegisterSize::_128Bit
+    }
+}
@@ -0,0 +1,79 @@
+use std::{collections::HashSet, marker::PhantomData};
+
+use crate::types::{Block, NumberType, Statement};
+
+pub trait ScopeNaming {
+    /// Name of the variable in the generated code, index is bigger than zero if the name is already used
+    fn variable_name(name: &str, index: usize) -> String;
+}
+
+#[derive(Debug, Clone)]
+pub struct ScopedVariable {
+    pub name: String,
+    pub target_name: String,
+    pub var_type: NumberType
+}
+
+/// Variables of a function for the targets that declare them once for the whole function. Every declaration
+/// gets its own name in the generated code, the shadowing ones get another name with an index.
+#[derive(Debug, Clone)]
+pub struct Scope<N: ScopeNaming> {
+    variables: Vec<ScopedVariable>,
+    used_names: HashSet<String>,
+    _mark: PhantomData<N>
+}
+
+impl<N: ScopeNaming> Default for Scope<N> {
+    fn default() -> Self {
+        Self::new(HashSet::new())
+    }
+}
+
+impl<N: ScopeNaming> Scope<N> {
+    /// Reserved names are not given to the variables, keywords of the target for example
+    pub fn new(reserved_names: HashSet<String>) -> Self {
+        Self { variables: Vec::new(), used_names: reserved_names, _mark: PhantomData }
+    }
+
+    pub fn find_variable(&self, variable: &str) -> Option<&ScopedVariable> {
+        // The last declaration shadows the previous ones
+        self.variables
+            .iter()
+            .rev()
+            .find(|item| item.name == variable)
+    }
+
+    pub fn add_variable(&mut self, name: &str, var_type: NumberType) -> &ScopedVariable {
+        let mut index = 0;
+        let mut target_name = N::variable_name(name, index);
+
+        while self.used_names.contains(&target_name) {
+            index += 1;
+            target_name = N::variable_name(name, index);
+        }
+
+        self.used_names.insert(target_name.clone());
+        self.variables.push(ScopedVariable { name: name.to_owned(), target_name, var_type });
+        &self.variables[self.variables.len()-1]
+    }
+
+    pub fn get_variables(&self) -> &[ScopedVariable] {
+        &self.variables
+    }
+}
+
+/// Statement compiler of a target, the blocks are compiled with it statement by statement
+pub trait StatementCompiler {
+    type Scope;
+    type Context;
+    type Error;
+
+    fn compile(statement: Statement, scope: &mut Self::Scope, context: &mut Self::Context) -> Result<(), Self::Error>;
+
+    fn compile_block(block: Block, scope: &mut Self::Scope, context: &mut Self::Context) -> Result<(), Self::Error> {
+        for item in block.items.into_iter() {
+            Self::compile(item, scope, context)?;
+        }
+        Ok(())
+    }
+}
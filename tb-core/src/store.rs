@@ -1,16 +1,18 @@
 use std::marker::PhantomData;
 
-use crate::types::{RegisterSize, RegisterTrait};
+use crate::{register_allocator::VirtualRegister, types::{NumberType, RegisterSize, RegisterTrait}};
 
 pub trait StoreDefaultRegisters<R: RegisterTrait>: Clone {
-    fn initialize() -> Vec<(R, bool)>;
+    /// Registers that the register allocator could use
+    fn initialize() -> Vec<R>;
 }
 
 #[derive(Debug, Clone)]
 pub struct Variable {
     pub name: String,
     pub size: u8,
-    pub position: usize
+    pub position: usize,
+    pub var_type: NumberType
 }
 
 #[derive(Debug, Clone)]
@@ -18,8 +20,8 @@ pub struct Store<R: RegisterTrait, L: Clone, D: StoreDefaultRegisters<R> + Clone
     variables: Vec<Variable>,
     last_position: usize,
     last_assigned_location: L,
-    last_size: RegisterSize,
-    registers: Vec<(R, bool)>,
+    registers: Vec<R>,
+    virtual_register_count: usize,
     has_function_call: bool,
     _mark: PhantomData<D>
 }
@@ -36,7 +38,7 @@ where
             last_position: 0,
             last_assigned_location: L::default(),
             registers: D::initialize(),
-            last_size: RegisterSize::_32Bit,
+            virtual_register_count: 0,
             has_function_call: false,
             _mark: PhantomData
         }
@@ -69,82 +71,36 @@ where
         self.last_assigned_location = location;
     }
     
-    pub fn get_last_size(&self) -> RegisterSize {
-        self.last_size
-    }
-
-    pub fn set_last_size(&mut self, size: RegisterSize) {
-        self.last_size = size;
-    }
-
     pub fn find_variable(&self, variable: &str) -> Option<&Variable> {
+        // The last declaration shadows the previous ones
         self.variables
             .iter()
+            .rev()
             .find(|item| item.name == variable)
     }
 
-    pub fn register_backup(&self) -> Vec<(R, bool)> {
-        self.registers.clone()
-    }
+    pub fn add_variable(&mut self, name: &str, var_type: NumberType) -> &Variable {
+        let size = var_type.size() as u8;
 
-    pub fn is_free(&mut self, register: R) -> bool {
-        for (item, status) in self.registers.iter() {
-            if item == &register {
-                return *status;
-            }
-        }
-        
-        true
-    }
-
-    pub fn mark_register(&mut self, register: R) {
-        for (index, (item, _)) in self.registers.iter().enumerate() {
-            if item == &register {
-                self.registers[index] = (register, false);
-                break;
-            }
-        }
-    }
-
-    pub fn unmark_register(&mut self, register: R) {
-        for (index, (item, _)) in self.registers.iter().enumerate() {
-            if item == &register {
-                self.registers[index] = (register, true);
-                break;
-            }
-        }
-    }
-
-    pub fn register_restore(&mut self, registers: Vec<(R, bool)>) {
-        self.registers = registers;
-    }
-
-    pub fn add_variable(&mut self, name: &str, size: u8) -> &Variable {
-        self.variables.push(Variable { name: name.to_owned(), size, position: self.last_position + size as usize });
-        self.last_position += size as usize;
+        // Keep the variable aligned to its own size
+        let position = (self.last_position + size as usize).next_multiple_of(size as usize);
+        self.variables.push(Variable { name: name.to_owned(), size, position, var_type });
+        self.last_position = position;
         &self.variables[self.variables.len()-1]
     }
 
-    pub fn add_temp_variable(&mut self, size: u8) -> &Variable {
-        self.variables.push(Variable { name: format!(".t{}", self.variables.len()), size, position: self.last_position + size as usize });
-        self.last_position += size as usize;
-        &self.variables[self.variables.len()-1]
+    pub fn add_temp_variable(&mut self, var_type: NumberType) -> &Variable {
+        let name = format!(".t{}", self.variables.len());
+        self.add_variable(&name, var_type)
     }
 
-    pub fn lock_register(&mut self, num_size: RegisterSize) -> Option<R> {
-        for (index, (register, status)) in self.registers.iter().enumerate() {
-            if *status {
-                let register = register.clone();
-                self.registers[index] = (register.clone(), false);
-                return Some(register.get_sized(num_size)); // Get register based on size (64: RAX, 32: EAX, 16: AX, 8bit: AL)
-            }
-        }
-        None
+    /// New virtual register, 128bit size means vector register
+    pub fn new_virtual_register(&mut self, size: RegisterSize) -> VirtualRegister {
+        self.virtual_register_count += 1;
+        VirtualRegister::new(self.virtual_register_count - 1, size)
     }
 
-    pub fn release_register(&mut self, register: R) {
-        if let Some(position) = self.registers.iter().position(|item| item.0 == register) {
-            self.registers[position] = (register, true);
-        }
+    pub fn get_registers(&self) -> Vec<R> {
+        self.registers.clone()
     }
 }
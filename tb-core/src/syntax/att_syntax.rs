@@ -2,7 +2,7 @@ use std::{cell::Cell, fmt::Debug, marker::PhantomData};
 
 use crate::{addressing_mode::AddressingMode, instruction::{AbstractInstruction, InstructionTrait, StorageTrait}, location::Location, types::{ApplicationContext, RegisterSize, RegisterTrait}};
 
-use super::{AsmStructure, DataItem, SyntaxGeneratorTrait};
+use super::{generate_data, AsmStructure, SyntaxGeneratorTrait};
 
 #[derive(Debug, Clone)]
 pub struct ATTSyntaxGenerator<I> where I: InstructionTrait {
@@ -21,11 +21,10 @@ impl<I> SyntaxGeneratorTrait<I> for ATTSyntaxGenerator<I> where I: InstructionTr
         let mut buffer = String::new();
 
         for item in context.datas.items.clone().into_iter() {
-            self.process_data(item, &mut buffer, context);
+            generate_data(item, &mut buffer, context);
         }
 
         buffer.push_str("\r\n.text\r\n");
-        buffer.push_str(&format!(".globl {}\r\n", context.os_specific_defs.main_function_name()));
         for item in context.instructions.items.clone().into_iter() {
             self.process_item(item, context, &mut buffer);
         }
@@ -36,16 +35,6 @@ impl<I> SyntaxGeneratorTrait<I> for ATTSyntaxGenerator<I> where I: InstructionTr
 }
 
 impl<I> ATTSyntaxGenerator<I> where I: InstructionTrait {
-    fn process_data<S: StorageTrait>(&self, item: DataItem, buffer: &mut String, context: &mut ApplicationContext<I, S>) {
-        buffer.push_str(&format!("\r\n{}\r\n.{}:\r\n", context.os_specific_defs.readonly_string_section(), &item.label));
-        for data in item.values.clone().into_iter() {
-            match data {
-                super::Data::String(data) => buffer.push_str(&format!("    .string \"{}\"\r\n", &data.replace("\"", "\\\""))),
-                super::Data::Byte(data) => buffer.push_str(&format!("    .byte {}\r\n", &data)),
-            }
-        }
-    }
-
     fn process_item<S: StorageTrait>(&self, item: AsmStructure<I>, context: &mut ApplicationContext<I, S>, buffer: &mut String) {
         match item {
             AsmStructure::Branch(name) => self.generate_branch(name, context, buffer),
@@ -66,6 +55,9 @@ impl<I> ATTSyntaxGenerator<I> where I: InstructionTrait {
             },
             Location::Imm(imm) => buffer.push_str(&format!("${}", imm)),
             Location::Label(label) => buffer.push_str(&format!(".{}(%rip)", label)),
+
+            // Should be replaced by the register allocator
+            Location::Virtual(register) => buffer.push_str(&format!("%v{}", register.index)),
         }
     }
 
@@ -111,6 +103,11 @@ impl<I> ATTSyntaxGenerator<I> where I: InstructionTrait {
     }
 
     fn generate_branch<S: StorageTrait>(&self, name: String, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        // Branch outside of the function is the function itself, it is visible to the other objects
+        if !self.in_branch.get() {
+            buffer.push_str(&format!(".globl {}\r\n", &name));
+        }
+
         self.in_branch.set(true);
         buffer.push_str(&name);
         buffer.push(':');
@@ -135,6 +132,9 @@ impl<I> ATTSyntaxGenerator<I> where I: InstructionTrait {
             RegisterSize::_16Bit => "w",
             RegisterSize::_32Bit => "l",
             RegisterSize::_64Bit => "q",
+
+            // SSE instructions have the size in the name
+            RegisterSize::_128Bit => "",
         }
     }
 
@@ -149,12 +149,17 @@ impl<I> ATTSyntaxGenerator<I> where I: InstructionTrait {
         let target_register_size = target_register.get_register_size();
         let source_register_size = source_register.get_register_size();
 
+        if target_register_size == RegisterSize::_128Bit || source_register_size == RegisterSize::_128Bit {
+            return ""
+        }
+
         match target_register_size != source_register_size {
             true => match std::cmp::min(source_register_size, target_register_size) {
                 RegisterSize::_8Bit => "b",
                 RegisterSize::_16Bit => "w",
                 RegisterSize::_32Bit => "l",
                 RegisterSize::_64Bit => "q",
+                RegisterSize::_128Bit => "",
             },
             false => ""
         }
@@ -0,0 +1,134 @@
+use std::{cell::Cell, fmt::Debug, marker::PhantomData};
+
+use crate::{addressing_mode::AddressingMode, instruction::{AbstractInstruction, InstructionTrait, StorageTrait}, location::Location, types::{ApplicationContext, RegisterSize}};
+
+use super::{generate_data, memory_operand_size, AsmStructure, SyntaxGeneratorTrait};
+
+/// GNU assembler output with the Intel operand order, target is written before the source
+#[derive(Debug, Clone)]
+pub struct IntelSyntaxGenerator<I> where I: InstructionTrait {
+    _marker1: PhantomData<I>,
+    in_branch: Cell<bool>
+}
+
+impl<I> Default for IntelSyntaxGenerator<I> where I: InstructionTrait {
+    fn default() -> Self {
+        Self { _marker1: PhantomData, in_branch: Default::default() }
+    }
+}
+
+impl<I> SyntaxGeneratorTrait<I> for IntelSyntaxGenerator<I> where I: InstructionTrait {
+    fn generate<S: StorageTrait>(&self, context: &mut ApplicationContext<I, S>) -> String {
+        let mut buffer = String::new();
+        buffer.push_str(".intel_syntax noprefix\r\n");
+
+        for item in context.datas.items.clone().into_iter() {
+            generate_data(item, &mut buffer, context);
+        }
+
+        buffer.push_str("\r\n.text\r\n");
+        for item in context.instructions.items.clone().into_iter() {
+            self.process_item(item, context, &mut buffer);
+        }
+
+        buffer.push_str(context.os_specific_defs.end_of_file_instructions());
+        buffer
+    }
+}
+
+impl<I> IntelSyntaxGenerator<I> where I: InstructionTrait {
+    fn process_item<S: StorageTrait>(&self, item: AsmStructure<I>, context: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        match item {
+            AsmStructure::Branch(name) => self.generate_branch(name, context, buffer),
+            AsmStructure::BranchFinished => self.in_branch.set(false),
+            AsmStructure::Comment(comment) => self.generate_comment(comment, context, buffer),
+            AsmStructure::Instruction(inst) => self.generate_instruction(inst.convert(), context, buffer),
+        };
+    }
+
+    fn generate_location(&self, location: Location<I::REG>, size: &str, buffer: &mut String) {
+        buffer.push(' ');
+        match location {
+            Location::Memory(memory) => buffer.push_str(&format!("{}[{:#01x}]", size, memory)),
+            Location::Register(mode) => match mode {
+                AddressingMode::Direct(reg) => buffer.push_str(&reg.to_string().to_lowercase()),
+                AddressingMode::Indirect(reg) => buffer.push_str(&format!("{}[{}]", size, reg.to_string().to_lowercase())),
+                AddressingMode::Based(num, reg) => buffer.push_str(&format!("{}[{}{:+}]", size, reg.to_string().to_lowercase(), num)),
+            },
+            Location::Imm(imm) => buffer.push_str(&imm.to_string()),
+            Location::Label(label) => buffer.push_str(&format!("{}[rip+.{}]", size, label)),
+
+            // Should be replaced by the register allocator
+            Location::Virtual(register) => buffer.push_str(&format!("v{}", register.index)),
+        }
+    }
+
+    fn generate_instruction<S: StorageTrait>(&self, inst: AbstractInstruction<I>, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        let name = inst.inst.to_string().to_lowercase();
+        buffer.push_str(&name);
+
+        if let Some(label) = inst.label.as_ref() {
+            buffer.push_str(&format!(" {}", label));
+        }
+
+        let target_size = self.get_size(&name, inst.target.as_ref(), inst.source1.as_ref());
+        let source_size = self.get_size(&name, inst.source1.as_ref(), inst.target.as_ref());
+
+        if let Some(target) = inst.target {
+            self.generate_location(target, target_size, buffer);
+
+            if inst.source1.is_some() {
+                buffer.push(',');
+            }
+        }
+
+        if let Some(source) = inst.source1 {
+            self.generate_location(source, source_size, buffer);
+        }
+
+        if let Some(comment) = inst.comment {
+            buffer.push_str(" # ");
+            buffer.push_str(&comment);
+        }
+
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_branch<S: StorageTrait>(&self, name: String, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        // Branch outside of the function is the function itself, it is visible to the other objects
+        if !self.in_branch.get() {
+            buffer.push_str(&format!(".globl {}\r\n", &name));
+        }
+
+        self.in_branch.set(true);
+        buffer.push_str(&name);
+        buffer.push(':');
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_comment<S: StorageTrait>(&self, name: String, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        buffer.push_str("# ");
+        buffer.push_str(&name);
+        buffer.push_str("\r\n");
+    }
+
+    /// Size specifier of the memory operand
+    fn get_size(&self, name: &str, location: Option<&Location<I::REG>>, other: Option<&Location<I::REG>>) -> &'static str {
+        match memory_operand_size(name, location, other) {
+            Some(RegisterSize::_8Bit) => "byte ptr ",
+            Some(RegisterSize::_16Bit) => "word ptr ",
+            Some(RegisterSize::_32Bit) => "dword ptr ",
+            Some(RegisterSize::_64Bit) => "qword ptr ",
+            Some(RegisterSize::_128Bit) => "xmmword ptr ",
+            None => ""
+        }
+    }
+}
@@ -1,10 +1,14 @@
 use std::fmt::Debug;
 
 use att_syntax::ATTSyntaxGenerator;
+use intel_syntax::IntelSyntaxGenerator;
+use nasm_syntax::NasmSyntaxGenerator;
 
-use crate::{error::TBError, instruction::{InstructionTrait, StorageTrait}, types::ApplicationContext};
+use crate::{addressing_mode::AddressingMode, error::TBError, instruction::{InstructionTrait, StorageTrait}, location::Location, types::{ApplicationContext, RegisterSize, RegisterTrait}};
 
 mod att_syntax;
+mod intel_syntax;
+mod nasm_syntax;
 
 pub trait SyntaxGeneratorTrait<I: InstructionTrait> where Self: Sized + Default {
     fn generate<S: StorageTrait>(&self, context: &mut ApplicationContext<I, S>) -> String;
@@ -17,6 +21,93 @@ impl TBSyntaxGenerator {
         let compiler = ATTSyntaxGenerator::default();
         Ok(Box::new(compiler))
     }
+
+    /// Assembly of the context with the selected syntax
+    pub fn generate<I: InstructionTrait, S: StorageTrait>(syntax: Syntax, context: &mut ApplicationContext<I, S>) -> String {
+        match syntax {
+            Syntax::ATT => ATTSyntaxGenerator::default().generate(context),
+            Syntax::Intel => IntelSyntaxGenerator::default().generate(context),
+            Syntax::Nasm => NasmSyntaxGenerator::default().generate(context)
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Syntax {
+    #[default]
+    ATT,
+
+    /// GNU assembler syntax with `.intel_syntax noprefix`
+    Intel,
+
+    /// NASM source, it could be assembled with nasm and linked with ld
+    Nasm
+}
+
+/// Data items are written with the GNU assembler directives, they are same for all syntaxes
+fn generate_data<I: InstructionTrait, S: StorageTrait>(item: DataItem, buffer: &mut String, context: &mut ApplicationContext<I, S>) {
+    let section = match item.values.iter().any(|data| matches!(data, Data::String(_))) {
+        true => context.os_specific_defs.readonly_string_section(),
+        false => context.os_specific_defs.readonly_data_section()
+    };
+
+    buffer.push_str(&format!("\r\n{}\r\n.{}:\r\n", section, &item.label));
+    for data in item.values.clone().into_iter() {
+        match data {
+            Data::String(data) => buffer.push_str(&format!("    .string \"{}\"\r\n", escape(&data))),
+            Data::Byte(data) => buffer.push_str(&format!("    .byte {}\r\n", &data)),
+
+            // Bit pattern keeps the exact value
+            Data::Float(data) => buffer.push_str(&format!("    .long {:#010x} # {}\r\n", data.to_bits(), &data)),
+            Data::Double(data) => buffer.push_str(&format!("    .quad {:#018x} # {}\r\n", data.to_bits(), &data)),
+        }
+    }
+}
+
+/// Size of the memory operand for the syntaxes that write it next to the address. Size comes from the other
+/// register operand like the AT&T suffix, otherwise from the register of the address.
+fn memory_operand_size<R: RegisterTrait>(name: &str, location: Option<&Location<R>>, other: Option<&Location<R>>) -> Option<RegisterSize> {
+    let address_register = match location {
+        Some(Location::Register(AddressingMode::Indirect(register))) | Some(Location::Register(AddressingMode::Based(_, register))) => Some(register),
+        Some(Location::Memory(_)) | Some(Location::Label(_)) => None,
+        _ => return None
+    };
+
+    // Only the address is calculated
+    if name == "lea" {
+        return None;
+    }
+
+    let is_shift = matches!(name, "shl" | "shr" | "sar");
+    match other.and_then(|other| other.get_addressing_mode()) {
+        Some(AddressingMode::Direct(register)) if register.get_register_size() == RegisterSize::_128Bit => Some(vector_operand_size(name)),
+        Some(AddressingMode::Direct(register)) if !is_shift => Some(register.get_register_size()),
+        _ => Some(address_register.map(|register| register.get_register_size()).unwrap_or(RegisterSize::_64Bit))
+    }
+}
+
+/// SSE instructions have the size in the name, conversions have the size of the source before the "2"
+fn vector_operand_size(name: &str) -> RegisterSize {
+    let operand = name.split_once('2').map(|(source, _)| source).unwrap_or(name);
+    match operand {
+        operand if operand.ends_with("ss") => RegisterSize::_32Bit,
+        operand if operand.ends_with("sd") || operand.ends_with("si") => RegisterSize::_64Bit,
+        _ => RegisterSize::_128Bit
+    }
+}
+
+/// Assembler strings could not contain a line break, control characters are written as octal escapes
+pub fn escape(data: &str) -> String {
+    let mut buffer = String::new();
+    for char in data.chars() {
+        match char {
+            '\\' => buffer.push_str("\\\\"),
+            '"' => buffer.push_str("\\\""),
+            char if char.is_ascii_control() => buffer.push_str(&format!("\\{:03o}", char as u32)),
+            char => buffer.push(char)
+        }
+    }
+    buffer
 }
 
 #[derive(Debug, Default, Clone)]
@@ -28,7 +119,9 @@ pub struct DataItem {
 #[derive(Debug, Clone)]
 pub enum Data {
     String(String),
-    Byte(u8)
+    Byte(u8),
+    Float(f32),
+    Double(f64)
 }
 
 #[derive(Debug, Clone)]
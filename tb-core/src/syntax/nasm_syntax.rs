@@ -0,0 +1,218 @@
+use std::{cell::Cell, fmt::Debug, marker::PhantomData};
+
+use crate::{addressing_mode::AddressingMode, instruction::{AbstractInstruction, InstructionTrait, StorageTrait}, location::Location, types::{ApplicationContext, RegisterSize, RegisterTrait}};
+
+use super::{memory_operand_size, AsmStructure, Data, DataItem, SyntaxGeneratorTrait};
+
+/// Labels that start with a dot are local to the previous label in NASM, data labels use the "..@" prefix instead
+const DATA_LABEL_PREFIX: &str = "..@";
+
+/// NASM source with the Intel operand order. Addresses are RIP relative with `default rel`.
+#[derive(Debug, Clone)]
+pub struct NasmSyntaxGenerator<I> where I: InstructionTrait {
+    _marker1: PhantomData<I>,
+    in_branch: Cell<bool>
+}
+
+impl<I> Default for NasmSyntaxGenerator<I> where I: InstructionTrait {
+    fn default() -> Self {
+        Self { _marker1: PhantomData, in_branch: Default::default() }
+    }
+}
+
+impl<I> SyntaxGeneratorTrait<I> for NasmSyntaxGenerator<I> where I: InstructionTrait {
+    fn generate<S: StorageTrait>(&self, context: &mut ApplicationContext<I, S>) -> String {
+        let mut buffer = String::new();
+        buffer.push_str("default rel\r\n");
+
+        // Every called function that is not in the source is in the other objects
+        let (functions, calls) = Self::get_functions(context);
+        for name in functions.iter() {
+            buffer.push_str(&format!("global {}\r\n", name));
+        }
+
+        for name in calls.iter().filter(|name| !functions.contains(name)) {
+            buffer.push_str(&format!("extern {}\r\n", name));
+        }
+
+        if !context.datas.items.is_empty() {
+            buffer.push_str("\r\nsection .rodata\r\n");
+        }
+
+        for item in context.datas.items.clone().into_iter() {
+            self.generate_data(item, &mut buffer);
+        }
+
+        buffer.push_str("\r\nsection .text\r\n");
+        for item in context.instructions.items.clone().into_iter() {
+            self.process_item(item, context, &mut buffer);
+        }
+
+        buffer
+    }
+}
+
+impl<I> NasmSyntaxGenerator<I> where I: InstructionTrait {
+    /// Defined functions and the called functions in the order they are seen
+    fn get_functions<S: StorageTrait>(context: &ApplicationContext<I, S>) -> (Vec<String>, Vec<String>) {
+        let mut functions = Vec::new();
+        let mut calls = Vec::new();
+        let mut in_branch = false;
+
+        for item in context.instructions.items.iter() {
+            match item {
+                AsmStructure::Branch(name) if !in_branch => {
+                    functions.push(name.clone());
+                    in_branch = true;
+                },
+                AsmStructure::BranchFinished => in_branch = false,
+                AsmStructure::Instruction(inst) => {
+                    let inst = inst.clone().convert();
+                    if let (true, Some(label)) = (inst.inst.to_string().to_lowercase() == "call", inst.label) {
+                        if !calls.contains(&label) {
+                            calls.push(label);
+                        }
+                    }
+                },
+                _ => ()
+            }
+        }
+
+        (functions, calls)
+    }
+
+    fn generate_data(&self, item: DataItem, buffer: &mut String) {
+        buffer.push_str(&format!("{}{}:\r\n", DATA_LABEL_PREFIX, &item.label));
+        for data in item.values.clone().into_iter() {
+            match data {
+                Data::String(data) => buffer.push_str(&format!("    db {}\r\n", Self::escape(&data))),
+                Data::Byte(data) => buffer.push_str(&format!("    db {}\r\n", &data)),
+
+                // Bit pattern keeps the exact value
+                Data::Float(data) => buffer.push_str(&format!("    dd {:#010x} ; {}\r\n", data.to_bits(), &data)),
+                Data::Double(data) => buffer.push_str(&format!("    dq {:#018x} ; {}\r\n", data.to_bits(), &data)),
+            }
+        }
+    }
+
+    /// Quoted strings do not have escapes, other bytes are written as numbers and the string is terminated with zero
+    fn escape(data: &str) -> String {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+
+        for byte in data.bytes() {
+            match byte {
+                b' '..=b'~' if byte != b'"' => text.push(byte as char),
+                _ => {
+                    if !text.is_empty() {
+                        parts.push(format!("\"{}\"", std::mem::take(&mut text)));
+                    }
+                    parts.push(byte.to_string());
+                }
+            }
+        }
+
+        if !text.is_empty() {
+            parts.push(format!("\"{}\"", text));
+        }
+
+        parts.push("0".to_owned());
+        parts.join(", ")
+    }
+
+    fn process_item<S: StorageTrait>(&self, item: AsmStructure<I>, context: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        match item {
+            AsmStructure::Branch(name) => self.generate_branch(name, context, buffer),
+            AsmStructure::BranchFinished => self.in_branch.set(false),
+            AsmStructure::Comment(comment) => self.generate_comment(comment, context, buffer),
+            AsmStructure::Instruction(inst) => self.generate_instruction(inst.convert(), context, buffer),
+        };
+    }
+
+    fn generate_location(&self, location: Location<I::REG>, size: &str, buffer: &mut String) {
+        buffer.push(' ');
+        match location {
+            Location::Memory(memory) => buffer.push_str(&format!("{}[abs {:#01x}]", size, memory)),
+            Location::Register(mode) => match mode {
+                AddressingMode::Direct(reg) => buffer.push_str(&reg.to_string().to_lowercase()),
+                AddressingMode::Indirect(reg) => buffer.push_str(&format!("{}[{}]", size, reg.to_string().to_lowercase())),
+                AddressingMode::Based(num, reg) => buffer.push_str(&format!("{}[{}{:+}]", size, reg.to_string().to_lowercase(), num)),
+            },
+            Location::Imm(imm) => buffer.push_str(&imm.to_string()),
+            Location::Label(label) => buffer.push_str(&format!("{}[{}{}]", size, DATA_LABEL_PREFIX, label)),
+
+            // Should be replaced by the register allocator
+            Location::Virtual(register) => buffer.push_str(&format!("v{}", register.index)),
+        }
+    }
+
+    fn generate_instruction<S: StorageTrait>(&self, inst: AbstractInstruction<I>, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        let name = inst.inst.to_string().to_lowercase();
+
+        // Sign extension from 32bit has its own name
+        let is_movsxd = name == "movsx" && matches!(inst.source1.as_ref().and_then(|source| source.get_addressing_mode()), Some(mode) if mode.get_register().get_register_size() == RegisterSize::_32Bit);
+        buffer.push_str(match is_movsxd {
+            true => "movsxd",
+            false => &name
+        });
+
+        if let Some(label) = inst.label.as_ref() {
+            buffer.push_str(&format!(" {}", label));
+        }
+
+        let target_size = self.get_size(&name, inst.target.as_ref(), inst.source1.as_ref());
+        let source_size = self.get_size(&name, inst.source1.as_ref(), inst.target.as_ref());
+
+        if let Some(target) = inst.target {
+            self.generate_location(target, target_size, buffer);
+
+            if inst.source1.is_some() {
+                buffer.push(',');
+            }
+        }
+
+        if let Some(source) = inst.source1 {
+            self.generate_location(source, source_size, buffer);
+        }
+
+        if let Some(comment) = inst.comment {
+            buffer.push_str(" ; ");
+            buffer.push_str(&comment);
+        }
+
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_branch<S: StorageTrait>(&self, name: String, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        self.in_branch.set(true);
+        buffer.push_str(&name);
+        buffer.push(':');
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_comment<S: StorageTrait>(&self, name: String, _: &mut ApplicationContext<I, S>, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        buffer.push_str("; ");
+        buffer.push_str(&name);
+        buffer.push_str("\r\n");
+    }
+
+    /// Size specifier of the memory operand
+    fn get_size(&self, name: &str, location: Option<&Location<I::REG>>, other: Option<&Location<I::REG>>) -> &'static str {
+        match memory_operand_size(name, location, other) {
+            Some(RegisterSize::_8Bit) => "byte ",
+            Some(RegisterSize::_16Bit) => "word ",
+            Some(RegisterSize::_32Bit) => "dword ",
+            Some(RegisterSize::_64Bit) => "qword ",
+            Some(RegisterSize::_128Bit) => "oword ",
+            None => ""
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Description of the platform that the code is generated for. It is given to the builder, so the output does not
+//! depend on the system that runs the generator.
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{error::TBError, types::CallingConventions, tool::{LinuxSpecificDefs, MacSpecificDefs, OsSpecificDefs, WindowsSpecificDefs}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Architecture {
+    X86_64,
+    Aarch64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatingSystem {
+    Linux,
+    MacOS,
+    Windows
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectFormat {
+    Elf,
+    MachO,
+    Coff
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    pub architecture: Architecture,
+    pub operating_system: OperatingSystem,
+    pub object_format: ObjectFormat,
+    pub calling_convention: CallingConventions
+}
+
+impl Target {
+    pub const X86_64_LINUX: Target = Target::new(Architecture::X86_64, OperatingSystem::Linux);
+    pub const X86_64_MACOS: Target = Target::new(Architecture::X86_64, OperatingSystem::MacOS);
+    pub const X86_64_WINDOWS: Target = Target::new(Architecture::X86_64, OperatingSystem::Windows);
+    pub const AARCH64_LINUX: Target = Target::new(Architecture::Aarch64, OperatingSystem::Linux);
+    pub const AARCH64_MACOS: Target = Target::new(Architecture::Aarch64, OperatingSystem::MacOS);
+
+    /// Object format and the calling convention are the usual ones of the operating system, AArch64 uses AAPCS64 on all of them
+    pub const fn new(architecture: Architecture, operating_system: OperatingSystem) -> Self {
+        let object_format = match operating_system {
+            OperatingSystem::Linux => ObjectFormat::Elf,
+            OperatingSystem::MacOS => ObjectFormat::MachO,
+            OperatingSystem::Windows => ObjectFormat::Coff
+        };
+
+        let calling_convention = match (architecture, operating_system) {
+            (Architecture::Aarch64, _) => CallingConventions::Arm,
+            (_, OperatingSystem::Windows) => CallingConventions::Microsoft,
+            _ => CallingConventions::Systemv
+        };
+
+        Self { architecture, operating_system, object_format, calling_convention }
+    }
+
+    /// Target of the running system, unknown systems use Linux
+    pub const fn host() -> Self {
+        let operating_system = match (cfg!(target_os = "macos"), cfg!(target_os = "windows")) {
+            (true, _) => OperatingSystem::MacOS,
+            (_, true) => OperatingSystem::Windows,
+            _ => OperatingSystem::Linux
+        };
+
+        let architecture = match cfg!(target_arch = "aarch64") {
+            true => Architecture::Aarch64,
+            false => Architecture::X86_64
+        };
+
+        Self::new(architecture, operating_system)
+    }
+
+    pub fn os_specific_defs(&self) -> Box<dyn OsSpecificDefs> {
+        match self.operating_system {
+            OperatingSystem::Linux => Box::new(LinuxSpecificDefs),
+            OperatingSystem::MacOS => Box::new(MacSpecificDefs),
+            OperatingSystem::Windows => Box::new(WindowsSpecificDefs)
+        }
+    }
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self::host()
+    }
+}
+
+/// Triples like x86_64-unknown-linux-gnu, x86_64-apple-darwin, x86_64-pc-windows-msvc and aarch64-unknown-linux-gnu
+impl FromStr for Target {
+    type Err = TBError;
+
+    fn from_str(triple: &str) -> Result<Self, Self::Err> {
+        let unknown = || TBError::UnknownTarget(triple.to_owned());
+        let mut parts = triple.split('-');
+
+        let architecture = match parts.next() {
+            Some("x86_64") | Some("amd64") => Architecture::X86_64,
+            Some("aarch64") | Some("arm64") => Architecture::Aarch64,
+            _ => return Err(unknown())
+        };
+
+        // Vendor is optional
+        let operating_system = parts.find_map(|part| match part {
+            "linux" => Some(OperatingSystem::Linux),
+            "darwin" | "macos" => Some(OperatingSystem::MacOS),
+            "windows" => Some(OperatingSystem::Windows),
+            _ => None
+        }).ok_or_else(unknown)?;
+
+        Ok(Self::new(architecture, operating_system))
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let architecture = match self.architecture {
+            Architecture::X86_64 => "x86_64",
+            Architecture::Aarch64 => "aarch64"
+        };
+
+        match self.operating_system {
+            OperatingSystem::Linux => write!(f, "{}-unknown-linux-gnu", architecture),
+            OperatingSystem::MacOS => write!(f, "{}-apple-darwin", architecture),
+            OperatingSystem::Windows => write!(f, "{}-pc-windows-msvc", architecture)
+        }
+    }
+}
@@ -3,6 +3,7 @@ pub trait OsSpecificDefs {
     fn end_of_file_instructions(&self) -> &'static str;
     fn print(&self) -> &'static str;
     fn readonly_string_section(&self) -> &'static str;
+    fn readonly_data_section(&self) -> &'static str;
 }
 
 #[derive(Debug, Clone, Default)]
@@ -31,6 +32,10 @@ impl OsSpecificDefs for MacSpecificDefs {
     fn readonly_string_section(&self) -> &'static str {
         ".section    __TEXT,__cstring,cstring_literals"
     }
+
+    fn readonly_data_section(&self) -> &'static str {
+        ".section    __TEXT,__const"
+    }
 }
 
 impl OsSpecificDefs for LinuxSpecificDefs {
@@ -49,6 +54,10 @@ impl OsSpecificDefs for LinuxSpecificDefs {
     fn readonly_string_section(&self) -> &'static str {
         ".text\r\n.section	.rodata"
     }
+
+    fn readonly_data_section(&self) -> &'static str {
+        ".text\r\n.section	.rodata"
+    }
 }
 
 impl OsSpecificDefs for WindowsSpecificDefs {
@@ -61,19 +70,14 @@ impl OsSpecificDefs for WindowsSpecificDefs {
     }
     
     fn print(&self) -> &'static str {
-        "_printf"
+        "printf"
     }
     
     fn readonly_string_section(&self) -> &'static str {
-        ".section    __TEXT,__cstring,cstring_literals"
+        ".section    .rdata,\"dr\""
     }
-}
 
-pub fn os_defs() -> Box<dyn OsSpecificDefs> {
-    match os_version::detect().unwrap() {
-        os_version::OsVersion::Linux(_) => Box::new(LinuxSpecificDefs),
-        os_version::OsVersion::MacOS(_) => Box::new(MacSpecificDefs),
-        os_version::OsVersion::Windows(_) => Box::new(WindowsSpecificDefs),
-        os => panic!("Unsupported OS ({:?})", os)
+    fn readonly_data_section(&self) -> &'static str {
+        ".section    .rdata,\"dr\""
     }
 }
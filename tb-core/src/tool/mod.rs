@@ -1,4 +1,4 @@
 mod defs;
 
 pub use defs::OsSpecificDefs;
-pub use defs::os_defs;
+pub use defs::{LinuxSpecificDefs, MacSpecificDefs, WindowsSpecificDefs};
@@ -1,11 +1,11 @@
 
-use std::fmt::{Debug, Display};
+use std::{collections::HashMap, fmt::{Debug, Display}};
 
 use strum_macros::EnumDiscriminants;
 
-use crate::{instruction::{InstructionTrait, StorageTrait}, syntax::{AsmStructure, Data, DataItem}, tool::{os_defs, OsSpecificDefs}};
+use crate::{error::TBError, instruction::{InstructionTrait, StorageTrait}, syntax::{AsmStructure, Data, DataItem, Syntax}, target::Target, tool::OsSpecificDefs};
 
-#[derive(Debug, Clone, EnumDiscriminants)]
+#[derive(Debug, Clone, PartialEq, EnumDiscriminants)]
 #[strum_discriminants(name(ValueType))]
 pub enum Value {
     Variable(String),
@@ -27,94 +27,112 @@ impl From<bool> for Value { fn from(value: bool) -> Self { Value::Number(value.i
 impl From<String> for Value { fn from(value: String) -> Self { Value::String(value) } }
 impl From<&str> for Value { fn from(value: &str) -> Self { Value::String(value.to_owned()) } }
 
-#[derive(Debug, Clone, EnumDiscriminants)]
+#[derive(Debug, Clone, PartialEq, EnumDiscriminants)]
 #[strum_discriminants(name(ExpressionDiscriminant))]
 pub enum Expression {
     Add {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     Sub {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     Div {
-        divider: Value,
-        divided: Value
+        divider: Box<Expression>,
+        divided: Box<Expression>
     },
     Mul {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     Modulo {
-        divider: Value,
-        divided: Value
+        divider: Box<Expression>,
+        divided: Box<Expression>
     },
     ShiftLeft {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     ShiftRight {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     BitwiseNot {
-        source: Value
+        source: Box<Expression>
     },
     BitwiseAnd {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     BitwiseOr {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     BitwiseXor {
-        target: Value,
-        source: Value
+        target: Box<Expression>,
+        source: Box<Expression>
     },
     BitwiseNeg {
-        source: Value
+        source: Box<Expression>
     },
     Inc {
-        source: Value
+        source: Box<Expression>
     },
     Dec {
-        source: Value
+        source: Box<Expression>
     },
+
+    // Comparison result as a boolean value
+    Condition(Box<Condition>),
     Value(Value)
 }
 
-#[derive(Debug, Clone, EnumDiscriminants)]
+impl From<Value> for Expression { fn from(value: Value) -> Self { Expression::Value(value) } }
+
+#[derive(Debug, Clone, PartialEq, EnumDiscriminants)]
 #[strum_discriminants(name(ConditionDiscriminant))]
 pub enum Condition {
     Eq {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
     },
     Ne {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
     },
     Gr {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
     },
     Ge {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
     },
     Ls {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
     },
     Le {
-        left: Value,
-        right: Value
+        left: Expression,
+        right: Expression
+    },
+
+    // Right side is evaluated only if the left side does not decide the result
+    And {
+        left: Box<Condition>,
+        right: Box<Condition>
+    },
+    Or {
+        left: Box<Condition>,
+        right: Box<Condition>
+    },
+    Not {
+        source: Box<Condition>
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Statement {
     Assign {
         name: String,
@@ -125,6 +143,20 @@ pub enum Statement {
         true_block: Block,
         false_block: Option<Block>
     },
+    While {
+        condition: Condition,
+        block: Block
+    },
+    Loop {
+        block: Block
+    },
+    Declare {
+        name: String,
+        ty: NumberType,
+        init: Option<Expression>
+    },
+    Break,
+    Continue,
     Print {
         format: String,
         arguments: Vec<Value>
@@ -139,13 +171,13 @@ pub enum Statement {
 }
 
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Parameter {
     pub name: String,
     pub param_type: NumberType
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Definition {
     Function {
         name: String,
@@ -154,7 +186,16 @@ pub enum Definition {
     },
 }
 
-#[derive(Debug, Clone)]
+impl Definition {
+    /// Parameter types of the functions by their names
+    pub fn parameter_types(definitions: &[Definition]) -> HashMap<String, Vec<NumberType>> {
+        definitions.iter().map(|definition| match definition {
+            Definition::Function { name, parameters, .. } => (name.clone(), parameters.iter().map(|parameter| parameter.param_type).collect())
+        }).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Block {
     pub items: Vec<Statement>
 }
@@ -186,9 +227,54 @@ impl DataItemCollection {
         };
     }
 
+    pub fn add_float_data<L: AsRef<str>>(&mut self, label: L, data: f32) {
+        match self.items.iter_mut().find(|item| item.label == label.as_ref()) {
+            Some(item) => item.values.push(Data::Float(data)),
+            None => {
+                let data = DataItem { label: label.as_ref().to_owned(), values: vec![Data::Float(data)] };
+                self.items.push(data);
+            },
+        };
+    }
+
+    pub fn add_double_data<L: AsRef<str>>(&mut self, label: L, data: f64) {
+        match self.items.iter_mut().find(|item| item.label == label.as_ref()) {
+            Some(item) => item.values.push(Data::Double(data)),
+            None => {
+                let data = DataItem { label: label.as_ref().to_owned(), values: vec![Data::Double(data)] };
+                self.items.push(data);
+            },
+        };
+    }
+
     pub fn create_label(&mut self) -> String {
         format!("LC{}", self.items.len() + 1)
     }
+
+    /// Bytes of all items and the offsets of the labels, every item starts 8 byte aligned
+    pub fn to_bytes(&self) -> (Vec<u8>, HashMap<String, usize>) {
+        let mut bytes = Vec::new();
+        let mut labels = HashMap::new();
+
+        for item in self.items.iter() {
+            bytes.resize(bytes.len().next_multiple_of(8), 0);
+            labels.insert(item.label.clone(), bytes.len());
+
+            for data in item.values.iter() {
+                match data {
+                    Data::String(data) => {
+                        bytes.extend_from_slice(data.as_bytes());
+                        bytes.push(0);
+                    },
+                    Data::Byte(data) => bytes.push(*data),
+                    Data::Float(data) => bytes.extend_from_slice(&data.to_le_bytes()),
+                    Data::Double(data) => bytes.extend_from_slice(&data.to_le_bytes())
+                }
+            }
+        }
+
+        (bytes, labels)
+    }
 }
 
 #[derive(Debug)]
@@ -218,6 +304,10 @@ impl<I> InstructionCollection<I> where I: InstructionTrait {
         self.items[position] = AsmStructure::Instruction(Box::new(instruction));
     }
 
+    pub fn insert_instruction(&mut self, instruction: I, position: usize) {
+        self.items.insert(position, AsmStructure::Instruction(Box::new(instruction)));
+    }
+
     pub fn remove_instruction(&mut self, position: usize) {
         self.items.remove(position);
     }
@@ -236,24 +326,32 @@ impl<I> InstructionCollection<I> where I: InstructionTrait {
 }
 
 pub struct ApplicationContext<I: InstructionTrait, S: StorageTrait> {
+    pub target: Target,
     pub os_specific_defs: Box<dyn OsSpecificDefs>,
     pub instructions: InstructionCollection<I>,
     pub datas: DataItemCollection,
     pub storage: S
 }
 
-impl<I, S> Default for ApplicationContext<I, S> where I: InstructionTrait, S: StorageTrait {
-    fn default() -> Self {
+impl<I, S> ApplicationContext<I, S> where I: InstructionTrait, S: StorageTrait {
+    pub fn new(target: Target, datas: DataItemCollection) -> Self {
         Self {
-            os_specific_defs: os_defs(),
+            target,
+            os_specific_defs: target.os_specific_defs(),
             storage: Default::default(),
-            datas: Default::default(),
+            datas,
             instructions: Default::default()
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, EnumDiscriminants)]
+impl<I, S> Default for ApplicationContext<I, S> where I: InstructionTrait, S: StorageTrait {
+    fn default() -> Self {
+        Self::new(Target::default(), Default::default())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, EnumDiscriminants)]
 #[strum_discriminants(name(NumberType))]
 pub enum Number {
     I8(i8),
@@ -282,6 +380,49 @@ impl From<f64> for Number { fn from(value: f64) -> Self { Number::Double(value)
 impl From<bool> for Number { fn from(value: bool) -> Self { Number::Bool(value) } }
 
 impl Number {
+    pub fn number_type(&self) -> NumberType {
+        self.into()
+    }
+
+    /// Convert the number to the other type with the C cast semantics
+    pub fn convert(&self, number_type: NumberType) -> Number {
+        let integer = match *self {
+            Number::I8(num) => num as i128,
+            Number::U8(num) => num as i128,
+            Number::I16(num) => num as i128,
+            Number::U16(num) => num as i128,
+            Number::I32(num) => num as i128,
+            Number::U32(num) => num as i128,
+            Number::I64(num) => num as i128,
+            Number::U64(num) => num as i128,
+            Number::Float(num) => num as i128,
+            Number::Double(num) => num as i128,
+            Number::Bool(num) => num as i128,
+        };
+
+        let float = match *self {
+            Number::Float(num) => num as f64,
+            Number::Double(num) => num,
+            _ => integer as f64
+        };
+
+        match number_type {
+            NumberType::I8 => Number::I8(integer as i8),
+            NumberType::U8 => Number::U8(integer as u8),
+            NumberType::I16 => Number::I16(integer as i16),
+            NumberType::U16 => Number::U16(integer as u16),
+            NumberType::I32 => Number::I32(integer as i32),
+            NumberType::U32 => Number::U32(integer as u32),
+            NumberType::I64 => Number::I64(integer as i64),
+            NumberType::U64 => Number::U64(integer as u64),
+            // Integers are rounded once, the rounding through double could give another value
+            NumberType::Float if !self.number_type().is_float() => Number::Float(integer as f32),
+            NumberType::Float => Number::Float(float as f32),
+            NumberType::Double => Number::Double(float),
+            NumberType::Bool => Number::Bool(integer != 0 || float != 0.0),
+        }
+    }
+
     pub fn size(&self) -> RegisterSize {
         match self {
             Number::I8(_) => RegisterSize::_8Bit,
@@ -300,6 +441,62 @@ impl Number {
 }
 
 impl NumberType {
+    pub fn is_signed(&self) -> bool {
+        matches!(self, NumberType::I8 | NumberType::I16 | NumberType::I32 | NumberType::I64 | NumberType::Float | NumberType::Double)
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, NumberType::Float | NumberType::Double)
+    }
+
+    /// Integer promotion, types smaller than 32bit are calculated as I32
+    pub fn promote(&self) -> NumberType {
+        match self {
+            NumberType::I8 | NumberType::U8 | NumberType::I16 | NumberType::U16 | NumberType::Bool => NumberType::I32,
+            other => *other
+        }
+    }
+
+    /// Common type of the two operand, based on C's usual arithmetic conversions
+    pub fn common(&self, other: NumberType) -> NumberType {
+        let (left, right) = (self.promote(), other.promote());
+
+        match (left, right) {
+            (NumberType::Double, _) | (_, NumberType::Double) => NumberType::Double,
+            (NumberType::Float, _) | (_, NumberType::Float) => NumberType::Float,
+            (left, right) if left.size() != right.size() => match left.size() > right.size() {
+                true => left,
+                false => right
+            },
+            (left, _) if !left.is_signed() => left,
+            (_, right) => right
+        }
+    }
+
+    /// Type of the expression's result, the variable types are resolved by the backend's lookup
+    pub fn of_expression<E, F: Fn(&str) -> Result<NumberType, E>>(expression: &Expression, find_variable: &F) -> Result<NumberType, E> {
+        match expression {
+            Expression::Value(Value::Variable(variable)) => find_variable(variable),
+            Expression::Value(Value::Number(num)) => Ok(num.number_type()),
+            Expression::Value(Value::String(_)) => Ok(NumberType::U64),
+            Expression::Condition(_) => Ok(NumberType::Bool),
+            Expression::ShiftLeft { target, .. } |
+            Expression::ShiftRight { target, .. } => Ok(Self::of_expression(target, find_variable)?.promote()),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Ok(Self::of_expression(source, find_variable)?.promote()),
+            Expression::Div { divider: left, divided: right } |
+            Expression::Modulo { divider: left, divided: right } |
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } => Ok(Self::of_expression(left, find_variable)?.common(Self::of_expression(right, find_variable)?)),
+        }
+    }
+
     pub fn size(&self) -> RegisterSize {
         match self {
             NumberType::I8 => RegisterSize::_8Bit,
@@ -338,7 +535,7 @@ impl Display for Number {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CallingConventions {
     Systemv,
     Microsoft,
@@ -348,14 +545,16 @@ pub enum CallingConventions {
 #[derive(Debug, Clone)]
 pub struct ProcedureCall<R: RegisterTrait> {
     pub convention: CallingConventions,
-    pub registers: Vec<R>
+    pub registers: Vec<R>,
+    pub float_registers: Vec<R>
 }
 
 impl<R> ProcedureCall<R> where R: RegisterTrait {
-    pub fn new(convention: CallingConventions, registers: Vec<R>) -> Self {
+    pub fn new(convention: CallingConventions, registers: Vec<R>, float_registers: Vec<R>) -> Self {
         Self {
             convention,
-            registers
+            registers,
+            float_registers
         }
     }
 
@@ -363,10 +562,32 @@ impl<R> ProcedureCall<R> where R: RegisterTrait {
         let reg = self.registers.get(index).cloned();
         reg
     }
+
+    pub fn get_float_register(&self, index: usize) -> Option<R> {
+        self.float_registers.get(index).cloned()
+    }
 }
 
 pub trait AssemblyGenerator: Default {
-    fn generate(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> String;
+    /// Generator for the given target, default one is for the running system
+    fn new(target: Target) -> Self;
+
+    /// Invalid program gives an error with the function and the statement of it
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, syntax: Syntax) -> Result<String, TBError>;
+
+    fn generate(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<String, TBError> {
+        self.generate_with_syntax(definitions, datas, Syntax::default())
+    }
+}
+
+/// Generators that write the object file without an assembler
+pub trait ObjectGenerator: AssemblyGenerator {
+    fn generate_object(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Vec<u8>, TBError>;
+}
+
+/// Generators that write the static executable without a C library
+pub trait ExecutableGenerator: AssemblyGenerator {
+    fn generate_executable(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Vec<u8>, TBError>;
 }
 
 #[derive(Ord, Eq, PartialOrd, Debug, Copy, Clone, PartialEq)]
@@ -374,7 +595,8 @@ pub enum RegisterSize {
     _8Bit = 1,
     _16Bit = 2,
     _32Bit = 4,
-    _64Bit = 8
+    _64Bit = 8,
+    _128Bit = 16
 }
 
 impl From<u8> for RegisterSize {
@@ -383,6 +605,7 @@ impl From<u8> for RegisterSize {
             1 => RegisterSize::_8Bit,
             2 => RegisterSize::_16Bit,
             4 => RegisterSize::_32Bit,
+            16 => RegisterSize::_128Bit,
             _ => RegisterSize::_64Bit
         }
     }
@@ -0,0 +1,16 @@
+use tb_core::types::Block;
+
+use crate::{AArch64ApplicationContext, AArch64Store};
+
+use super::{error::AArch64Error, AArch64StatementCompiler};
+
+pub struct AArch64BlockCompiler;
+
+impl AArch64BlockCompiler {
+    pub fn compile(block: Block, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        for item in block.items.into_iter() {
+            AArch64StatementCompiler::compile(item, scope, context)?;
+        }
+        Ok(())
+    }
+}
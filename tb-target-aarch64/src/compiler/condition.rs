@@ -0,0 +1,127 @@
+use tb_core::types::{Condition, ConditionDiscriminant, Number, NumberType, RegisterSize, RegisterTrait};
+
+use crate::{instruction::{AArch64Condition, AArch64Instruction}, register::Register, AArch64ApplicationContext, AArch64Location, AArch64Store};
+
+use super::{error::AArch64Error, AArch64ExpressionCompiler};
+
+pub struct AArch64ConditionCompiler;
+
+impl AArch64ConditionCompiler {
+    /// Jump to the label if the condition result is same with the expected one.
+    /// Logical conditions are short-circuit, the right side is not evaluated if the left side decides the result.
+    pub fn compile_jump(condition: Condition, label: &str, jump_when: bool, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        match condition {
+            Condition::Not { source } => Self::compile_jump(*source, label, !jump_when, depth, scope, context),
+
+            // Any side could finish the condition
+            Condition::And { left, right } if !jump_when => {
+                Self::compile_jump(*left, label, false, depth, scope, context)?;
+                Self::compile_jump(*right, label, false, depth, scope, context)
+            },
+            Condition::Or { left, right } if jump_when => {
+                Self::compile_jump(*left, label, true, depth, scope, context)?;
+                Self::compile_jump(*right, label, true, depth, scope, context)
+            },
+
+            // Left side could skip the right side, but only the right side decides the jump
+            Condition::And { left, right } => {
+                let skip = context.storage.create_branch();
+                Self::compile_jump(*left, &skip, false, depth, scope, context)?;
+                Self::compile_jump(*right, label, true, depth, scope, context)?;
+                context.instructions.add_branch(skip);
+                Ok(())
+            },
+            Condition::Or { left, right } => {
+                let skip = context.storage.create_branch();
+                Self::compile_jump(*left, &skip, true, depth, scope, context)?;
+                Self::compile_jump(*right, label, false, depth, scope, context)?;
+                context.instructions.add_branch(skip);
+                Ok(())
+            },
+            condition => {
+                let condition_code = Self::compile_comparison(condition, depth, scope, context)?;
+                let condition_code = match jump_when {
+                    true => condition_code,
+                    false => condition_code.invert()
+                };
+
+                context.instructions.add_instruction(AArch64Instruction::BCond(condition_code, label.to_owned()));
+                Ok(())
+            }
+        }
+    }
+
+    /// Boolean value of the condition in the 32bit temporary register of the depth
+    pub fn compile_value(condition: Condition, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let result = AArch64ExpressionCompiler::temporary(scope, NumberType::Bool, depth)?.get_sized(RegisterSize::_32Bit);
+        let location = AArch64ExpressionCompiler::location(result);
+
+        match condition {
+            Condition::Not { source } => {
+                Self::compile_value(*source, depth, scope, context)?;
+                context.instructions.add_instruction(AArch64Instruction::Eor { target: location.clone(), left: location, right: AArch64Location::Imm(Number::U8(1)), comment: None });
+            },
+
+            // Comparisons use the same temporary register, so the result is written after the jumps
+            condition @ (Condition::And { .. } | Condition::Or { .. }) => {
+                let false_branch = context.storage.create_branch();
+                let end = context.storage.create_branch();
+
+                Self::compile_jump(condition, &false_branch, false, depth, scope, context)?;
+                context.instructions.add_instruction(AArch64Instruction::Mov { source: AArch64Location::Imm(Number::U8(1)), target: location.clone(), comment: None });
+                context.instructions.add_instruction(AArch64Instruction::B(end.clone()));
+                context.instructions.add_branch(false_branch);
+                context.instructions.add_instruction(AArch64Instruction::Mov { source: AArch64Location::Imm(Number::U8(0)), target: location, comment: None });
+                context.instructions.add_branch(end);
+            },
+            condition => {
+                let condition_code = Self::compile_comparison(condition, depth, scope, context)?;
+                context.instructions.add_instruction(AArch64Instruction::Cset { condition: condition_code, target: location });
+            }
+        };
+
+        Ok(result)
+    }
+
+    /// Compare both sides and return the condition code that is true when the condition is true.
+    /// Unordered floating point comparison (NaN) is false for all of them except not equal.
+    fn compile_comparison(condition: Condition, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<AArch64Condition, AArch64Error> {
+        let condition_type = ConditionDiscriminant::from(&condition);
+        let (left, right) = match condition {
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => (left, right),
+            _ => return Err(AArch64Error::UnexpectedInstruction)
+        };
+
+        // Both sides compared with the common type
+        let number_type = AArch64ExpressionCompiler::get_type(&left, scope)?.common(AArch64ExpressionCompiler::get_type(&right, scope)?);
+        let (left, right) = AArch64ExpressionCompiler::compile_pair(left, right, number_type, depth, true, scope, context)?;
+
+        context.instructions.add_instruction(match number_type.is_float() {
+            true => AArch64Instruction::Fcmp { left: AArch64ExpressionCompiler::location(left), right, comment: None },
+            false => AArch64Instruction::Cmp { left: AArch64ExpressionCompiler::location(left), right, comment: None }
+        });
+
+        Ok(match (condition_type, number_type.is_float(), number_type.is_signed()) {
+            (ConditionDiscriminant::Eq, _, _) => AArch64Condition::Eq,
+            (ConditionDiscriminant::Ne, _, _) => AArch64Condition::Ne,
+            (ConditionDiscriminant::Gr, true, _) => AArch64Condition::Gt,
+            (ConditionDiscriminant::Ge, true, _) => AArch64Condition::Ge,
+            (ConditionDiscriminant::Ls, true, _) => AArch64Condition::Mi,
+            (ConditionDiscriminant::Le, true, _) => AArch64Condition::Ls,
+            (ConditionDiscriminant::Gr, false, true) => AArch64Condition::Gt,
+            (ConditionDiscriminant::Ge, false, true) => AArch64Condition::Ge,
+            (ConditionDiscriminant::Ls, false, true) => AArch64Condition::Lt,
+            (ConditionDiscriminant::Le, false, true) => AArch64Condition::Le,
+            (ConditionDiscriminant::Gr, false, false) => AArch64Condition::Hi,
+            (ConditionDiscriminant::Ge, false, false) => AArch64Condition::Hs,
+            (ConditionDiscriminant::Ls, false, false) => AArch64Condition::Lo,
+            (ConditionDiscriminant::Le, false, false) => AArch64Condition::Ls,
+            _ => return Err(AArch64Error::UnexpectedInstruction)
+        })
+    }
+}
@@ -0,0 +1,110 @@
+use tb_core::{error::CodeGenerationError, types::{Block, Definition, Number, Parameter, RegisterTrait, Statement, Value}};
+
+use crate::{instruction::AArch64Instruction, register::Register, AArch64AddressingMode, AArch64ApplicationContext, AArch64Location, AArch64Store};
+
+use super::{expression::AArch64ExpressionCompiler, statement::{get_argument_registers, AArch64StatementCompiler, STACK_SLOT_SIZE}};
+
+/// Sub instruction has a 12bit immediate, bigger frames use the shifted form of it
+const MAX_UNSHIFTED_STACK_SIZE: usize = 4095;
+
+pub struct AArch64DefinitionCompiler;
+
+impl AArch64DefinitionCompiler {
+    pub fn compile(definition: Definition, context: &mut AArch64ApplicationContext) -> Result<(), CodeGenerationError> {
+        match definition {
+            Definition::Function { name, parameters, block } => Self::compile_function(name, parameters, block, context),
+        }
+    }
+
+    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut AArch64ApplicationContext) -> Result<(), CodeGenerationError> {
+        let mut scope = AArch64Store::default();
+
+        // Function name, entry point could have a different name on the target
+        let is_main = name == "main";
+        let label = match is_main {
+            true => context.os_specific_defs.main_function_name().to_owned(),
+            false => name.clone()
+        };
+        context.instructions.add_branch(label);
+
+        // Function begin, frame pointer and link register are stored together
+        context.instructions.add_instruction(AArch64Instruction::StpPre { first: Register::X29, second: Register::X30, offset: -16 });
+        context.instructions.add_instruction(AArch64Instruction::Mov { source: AArch64ExpressionCompiler::location(Register::SP), target: AArch64ExpressionCompiler::location(Register::X29), comment: None });
+
+        // There is no red zone, the stack is allocated before the parameters are stored
+        context.instructions.add_instruction(Self::allocate_stack(0));
+        let stack_pointer_position = context.instructions.last_instruction_position();
+
+        let argument_types = arguments.iter().map(|parameter| parameter.param_type).collect::<Vec<_>>();
+        let argument_registers = get_argument_registers(&argument_types, None, context);
+
+        // Stack arguments are after the saved frame pointer and link register
+        let mut stack_offset = 16;
+
+        for (parameter, register) in arguments.into_iter().zip(argument_registers) {
+            let position = scope.add_variable(&parameter.name, parameter.param_type).position;
+
+            let register = match register {
+                Some(register) => register,
+                None => {
+                    // Copy the stack argument into the local variable
+                    let register = AArch64ExpressionCompiler::scratch(parameter.param_type);
+                    let slot = AArch64Location::Register(AArch64AddressingMode::Based(stack_offset, Register::X29));
+                    context.instructions.add_instruction(AArch64Instruction::Ldr { source: slot, target: AArch64ExpressionCompiler::location(register.get_sized(parameter.param_type.size())), comment: Some(format!("parameter {}", parameter.name)) });
+                    stack_offset += STACK_SLOT_SIZE as i32;
+                    register
+                }
+            };
+
+            AArch64StatementCompiler::store_variable(parameter.param_type, register, position, None, context);
+        }
+
+        context.instructions.add_comment("function body begin".to_owned());
+
+        let function_end = context.storage.create_branch();
+        context.storage.function_end = Some(function_end.clone());
+
+        // Like C, main returns zero if it reaches the end without a return
+        let returns_at_end = matches!(block.items.last(), Some(Statement::Return(_)));
+        let statement_count = block.items.len();
+
+        for (index, item) in block.items.into_iter().enumerate() {
+            AArch64StatementCompiler::compile(item, &mut scope, context).map_err(|error| CodeGenerationError::new(&name, index, error))?;
+        }
+
+        // Implicit return is after the last statement
+        if is_main && !returns_at_end {
+            AArch64StatementCompiler::compile(Statement::Return(Some(Value::Number(Number::I32(0)))), &mut scope, context).map_err(|error| CodeGenerationError::new(&name, statement_count, error))?;
+        }
+
+        context.instructions.add_branch(function_end);
+        context.storage.function_end = None;
+        context.instructions.add_comment("function body end".to_owned());
+
+        // Function end
+        context.instructions.add_instruction(AArch64Instruction::Mov { source: AArch64ExpressionCompiler::location(Register::X29), target: AArch64ExpressionCompiler::location(Register::SP), comment: None });
+        context.instructions.add_instruction(AArch64Instruction::LdpPost { first: Register::X29, second: Register::X30, offset: 16 });
+        context.instructions.add_instruction(AArch64Instruction::Ret);
+        context.instructions.add_close_branch();
+
+        if scope.get_last_position() > 0 {
+            // Stack pointer should be 16 byte aligned
+            let stack_size = match scope.get_last_position().next_multiple_of(16) {
+                stack_size if stack_size > MAX_UNSHIFTED_STACK_SIZE => stack_size.next_multiple_of(4096),
+                stack_size => stack_size
+            };
+
+            context.instructions.update_instruction(Self::allocate_stack(stack_size), stack_pointer_position);
+
+        } else {
+            // No need this instruction, remove it
+            context.instructions.remove_instruction(stack_pointer_position);
+        }
+
+        Ok(())
+    }
+
+    fn allocate_stack(size: usize) -> AArch64Instruction {
+        AArch64Instruction::Sub { target: AArch64ExpressionCompiler::location(Register::SP), left: AArch64ExpressionCompiler::location(Register::SP), right: AArch64Location::Imm(Number::U64(size as u64)), comment: None }
+    }
+}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum AArch64Error {
+    #[error("Variable not found ({0})")]
+    VariableNotFound(String),
+
+    #[error("Unexpected instruction")]
+    UnexpectedInstruction,
+
+    #[error("Break or continue is outside of a loop")]
+    OutsideOfLoop,
+
+    #[error("Return is outside of a function")]
+    OutsideOfFunction,
+
+    #[error("Unsupported float operation")]
+    UnsupportedFloatOperation
+}
@@ -0,0 +1,291 @@
+use tb_core::types::{Expression, ExpressionDiscriminant, Number, NumberType, RegisterSize, RegisterTrait, Value};
+
+use crate::{instruction::{AArch64Condition, AArch64Instruction}, register::Register, AArch64AddressingMode, AArch64ApplicationContext, AArch64Location, AArch64Store};
+
+use super::{condition::AArch64ConditionCompiler, error::AArch64Error, value::AArch64ValueCompiler};
+
+/// Nested expressions use the next temporary register, deeper ones keep the left value in the stack
+const MAX_DEPTH: usize = 7;
+
+pub struct AArch64ExpressionCompiler;
+
+impl AArch64ExpressionCompiler {
+    /// Compile the expression tree into the temporary register of the depth, the value converted to the expected type.
+    /// Temporary registers of the lower depths are not changed.
+    pub fn compile(expression: Expression, number_type: NumberType, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let target = Self::temporary(scope, number_type, depth)?;
+
+        match expression {
+            Expression::Value(value) => AArch64ValueCompiler::compile(value, number_type, target, scope, context),
+            expression => {
+                let expression_type = Self::get_type(&expression, scope)?;
+                let register = Self::compile_expression(expression, expression_type, depth, scope, context)?;
+                Self::convert(register, expression_type, number_type, target, context)
+            }
+        }
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    pub fn get_type(expression: &Expression, scope: &AArch64Store) -> Result<NumberType, AArch64Error> {
+        NumberType::of_expression(expression, &|variable| scope.find_variable(variable).map(|item| item.var_type).ok_or(AArch64Error::VariableNotFound(variable.to_owned())))
+    }
+
+    /// Result is in the temporary register of the depth with the type of the expression
+    fn compile_expression(expression: Expression, number_type: NumberType, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let operation = ExpressionDiscriminant::from(&expression);
+
+        match expression {
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } |
+            Expression::Div { divider: right, divided: left } |
+            Expression::Modulo { divider: right, divided: left } => Self::compile_binary(operation, *left, *right, number_type, depth, scope, context),
+            Expression::ShiftLeft { target, source } |
+            Expression::ShiftRight { target, source } => Self::compile_shift(operation, *target, *source, number_type, depth, scope, context),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Self::compile_unary(operation, *source, number_type, depth, scope, context),
+            Expression::Condition(condition) => AArch64ConditionCompiler::compile_value(*condition, depth, scope, context),
+            expression => Self::compile(expression, number_type, depth, scope, context)
+        }
+    }
+
+    /// Both sides with the same type, left one in the temporary register of the depth. Right side is an immediate if it is possible.
+    pub fn compile_pair(left: Expression, right: Expression, number_type: NumberType, depth: usize, allow_immediate: bool, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<(Register, AArch64Location), AArch64Error> {
+        let left = Self::compile(left, number_type, depth, scope, context)?;
+
+        if let (true, Some(immediate)) = (allow_immediate, Self::get_immediate(&right, number_type)) {
+            return Ok((left, AArch64Location::Imm(immediate)));
+        }
+
+        if depth + 1 < MAX_DEPTH {
+            let right = Self::compile(right, number_type, depth + 1, scope, context)?;
+            return Ok((left, Self::location(right)));
+        }
+
+        // There is no free temporary register, the left value waits in the stack (it should stay 16 byte aligned)
+        context.instructions.add_instruction(AArch64Instruction::StrPre { source: left.get_sized(RegisterSize::_64Bit), offset: -16 });
+        let right = Self::compile(right, number_type, depth, scope, context)?;
+
+        let scratch = Self::scratch(number_type);
+        context.instructions.add_instruction(AArch64Instruction::typed_movement(number_type, Self::location(right), Self::location(scratch), None));
+        context.instructions.add_instruction(AArch64Instruction::LdrPost { target: left.get_sized(RegisterSize::_64Bit), offset: 16 });
+        Ok((left, Self::location(scratch)))
+    }
+
+    /// Add, sub and cmp have a 12bit unsigned immediate
+    fn get_immediate(expression: &Expression, number_type: NumberType) -> Option<Number> {
+        match expression {
+            Expression::Value(Value::Number(num)) if !number_type.is_float() => {
+                let num = num.convert(number_type);
+                match num.convert(NumberType::I64) {
+                    Number::I64(0..=4095) => Some(num),
+                    _ => None
+                }
+            },
+            _ => None
+        }
+    }
+
+    fn compile_binary(operation: ExpressionDiscriminant, left: Expression, right: Expression, number_type: NumberType, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let allow_immediate = matches!(operation, ExpressionDiscriminant::Add | ExpressionDiscriminant::Sub);
+        let (register, right) = Self::compile_pair(left, right, number_type, depth, allow_immediate, scope, context)?;
+        let (target, left) = (Self::location(register), Self::location(register));
+
+        let instruction = match (operation, number_type.is_float()) {
+            (ExpressionDiscriminant::Add, false) => AArch64Instruction::Add { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Add, true) => AArch64Instruction::FAdd { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Sub, false) => AArch64Instruction::Sub { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Sub, true) => AArch64Instruction::FSub { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Mul, false) => AArch64Instruction::Mul { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Mul, true) => AArch64Instruction::FMul { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Div, true) => AArch64Instruction::FDiv { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::Div, false) => Self::division(number_type, target.clone(), left, right),
+            (ExpressionDiscriminant::Modulo, false) => {
+                // There is no remainder instruction, it is the divided minus the quotient multiplied with the divider
+                let quotient = Self::location(Register::X17.get_sized(number_type.size()));
+                context.instructions.add_instruction(Self::division(number_type, quotient.clone(), left.clone(), right.clone()));
+                context.instructions.add_instruction(AArch64Instruction::Mul { target: quotient.clone(), left: quotient.clone(), right, comment: None });
+                AArch64Instruction::Sub { target: target.clone(), left, right: quotient, comment: None }
+            },
+            (ExpressionDiscriminant::BitwiseAnd, false) => AArch64Instruction::And { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::BitwiseOr, false) => AArch64Instruction::Orr { target: target.clone(), left, right, comment: None },
+            (ExpressionDiscriminant::BitwiseXor, false) => AArch64Instruction::Eor { target: target.clone(), left, right, comment: None },
+            (_, true) => return Err(AArch64Error::UnsupportedFloatOperation),
+            _ => return Err(AArch64Error::UnexpectedInstruction)
+        };
+
+        context.instructions.add_instruction(instruction);
+        Ok(register)
+    }
+
+    fn division(number_type: NumberType, target: AArch64Location, left: AArch64Location, right: AArch64Location) -> AArch64Instruction {
+        match number_type.is_signed() {
+            true => AArch64Instruction::SDiv { target, left, right, comment: None },
+            false => AArch64Instruction::UDiv { target, left, right, comment: None }
+        }
+    }
+
+    /// Shift count uses only the lower bits like the register version of the instruction
+    fn compile_shift(operation: ExpressionDiscriminant, target: Expression, source: Expression, number_type: NumberType, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        if number_type.is_float() {
+            return Err(AArch64Error::UnsupportedFloatOperation);
+        }
+
+        let (target, count) = match source {
+            Expression::Value(Value::Number(num)) => {
+                let bits = number_type.size() as u8 * 8;
+                let count = match num.convert(NumberType::U8) {
+                    Number::U8(count) => count & (bits - 1),
+                    _ => 0
+                };
+
+                (Self::compile(target, number_type, depth, scope, context)?, AArch64Location::Imm(Number::U8(count)))
+            },
+            source => Self::compile_pair(target, source, number_type, depth, false, scope, context)?
+        };
+
+        let location = Self::location(target);
+        context.instructions.add_instruction(match (operation, number_type.is_signed()) {
+            (ExpressionDiscriminant::ShiftLeft, _) => AArch64Instruction::Lsl { target: location.clone(), left: location, right: count, comment: None },
+            (_, true) => AArch64Instruction::Asr { target: location.clone(), left: location, right: count, comment: None },
+            (_, false) => AArch64Instruction::Lsr { target: location.clone(), left: location, right: count, comment: None }
+        });
+
+        Ok(target)
+    }
+
+    fn compile_unary(operation: ExpressionDiscriminant, source: Expression, number_type: NumberType, depth: usize, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let register = Self::compile(source, number_type, depth, scope, context)?;
+        let location = Self::location(register);
+
+        let instruction = match (operation, number_type.is_float()) {
+            (ExpressionDiscriminant::BitwiseNot, false) => AArch64Instruction::Mvn { source: location.clone(), target: location, comment: None },
+            (ExpressionDiscriminant::BitwiseNeg, false) => AArch64Instruction::Neg { source: location.clone(), target: location, comment: None },
+            (ExpressionDiscriminant::Inc, false) => AArch64Instruction::Add { target: location.clone(), left: location, right: AArch64Location::Imm(Number::U8(1)), comment: None },
+            (ExpressionDiscriminant::Dec, false) => AArch64Instruction::Sub { target: location.clone(), left: location, right: AArch64Location::Imm(Number::U8(1)), comment: None },
+            (ExpressionDiscriminant::BitwiseNeg, true) => {
+                // Value is subtracted from zero like the other targets, so the negative zero is not generated
+                let zero = Self::scratch(number_type);
+                let zero_register = Register::XZR.get_sized(number_type.size());
+                context.instructions.add_instruction(AArch64Instruction::FMov { source: Self::location(zero_register), target: Self::location(zero), comment: None });
+                AArch64Instruction::FSub { target: location.clone(), left: Self::location(zero), right: location, comment: None }
+            },
+            (ExpressionDiscriminant::Inc | ExpressionDiscriminant::Dec, true) => {
+                let one = Self::scratch(number_type);
+                AArch64ValueCompiler::load_float(Number::I32(1).convert(number_type), one, context);
+
+                match operation {
+                    ExpressionDiscriminant::Inc => AArch64Instruction::FAdd { target: location.clone(), left: location, right: Self::location(one), comment: None },
+                    _ => AArch64Instruction::FSub { target: location.clone(), left: location, right: Self::location(one), comment: None }
+                }
+            },
+            (_, true) => return Err(AArch64Error::UnsupportedFloatOperation),
+            _ => return Err(AArch64Error::UnexpectedInstruction)
+        };
+
+        context.instructions.add_instruction(instruction);
+        Ok(register)
+    }
+
+    /// Convert the value in the source register to the other type and write it into the target register.
+    /// Integers smaller than 32bit are kept extended in the 32bit view of the register.
+    pub fn convert(source: Register, from: NumberType, to: NumberType, target: Register, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let source = source.get_sized(from.size());
+        let target = target.get_sized(to.size());
+
+        match (from.is_float(), to.is_float()) {
+            (true, true) if from != to => { context.instructions.add_instruction(AArch64Instruction::Fcvt { source: Self::location(source), target: Self::location(target), comment: None }); },
+            (true, true) => Self::move_register(to, source, target, context),
+            (false, true) => {
+                context.instructions.add_instruction(match from.is_signed() {
+                    true => AArch64Instruction::Scvtf { source: Self::location(source), target: Self::location(target), comment: None },
+                    false => AArch64Instruction::Ucvtf { source: Self::location(source), target: Self::location(target), comment: None }
+                });
+            },
+            (true, false) if to == NumberType::Bool => {
+                // Like C, every value except zero is true (NaN too)
+                context.instructions.add_instruction(AArch64Instruction::Fcmp { left: Self::location(source), right: AArch64Location::Imm(Number::Double(0.0)), comment: None });
+                context.instructions.add_instruction(AArch64Instruction::Cset { condition: AArch64Condition::Ne, target: Self::location(target) });
+            },
+            (true, false) => {
+                // Values are truncated to 64bit first, smaller types keep the lower bits of it
+                let wide = target.get_sized(RegisterSize::_64Bit);
+                let (instruction, wide_type) = match to {
+                    NumberType::U64 => (AArch64Instruction::Fcvtzu { source: Self::location(source), target: Self::location(wide), comment: None }, NumberType::U64),
+                    _ => (AArch64Instruction::Fcvtzs { source: Self::location(source), target: Self::location(wide), comment: None }, NumberType::I64)
+                };
+
+                context.instructions.add_instruction(instruction);
+                return Self::convert(wide, wide_type, to, target, context);
+            },
+            (false, false) => Self::convert_integer(source, from, to, target, context)
+        };
+
+        Ok(target)
+    }
+
+    fn convert_integer(source: Register, from: NumberType, to: NumberType, target: Register, context: &mut AArch64ApplicationContext) {
+        let narrow_source = Self::location(source.get_sized(RegisterSize::_32Bit));
+        let narrow_target = Self::location(target.get_sized(RegisterSize::_32Bit));
+
+        let instruction = match to {
+            to if to == from => return Self::move_register(to, source, target, context),
+            NumberType::Bool => {
+                context.instructions.add_instruction(AArch64Instruction::Cmp { left: Self::location(source), right: AArch64Location::Imm(Number::U8(0)), comment: None });
+                AArch64Instruction::Cset { condition: AArch64Condition::Ne, target: Self::location(target) }
+            },
+            NumberType::I64 | NumberType::U64 if from.size() == RegisterSize::_64Bit => return Self::move_register(to, source, target, context),
+
+            // 32bit move clears the upper part of the register
+            NumberType::I64 | NumberType::U64 if from.is_signed() => AArch64Instruction::Sxtw { source: narrow_source, target: Self::location(target), comment: None },
+            NumberType::I64 | NumberType::U64 => AArch64Instruction::Mov { source: narrow_source, target: narrow_target, comment: None },
+
+            // Smaller value is already extended with the same sign
+            to if to.size() == RegisterSize::_32Bit || (from.size() < to.size() && (from.is_signed() == to.is_signed() || !from.is_signed())) => return Self::move_register(to, source, target, context),
+            NumberType::I8 => AArch64Instruction::Sxtb { source: narrow_source, target: narrow_target, comment: None },
+            NumberType::U8 => AArch64Instruction::Uxtb { source: narrow_source, target: narrow_target, comment: None },
+            NumberType::I16 => AArch64Instruction::Sxth { source: narrow_source, target: narrow_target, comment: None },
+            _ => AArch64Instruction::Uxth { source: narrow_source, target: narrow_target, comment: None }
+        };
+
+        context.instructions.add_instruction(instruction);
+    }
+
+    fn move_register(number_type: NumberType, source: Register, target: Register, context: &mut AArch64ApplicationContext) {
+        if source.get_number() != target.get_number() || source.is_vector() != target.is_vector() {
+            let size = match number_type.is_float() {
+                true => number_type.size(),
+                false => std::cmp::max(number_type.size(), RegisterSize::_32Bit)
+            };
+
+            context.instructions.add_instruction(AArch64Instruction::typed_movement(number_type, Self::location(source.get_sized(size)), Self::location(target.get_sized(size)), None));
+        }
+    }
+
+    /// Temporary register of the depth, sized for the type
+    pub fn temporary(scope: &AArch64Store, number_type: NumberType, depth: usize) -> Result<Register, AArch64Error> {
+        scope.get_registers()
+            .into_iter()
+            .filter(|register| register.is_vector() == number_type.is_float())
+            .nth(depth)
+            .map(|register| register.get_sized(number_type.size()))
+            .ok_or(AArch64Error::UnexpectedInstruction)
+    }
+
+    /// Register for the short living values, it is not used by the temporaries
+    pub fn scratch(number_type: NumberType) -> Register {
+        match number_type.is_float() {
+            true => Register::D31.get_sized(number_type.size()),
+            false => Register::X16.get_sized(number_type.size())
+        }
+    }
+
+    pub fn location(register: Register) -> AArch64Location {
+        AArch64Location::Register(AArch64AddressingMode::Direct(register))
+    }
+}
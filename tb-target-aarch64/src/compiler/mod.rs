@@ -0,0 +1,12 @@
+mod value;
+mod expression;
+mod statement;
+mod definition;
+mod condition;
+mod block;
+pub mod error;
+
+pub use value::AArch64ValueCompiler;
+pub use expression::AArch64ExpressionCompiler;
+pub use statement::AArch64StatementCompiler;
+pub use definition::AArch64DefinitionCompiler;
@@ -0,0 +1,255 @@
+use std::sync::LazyLock;
+
+use tb_core::{target::OperatingSystem, types::{Block, CallingConventions, Condition, Expression, Number, NumberType, ProcedureCall, RegisterTrait, Statement, Value}};
+
+use crate::{instruction::{AArch64Instruction, ARGUMENT_REGISTERS, FLOAT_ARGUMENT_REGISTERS}, register::Register, AArch64AddressingMode, AArch64ApplicationContext, AArch64Location, AArch64Store};
+
+use super::{block::AArch64BlockCompiler, condition::AArch64ConditionCompiler, error::AArch64Error, expression::AArch64ExpressionCompiler, value::AArch64ValueCompiler};
+
+pub static CALL_CONVENTION: LazyLock<ProcedureCall<Register>>= LazyLock::new(|| {
+    ProcedureCall {
+        convention: CallingConventions::Arm,
+        registers: ARGUMENT_REGISTERS.to_vec(),
+        float_registers: FLOAT_ARGUMENT_REGISTERS.to_vec()
+    }
+});
+
+/// Every stack argument uses an 8 byte slot
+pub const STACK_SLOT_SIZE: usize = 8;
+
+/// AAPCS64 counts the integer and floating point arguments separately, the rest of the arguments are passed with stack.
+/// Apple passes all variadic arguments with stack, only the fixed ones use the registers.
+pub fn get_argument_registers(argument_types: &[NumberType], fixed_arguments: Option<usize>, context: &AArch64ApplicationContext) -> Vec<Option<Register>> {
+    let mut integer_index = 0;
+    let mut float_index = 0;
+
+    argument_types.iter().enumerate().map(|(index, argument_type)| match (fixed_arguments, argument_type.is_float()) {
+        (Some(fixed_arguments), _) if index >= fixed_arguments && context.target.operating_system == OperatingSystem::MacOS => None,
+        (_, true) => {
+            float_index += 1;
+            CALL_CONVENTION.get_float_register(float_index - 1).map(|register| register.get_sized(argument_type.size()))
+        },
+        (_, false) => {
+            integer_index += 1;
+            CALL_CONVENTION.get_register(integer_index - 1).map(|register| register.get_sized(argument_type.size()))
+        }
+    }).collect()
+}
+
+pub struct AArch64StatementCompiler;
+
+impl AArch64StatementCompiler {
+    pub fn compile(statement: Statement, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        match statement {
+            Statement::Assign { name, assigne } => Self::compile_assign(scope, name, assigne, context),
+            Statement::Declare { name, ty, init } => Self::compile_declare(scope, name, ty, init, context),
+            Statement::Call { name, arguments, assign, is_variadic } => Self::compile_call(scope, name, arguments, assign, is_variadic, context),
+            Statement::Print { format, arguments } => Self::compile_print(scope, format, arguments, context),
+            Statement::Return(expr) => Self::compile_return(scope, expr, context),
+            Statement::If { condition, true_block, false_block } => Self::compile_if(scope, condition, true_block, false_block, context),
+            Statement::While { condition, block } => Self::compile_while(scope, condition, block, context),
+            Statement::Loop { block } => Self::compile_loop(scope, block, context),
+            Statement::Break => Self::compile_break(context),
+            Statement::Continue => Self::compile_continue(context),
+        }
+    }
+
+    fn compile_assign(scope: &mut AArch64Store, name: String, assigne: Expression, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        // Undeclared variable takes the type of the expression
+        let var_type = match scope.find_variable(&name) {
+            Some(variable) => variable.var_type,
+            None => AArch64ExpressionCompiler::get_type(&assigne, scope)?
+        };
+
+        let register = AArch64ExpressionCompiler::compile(assigne, var_type, 0, scope, context)?;
+
+        let position = match scope.find_variable(&name) {
+            Some(variable) => variable.position,
+            None => scope.add_variable(&name, var_type).position
+        };
+
+        Self::store_variable(var_type, register, position, Some(format!("assign {}", name)), context);
+        Ok(())
+    }
+
+    fn compile_declare(scope: &mut AArch64Store, name: String, var_type: NumberType, init: Option<Expression>, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        // Initial value could use the shadowed variable, so, calculate it before the declaration
+        let register = match init {
+            Some(init) => Some(AArch64ExpressionCompiler::compile(init, var_type, 0, scope, context)?),
+            None => None
+        };
+
+        let position = scope.add_variable(&name, var_type).position;
+
+        if let Some(register) = register {
+            Self::store_variable(var_type, register, position, Some(format!("declare {}", name)), context);
+        }
+
+        Ok(())
+    }
+
+    /// Only the bytes of the variable type are written
+    pub fn store_variable(var_type: NumberType, register: Register, position: usize, comment: Option<String>, context: &mut AArch64ApplicationContext) {
+        let address = AArch64ValueCompiler::variable_address(position, context);
+        context.instructions.add_instruction(AArch64Instruction::typed_store(var_type, AArch64ExpressionCompiler::location(register.get_sized(var_type.size())), address, comment));
+    }
+
+    fn compile_print(scope: &mut AArch64Store, format: String, arguments: Vec<Value>, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let mut tmp_arguments = Vec::new();
+        tmp_arguments.push(Value::String(format));
+        tmp_arguments.extend(arguments);
+
+        Self::compile_call(scope, context.os_specific_defs.print().to_owned(), tmp_arguments, None, true, context)
+    }
+
+    /// Variadic functions have one fixed argument, like printf
+    fn compile_call(scope: &mut AArch64Store, name: String, arguments: Vec<Value>, assign: Option<String>, is_variadic: bool, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        // Arguments of the application functions are converted to the parameter types
+        let parameters = context.storage.parameters.get(&name).cloned().unwrap_or_default();
+
+        let mut argument_types = Vec::new();
+        for (index, argument) in arguments.iter().enumerate() {
+            let value_type = match parameters.get(index) {
+                Some(parameter_type) => *parameter_type,
+                None => AArch64ExpressionCompiler::get_type(&Expression::Value(argument.clone()), scope)?
+            };
+
+            let argument_type = match value_type {
+
+                // Variadic functions get float as double, integers are extended to the full register like the stack slots
+                NumberType::Float if is_variadic => NumberType::Double,
+                argument_type if argument_type.is_float() => argument_type,
+                argument_type if argument_type.is_signed() => NumberType::I64,
+                _ => NumberType::U64
+            };
+            argument_types.push(argument_type);
+        }
+
+        let fixed_arguments = match is_variadic {
+            true => Some(1),
+            false => None
+        };
+        let argument_registers = get_argument_registers(&argument_types, fixed_arguments, context);
+
+        // Stack should be 16 byte aligned all the time
+        let stack_size = (argument_registers.iter().filter(|register| register.is_none()).count() * STACK_SLOT_SIZE).next_multiple_of(16);
+        if stack_size > 0 {
+            context.instructions.add_instruction(AArch64Instruction::Sub { target: AArch64ExpressionCompiler::location(Register::SP), left: AArch64ExpressionCompiler::location(Register::SP), right: AArch64Location::Imm(Number::U64(stack_size as u64)), comment: Some("stack arguments".to_owned()) });
+        }
+
+        // Stack arguments use the temporary registers, so they are written before the register arguments
+        let mut stack_offset = 0;
+        for ((argument, argument_type), register) in arguments.iter().zip(argument_types.iter()).zip(argument_registers.iter()) {
+            if register.is_none() {
+                let value = AArch64ExpressionCompiler::compile(Expression::Value(argument.clone()), *argument_type, 0, scope, context)?;
+                context.instructions.add_instruction(AArch64Instruction::Str { source: AArch64ExpressionCompiler::location(value), target: AArch64Location::Register(AArch64AddressingMode::Based(stack_offset, Register::SP)), comment: None });
+                stack_offset += STACK_SLOT_SIZE as i32;
+            }
+        }
+
+        for ((argument, argument_type), register) in arguments.into_iter().zip(argument_types).zip(argument_registers) {
+            if let Some(register) = register {
+                AArch64ValueCompiler::compile(argument, argument_type, register, scope, context)?;
+            }
+        }
+
+        context.instructions.add_instruction(AArch64Instruction::Bl(name));
+
+        if stack_size > 0 {
+            context.instructions.add_instruction(AArch64Instruction::Add { target: AArch64ExpressionCompiler::location(Register::SP), left: AArch64ExpressionCompiler::location(Register::SP), right: AArch64Location::Imm(Number::U64(stack_size as u64)), comment: None });
+        }
+
+        if let Some(assigned) = assign {
+            let (position, var_type) = match scope.find_variable(&assigned) {
+                Some(variable) => (variable.position, variable.var_type),
+                None => (scope.add_variable(&assigned, NumberType::I64).position, NumberType::I64)
+            };
+
+            // Floating point result is in D0
+            let result = match var_type.is_float() {
+                true => Register::D0,
+                false => Register::X0
+            };
+            Self::store_variable(var_type, result, position, None, context);
+        }
+
+        Ok(())
+    }
+
+    fn compile_if(scope: &mut AArch64Store, condition: Condition, true_block: Block, false_block: Option<Block>, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let false_branch = context.storage.create_branch();
+        AArch64ConditionCompiler::compile_jump(condition, &false_branch, false, 0, scope, context)?;
+
+        AArch64BlockCompiler::compile(true_block, scope, context)?;
+
+        if let Some(false_block) = false_block {
+            let end_branch = context.storage.create_branch();
+            context.instructions.add_instruction(AArch64Instruction::B(end_branch.clone()));
+
+            // Jump to else block
+            context.instructions.add_branch(false_branch);
+            AArch64BlockCompiler::compile(false_block, scope, context)?;
+            context.instructions.add_branch(end_branch);
+
+        } else {
+            context.instructions.add_branch(false_branch);
+        }
+
+        Ok(())
+    }
+
+    fn compile_while(scope: &mut AArch64Store, condition: Condition, block: Block, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let branches = context.storage.begin_loop();
+
+        context.instructions.add_branch(branches.begin.clone());
+        AArch64ConditionCompiler::compile_jump(condition, &branches.end, false, 0, scope, context)?; // Exit from loop
+
+        AArch64BlockCompiler::compile(block, scope, context)?;
+        context.instructions.add_instruction(AArch64Instruction::B(branches.begin.clone()));
+        context.instructions.add_branch(branches.end.clone());
+
+        context.storage.end_loop();
+        Ok(())
+    }
+
+    fn compile_loop(scope: &mut AArch64Store, block: Block, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let branches = context.storage.begin_loop();
+
+        context.instructions.add_branch(branches.begin.clone());
+        AArch64BlockCompiler::compile(block, scope, context)?;
+        context.instructions.add_instruction(AArch64Instruction::B(branches.begin.clone()));
+        context.instructions.add_branch(branches.end.clone());
+
+        context.storage.end_loop();
+        Ok(())
+    }
+
+    fn compile_break(context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let branches = context.storage.current_loop().ok_or(AArch64Error::OutsideOfLoop)?;
+        context.instructions.add_instruction(AArch64Instruction::B(branches.end.clone()));
+        Ok(())
+    }
+
+    fn compile_continue(context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        let branches = context.storage.current_loop().ok_or(AArch64Error::OutsideOfLoop)?;
+        context.instructions.add_instruction(AArch64Instruction::B(branches.begin.clone()));
+        Ok(())
+    }
+
+    fn compile_return(scope: &mut AArch64Store, expr: Option<Value>, context: &mut AArch64ApplicationContext) -> Result<(), AArch64Error> {
+        if let Some(value) = expr {
+            // Integers are extended to the full register
+            let (number_type, target) = match AArch64ExpressionCompiler::get_type(&Expression::Value(value.clone()), scope)? {
+                number_type if number_type.is_float() => (number_type, Register::D0),
+                number_type if number_type.is_signed() => (NumberType::I64, Register::X0),
+                _ => (NumberType::U64, Register::X0)
+            };
+
+            AArch64ValueCompiler::compile(value, number_type, target, scope, context)?;
+        }
+
+        let function_end = context.storage.function_end.clone().ok_or(AArch64Error::OutsideOfFunction)?;
+        context.instructions.add_instruction(AArch64Instruction::B(function_end));
+        Ok(())
+    }
+}
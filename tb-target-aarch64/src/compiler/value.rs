@@ -0,0 +1,124 @@
+use tb_core::types::{Number, NumberType, RegisterSize, RegisterTrait, Value};
+
+use crate::{instruction::AArch64Instruction, register::Register, AArch64AddressingMode, AArch64ApplicationContext, AArch64Location, AArch64Store};
+
+use super::{error::AArch64Error, AArch64ExpressionCompiler};
+
+/// Loads and stores could use an offset down to -256 from the frame pointer, farther variables use X17 for the address
+const MAX_FRAME_OFFSET: usize = 256;
+
+pub struct AArch64ValueCompiler;
+
+impl AArch64ValueCompiler {
+    /// Value converted to the number type in the target register
+    pub fn compile(value: Value, number_type: NumberType, target: Register, scope: &mut AArch64Store, context: &mut AArch64ApplicationContext) -> Result<Register, AArch64Error> {
+        let target = target.get_sized(number_type.size());
+
+        match value {
+            Value::Variable(variable) => {
+                let (variable_type, variable_position) = {
+                    let variable = scope.find_variable(&variable).ok_or(AArch64Error::VariableNotFound(variable.to_owned()))?;
+                    (variable.var_type, variable.position)
+                };
+
+                // Other kind of register needs a conversion instruction, the value is loaded into the scratch register first
+                let register = match variable_type.is_float() == number_type.is_float() {
+                    true => target.get_sized(variable_type.size()),
+                    false => AArch64ExpressionCompiler::scratch(variable_type)
+                };
+
+                let address = Self::variable_address(variable_position, context);
+                context.instructions.add_instruction(AArch64Instruction::typed_load(variable_type, address, AArch64ExpressionCompiler::location(register), None));
+                AArch64ExpressionCompiler::convert(register, variable_type, number_type, target, context)
+            },
+            Value::Number(num) if number_type.is_float() => {
+                Self::load_float(num.convert(number_type), target, context);
+                Ok(target)
+            },
+            Value::Number(num) => {
+                Self::load_immediate(num.convert(number_type), target, context);
+                Ok(target)
+            },
+            Value::String(data) => {
+                let label = context.datas.create_label();
+                context.datas.add_string_data(&label, &data);
+
+                let target = target.get_sized(RegisterSize::_64Bit);
+                Self::load_address(label, target, context);
+                Ok(target)
+            }
+        }
+    }
+
+    /// Address of the variable in the stack frame
+    pub fn variable_address(position: usize, context: &mut AArch64ApplicationContext) -> AArch64Location {
+        if position <= MAX_FRAME_OFFSET {
+            return AArch64Location::Register(AArch64AddressingMode::Based(-(position as i32), Register::X29));
+        }
+
+        Self::load_immediate(Number::U64(position as u64), Register::X17, context);
+        context.instructions.add_instruction(AArch64Instruction::Sub { target: AArch64ExpressionCompiler::location(Register::X17), left: AArch64ExpressionCompiler::location(Register::X29), right: AArch64ExpressionCompiler::location(Register::X17), comment: None });
+        AArch64Location::Register(AArch64AddressingMode::Indirect(Register::X17))
+    }
+
+    /// Mov could only write 16 bits, the others are written with movk. Values that have only one
+    /// 16bit part different from all ones are written with a negative mov.
+    pub fn load_immediate(num: Number, target: Register, context: &mut AArch64ApplicationContext) {
+        let is_wide = target.get_register_size() == RegisterSize::_64Bit;
+        let mask = match is_wide {
+            true => u64::MAX,
+            false => u32::MAX as u64
+        };
+
+        let bits = match num.convert(NumberType::I64) {
+            Number::I64(value) => value as u64 & mask,
+            _ => 0
+        };
+
+        let value = match bits {
+            bits if bits <= 0xffff => Number::U64(bits),
+            bits if !bits & mask <= 0xffff && is_wide => Number::I64(bits as i64),
+            bits if !bits & mask <= 0xffff => Number::I32(bits as u32 as i32),
+            bits => Number::U64(bits & 0xffff)
+        };
+
+        context.instructions.add_instruction(AArch64Instruction::Mov { source: AArch64Location::Imm(value), target: AArch64ExpressionCompiler::location(target), comment: None });
+
+        // Value fits into the mov instruction
+        if matches!(value, Number::I64(_) | Number::I32(_)) || bits <= 0xffff {
+            return;
+        }
+
+        let parts = match is_wide {
+            true => 4,
+            false => 2
+        };
+
+        for part in 1..parts {
+            let value = (bits >> (16 * part)) as u16;
+            if value != 0 {
+                context.instructions.add_instruction(AArch64Instruction::Movk { value, shift: 16 * part as u8, target: AArch64ExpressionCompiler::location(target), comment: None });
+            }
+        }
+    }
+
+    /// Floating point numbers can not be used as an immediate, they are stored in the readonly data
+    pub fn load_float(num: Number, target: Register, context: &mut AArch64ApplicationContext) {
+        let label = context.datas.create_label();
+
+        match num {
+            Number::Float(num) => context.datas.add_float_data(&label, num),
+            Number::Double(num) => context.datas.add_double_data(&label, num),
+            num => return Self::load_float(num.convert(NumberType::Double), target, context)
+        };
+
+        Self::load_address(label, Register::X16, context);
+        context.instructions.add_instruction(AArch64Instruction::Ldr { source: AArch64Location::Register(AArch64AddressingMode::Indirect(Register::X16)), target: AArch64ExpressionCompiler::location(target), comment: None });
+    }
+
+    /// Page of the label and the offset in the page
+    pub fn load_address(label: String, target: Register, context: &mut AArch64ApplicationContext) {
+        context.instructions.add_instruction(AArch64Instruction::Adrp { label: label.clone(), target: AArch64ExpressionCompiler::location(target) });
+        context.instructions.add_instruction(AArch64Instruction::Add { target: AArch64ExpressionCompiler::location(target), left: AArch64ExpressionCompiler::location(target), right: AArch64Location::Label(label), comment: None });
+    }
+}
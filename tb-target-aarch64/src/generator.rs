@@ -0,0 +1,42 @@
+use tb_core::{error::{CodeGenerationError, TBError}, syntax::{Syntax, SyntaxGeneratorTrait}, target::{Architecture, Target}, types::{AssemblyGenerator, DataItemCollection, Definition}};
+
+use crate::{compiler::AArch64DefinitionCompiler, syntax::AArch64SyntaxGenerator, AArch64ApplicationContext};
+
+#[derive(Debug, Clone)]
+pub struct AArch64AssemblyGenerator {
+    target: Target
+}
+
+impl AArch64AssemblyGenerator {
+    /// Instructions of the definitions before they are printed
+    pub fn compile(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<AArch64ApplicationContext, CodeGenerationError> {
+        let mut context = AArch64ApplicationContext::new(self.target, datas);
+        context.storage.parameters = Definition::parameter_types(&definitions);
+
+        for item in definitions.into_iter() {
+            AArch64DefinitionCompiler::compile(item, &mut context)?;
+        }
+
+        Ok(context)
+    }
+}
+
+/// Default one is for the operating system of the running system
+impl Default for AArch64AssemblyGenerator {
+    fn default() -> Self {
+        Self::new(Target::default())
+    }
+}
+
+impl AssemblyGenerator for AArch64AssemblyGenerator {
+    /// Only the operating system of the target is used, the architecture is always AArch64
+    fn new(target: Target) -> Self {
+        Self { target: Target::new(Architecture::Aarch64, target.operating_system) }
+    }
+
+    /// There is only the GNU syntax for A64, the requested syntax is ignored
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, _: Syntax) -> Result<String, TBError> {
+        let mut context = self.compile(definitions, datas)?;
+        Ok(AArch64SyntaxGenerator::default().generate(&mut context))
+    }
+}
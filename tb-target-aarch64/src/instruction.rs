@@ -0,0 +1,451 @@
+use std::fmt::Display;
+
+use strum_macros::EnumDiscriminants;
+use strum_macros::Display;
+use tb_core::addressing_mode::AddressingMode;
+use tb_core::instruction::InstructionTrait;
+use tb_core::types::NumberType;
+
+use crate::register::Register;
+use crate::AArch64AbstractInstruction;
+use crate::AArch64Location;
+
+/// Registers that are used for the arguments of the function call
+pub const ARGUMENT_REGISTERS: [Register; 8] = [
+    Register::X0, Register::X1, Register::X2, Register::X3, Register::X4, Register::X5, Register::X6, Register::X7
+];
+
+/// Floating point arguments are counted separately
+pub const FLOAT_ARGUMENT_REGISTERS: [Register; 8] = [
+    Register::D0, Register::D1, Register::D2, Register::D3, Register::D4, Register::D5, Register::D6, Register::D7
+];
+
+/// Condition codes of the flags, comparisons are written with cmp and fcmp before them
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum AArch64Condition {
+    Eq,
+    Ne,
+    Hs,
+    Lo,
+    Mi,
+    Pl,
+    Hi,
+    Ls,
+    Ge,
+    Lt,
+    Gt,
+    Le
+}
+
+impl AArch64Condition {
+    /// Opposite of the condition, unordered floating point comparison (NaN) is true for the opposite of mi, ls, gt and ge
+    pub fn invert(&self) -> Self {
+        match self {
+            AArch64Condition::Eq => AArch64Condition::Ne,
+            AArch64Condition::Ne => AArch64Condition::Eq,
+            AArch64Condition::Hs => AArch64Condition::Lo,
+            AArch64Condition::Lo => AArch64Condition::Hs,
+            AArch64Condition::Mi => AArch64Condition::Pl,
+            AArch64Condition::Pl => AArch64Condition::Mi,
+            AArch64Condition::Hi => AArch64Condition::Ls,
+            AArch64Condition::Ls => AArch64Condition::Hi,
+            AArch64Condition::Ge => AArch64Condition::Lt,
+            AArch64Condition::Lt => AArch64Condition::Ge,
+            AArch64Condition::Gt => AArch64Condition::Le,
+            AArch64Condition::Le => AArch64Condition::Gt
+        }
+    }
+}
+
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(name(AArch64InstructionType))]
+#[strum_discriminants(derive(Display))]
+pub enum AArch64Instruction {
+    Add {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Sub {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Mul {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    SDiv {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    UDiv {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    And {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Orr {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Eor {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Lsl {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Lsr {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Asr {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    FAdd {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    FSub {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    FMul {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    FDiv {
+        target: AArch64Location,
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Mov {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+
+    /// 16bit part of the register, the other parts are kept
+    Movk {
+        value: u16,
+        shift: u8,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    FMov {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Mvn {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Neg {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    FNeg {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Sxtb {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Sxth {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Sxtw {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Uxtb {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Uxth {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Scvtf {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Ucvtf {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Fcvtzs {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Fcvtzu {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+
+    /// Conversion between single and double precision
+    Fcvt {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Cmp {
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Fcmp {
+        left: AArch64Location,
+        right: AArch64Location,
+        comment: Option<String>
+    },
+    Ldr {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Ldrb {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Ldrsb {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Ldrh {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Ldrsh {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Str {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Strb {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+    Strh {
+        source: AArch64Location,
+        target: AArch64Location,
+        comment: Option<String>
+    },
+
+    /// Address of the 4KB page of the label, the offset in the page is added with Add
+    Adrp {
+        label: String,
+        target: AArch64Location
+    },
+    Cset {
+        condition: AArch64Condition,
+        target: AArch64Location
+    },
+
+    /// Stack pointer is decreased before the pair is stored
+    StpPre {
+        first: Register,
+        second: Register,
+        offset: i32
+    },
+
+    /// Stack pointer is increased after the pair is loaded
+    LdpPost {
+        first: Register,
+        second: Register,
+        offset: i32
+    },
+    StrPre {
+        source: Register,
+        offset: i32
+    },
+    LdrPost {
+        target: Register,
+        offset: i32
+    },
+    B(String),
+    BCond(AArch64Condition, String),
+    Bl(String),
+    Ret
+}
+
+impl AArch64Instruction {
+    /// Load instruction for the number type, smaller integers are extended to 32bit
+    pub fn typed_load(number_type: NumberType, source: AArch64Location, target: AArch64Location, comment: Option<String>) -> AArch64Instruction {
+        match number_type {
+            NumberType::I8 => AArch64Instruction::Ldrsb { source, target, comment },
+            NumberType::U8 | NumberType::Bool => AArch64Instruction::Ldrb { source, target, comment },
+            NumberType::I16 => AArch64Instruction::Ldrsh { source, target, comment },
+            NumberType::U16 => AArch64Instruction::Ldrh { source, target, comment },
+            _ => AArch64Instruction::Ldr { source, target, comment }
+        }
+    }
+
+    /// Store instruction for the number type, only the bytes of the type are written
+    pub fn typed_store(number_type: NumberType, source: AArch64Location, target: AArch64Location, comment: Option<String>) -> AArch64Instruction {
+        match number_type {
+            NumberType::I8 | NumberType::U8 | NumberType::Bool => AArch64Instruction::Strb { source, target, comment },
+            NumberType::I16 | NumberType::U16 => AArch64Instruction::Strh { source, target, comment },
+            _ => AArch64Instruction::Str { source, target, comment }
+        }
+    }
+
+    /// Move between the registers of the same kind
+    pub fn typed_movement(number_type: NumberType, source: AArch64Location, target: AArch64Location, comment: Option<String>) -> AArch64Instruction {
+        match number_type.is_float() {
+            true => AArch64Instruction::FMov { source, target, comment },
+            false => AArch64Instruction::Mov { source, target, comment }
+        }
+    }
+
+    pub fn mnemonic(&self) -> String {
+        match self {
+            AArch64Instruction::BCond(condition, _) => format!("b.{}", condition),
+            AArch64Instruction::StpPre { .. } => "stp".to_owned(),
+            AArch64Instruction::LdpPost { .. } => "ldp".to_owned(),
+            AArch64Instruction::StrPre { .. } => "str".to_owned(),
+            AArch64Instruction::LdrPost { .. } => "ldr".to_owned(),
+            instruction => AArch64InstructionType::from(instruction).to_string().to_lowercase()
+        }
+    }
+}
+
+impl Display for AArch64Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.mnemonic())
+    }
+}
+
+impl From<AArch64Instruction> for AArch64AbstractInstruction {
+    fn from(value: AArch64Instruction) -> Self {
+        value.convert()
+    }
+}
+
+impl InstructionTrait for AArch64Instruction {
+    type IT = AArch64InstructionType;
+    type REG = Register;
+
+    /// Operands are in the order of the assembly, the destination is the first one
+    fn convert(self) -> AArch64AbstractInstruction {
+        match self.clone() {
+            AArch64Instruction::Add { target, left, right, comment } |
+            AArch64Instruction::Sub { target, left, right, comment } |
+            AArch64Instruction::Mul { target, left, right, comment } |
+            AArch64Instruction::SDiv { target, left, right, comment } |
+            AArch64Instruction::UDiv { target, left, right, comment } |
+            AArch64Instruction::And { target, left, right, comment } |
+            AArch64Instruction::Orr { target, left, right, comment } |
+            AArch64Instruction::Eor { target, left, right, comment } |
+            AArch64Instruction::Lsl { target, left, right, comment } |
+            AArch64Instruction::Lsr { target, left, right, comment } |
+            AArch64Instruction::Asr { target, left, right, comment } |
+            AArch64Instruction::FAdd { target, left, right, comment } |
+            AArch64Instruction::FSub { target, left, right, comment } |
+            AArch64Instruction::FMul { target, left, right, comment } |
+            AArch64Instruction::FDiv { target, left, right, comment } => AArch64AbstractInstruction::target_source2_with_comment(self, target, left, right, comment),
+            AArch64Instruction::Mov { source, target, comment } |
+            AArch64Instruction::FMov { source, target, comment } |
+            AArch64Instruction::Mvn { source, target, comment } |
+            AArch64Instruction::Neg { source, target, comment } |
+            AArch64Instruction::FNeg { source, target, comment } |
+            AArch64Instruction::Sxtb { source, target, comment } |
+            AArch64Instruction::Sxth { source, target, comment } |
+            AArch64Instruction::Sxtw { source, target, comment } |
+            AArch64Instruction::Uxtb { source, target, comment } |
+            AArch64Instruction::Uxth { source, target, comment } |
+            AArch64Instruction::Scvtf { source, target, comment } |
+            AArch64Instruction::Ucvtf { source, target, comment } |
+            AArch64Instruction::Fcvtzs { source, target, comment } |
+            AArch64Instruction::Fcvtzu { source, target, comment } |
+            AArch64Instruction::Fcvt { source, target, comment } |
+            AArch64Instruction::Ldr { source, target, comment } |
+            AArch64Instruction::Ldrb { source, target, comment } |
+            AArch64Instruction::Ldrsb { source, target, comment } |
+            AArch64Instruction::Ldrh { source, target, comment } |
+            AArch64Instruction::Ldrsh { source, target, comment } => AArch64AbstractInstruction::target_source_with_comment(self, target, source, comment),
+
+            // Stored register is written before the address
+            AArch64Instruction::Str { source, target, comment } |
+            AArch64Instruction::Strb { source, target, comment } |
+            AArch64Instruction::Strh { source, target, comment } => AArch64AbstractInstruction::target_source_with_comment(self, source, target, comment),
+            AArch64Instruction::Movk { value, target, comment, .. } => AArch64AbstractInstruction::target_source_with_comment(self, target, AArch64Location::Imm(value.into()), comment),
+            AArch64Instruction::Cmp { left, right, comment } |
+            AArch64Instruction::Fcmp { left, right, comment } => AArch64AbstractInstruction::target_source_with_comment(self, left, right, comment),
+            AArch64Instruction::Adrp { label, target } => AArch64AbstractInstruction::target_source(self, target, AArch64Location::Label(label)),
+            AArch64Instruction::Cset { condition, target } => {
+                let mut instruction = AArch64AbstractInstruction::target(self, target);
+                instruction.label = Some(condition.to_string());
+                instruction
+            },
+            AArch64Instruction::StpPre { first, second, offset } |
+            AArch64Instruction::LdpPost { first, second, offset } => AArch64AbstractInstruction::target_source2(self, AArch64Location::Register(AddressingMode::Direct(first)), AArch64Location::Register(AddressingMode::Direct(second)), AArch64Location::Register(AddressingMode::Based(offset, Register::SP))),
+            AArch64Instruction::StrPre { source: register, offset } |
+            AArch64Instruction::LdrPost { target: register, offset } => AArch64AbstractInstruction::target_source(self, AArch64Location::Register(AddressingMode::Direct(register)), AArch64Location::Register(AddressingMode::Based(offset, Register::SP))),
+            AArch64Instruction::B(label) |
+            AArch64Instruction::BCond(_, label) |
+            AArch64Instruction::Bl(label) => AArch64AbstractInstruction::label(self, label),
+            AArch64Instruction::Ret => AArch64AbstractInstruction::simple(self)
+        }
+    }
+
+    fn name(&self) -> String {
+        self.mnemonic()
+    }
+}
@@ -0,0 +1,32 @@
+use instruction::AArch64Instruction;
+use register::Register;
+use storage::AArch64Storage;
+use tb_core::{addressing_mode::AddressingMode, instruction::AbstractInstruction, location::Location, store::{Store, StoreDefaultRegisters}, types::ApplicationContext};
+
+pub mod register;
+pub mod compiler;
+pub mod instruction;
+pub mod generator;
+pub mod storage;
+pub mod syntax;
+
+#[derive(Debug, Clone, Default)]
+pub struct AArch64StoreDefaultRegisters;
+
+impl StoreDefaultRegisters<Register> for AArch64StoreDefaultRegisters {
+    fn initialize() -> Vec<Register> {
+        vec![
+            // Temporary registers, nested expressions use the next one. X16 and X17 are the scratch registers.
+            Register::X9, Register::X10, Register::X11, Register::X12, Register::X13, Register::X14, Register::X15,
+
+            // D31 is the floating point scratch register
+            Register::D16, Register::D17, Register::D18, Register::D19, Register::D20, Register::D21, Register::D22
+        ]
+    }
+}
+
+pub type AArch64Store = Store<Register, Location<Register>, AArch64StoreDefaultRegisters>;
+pub type AArch64AddressingMode = AddressingMode<Register>;
+pub type AArch64Location = Location<Register>;
+pub type AArch64ApplicationContext = ApplicationContext<AArch64Instruction, AArch64Storage>;
+pub type AArch64AbstractInstruction = AbstractInstruction<AArch64Instruction>;
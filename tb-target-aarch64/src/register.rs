@@ -0,0 +1,98 @@
+use std::fmt::Display;
+
+use tb_core::types::{RegisterSize, RegisterTrait};
+
+#[repr(usize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Register {
+    X0, X1, X2, X3, X4, X5, X6, X7, X8, X9, X10, X11, X12, X13, X14, X15, X16, X17, X18, X19, X20, X21, X22, X23, X24, X25, X26, X27, X28, X29, X30, // 64bit General Purpose Registers
+    W0, W1, W2, W3, W4, W5, W6, W7, W8, W9, W10, W11, W12, W13, W14, W15, W16, W17, W18, W19, W20, W21, W22, W23, W24, W25, W26, W27, W28, W29, W30, // 32bit views of the General Purpose Registers
+    D0, D1, D2, D3, D4, D5, D6, D7, D8, D9, D10, D11, D12, D13, D14, D15, D16, D17, D18, D19, D20, D21, D22, D23, D24, D25, D26, D27, D28, D29, D30, D31, // 64bit Floating Point Registers
+    S0, S1, S2, S3, S4, S5, S6, S7, S8, S9, S10, S11, S12, S13, S14, S15, S16, S17, S18, S19, S20, S21, S22, S23, S24, S25, S26, S27, S28, S29, S30, S31, // 32bit views of the Floating Point Registers
+    SP, XZR, WZR // Stack pointer and the zero registers
+}
+
+pub const X_REGISTERS: [Register; 31] = [
+    Register::X0, Register::X1, Register::X2, Register::X3, Register::X4, Register::X5, Register::X6, Register::X7,
+    Register::X8, Register::X9, Register::X10, Register::X11, Register::X12, Register::X13, Register::X14, Register::X15,
+    Register::X16, Register::X17, Register::X18, Register::X19, Register::X20, Register::X21, Register::X22, Register::X23,
+    Register::X24, Register::X25, Register::X26, Register::X27, Register::X28, Register::X29, Register::X30
+];
+
+pub const W_REGISTERS: [Register; 31] = [
+    Register::W0, Register::W1, Register::W2, Register::W3, Register::W4, Register::W5, Register::W6, Register::W7,
+    Register::W8, Register::W9, Register::W10, Register::W11, Register::W12, Register::W13, Register::W14, Register::W15,
+    Register::W16, Register::W17, Register::W18, Register::W19, Register::W20, Register::W21, Register::W22, Register::W23,
+    Register::W24, Register::W25, Register::W26, Register::W27, Register::W28, Register::W29, Register::W30
+];
+
+pub const D_REGISTERS: [Register; 32] = [
+    Register::D0, Register::D1, Register::D2, Register::D3, Register::D4, Register::D5, Register::D6, Register::D7,
+    Register::D8, Register::D9, Register::D10, Register::D11, Register::D12, Register::D13, Register::D14, Register::D15,
+    Register::D16, Register::D17, Register::D18, Register::D19, Register::D20, Register::D21, Register::D22, Register::D23,
+    Register::D24, Register::D25, Register::D26, Register::D27, Register::D28, Register::D29, Register::D30, Register::D31
+];
+
+pub const S_REGISTERS: [Register; 32] = [
+    Register::S0, Register::S1, Register::S2, Register::S3, Register::S4, Register::S5, Register::S6, Register::S7,
+    Register::S8, Register::S9, Register::S10, Register::S11, Register::S12, Register::S13, Register::S14, Register::S15,
+    Register::S16, Register::S17, Register::S18, Register::S19, Register::S20, Register::S21, Register::S22, Register::S23,
+    Register::S24, Register::S25, Register::S26, Register::S27, Register::S28, Register::S29, Register::S30, Register::S31
+];
+
+impl Register {
+    /// Floating point register, D or S view
+    pub fn is_vector(&self) -> bool {
+        (Register::D0 as usize..=Register::S31 as usize).contains(&(*self as usize))
+    }
+
+    /// Number of the register in the instruction encoding, views of the same register have the same number
+    pub fn get_number(&self) -> usize {
+        match *self as usize {
+            index if index < Register::W0 as usize => index,
+            index if index < Register::D0 as usize => index - Register::W0 as usize,
+            index if index < Register::S0 as usize => index - Register::D0 as usize,
+            index if index < Register::SP as usize => index - Register::S0 as usize,
+            _ => 31
+        }
+    }
+}
+
+impl RegisterTrait for Register {
+    fn get_register_size(&self) -> RegisterSize {
+        match *self as usize {
+            index if index < Register::W0 as usize => RegisterSize::_64Bit,
+            index if index < Register::D0 as usize => RegisterSize::_32Bit,
+            index if index < Register::S0 as usize => RegisterSize::_64Bit,
+            index if index < Register::SP as usize => RegisterSize::_32Bit,
+            _ => match self {
+                Register::WZR => RegisterSize::_32Bit,
+                _ => RegisterSize::_64Bit
+            }
+        }
+    }
+
+    /// There are no 8bit and 16bit views, they use the 32bit view like the load and store instructions
+    fn get_sized(self, size: RegisterSize) -> Self {
+        let is_wide = matches!(size, RegisterSize::_64Bit | RegisterSize::_128Bit);
+
+        match (self, self.is_vector(), is_wide) {
+            (Register::SP, _, _) => Register::SP,
+            (Register::XZR | Register::WZR, _, true) => Register::XZR,
+            (Register::XZR | Register::WZR, _, false) => Register::WZR,
+            (register, true, true) => D_REGISTERS[register.get_number()],
+            (register, true, false) => S_REGISTERS[register.get_number()],
+            (register, false, true) => X_REGISTERS[register.get_number()],
+            (register, false, false) => W_REGISTERS[register.get_number()]
+        }
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// Compile time checks
+const _: () = assert!(Register::WZR as usize == 31 + 31 + 32 + 32 + 2, "Missing registers");
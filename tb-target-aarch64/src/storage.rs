@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use tb_core::{instruction::StorageTrait, types::NumberType};
+
+#[derive(Debug, Default)]
+pub struct AArch64Storage {
+    pub branch_counter: usize,
+    pub loops: Vec<AArch64LoopBranches>,
+
+    // Return statements jump to the end of the current function
+    pub function_end: Option<String>,
+
+    // Parameter types of the application functions, the arguments are converted to them
+    pub parameters: HashMap<String, Vec<NumberType>>
+}
+
+#[derive(Debug, Clone)]
+pub struct AArch64LoopBranches {
+    pub begin: String,
+    pub end: String
+}
+
+impl AArch64Storage {
+    pub fn create_branch(&mut self) -> String {
+        let name = format!("L{}", self.branch_counter);
+        self.branch_counter += 1;
+        name
+    }
+
+    pub fn begin_loop(&mut self) -> AArch64LoopBranches {
+        let branches = AArch64LoopBranches { begin: self.create_branch(), end: self.create_branch() };
+        self.loops.push(branches.clone());
+        branches
+    }
+
+    pub fn end_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    pub fn current_loop(&self) -> Option<&AArch64LoopBranches> {
+        self.loops.last()
+    }
+}
+
+impl StorageTrait for AArch64Storage {
+
+}
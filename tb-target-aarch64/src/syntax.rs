@@ -0,0 +1,148 @@
+use std::cell::Cell;
+
+use tb_core::{addressing_mode::AddressingMode, instruction::{AbstractInstruction, InstructionTrait, StorageTrait}, syntax::{escape, AsmStructure, Data, DataItem, SyntaxGeneratorTrait}, target::OperatingSystem, types::{ApplicationContext, Number}};
+
+use crate::{instruction::AArch64Instruction, AArch64Location};
+
+/// GNU assembler syntax of A64, comments start with "//" because "#" is the immediate prefix
+#[derive(Debug, Clone, Default)]
+pub struct AArch64SyntaxGenerator {
+    in_branch: Cell<bool>
+}
+
+impl SyntaxGeneratorTrait<AArch64Instruction> for AArch64SyntaxGenerator {
+    fn generate<S: StorageTrait>(&self, context: &mut ApplicationContext<AArch64Instruction, S>) -> String {
+        let mut buffer = String::new();
+
+        for item in context.datas.items.clone().into_iter() {
+            self.generate_data(item, &mut buffer, context);
+        }
+
+        buffer.push_str("\r\n.text\r\n");
+        for item in context.instructions.items.clone().into_iter() {
+            self.process_item(item, context, &mut buffer);
+        }
+
+        buffer.push_str(context.os_specific_defs.end_of_file_instructions());
+        buffer
+    }
+}
+
+impl AArch64SyntaxGenerator {
+    fn process_item<S: StorageTrait>(&self, item: AsmStructure<AArch64Instruction>, context: &mut ApplicationContext<AArch64Instruction, S>, buffer: &mut String) {
+        match item {
+            AsmStructure::Branch(name) => self.generate_branch(name, buffer),
+            AsmStructure::BranchFinished => self.in_branch.set(false),
+            AsmStructure::Comment(comment) => self.generate_comment(comment, buffer),
+            AsmStructure::Instruction(inst) => self.generate_instruction(*inst, context, buffer),
+        };
+    }
+
+    /// Every item is 8 byte aligned, so the doubles could be loaded directly
+    fn generate_data<S: StorageTrait>(&self, item: DataItem, buffer: &mut String, context: &mut ApplicationContext<AArch64Instruction, S>) {
+        let section = match item.values.iter().any(|data| matches!(data, Data::String(_))) {
+            true => context.os_specific_defs.readonly_string_section(),
+            false => context.os_specific_defs.readonly_data_section()
+        };
+
+        buffer.push_str(&format!("\r\n{}\r\n.p2align 3\r\n.{}:\r\n", section, &item.label));
+        for data in item.values.into_iter() {
+            match data {
+                Data::String(data) => buffer.push_str(&format!("    .string \"{}\"\r\n", escape(&data))),
+                Data::Byte(data) => buffer.push_str(&format!("    .byte {}\r\n", &data)),
+
+                // Bit pattern keeps the exact value
+                Data::Float(data) => buffer.push_str(&format!("    .word {:#010x} // {}\r\n", data.to_bits(), &data)),
+                Data::Double(data) => buffer.push_str(&format!("    .xword {:#018x} // {}\r\n", data.to_bits(), &data)),
+            }
+        }
+    }
+
+    /// Labels are used with adrp and the offset in the page, Mach-O has its own relocation operators
+    fn generate_location(&self, location: AArch64Location, is_page: bool, operating_system: OperatingSystem) -> String {
+        match location {
+            AArch64Location::Memory(memory) => format!("{:#01x}", memory),
+            AArch64Location::Register(mode) => match mode {
+                AddressingMode::Direct(reg) => reg.to_string().to_lowercase(),
+                AddressingMode::Indirect(reg) => format!("[{}]", reg.to_string().to_lowercase()),
+                AddressingMode::Based(0, reg) => format!("[{}]", reg.to_string().to_lowercase()),
+                AddressingMode::Based(num, reg) => format!("[{}, #{}]", reg.to_string().to_lowercase(), num),
+            },
+
+            // Floating point immediate is only the zero of fcmp, it needs the decimal point
+            AArch64Location::Imm(Number::Float(imm)) => format!("#{:?}", imm),
+            AArch64Location::Imm(Number::Double(imm)) => format!("#{:?}", imm),
+            AArch64Location::Imm(imm) => format!("#{}", imm),
+            AArch64Location::Label(label) => match (is_page, operating_system) {
+                (true, OperatingSystem::MacOS) => format!(".{}@PAGE", label),
+                (true, _) => format!(".{}", label),
+                (false, OperatingSystem::MacOS) => format!(".{}@PAGEOFF", label),
+                (false, _) => format!(":lo12:.{}", label)
+            },
+
+            // There is no register allocator for AArch64
+            AArch64Location::Virtual(register) => format!("v{}", register.index),
+        }
+    }
+
+    fn generate_instruction<S: StorageTrait>(&self, inst: AArch64Instruction, context: &mut ApplicationContext<AArch64Instruction, S>, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        // Write-back addressing modes are only used by these instructions
+        let operands = match &inst {
+            AArch64Instruction::StpPre { first, second, offset } => Some(format!("{}, {}, [sp, #{}]!", first.to_string().to_lowercase(), second.to_string().to_lowercase(), offset)),
+            AArch64Instruction::LdpPost { first, second, offset } => Some(format!("{}, {}, [sp], #{}", first.to_string().to_lowercase(), second.to_string().to_lowercase(), offset)),
+            AArch64Instruction::StrPre { source, offset } => Some(format!("{}, [sp, #{}]!", source.to_string().to_lowercase(), offset)),
+            AArch64Instruction::LdrPost { target, offset } => Some(format!("{}, [sp], #{}", target.to_string().to_lowercase(), offset)),
+            AArch64Instruction::Movk { value, shift, target, .. } => Some(format!("{}, #{}, lsl #{}", self.generate_location(target.clone(), false, context.target.operating_system), value, shift)),
+            _ => None
+        };
+
+        let is_page = matches!(inst, AArch64Instruction::Adrp { .. });
+        let AbstractInstruction { inst, target, source1, source2, label, comment } = inst.convert();
+        buffer.push_str(&inst.name());
+
+        let operands = operands.unwrap_or_else(|| [target, source1, source2].into_iter()
+            .flatten()
+            .map(|location| self.generate_location(location, is_page, context.target.operating_system))
+            .chain(label)
+            .collect::<Vec<_>>()
+            .join(", "));
+
+        if !operands.is_empty() {
+            buffer.push(' ');
+            buffer.push_str(&operands);
+        }
+
+        if let Some(comment) = comment {
+            buffer.push_str(" // ");
+            buffer.push_str(&comment);
+        }
+
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_branch(&self, name: String, buffer: &mut String) {
+        // Branch outside of the function is the function itself, it is visible to the other objects
+        if !self.in_branch.get() {
+            buffer.push_str(&format!(".globl {}\r\n", &name));
+        }
+
+        self.in_branch.set(true);
+        buffer.push_str(&name);
+        buffer.push(':');
+        buffer.push_str("\r\n");
+    }
+
+    fn generate_comment(&self, name: String, buffer: &mut String) {
+        if self.in_branch.get() {
+            buffer.push_str("    ");
+        }
+
+        buffer.push_str("// ");
+        buffer.push_str(&name);
+        buffer.push_str("\r\n");
+    }
+}
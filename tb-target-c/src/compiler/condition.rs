@@ -0,0 +1,54 @@
+use tb_core::{error::SourceError, types::{Condition, ConditionDiscriminant}};
+
+use crate::scope::CScope;
+
+use super::{expression::CExpressionCompiler};
+
+pub struct CConditionCompiler;
+
+impl CConditionCompiler {
+    /// Condition without the outer parentheses, C has the same short circuit evaluation for And and Or.
+    /// Comparisons with NaN are false except not equal, same as the processor.
+    pub fn compile(condition: Condition, scope: &CScope) -> Result<String, SourceError> {
+        let operation = ConditionDiscriminant::from(&condition);
+
+        match condition {
+            Condition::And { left, right } |
+            Condition::Or { left, right } => Ok(format!("{} {} {}", Self::compile_nested(*left, scope)?, Self::operator(operation), Self::compile_nested(*right, scope)?)),
+            Condition::Not { source } => Ok(format!("{}{}", Self::operator(operation), Self::compile_nested(*source, scope)?)),
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => {
+                let number_type = CExpressionCompiler::get_type(&left, scope)?.common(CExpressionCompiler::get_type(&right, scope)?);
+                let left = CExpressionCompiler::compile(left, number_type, scope)?;
+                let right = CExpressionCompiler::compile(right, number_type, scope)?;
+                Ok(format!("{} {} {}", left, Self::operator(operation), right))
+            }
+        }
+    }
+
+    /// Condition that could be used as an operand
+    pub fn compile_nested(condition: Condition, scope: &CScope) -> Result<String, SourceError> {
+        match condition {
+            Condition::Not { .. } => Self::compile(condition, scope),
+            condition => Ok(format!("({})", Self::compile(condition, scope)?))
+        }
+    }
+
+    fn operator(operation: ConditionDiscriminant) -> &'static str {
+        match operation {
+            ConditionDiscriminant::Eq => "==",
+            ConditionDiscriminant::Ne => "!=",
+            ConditionDiscriminant::Gr => ">",
+            ConditionDiscriminant::Ge => ">=",
+            ConditionDiscriminant::Ls => "<",
+            ConditionDiscriminant::Le => "<=",
+            ConditionDiscriminant::And => "&&",
+            ConditionDiscriminant::Or => "||",
+            ConditionDiscriminant::Not => "!"
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use tb_core::{error::CodeGenerationError, scope::StatementCompiler, types::{Block, Definition, NumberType, Parameter, Statement}};
+
+use crate::{scope::CScope, types::type_name, CApplicationContext, INDENT};
+
+use super::CStatementCompiler;
+
+pub struct CDefinitionCompiler;
+
+impl CDefinitionCompiler {
+    pub fn compile(definition: Definition, context: &mut CApplicationContext) -> Result<(), CodeGenerationError> {
+        match definition {
+            Definition::Function { name, parameters, block } => Self::compile_function(name, parameters, block, context),
+        }
+    }
+
+    /// Functions without a returned value are void, calls to them could be compiled before the function itself
+    pub fn returns_value(definition: &Definition) -> bool {
+        match definition {
+            Definition::Function { name, block, .. } => name == "main" || Self::block_returns_value(block)
+        }
+    }
+
+    fn block_returns_value(block: &Block) -> bool {
+        block.items.iter().any(|item| match item {
+            Statement::Return(value) => value.is_some(),
+            Statement::If { true_block, false_block, .. } => Self::block_returns_value(true_block) || false_block.as_ref().map(Self::block_returns_value).unwrap_or_default(),
+            Statement::While { block, .. } |
+            Statement::Loop { block } => Self::block_returns_value(block),
+            _ => false
+        })
+    }
+
+    /// Returned values are converted to one type, integers are extended to 64bit like the registers
+    fn return_type(return_types: &[NumberType]) -> NumberType {
+        match return_types {
+            return_types if return_types.iter().all(|return_type| *return_type == NumberType::Float) => NumberType::Float,
+            return_types if return_types.iter().any(|return_type| return_type.is_float()) => NumberType::Double,
+            return_types if return_types.iter().any(|return_type| return_type.is_signed()) => NumberType::I64,
+            _ => NumberType::U64
+        }
+    }
+
+    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut CApplicationContext) -> Result<(), CodeGenerationError> {
+        let mut scope = CScope::new(context.reserved_names());
+        scope.returns_value = context.functions.get(&name).copied().unwrap_or_default();
+
+        for parameter in arguments.into_iter() {
+            scope.add_parameter(&parameter.name, parameter.param_type);
+        }
+
+        // Function body is compiled first, the variables are declared before it
+        let body_position = context.lines.len();
+        context.indent += 1;
+
+        // Like C, main returns zero if it reaches the end without a return
+        let is_main = name == "main";
+        let returns_at_end = matches!(block.items.last(), Some(Statement::Return(_)));
+
+        for (index, item) in block.items.into_iter().enumerate() {
+            CStatementCompiler::compile(item, &mut scope, context).map_err(|error| CodeGenerationError::new(&name, index, error))?;
+        }
+
+        if is_main && !returns_at_end {
+            context.add_line("return 0;".to_owned());
+        }
+
+        context.indent -= 1;
+        context.add_line("}".to_owned());
+        context.add_line(String::new());
+
+        let return_type = match (is_main, scope.returns_value) {
+            (true, _) => "int",
+            (false, true) => type_name(Self::return_type(&scope.return_types)),
+            (false, false) => "void"
+        };
+
+        let parameters = match scope.get_parameters().is_empty() {
+            true => "void".to_owned(),
+            false => scope.get_parameters().iter().map(|parameter| format!("{} {}", type_name(parameter.var_type), parameter.target_name)).collect::<Vec<_>>().join(", ")
+        };
+
+        let signature = format!("{} {}({})", return_type, name, parameters);
+        if !is_main {
+            context.prototypes.push(format!("{};", signature));
+        }
+
+        let mut header = vec![format!("{} {{", signature)];
+        header.extend(scope.get_variables().iter().map(|variable| format!("{}{} {};", INDENT, type_name(variable.var_type), variable.target_name)));
+        context.lines.splice(body_position..body_position, header);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use tb_core::{error::SourceError, types::{Expression, ExpressionDiscriminant, Number, NumberType, Value}};
+
+use crate::{scope::CScope, types::{literal, string_literal, type_name, unsigned_type_name}};
+
+use super::{condition::CConditionCompiler};
+
+pub struct CExpressionCompiler;
+
+impl CExpressionCompiler {
+    /// C expression of the value converted to the expected type, the cast is written only if the types are different
+    pub fn compile(expression: Expression, number_type: NumberType, scope: &CScope) -> Result<String, SourceError> {
+        let expression_type = Self::get_type(&expression, scope)?;
+        let source = Self::compile_expression(expression, expression_type, scope)?;
+
+        match expression_type == number_type {
+            true => Ok(source),
+            false => Ok(format!("({}){}", type_name(number_type), source))
+        }
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    pub fn get_type(expression: &Expression, scope: &CScope) -> Result<NumberType, SourceError> {
+        NumberType::of_expression(expression, &|variable| scope.find_variable(variable).map(|item| item.var_type).ok_or(SourceError::VariableNotFound(variable.to_owned())))
+    }
+
+    /// Value with the type of the expression, operations are always in parentheses
+    fn compile_expression(expression: Expression, number_type: NumberType, scope: &CScope) -> Result<String, SourceError> {
+        let operation = ExpressionDiscriminant::from(&expression);
+
+        match expression {
+            Expression::Value(Value::Variable(variable)) => scope.find_variable(&variable).map(|item| item.target_name.clone()).ok_or(SourceError::VariableNotFound(variable)),
+            Expression::Value(Value::Number(num)) => Ok(literal(num)),
+
+            // Address of the string, like the assembly backends
+            Expression::Value(Value::String(data)) => Ok(format!("(uint64_t)(uintptr_t){}", string_literal(&data))),
+            Expression::Condition(condition) => Ok(format!("(bool){}", CConditionCompiler::compile_nested(*condition, scope)?)),
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } |
+            Expression::Div { divider: right, divided: left } |
+            Expression::Modulo { divider: right, divided: left } => Self::compile_binary(operation, *left, *right, number_type, scope),
+            Expression::ShiftLeft { target, source } |
+            Expression::ShiftRight { target, source } => Self::compile_shift(operation, *target, *source, number_type, scope),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Self::compile_unary(operation, *source, number_type, scope)
+        }
+    }
+
+    fn operator(operation: ExpressionDiscriminant) -> &'static str {
+        match operation {
+            ExpressionDiscriminant::Add | ExpressionDiscriminant::Inc => "+",
+            ExpressionDiscriminant::Sub | ExpressionDiscriminant::Dec | ExpressionDiscriminant::BitwiseNeg => "-",
+            ExpressionDiscriminant::Mul => "*",
+            ExpressionDiscriminant::Div => "/",
+            ExpressionDiscriminant::Modulo => "%",
+            ExpressionDiscriminant::ShiftLeft => "<<",
+            ExpressionDiscriminant::ShiftRight => ">>",
+            ExpressionDiscriminant::BitwiseAnd => "&",
+            ExpressionDiscriminant::BitwiseOr => "|",
+            ExpressionDiscriminant::BitwiseXor => "^",
+            ExpressionDiscriminant::BitwiseNot => "~",
+            ExpressionDiscriminant::Condition | ExpressionDiscriminant::Value => ""
+        }
+    }
+
+    /// Both operands are converted to the type of the operation
+    fn compile_binary(operation: ExpressionDiscriminant, left: Expression, right: Expression, number_type: NumberType, scope: &CScope) -> Result<String, SourceError> {
+        let left = Self::compile(left, number_type, scope)?;
+        let right = Self::compile(right, number_type, scope)?;
+        let operator = Self::operator(operation);
+
+        match (number_type, operation) {
+            (NumberType::Float | NumberType::Double, ExpressionDiscriminant::Add | ExpressionDiscriminant::Sub | ExpressionDiscriminant::Mul | ExpressionDiscriminant::Div) => Ok(format!("({} {} {})", left, operator, right)),
+            (NumberType::Float | NumberType::Double, _) => Err(SourceError::UnsupportedFloatOperation),
+
+            // Signed overflow wraps around like the processor does
+            (number_type, ExpressionDiscriminant::Add | ExpressionDiscriminant::Sub | ExpressionDiscriminant::Mul) if number_type.is_signed() => {
+                let unsigned_type = unsigned_type_name(number_type);
+                Ok(format!("({})(({}){} {} ({}){})", type_name(number_type), unsigned_type, left, operator, unsigned_type, right))
+            },
+            _ => Ok(format!("({} {} {})", left, operator, right))
+        }
+    }
+
+    /// Processor uses only the lower bits of the shift count, signed values are shifted arithmetically to right
+    fn compile_shift(operation: ExpressionDiscriminant, target: Expression, source: Expression, number_type: NumberType, scope: &CScope) -> Result<String, SourceError> {
+        if number_type.is_float() {
+            return Err(SourceError::UnsupportedFloatOperation);
+        }
+
+        let unsigned_type = unsigned_type_name(number_type);
+        let source_type = Self::get_type(&source, scope)?;
+        let target = Self::compile(target, number_type, scope)?;
+        let count = format!("(({}){} & {})", unsigned_type, Self::compile(source, source_type, scope)?, number_type.size() as u32 * 8 - 1);
+
+        match (number_type.is_signed(), operation) {
+            (true, ExpressionDiscriminant::ShiftLeft) => Ok(format!("({})(({}){} << {})", type_name(number_type), unsigned_type, target, count)),
+            _ => Ok(format!("({} {} {})", target, Self::operator(operation), count))
+        }
+    }
+
+    /// There is no negate instruction for SSE, floating point values are subtracted from zero
+    fn compile_unary(operation: ExpressionDiscriminant, source: Expression, number_type: NumberType, scope: &CScope) -> Result<String, SourceError> {
+        let source = Self::compile(source, number_type, scope)?;
+        let operator = Self::operator(operation);
+
+        match (number_type.is_float(), operation) {
+            (true, ExpressionDiscriminant::BitwiseNeg) => Ok(format!("({} - {})", literal(Number::I32(0).convert(number_type)), source)),
+            (true, ExpressionDiscriminant::Inc | ExpressionDiscriminant::Dec) => Ok(format!("({} {} {})", source, operator, literal(Number::I32(1).convert(number_type)))),
+            (true, _) => Err(SourceError::UnsupportedFloatOperation),
+            (false, ExpressionDiscriminant::BitwiseNot) => Ok(format!("(~{})", source)),
+            (false, ExpressionDiscriminant::BitwiseNeg) if number_type.is_signed() => Ok(format!("(({})-({}){})", type_name(number_type), unsigned_type_name(number_type), source)),
+            (false, ExpressionDiscriminant::BitwiseNeg) => Ok(format!("(-{})", source)),
+            (false, _) if number_type.is_signed() => Ok(format!("({})(({}){} {} 1u)", type_name(number_type), unsigned_type_name(number_type), source, operator)),
+            (false, _) => Ok(format!("({} {} 1u)", source, operator))
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod expression;
+mod statement;
+mod definition;
+mod condition;
+
+pub use expression::CExpressionCompiler;
+pub use statement::CStatementCompiler;
+pub use definition::CDefinitionCompiler;
+pub use condition::CConditionCompiler;
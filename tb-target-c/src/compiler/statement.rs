@@ -0,0 +1,196 @@
+use tb_core::{error::SourceError, scope::StatementCompiler, types::{Block, Condition, Expression, NumberType, Statement, Value}};
+
+use crate::{scope::CScope, types::{string_literal, type_name}, CApplicationContext};
+
+use super::{condition::CConditionCompiler, expression::CExpressionCompiler};
+
+/// C compiler adds the underscore prefix if the target needs it
+const PRINT_FUNCTION: &str = "printf";
+
+pub struct CStatementCompiler;
+
+impl StatementCompiler for CStatementCompiler {
+    type Scope = CScope;
+    type Context = CApplicationContext;
+    type Error = SourceError;
+
+    fn compile(statement: Statement, scope: &mut CScope, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        match statement {
+            Statement::Assign { name, assigne } => Self::compile_assign(scope, name, assigne, context),
+            Statement::Declare { name, ty, init } => Self::compile_declare(scope, name, ty, init, context),
+            Statement::Call { name, arguments, assign, is_variadic } => Self::compile_call(scope, name, arguments, assign, is_variadic, context),
+            Statement::Print { format, arguments } => Self::compile_print(scope, format, arguments, context),
+            Statement::Return(expr) => Self::compile_return(scope, expr, context),
+            Statement::If { condition, true_block, false_block } => Self::compile_if(scope, condition, true_block, false_block, context),
+            Statement::While { condition, block } => Self::compile_while(scope, condition, block, context),
+            Statement::Loop { block } => Self::compile_loop(scope, block, context),
+            Statement::Break => Self::compile_jump(scope, "break", context),
+            Statement::Continue => Self::compile_jump(scope, "continue", context),
+        }
+    }
+}
+
+impl CStatementCompiler {
+    fn compile_assign(scope: &mut CScope, name: String, assigne: Expression, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        // Undeclared variable takes the type of the expression
+        let var_type = match scope.find_variable(&name) {
+            Some(variable) => variable.var_type,
+            None => CExpressionCompiler::get_type(&assigne, scope)?
+        };
+
+        let value = CExpressionCompiler::compile(assigne, var_type, scope)?;
+
+        let c_name = match scope.find_variable(&name) {
+            Some(variable) => variable.target_name.clone(),
+            None => scope.add_variable(&name, var_type).target_name.clone()
+        };
+
+        context.add_line(format!("{} = {};", c_name, value));
+        Ok(())
+    }
+
+    fn compile_declare(scope: &mut CScope, name: String, var_type: NumberType, init: Option<Expression>, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        // Initial value could use the shadowed variable, so, calculate it before the declaration
+        let value = match init {
+            Some(init) => Some(CExpressionCompiler::compile(init, var_type, scope)?),
+            None => None
+        };
+
+        let c_name = scope.add_variable(&name, var_type).target_name.clone();
+
+        if let Some(value) = value {
+            context.add_line(format!("{} = {};", c_name, value));
+        }
+
+        Ok(())
+    }
+
+    /// Arguments get the default argument promotions of C, the format should match their types
+    fn variadic_argument(argument: Value, scope: &CScope, context: &CApplicationContext) -> Result<String, SourceError> {
+        match argument {
+            Value::String(data) => Ok(string_literal(&data)),
+
+            // Data labels could be used as the string arguments
+            Value::Variable(name) if scope.find_variable(&name).is_none() && context.datas.items.iter().any(|item| item.label == name) => Ok(name),
+            argument => {
+                let argument = Expression::Value(argument);
+                let argument_type = CExpressionCompiler::get_type(&argument, scope)?;
+                CExpressionCompiler::compile(argument, argument_type, scope)
+            }
+        }
+    }
+
+    fn compile_print(scope: &mut CScope, format: String, arguments: Vec<Value>, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        let mut tmp_arguments = Vec::new();
+        tmp_arguments.push(Value::String(format));
+        tmp_arguments.extend(arguments);
+
+        Self::compile_call(scope, PRINT_FUNCTION.to_owned(), tmp_arguments, None, true, context)
+    }
+
+    /// Arguments of the application functions are converted by the prototypes, the others get the variadic arguments
+    fn compile_call(scope: &mut CScope, name: String, arguments: Vec<Value>, assign: Option<String>, is_variadic: bool, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        let call = match context.functions.get(&name).copied() {
+            Some(returns_value) => {
+                if assign.is_some() && !returns_value {
+                    return Err(SourceError::MissingReturnValue(name));
+                }
+
+                let mut c_arguments = Vec::new();
+                for argument in arguments.into_iter() {
+                    let argument = Expression::Value(argument);
+                    let argument_type = CExpressionCompiler::get_type(&argument, scope)?;
+                    c_arguments.push(CExpressionCompiler::compile(argument, argument_type, scope)?);
+                }
+                format!("{}({})", name, c_arguments.join(", "))
+            },
+            None => {
+                let name = match is_variadic && name.trim_start_matches('_') == PRINT_FUNCTION {
+                    true => PRINT_FUNCTION.to_owned(),
+                    false => {
+                        context.add_external(&name);
+                        name
+                    }
+                };
+
+                let mut c_arguments = Vec::new();
+                for argument in arguments.into_iter() {
+                    c_arguments.push(Self::variadic_argument(argument, scope, context)?);
+                }
+                format!("{}({})", name, c_arguments.join(", "))
+            }
+        };
+
+        match assign {
+            Some(assigned) => {
+                // Undeclared variable gets the full register
+                let (c_name, var_type) = match scope.find_variable(&assigned) {
+                    Some(variable) => (variable.target_name.clone(), variable.var_type),
+                    None => (scope.add_variable(&assigned, NumberType::I64).target_name.clone(), NumberType::I64)
+                };
+                context.add_line(format!("{} = ({}){};", c_name, type_name(var_type), call));
+            },
+            None => context.add_line(format!("{};", call))
+        };
+
+        Ok(())
+    }
+
+    fn compile_if(scope: &mut CScope, condition: Condition, true_block: Block, false_block: Option<Block>, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        context.begin_block(format!("if ({}) {{", CConditionCompiler::compile(condition, scope)?));
+        Self::compile_block(true_block, scope, context)?;
+
+        if let Some(false_block) = false_block {
+            context.end_block("} else {".to_owned());
+            context.indent += 1;
+            Self::compile_block(false_block, scope, context)?;
+        }
+
+        context.end_block("}".to_owned());
+        Ok(())
+    }
+
+    fn compile_while(scope: &mut CScope, condition: Condition, block: Block, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        context.begin_block(format!("while ({}) {{", CConditionCompiler::compile(condition, scope)?));
+        Self::compile_loop_block(scope, block, context)
+    }
+
+    fn compile_loop(scope: &mut CScope, block: Block, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        context.begin_block("for (;;) {".to_owned());
+        Self::compile_loop_block(scope, block, context)
+    }
+
+    fn compile_loop_block(scope: &mut CScope, block: Block, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        scope.loop_depth += 1;
+        Self::compile_block(block, scope, context)?;
+        scope.loop_depth -= 1;
+
+        context.end_block("}".to_owned());
+        Ok(())
+    }
+
+    fn compile_jump(scope: &mut CScope, jump: &str, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        if scope.loop_depth == 0 {
+            return Err(SourceError::OutsideOfLoop);
+        }
+
+        context.add_line(format!("{};", jump));
+        Ok(())
+    }
+
+    /// Function without a returned value is void, the others return zero for the empty return
+    fn compile_return(scope: &mut CScope, expr: Option<Value>, context: &mut CApplicationContext) -> Result<(), SourceError> {
+        match expr {
+            Some(value) => {
+                let value = Expression::Value(value);
+                let value_type = CExpressionCompiler::get_type(&value, scope)?;
+                scope.return_types.push(value_type);
+                context.add_line(format!("return {};", CExpressionCompiler::compile(value, value_type, scope)?));
+            },
+            None if scope.returns_value => context.add_line("return 0;".to_owned()),
+            None => context.add_line("return;".to_owned())
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use tb_core::{error::{CodeGenerationError, TBError}, syntax::Syntax, target::Target, types::{AssemblyGenerator, DataItemCollection, Definition}};
+
+use crate::{compiler::CDefinitionCompiler, types::data_literal, CApplicationContext};
+
+/// C99 source instead of the assembly, it could be compiled for any target that has a C compiler
+#[derive(Debug, Clone, Default)]
+pub struct CSourceGenerator {
+    target: Target
+}
+
+impl CSourceGenerator {
+    /// Functions of the definitions before the declarations are added
+    pub fn compile(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<CApplicationContext, CodeGenerationError> {
+        let mut context = CApplicationContext::new(self.target, datas);
+        context.functions = definitions.iter().map(|definition| match definition {
+            Definition::Function { name, .. } => (name.clone(), CDefinitionCompiler::returns_value(definition))
+        }).collect();
+
+        for item in definitions.into_iter() {
+            CDefinitionCompiler::compile(item, &mut context)?;
+        }
+
+        Ok(context)
+    }
+
+    pub fn write(context: CApplicationContext) -> String {
+        let mut lines = vec![
+            "#include <stdbool.h>".to_owned(),
+            "#include <stdint.h>".to_owned(),
+            "#include <stdio.h>".to_owned(),
+            String::new()
+        ];
+
+        if !context.datas.items.is_empty() {
+            lines.extend(context.datas.items.iter().map(|item| format!("static const char {}[] = {};", item.label, data_literal(&item.values))));
+            lines.push(String::new());
+        }
+
+        // Parameters of the external functions are not known
+        if !context.externals.is_empty() {
+            lines.extend(context.externals.iter().map(|name| format!("int64_t {}();", name)));
+            lines.push(String::new());
+        }
+
+        if !context.prototypes.is_empty() {
+            lines.extend(context.prototypes);
+            lines.push(String::new());
+        }
+
+        lines.extend(context.lines);
+        lines.into_iter().map(|line| format!("{}\r\n", line)).collect()
+    }
+}
+
+impl AssemblyGenerator for CSourceGenerator {
+    fn new(target: Target) -> Self {
+        Self { target }
+    }
+
+    /// C has no assembly syntax, the requested syntax is ignored
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, _: Syntax) -> Result<String, TBError> {
+        let context = self.compile(definitions, datas)?;
+        Ok(Self::write(context))
+    }
+}
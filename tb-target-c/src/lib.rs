@@ -0,0 +1,70 @@
+use std::collections::{HashMap, HashSet};
+
+use tb_core::{target::Target, types::DataItemCollection};
+
+pub mod compiler;
+pub mod generator;
+pub mod scope;
+pub mod types;
+
+/// Names that could not be used for the variables
+const RESERVED_NAMES: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum", "extern", "float", "for",
+    "goto", "if", "inline", "int", "long", "register", "restrict", "return", "short", "signed", "sizeof", "static", "struct",
+    "switch", "typedef", "union", "unsigned", "void", "volatile", "while", "_Bool", "_Complex", "_Imaginary",
+    "bool", "true", "false", "int8_t", "uint8_t", "int16_t", "uint16_t", "int32_t", "uint32_t", "int64_t", "uint64_t",
+    "uintptr_t", "printf", "main", "NULL"
+];
+
+pub const INDENT: &str = "    ";
+
+/// Lines of the functions, declarations before them are written after all functions are compiled
+#[derive(Debug, Default)]
+pub struct CApplicationContext {
+    pub target: Target,
+    pub datas: DataItemCollection,
+
+    /// Functions of the application and whether they return a value or not
+    pub functions: HashMap<String, bool>,
+
+    /// Called functions that are not in the application, they are declared without the parameters
+    pub externals: Vec<String>,
+    pub prototypes: Vec<String>,
+    pub lines: Vec<String>,
+    pub indent: usize
+}
+
+impl CApplicationContext {
+    pub fn new(target: Target, datas: DataItemCollection) -> Self {
+        Self { target, datas, ..Default::default() }
+    }
+
+    pub fn add_line(&mut self, line: String) {
+        self.lines.push(format!("{}{}", INDENT.repeat(self.indent), line));
+    }
+
+    /// Line that opens a brace, following lines are indented
+    pub fn begin_block(&mut self, line: String) {
+        self.add_line(line);
+        self.indent += 1;
+    }
+
+    pub fn end_block(&mut self, line: String) {
+        self.indent -= 1;
+        self.add_line(line);
+    }
+
+    pub fn add_external(&mut self, name: &str) {
+        if !self.externals.iter().any(|external| external == name) {
+            self.externals.push(name.to_owned());
+        }
+    }
+
+    /// Variables could not use the keywords, function names and data labels
+    pub fn reserved_names(&self) -> HashSet<String> {
+        RESERVED_NAMES.iter().map(|name| name.to_string())
+            .chain(self.functions.keys().cloned())
+            .chain(self.datas.items.iter().map(|item| item.label.clone()))
+            .collect()
+    }
+}
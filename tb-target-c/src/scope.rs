@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use tb_core::{scope::{Scope, ScopeNaming, ScopedVariable}, types::NumberType};
+
+/// Shadowing declarations get a suffix, dots are not allowed in the C names
+#[derive(Debug, Clone)]
+pub struct CNaming;
+
+impl ScopeNaming for CNaming {
+    fn variable_name(name: &str, index: usize) -> String {
+        match index {
+            0 => name.to_owned(),
+            index => format!("{}_{}", name, index)
+        }
+    }
+}
+
+/// Variables of a function. C variables are visible only in their block, so, all of them are declared at the
+/// beginning of the function and every declaration gets its own name.
+#[derive(Debug, Default)]
+pub struct CScope {
+    variables: Scope<CNaming>,
+    parameters: usize,
+    pub return_types: Vec<NumberType>,
+    pub returns_value: bool,
+    pub loop_depth: usize
+}
+
+impl CScope {
+    pub fn new(reserved_names: HashSet<String>) -> Self {
+        Self { variables: Scope::new(reserved_names), ..Default::default() }
+    }
+
+    pub fn find_variable(&self, variable: &str) -> Option<&ScopedVariable> {
+        self.variables.find_variable(variable)
+    }
+
+    /// Parameters should be added before the variables
+    pub fn add_parameter(&mut self, name: &str, var_type: NumberType) -> &ScopedVariable {
+        self.parameters += 1;
+        self.add_variable(name, var_type)
+    }
+
+    pub fn add_variable(&mut self, name: &str, var_type: NumberType) -> &ScopedVariable {
+        self.variables.add_variable(name, var_type)
+    }
+
+    pub fn get_parameters(&self) -> &[ScopedVariable] {
+        &self.variables.get_variables()[..self.parameters]
+    }
+
+    pub fn get_variables(&self) -> &[ScopedVariable] {
+        &self.variables.get_variables()[self.parameters..]
+    }
+}
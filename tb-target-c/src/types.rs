@@ -0,0 +1,94 @@
+use tb_core::{syntax::Data, types::{Number, NumberType}};
+
+pub fn type_name(number_type: NumberType) -> &'static str {
+    match number_type {
+        NumberType::I8 => "int8_t",
+        NumberType::U8 => "uint8_t",
+        NumberType::I16 => "int16_t",
+        NumberType::U16 => "uint16_t",
+        NumberType::I32 => "int32_t",
+        NumberType::U32 => "uint32_t",
+        NumberType::I64 => "int64_t",
+        NumberType::U64 => "uint64_t",
+        NumberType::Float => "float",
+        NumberType::Double => "double",
+        NumberType::Bool => "bool"
+    }
+}
+
+/// Signed overflow is undefined in C, wrapping operations are calculated with the unsigned type of the same size
+pub fn unsigned_type_name(number_type: NumberType) -> &'static str {
+    match number_type {
+        NumberType::I8 | NumberType::U8 | NumberType::Bool => "uint8_t",
+        NumberType::I16 | NumberType::U16 => "uint16_t",
+        NumberType::I32 | NumberType::U32 | NumberType::Float => "uint32_t",
+        NumberType::I64 | NumberType::U64 | NumberType::Double => "uint64_t"
+    }
+}
+
+/// Small integers are written as int, the operations convert them to the expected type
+pub fn literal(number: Number) -> String {
+    match number {
+        Number::I8(num) => num.to_string(),
+        Number::U8(num) => num.to_string(),
+        Number::I16(num) => num.to_string(),
+        Number::U16(num) => num.to_string(),
+        Number::I32(i32::MIN) => "INT32_MIN".to_owned(),
+        Number::I32(num) => num.to_string(),
+        Number::U32(num) => format!("{}u", num),
+        Number::I64(i64::MIN) => "INT64_MIN".to_owned(),
+        Number::I64(num) => format!("INT64_C({})", num),
+        Number::U64(num) => format!("UINT64_C({})", num),
+        Number::Float(num) if num.is_nan() => "(0.0f / 0.0f)".to_owned(),
+        Number::Float(num) if num.is_infinite() => format!("({:?}f / 0.0f)", num.signum()),
+        Number::Float(num) => format!("{:?}f", num),
+        Number::Double(num) if num.is_nan() => "(0.0 / 0.0)".to_owned(),
+        Number::Double(num) if num.is_infinite() => format!("({:?} / 0.0)", num.signum()),
+        Number::Double(num) => format!("{:?}", num),
+        Number::Bool(num) => num.to_string()
+    }
+}
+
+pub fn string_literal(data: &str) -> String {
+    bytes_literal(data.as_bytes())
+}
+
+/// Bytes of the data item as a string literal, C adds the zero terminator of the last string
+pub fn data_literal(values: &[Data]) -> String {
+    let mut bytes = Vec::new();
+    for data in values.iter() {
+        match data {
+            Data::String(data) => {
+                bytes.extend_from_slice(data.as_bytes());
+                bytes.push(0);
+            },
+            Data::Byte(data) => bytes.push(*data),
+            Data::Float(data) => bytes.extend_from_slice(&data.to_le_bytes()),
+            Data::Double(data) => bytes.extend_from_slice(&data.to_le_bytes())
+        }
+    }
+
+    if bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+
+    bytes_literal(&bytes)
+}
+
+/// Octal escapes always have three digits, so, the next character could be a digit
+fn bytes_literal(bytes: &[u8]) -> String {
+    let mut buffer = String::from("\"");
+    for byte in bytes.iter() {
+        match byte {
+            b'\\' => buffer.push_str("\\\\"),
+            b'"' => buffer.push_str("\\\""),
+            b'\n' => buffer.push_str("\\n"),
+            b'\t' => buffer.push_str("\\t"),
+            b'\r' => buffer.push_str("\\r"),
+            byte if byte.is_ascii_graphic() || *byte == b' ' => buffer.push(*byte as char),
+            byte => buffer.push_str(&format!("\\{:03o}", byte))
+        }
+    }
+    buffer.push('"');
+    buffer
+}
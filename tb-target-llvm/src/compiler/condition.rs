@@ -0,0 +1,88 @@
+use tb_core::{error::SourceError, types::{Condition, ConditionDiscriminant}};
+
+use crate::{scope::LlvmScope, types::type_name, LlvmApplicationContext};
+
+use super::{expression::LlvmExpressionCompiler};
+
+pub struct LlvmConditionCompiler;
+
+impl LlvmConditionCompiler {
+    /// Branch to one of the labels, right side of And and Or is evaluated only if the left side does not decide the result
+    pub fn compile_jump(condition: Condition, true_label: &str, false_label: &str, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        match condition {
+            Condition::And { left, right } => {
+                let next_label = context.new_label();
+                Self::compile_jump(*left, &next_label, false_label, scope, context)?;
+                context.add_label(next_label);
+                Self::compile_jump(*right, true_label, false_label, scope, context)
+            },
+            Condition::Or { left, right } => {
+                let next_label = context.new_label();
+                Self::compile_jump(*left, true_label, &next_label, scope, context)?;
+                context.add_label(next_label);
+                Self::compile_jump(*right, true_label, false_label, scope, context)
+            },
+            Condition::Not { source } => Self::compile_jump(*source, false_label, true_label, scope, context),
+            condition => {
+                let value = Self::compile_value(condition, scope, context)?;
+                context.add_terminator(format!("br i1 {}, label %{}, label %{}", value, true_label, false_label));
+                Ok(())
+            }
+        }
+    }
+
+    /// Result of the condition as i1, And and Or are joined with a phi
+    pub fn compile_value(condition: Condition, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        let operation = ConditionDiscriminant::from(&condition);
+
+        match condition {
+            Condition::And { .. } |
+            Condition::Or { .. } => {
+                let (true_label, false_label, end_label) = (context.new_label(), context.new_label(), context.new_label());
+                Self::compile_jump(condition, &true_label, &false_label, scope, context)?;
+
+                context.add_label(true_label.clone());
+                context.add_jump(&end_label);
+                context.add_label(false_label.clone());
+                context.add_label(end_label);
+                Ok(context.add_value(format!("phi i1 [ true, %{} ], [ false, %{} ]", true_label, false_label)))
+            },
+            Condition::Not { source } => {
+                let value = Self::compile_value(*source, scope, context)?;
+                Ok(context.add_value(format!("xor i1 {}, true", value)))
+            },
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => {
+                let number_type = LlvmExpressionCompiler::get_type(&left, scope)?.common(LlvmExpressionCompiler::get_type(&right, scope)?);
+                let left = LlvmExpressionCompiler::compile(left, number_type, scope, context)?;
+                let right = LlvmExpressionCompiler::compile(right, number_type, scope, context)?;
+
+                // Ordered comparisons are false for NaN, not equal is the unordered one
+                let (instruction, predicate) = match (number_type.is_float(), number_type.is_signed(), operation) {
+                    (true, _, ConditionDiscriminant::Eq) => ("fcmp", "oeq"),
+                    (true, _, ConditionDiscriminant::Ne) => ("fcmp", "une"),
+                    (true, _, ConditionDiscriminant::Gr) => ("fcmp", "ogt"),
+                    (true, _, ConditionDiscriminant::Ge) => ("fcmp", "oge"),
+                    (true, _, ConditionDiscriminant::Ls) => ("fcmp", "olt"),
+                    (true, _, _) => ("fcmp", "ole"),
+                    (false, _, ConditionDiscriminant::Eq) => ("icmp", "eq"),
+                    (false, _, ConditionDiscriminant::Ne) => ("icmp", "ne"),
+                    (false, true, ConditionDiscriminant::Gr) => ("icmp", "sgt"),
+                    (false, true, ConditionDiscriminant::Ge) => ("icmp", "sge"),
+                    (false, true, ConditionDiscriminant::Ls) => ("icmp", "slt"),
+                    (false, true, _) => ("icmp", "sle"),
+                    (false, false, ConditionDiscriminant::Gr) => ("icmp", "ugt"),
+                    (false, false, ConditionDiscriminant::Ge) => ("icmp", "uge"),
+                    (false, false, ConditionDiscriminant::Ls) => ("icmp", "ult"),
+                    (false, false, _) => ("icmp", "ule")
+                };
+
+                Ok(context.add_value(format!("{} {} {} {}, {}", instruction, predicate, type_name(number_type), left, right)))
+            }
+        }
+    }
+}
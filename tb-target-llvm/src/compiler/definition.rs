@@ -0,0 +1,98 @@
+use tb_core::{error::CodeGenerationError, scope::StatementCompiler, types::{Block, Definition, Number, NumberType, Parameter, Statement}};
+
+use crate::{scope::LlvmScope, types::{literal, type_name}, LlvmApplicationContext, LlvmFunction, INDENT};
+
+use super::statement::LlvmStatementCompiler;
+
+pub struct LlvmDefinitionCompiler;
+
+impl LlvmDefinitionCompiler {
+    pub fn compile(definition: Definition, context: &mut LlvmApplicationContext) -> Result<(), CodeGenerationError> {
+        match definition {
+            Definition::Function { name, parameters, block } => Self::compile_function(name, parameters, block, context).map(|_| ())
+        }
+    }
+
+    /// Function without a returned value is void, the others get a temporary type until the values are known.
+    /// main returns int like C.
+    pub fn signature(definition: &Definition) -> LlvmFunction {
+        match definition {
+            Definition::Function { name, parameters, block } => LlvmFunction {
+                parameters: parameters.iter().map(|parameter| parameter.param_type).collect(),
+                return_type: match (name == "main", Self::block_returns_value(block)) {
+                    (true, _) => Some(NumberType::I32),
+                    (false, true) => Some(NumberType::I64),
+                    (false, false) => None
+                }
+            }
+        }
+    }
+
+    /// Types of the returned values depend only on the function itself, it is compiled alone to find them.
+    /// Integers are extended to 64bit like the registers.
+    pub fn return_type(definition: Definition, context: &LlvmApplicationContext) -> Result<Option<NumberType>, CodeGenerationError> {
+        let Definition::Function { name, parameters, block } = definition;
+        let mut function_context = LlvmApplicationContext::new(context.target, context.datas.clone());
+        function_context.functions = context.functions.clone();
+
+        let return_types = Self::compile_function(name.clone(), parameters, block, &mut function_context)?.return_types;
+
+        Ok(match context.functions.get(&name).and_then(|function| function.return_type) {
+            None => None,
+            Some(_) if name == "main" => Some(NumberType::I32),
+            Some(_) if return_types.iter().all(|return_type| *return_type == NumberType::Float) => Some(NumberType::Float),
+            Some(_) if return_types.iter().any(|return_type| return_type.is_float()) => Some(NumberType::Double),
+            Some(_) => Some(NumberType::I64)
+        })
+    }
+
+    fn block_returns_value(block: &Block) -> bool {
+        block.items.iter().any(|item| match item {
+            Statement::Return(value) => value.is_some(),
+            Statement::If { true_block, false_block, .. } => Self::block_returns_value(true_block) || false_block.as_ref().map(Self::block_returns_value).unwrap_or_default(),
+            Statement::While { block, .. } |
+            Statement::Loop { block } => Self::block_returns_value(block),
+            _ => false
+        })
+    }
+
+    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut LlvmApplicationContext) -> Result<LlvmScope, CodeGenerationError> {
+        let mut scope = LlvmScope::default();
+        scope.return_type = context.functions.get(&name).and_then(|function| function.return_type);
+
+        let parameters = arguments.into_iter().map(|parameter| scope.add_variable(&parameter.name, parameter.param_type).clone()).collect::<Vec<_>>();
+
+        // Function body is compiled first, the stack slots are allocated before it
+        let body_position = context.lines.len();
+        context.begin_function();
+
+        for (index, item) in block.items.into_iter().enumerate() {
+            LlvmStatementCompiler::compile(item, &mut scope, context).map_err(|error| CodeGenerationError::new(&name, index, error))?;
+        }
+
+        // Like C, main returns zero if it reaches the end without a return, the other functions return zero too
+        if !context.is_terminated() {
+            match scope.return_type {
+                Some(return_type) => context.add_terminator(format!("ret {} {}", type_name(return_type), literal(Number::I32(0).convert(return_type)))),
+                None => context.add_terminator("ret void".to_owned())
+            };
+        }
+
+        context.lines.push("}".to_owned());
+        context.lines.push(String::new());
+
+        let return_type = match scope.return_type {
+            Some(return_type) => type_name(return_type),
+            None => "void"
+        };
+
+        let llvm_parameters = parameters.iter().map(|parameter| format!("{} {}.param", type_name(parameter.var_type), parameter.target_name)).collect::<Vec<_>>();
+
+        let mut header = vec![format!("define {} @{}({}) {{", return_type, name, llvm_parameters.join(", ")), "entry:".to_owned()];
+        header.extend(scope.get_variables().iter().map(|variable| format!("{}{} = alloca {}", INDENT, variable.target_name, type_name(variable.var_type))));
+        header.extend(parameters.iter().map(|parameter| format!("{}store {} {}.param, ptr {}", INDENT, type_name(parameter.var_type), parameter.target_name, parameter.target_name)));
+        context.lines.splice(body_position..body_position, header);
+
+        Ok(scope)
+    }
+}
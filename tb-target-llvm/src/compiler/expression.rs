@@ -0,0 +1,151 @@
+use tb_core::{error::SourceError, types::{Expression, ExpressionDiscriminant, Number, NumberType, Value}};
+
+use crate::{scope::LlvmScope, types::{literal, type_name}, LlvmApplicationContext};
+
+use super::{condition::LlvmConditionCompiler};
+
+pub struct LlvmExpressionCompiler;
+
+impl LlvmExpressionCompiler {
+    /// SSA value of the expression converted to the expected type
+    pub fn compile(expression: Expression, number_type: NumberType, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        // Constants are converted without an instruction
+        if let Expression::Value(Value::Number(num)) = expression {
+            return Ok(literal(num.convert(number_type)));
+        }
+
+        let expression_type = Self::get_type(&expression, scope)?;
+        let value = Self::compile_expression(expression, expression_type, scope, context)?;
+        Ok(Self::convert(value, expression_type, number_type, context))
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    pub fn get_type(expression: &Expression, scope: &LlvmScope) -> Result<NumberType, SourceError> {
+        NumberType::of_expression(expression, &|variable| scope.find_variable(variable).map(|item| item.var_type).ok_or(SourceError::VariableNotFound(variable.to_owned())))
+    }
+
+    /// Bool is a single bit, the other integers use their size
+    fn bits(number_type: NumberType) -> usize {
+        match number_type {
+            NumberType::Bool => 1,
+            number_type => number_type.size() as usize * 8
+        }
+    }
+
+    /// Conversion with the C cast semantics, signedness of the integers is only in the instructions
+    pub fn convert(value: String, from: NumberType, to: NumberType, context: &mut LlvmApplicationContext) -> String {
+        let instruction = match (from, to) {
+            (from, to) if from == to => return value,
+            (from, NumberType::Bool) if from.is_float() => return context.add_value(format!("fcmp une {} {}, 0.0", type_name(from), value)),
+            (from, NumberType::Bool) => return context.add_value(format!("icmp ne {} {}, 0", type_name(from), value)),
+            (NumberType::Float, NumberType::Double) => "fpext",
+            (NumberType::Double, NumberType::Float) => "fptrunc",
+            (from, to) if from.is_float() && to.is_signed() => "fptosi",
+            (from, _) if from.is_float() => "fptoui",
+            (from, to) if to.is_float() && from.is_signed() => "sitofp",
+            (_, to) if to.is_float() => "uitofp",
+            (from, to) if Self::bits(from) == Self::bits(to) => return value,
+            (from, to) if Self::bits(from) > Self::bits(to) => "trunc",
+            (from, _) if from.is_signed() => "sext",
+            _ => "zext"
+        };
+
+        context.add_value(format!("{} {} {} to {}", instruction, type_name(from), value, type_name(to)))
+    }
+
+    /// Value with the type of the expression
+    fn compile_expression(expression: Expression, number_type: NumberType, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        let operation = ExpressionDiscriminant::from(&expression);
+
+        match expression {
+            Expression::Value(Value::Variable(variable)) => {
+                let variable = scope.find_variable(&variable).ok_or(SourceError::VariableNotFound(variable))?;
+                Ok(context.add_value(format!("load {}, ptr {}", type_name(variable.var_type), variable.target_name)))
+            },
+            Expression::Value(Value::Number(num)) => Ok(literal(num)),
+
+            // Address of the string, like the assembly backends
+            Expression::Value(Value::String(data)) => Ok(format!("ptrtoint (ptr {} to i64)", context.add_string(&data))),
+            Expression::Condition(condition) => LlvmConditionCompiler::compile_value(*condition, scope, context),
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } |
+            Expression::Div { divider: right, divided: left } |
+            Expression::Modulo { divider: right, divided: left } => Self::compile_binary(operation, *left, *right, number_type, scope, context),
+            Expression::ShiftLeft { target, source } |
+            Expression::ShiftRight { target, source } => Self::compile_shift(operation, *target, *source, number_type, scope, context),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Self::compile_unary(operation, *source, number_type, scope, context)
+        }
+    }
+
+    /// Both operands are converted to the type of the operation
+    fn compile_binary(operation: ExpressionDiscriminant, left: Expression, right: Expression, number_type: NumberType, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        let left = Self::compile(left, number_type, scope, context)?;
+        let right = Self::compile(right, number_type, scope, context)?;
+
+        let instruction = match (number_type.is_float(), operation) {
+            (true, ExpressionDiscriminant::Add) => "fadd",
+            (true, ExpressionDiscriminant::Sub) => "fsub",
+            (true, ExpressionDiscriminant::Mul) => "fmul",
+            (true, ExpressionDiscriminant::Div) => "fdiv",
+            (true, _) => return Err(SourceError::UnsupportedFloatOperation),
+            (false, ExpressionDiscriminant::Add) => "add",
+            (false, ExpressionDiscriminant::Sub) => "sub",
+            (false, ExpressionDiscriminant::Mul) => "mul",
+            (false, ExpressionDiscriminant::BitwiseAnd) => "and",
+            (false, ExpressionDiscriminant::BitwiseOr) => "or",
+            (false, ExpressionDiscriminant::BitwiseXor) => "xor",
+            (false, ExpressionDiscriminant::Div) if number_type.is_signed() => "sdiv",
+            (false, ExpressionDiscriminant::Div) => "udiv",
+            (false, _) if number_type.is_signed() => "srem",
+            (false, _) => "urem"
+        };
+
+        Ok(context.add_value(format!("{} {} {}, {}", instruction, type_name(number_type), left, right)))
+    }
+
+    /// Processor uses only the lower bits of the shift count, signed values are shifted arithmetically to right
+    fn compile_shift(operation: ExpressionDiscriminant, target: Expression, source: Expression, number_type: NumberType, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        if number_type.is_float() {
+            return Err(SourceError::UnsupportedFloatOperation);
+        }
+
+        let target = Self::compile(target, number_type, scope, context)?;
+        let count = Self::compile(source, number_type, scope, context)?;
+        let count = context.add_value(format!("and {} {}, {}", type_name(number_type), count, Self::bits(number_type) - 1));
+
+        let instruction = match (operation, number_type.is_signed()) {
+            (ExpressionDiscriminant::ShiftLeft, _) => "shl",
+            (_, true) => "ashr",
+            (_, false) => "lshr"
+        };
+
+        Ok(context.add_value(format!("{} {} {}, {}", instruction, type_name(number_type), target, count)))
+    }
+
+    /// Floating point values are subtracted from zero like the SSE code
+    fn compile_unary(operation: ExpressionDiscriminant, source: Expression, number_type: NumberType, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        let source = Self::compile(source, number_type, scope, context)?;
+        let zero = literal(Number::I32(0).convert(number_type));
+        let one = literal(Number::I32(1).convert(number_type));
+
+        let (instruction, left, right) = match (number_type.is_float(), operation) {
+            (true, ExpressionDiscriminant::BitwiseNeg) => ("fsub", zero, source),
+            (true, ExpressionDiscriminant::Inc) => ("fadd", source, one),
+            (true, ExpressionDiscriminant::Dec) => ("fsub", source, one),
+            (true, _) => return Err(SourceError::UnsupportedFloatOperation),
+            (false, ExpressionDiscriminant::BitwiseNot) => ("xor", source, "-1".to_owned()),
+            (false, ExpressionDiscriminant::BitwiseNeg) => ("sub", zero, source),
+            (false, ExpressionDiscriminant::Inc) => ("add", source, one),
+            (false, _) => ("sub", source, one)
+        };
+
+        Ok(context.add_value(format!("{} {} {}, {}", instruction, type_name(number_type), left, right)))
+    }
+}
@@ -0,0 +1,9 @@
+mod expression;
+mod statement;
+mod definition;
+mod condition;
+
+pub use expression::LlvmExpressionCompiler;
+pub use statement::LlvmStatementCompiler;
+pub use definition::LlvmDefinitionCompiler;
+pub use condition::LlvmConditionCompiler;
@@ -0,0 +1,239 @@
+use tb_core::{error::SourceError, scope::StatementCompiler, types::{Block, Condition, Expression, Number, NumberType, Statement, Value}};
+
+use crate::{scope::{LlvmLoop, LlvmScope}, types::{literal, type_name}, LlvmApplicationContext, PRINT_FUNCTION};
+
+use super::{condition::LlvmConditionCompiler, expression::LlvmExpressionCompiler};
+
+pub struct LlvmStatementCompiler;
+
+impl StatementCompiler for LlvmStatementCompiler {
+    type Scope = LlvmScope;
+    type Context = LlvmApplicationContext;
+    type Error = SourceError;
+
+    fn compile(statement: Statement, scope: &mut LlvmScope, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        match statement {
+            Statement::Assign { name, assigne } => Self::compile_assign(scope, name, assigne, context),
+            Statement::Declare { name, ty, init } => Self::compile_declare(scope, name, ty, init, context),
+            Statement::Call { name, arguments, assign, is_variadic } => Self::compile_call(scope, name, arguments, assign, is_variadic, context),
+            Statement::Print { format, arguments } => Self::compile_print(scope, format, arguments, context),
+            Statement::Return(expr) => Self::compile_return(scope, expr, context),
+            Statement::If { condition, true_block, false_block } => Self::compile_if(scope, condition, true_block, false_block, context),
+            Statement::While { condition, block } => Self::compile_while(scope, condition, block, context),
+            Statement::Loop { block } => Self::compile_loop(scope, block, context),
+            Statement::Break => Self::compile_break(scope, context),
+            Statement::Continue => Self::compile_continue(scope, context),
+        }
+    }
+}
+
+impl LlvmStatementCompiler {
+    fn compile_assign(scope: &mut LlvmScope, name: String, assigne: Expression, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        // Undeclared variable takes the type of the expression
+        let var_type = match scope.find_variable(&name) {
+            Some(variable) => variable.var_type,
+            None => LlvmExpressionCompiler::get_type(&assigne, scope)?
+        };
+
+        let value = LlvmExpressionCompiler::compile(assigne, var_type, scope, context)?;
+
+        let llvm_name = match scope.find_variable(&name) {
+            Some(variable) => variable.target_name.clone(),
+            None => scope.add_variable(&name, var_type).target_name.clone()
+        };
+
+        context.add_instruction(format!("store {} {}, ptr {}", type_name(var_type), value, llvm_name));
+        Ok(())
+    }
+
+    fn compile_declare(scope: &mut LlvmScope, name: String, var_type: NumberType, init: Option<Expression>, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        // Initial value could use the shadowed variable, so, calculate it before the declaration
+        let value = match init {
+            Some(init) => Some(LlvmExpressionCompiler::compile(init, var_type, scope, context)?),
+            None => None
+        };
+
+        let llvm_name = scope.add_variable(&name, var_type).target_name.clone();
+
+        if let Some(value) = value {
+            context.add_instruction(format!("store {} {}, ptr {}", type_name(var_type), value, llvm_name));
+        }
+
+        Ok(())
+    }
+
+    /// Arguments get the default argument promotions of C, the format should match their types
+    fn variadic_argument(argument: Value, scope: &LlvmScope, context: &mut LlvmApplicationContext) -> Result<String, SourceError> {
+        match argument {
+            Value::String(data) => Ok(format!("ptr {}", context.add_string(&data))),
+
+            // Data labels could be used as the string arguments
+            Value::Variable(name) if scope.find_variable(&name).is_none() && context.datas.items.iter().any(|item| item.label == name) => Ok(format!("ptr @{}", name)),
+            argument => {
+                let argument = Expression::Value(argument);
+                let argument_type = match LlvmExpressionCompiler::get_type(&argument, scope)? {
+                    NumberType::Float => NumberType::Double,
+                    NumberType::I8 | NumberType::U8 | NumberType::I16 | NumberType::U16 | NumberType::Bool => NumberType::I32,
+                    argument_type => argument_type
+                };
+
+                let value = LlvmExpressionCompiler::compile(argument, argument_type, scope, context)?;
+                Ok(format!("{} {}", type_name(argument_type), value))
+            }
+        }
+    }
+
+    fn compile_print(scope: &mut LlvmScope, format: String, arguments: Vec<Value>, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let mut tmp_arguments = Vec::new();
+        tmp_arguments.push(Value::String(format));
+        tmp_arguments.extend(arguments);
+
+        Self::compile_call(scope, PRINT_FUNCTION.to_owned(), tmp_arguments, None, true, context)
+    }
+
+    /// Arguments of the application functions are converted to the parameter types, the others are called as variadic functions
+    fn compile_call(scope: &mut LlvmScope, name: String, arguments: Vec<Value>, assign: Option<String>, is_variadic: bool, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let (callee, return_type, llvm_arguments) = match context.functions.get(&name).cloned() {
+            Some(function) => {
+                let mut llvm_arguments = Vec::new();
+                for (index, argument) in arguments.into_iter().enumerate() {
+                    let argument = Expression::Value(argument);
+                    let argument_type = match function.parameters.get(index) {
+                        Some(parameter_type) => *parameter_type,
+                        None => LlvmExpressionCompiler::get_type(&argument, scope)?
+                    };
+                    let value = LlvmExpressionCompiler::compile(argument, argument_type, scope, context)?;
+                    llvm_arguments.push(format!("{} {}", type_name(argument_type), value));
+                }
+
+                let return_type = match function.return_type {
+                    Some(return_type) => type_name(return_type),
+                    None => "void"
+                };
+                (format!("{} @{}", return_type, name), function.return_type, llvm_arguments)
+            },
+            None => {
+                let mut llvm_arguments = Vec::new();
+                for argument in arguments.into_iter() {
+                    llvm_arguments.push(Self::variadic_argument(argument, scope, context)?);
+                }
+
+                // printf returns the number of the printed bytes, the others are expected to return a full register
+                match is_variadic && name.trim_start_matches('_') == PRINT_FUNCTION {
+                    true => {
+                        context.add_external(PRINT_FUNCTION);
+                        (format!("i32 (ptr, ...) @{}", PRINT_FUNCTION), Some(NumberType::I32), llvm_arguments)
+                    },
+                    false => {
+                        context.add_external(&name);
+                        (format!("i64 (...) @{}", name), Some(NumberType::I64), llvm_arguments)
+                    }
+                }
+            }
+        };
+
+        let call = format!("call {}({})", callee, llvm_arguments.join(", "));
+
+        match (assign, return_type) {
+            (Some(_), None) => return Err(SourceError::MissingReturnValue(name)),
+            (Some(assigned), Some(return_type)) => {
+                let result = context.add_value(call);
+
+                // Undeclared variable gets the full register
+                let (llvm_name, var_type) = match scope.find_variable(&assigned) {
+                    Some(variable) => (variable.target_name.clone(), variable.var_type),
+                    None => (scope.add_variable(&assigned, NumberType::I64).target_name.clone(), NumberType::I64)
+                };
+
+                let value = LlvmExpressionCompiler::convert(result, return_type, var_type, context);
+                context.add_instruction(format!("store {} {}, ptr {}", type_name(var_type), value, llvm_name));
+            },
+            (None, Some(_)) => {
+                context.add_value(call);
+            },
+            (None, None) => context.add_instruction(call)
+        };
+
+        Ok(())
+    }
+
+    fn compile_if(scope: &mut LlvmScope, condition: Condition, true_block: Block, false_block: Option<Block>, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let true_label = context.new_label();
+        let false_label = context.new_label();
+        LlvmConditionCompiler::compile_jump(condition, &true_label, &false_label, scope, context)?;
+
+        context.add_label(true_label);
+        Self::compile_block(true_block, scope, context)?;
+
+        if let Some(false_block) = false_block {
+            let end_label = context.new_label();
+            context.add_jump(&end_label);
+
+            context.add_label(false_label);
+            Self::compile_block(false_block, scope, context)?;
+            context.add_label(end_label);
+
+        } else {
+            context.add_label(false_label);
+        }
+
+        Ok(())
+    }
+
+    fn compile_while(scope: &mut LlvmScope, condition: Condition, block: Block, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let branches = LlvmLoop { begin: context.new_label(), end: context.new_label() };
+        let body_label = context.new_label();
+
+        context.add_label(branches.begin.clone());
+        LlvmConditionCompiler::compile_jump(condition, &body_label, &branches.end, scope, context)?; // Exit from loop
+        context.add_label(body_label);
+
+        Self::compile_loop_body(scope, branches, block, context)
+    }
+
+    fn compile_loop(scope: &mut LlvmScope, block: Block, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let branches = LlvmLoop { begin: context.new_label(), end: context.new_label() };
+        context.add_label(branches.begin.clone());
+
+        Self::compile_loop_body(scope, branches, block, context)
+    }
+
+    fn compile_loop_body(scope: &mut LlvmScope, branches: LlvmLoop, block: Block, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        scope.loops.push(branches.clone());
+        Self::compile_block(block, scope, context)?;
+        scope.loops.pop();
+
+        context.add_jump(&branches.begin);
+        context.add_label(branches.end);
+        Ok(())
+    }
+
+    fn compile_break(scope: &mut LlvmScope, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let branches = scope.loops.last().ok_or(SourceError::OutsideOfLoop)?;
+        context.add_terminator(format!("br label %{}", branches.end));
+        Ok(())
+    }
+
+    fn compile_continue(scope: &mut LlvmScope, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        let branches = scope.loops.last().ok_or(SourceError::OutsideOfLoop)?;
+        context.add_terminator(format!("br label %{}", branches.begin));
+        Ok(())
+    }
+
+    /// Value is converted to the return type of the function, the empty return gives zero if the function has a type
+    pub fn compile_return(scope: &mut LlvmScope, expr: Option<Value>, context: &mut LlvmApplicationContext) -> Result<(), SourceError> {
+        match (expr, scope.return_type) {
+            (Some(value), Some(return_type)) => {
+                let value = Expression::Value(value);
+                scope.return_types.push(LlvmExpressionCompiler::get_type(&value, scope)?);
+
+                let value = LlvmExpressionCompiler::compile(value, return_type, scope, context)?;
+                context.add_terminator(format!("ret {} {}", type_name(return_type), value));
+            },
+            (None, Some(return_type)) => context.add_terminator(format!("ret {} {}", type_name(return_type), literal(Number::I32(0).convert(return_type)))),
+            (_, None) => context.add_terminator("ret void".to_owned())
+        };
+
+        Ok(())
+    }
+}
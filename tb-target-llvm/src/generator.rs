@@ -0,0 +1,83 @@
+use tb_core::{error::{CodeGenerationError, TBError}, syntax::Syntax, target::{Architecture, OperatingSystem, Target}, types::{AssemblyGenerator, DataItemCollection, Definition}};
+
+use crate::{compiler::LlvmDefinitionCompiler, types::data_bytes, LlvmApplicationContext, PRINT_FUNCTION};
+
+/// LLVM IR in the text format with the opaque pointers, it could be compiled with clang or llc of LLVM 15 or newer (LLVM 14 needs -opaque-pointers)
+#[derive(Debug, Clone, Default)]
+pub struct LlvmIrGenerator {
+    target: Target
+}
+
+impl LlvmIrGenerator {
+    /// Functions of the definitions before the module level declarations are added
+    pub fn compile(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<LlvmApplicationContext, CodeGenerationError> {
+        let mut context = LlvmApplicationContext::new(self.target, datas);
+        context.functions = definitions.iter().map(|definition| match definition {
+            Definition::Function { name, .. } => (name.clone(), LlvmDefinitionCompiler::signature(definition))
+        }).collect();
+
+        // Calls could be compiled before the called function, so, the return types are found first
+        let mut return_types = Vec::new();
+        for definition in definitions.iter() {
+            let Definition::Function { name, .. } = definition;
+            return_types.push((name.clone(), LlvmDefinitionCompiler::return_type(definition.clone(), &context)?));
+        }
+
+        for (name, return_type) in return_types.into_iter() {
+            if let Some(function) = context.functions.get_mut(&name) {
+                function.return_type = return_type;
+            }
+        }
+
+        for item in definitions.into_iter() {
+            LlvmDefinitionCompiler::compile(item, &mut context)?;
+        }
+
+        Ok(context)
+    }
+
+    pub fn target_triple(target: Target) -> &'static str {
+        match (target.architecture, target.operating_system) {
+            (Architecture::X86_64, OperatingSystem::Linux) => "x86_64-pc-linux-gnu",
+            (Architecture::X86_64, OperatingSystem::MacOS) => "x86_64-apple-macosx",
+            (Architecture::X86_64, OperatingSystem::Windows) => "x86_64-pc-windows-msvc",
+            (Architecture::Aarch64, OperatingSystem::MacOS) => "arm64-apple-macosx",
+            (Architecture::Aarch64, OperatingSystem::Windows) => "aarch64-pc-windows-msvc",
+            (Architecture::Aarch64, OperatingSystem::Linux) => "aarch64-unknown-linux-gnu"
+        }
+    }
+
+    pub fn write(context: LlvmApplicationContext) -> String {
+        let mut lines = vec![format!("target triple = \"{}\"", Self::target_triple(context.target)), String::new()];
+
+        if !context.strings.is_empty() || !context.datas.items.is_empty() {
+            lines.extend(context.strings.iter().enumerate().map(|(index, data)| LlvmApplicationContext::string_declaration(&format!("@.str.{}", index + 1), data.as_bytes())));
+            lines.extend(context.datas.items.iter().map(|item| LlvmApplicationContext::string_declaration(&format!("@{}", item.label), &data_bytes(&item.values))));
+            lines.push(String::new());
+        }
+
+        // Parameters of the external functions are not known
+        if !context.externals.is_empty() {
+            lines.extend(context.externals.iter().map(|name| match name == PRINT_FUNCTION {
+                true => format!("declare i32 @{}(ptr, ...)", name),
+                false => format!("declare i64 @{}(...)", name)
+            }));
+            lines.push(String::new());
+        }
+
+        lines.extend(context.lines);
+        lines.into_iter().map(|line| format!("{}\r\n", line)).collect()
+    }
+}
+
+impl AssemblyGenerator for LlvmIrGenerator {
+    fn new(target: Target) -> Self {
+        Self { target }
+    }
+
+    /// LLVM IR has only one syntax, the requested syntax is ignored
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, _: Syntax) -> Result<String, TBError> {
+        let context = self.compile(definitions, datas)?;
+        Ok(Self::write(context))
+    }
+}
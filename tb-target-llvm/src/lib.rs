@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use tb_core::{target::Target, types::{DataItemCollection, NumberType}};
+use types::bytes_literal;
+
+pub mod compiler;
+pub mod generator;
+pub mod scope;
+pub mod types;
+
+pub const INDENT: &str = "  ";
+
+/// Print statements call printf, the name does not have the prefix of the target
+pub const PRINT_FUNCTION: &str = "printf";
+
+#[derive(Debug, Clone)]
+pub struct LlvmFunction {
+    pub parameters: Vec<NumberType>,
+
+    /// Void function does not have a type
+    pub return_type: Option<NumberType>
+}
+
+/// Instructions of the functions, the module level declarations are written after all functions are compiled
+#[derive(Debug, Default)]
+pub struct LlvmApplicationContext {
+    pub target: Target,
+    pub datas: DataItemCollection,
+    pub functions: HashMap<String, LlvmFunction>,
+
+    /// Called functions that are not in the application, they are declared as variadic functions
+    pub externals: Vec<String>,
+    pub strings: Vec<String>,
+    pub lines: Vec<String>,
+    temporary_count: usize,
+    label_count: usize,
+
+    /// Every basic block ends with a terminator, instructions after it start an unreachable block
+    terminated: bool
+}
+
+impl LlvmApplicationContext {
+    pub fn new(target: Target, datas: DataItemCollection) -> Self {
+        Self { target, datas, ..Default::default() }
+    }
+
+    /// Local names of the temporaries and the labels have a dot, so they could not be the same with the variables
+    pub fn new_temporary(&mut self) -> String {
+        self.temporary_count += 1;
+        format!("%.t{}", self.temporary_count)
+    }
+
+    pub fn new_label(&mut self) -> String {
+        self.label_count += 1;
+        format!(".L{}", self.label_count)
+    }
+
+    pub fn begin_function(&mut self) {
+        self.temporary_count = 0;
+        self.label_count = 0;
+        self.terminated = false;
+    }
+
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+
+    pub fn add_instruction(&mut self, instruction: String) {
+        if self.terminated {
+            let label = self.new_label();
+            self.add_label(label);
+        }
+
+        self.lines.push(format!("{}{}", INDENT, instruction));
+    }
+
+    /// Instruction with the result in a new temporary
+    pub fn add_value(&mut self, instruction: String) -> String {
+        let temporary = self.new_temporary();
+        self.add_instruction(format!("{} = {}", temporary, instruction));
+        temporary
+    }
+
+    pub fn add_terminator(&mut self, instruction: String) {
+        self.add_instruction(instruction);
+        self.terminated = true;
+    }
+
+    /// Previous block falls through into the new one
+    pub fn add_label(&mut self, label: String) {
+        if !self.terminated {
+            self.add_terminator(format!("br label %{}", label));
+        }
+
+        self.lines.push(format!("{}:", label));
+        self.terminated = false;
+    }
+
+    /// Jump is not added if the block already has a terminator
+    pub fn add_jump(&mut self, label: &str) {
+        if !self.terminated {
+            self.add_terminator(format!("br label %{}", label));
+        }
+    }
+
+    pub fn add_external(&mut self, name: &str) {
+        if !self.externals.iter().any(|external| external == name) {
+            self.externals.push(name.to_owned());
+        }
+    }
+
+    /// Global constant of the string, the same strings use the same constant
+    pub fn add_string(&mut self, data: &str) -> String {
+        let index = match self.strings.iter().position(|item| item == data) {
+            Some(index) => index,
+            None => {
+                self.strings.push(data.to_owned());
+                self.strings.len() - 1
+            }
+        };
+        format!("@.str.{}", index + 1)
+    }
+
+    /// Declaration of the string constant with its zero terminator
+    pub fn string_declaration(name: &str, bytes: &[u8]) -> String {
+        format!("{} = private unnamed_addr constant [{} x i8] {}", name, bytes.len() + 1, bytes_literal(bytes))
+    }
+}
@@ -0,0 +1,43 @@
+use tb_core::{scope::{Scope, ScopeNaming, ScopedVariable}, types::NumberType};
+
+/// Local names start with a percent sign, shadowing declarations get a suffix
+#[derive(Debug, Clone)]
+pub struct LlvmNaming;
+
+impl ScopeNaming for LlvmNaming {
+    fn variable_name(name: &str, index: usize) -> String {
+        match index {
+            0 => format!("%{}", name),
+            index => format!("%{}.{}", name, index)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LlvmLoop {
+    pub begin: String,
+    pub end: String
+}
+
+/// Variables of a function, every declaration has its own stack slot that is allocated in the entry block
+#[derive(Debug, Default)]
+pub struct LlvmScope {
+    variables: Scope<LlvmNaming>,
+    pub loops: Vec<LlvmLoop>,
+    pub return_type: Option<NumberType>,
+    pub return_types: Vec<NumberType>
+}
+
+impl LlvmScope {
+    pub fn find_variable(&self, variable: &str) -> Option<&ScopedVariable> {
+        self.variables.find_variable(variable)
+    }
+
+    pub fn add_variable(&mut self, name: &str, var_type: NumberType) -> &ScopedVariable {
+        self.variables.add_variable(name, var_type)
+    }
+
+    pub fn get_variables(&self) -> &[ScopedVariable] {
+        self.variables.get_variables()
+    }
+}
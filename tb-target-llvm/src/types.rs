@@ -0,0 +1,75 @@
+use tb_core::{syntax::Data, types::{Number, NumberType}};
+
+pub fn type_name(number_type: NumberType) -> &'static str {
+    match number_type {
+        NumberType::I8 | NumberType::U8 => "i8",
+        NumberType::I16 | NumberType::U16 => "i16",
+        NumberType::I32 | NumberType::U32 => "i32",
+        NumberType::I64 | NumberType::U64 => "i64",
+        NumberType::Float => "float",
+        NumberType::Double => "double",
+        NumberType::Bool => "i1"
+    }
+}
+
+/// Integers do not have a sign in LLVM, unsigned ones are written with the same bits
+pub fn literal(number: Number) -> String {
+    match number {
+        Number::I8(num) => num.to_string(),
+        Number::U8(num) => (num as i8).to_string(),
+        Number::I16(num) => num.to_string(),
+        Number::U16(num) => (num as i16).to_string(),
+        Number::I32(num) => num.to_string(),
+        Number::U32(num) => (num as i32).to_string(),
+        Number::I64(num) => num.to_string(),
+        Number::U64(num) => (num as i64).to_string(),
+        Number::Float(num) => float_literal(num as f64),
+        Number::Double(num) => float_literal(num),
+        Number::Bool(num) => num.to_string()
+    }
+}
+
+/// Decimal form should be exact and it needs a dot, the others are written with the bits of the double.
+/// Float constants use the double form too, the value of a float is always exact in double.
+fn float_literal(number: f64) -> String {
+    let decimal = format!("{:?}", number);
+    match decimal.trim_start_matches('-').chars().all(|char| char.is_ascii_digit() || char == '.') && decimal.contains('.') {
+        true => decimal,
+        false => format!("0x{:016X}", number.to_bits())
+    }
+}
+
+/// String constant with the zero terminator, the other characters are escaped with two hex digits
+pub fn bytes_literal(bytes: &[u8]) -> String {
+    let mut buffer = String::from("c\"");
+    for byte in bytes.iter() {
+        match byte {
+            byte if (byte.is_ascii_graphic() || *byte == b' ') && *byte != b'\\' && *byte != b'"' => buffer.push(*byte as char),
+            byte => buffer.push_str(&format!("\\{:02X}", byte))
+        }
+    }
+    buffer.push_str("\\00\"");
+    buffer
+}
+
+/// Bytes of the data item, the terminator of the last string is added by the constant
+pub fn data_bytes(values: &[Data]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for data in values.iter() {
+        match data {
+            Data::String(data) => {
+                bytes.extend_from_slice(data.as_bytes());
+                bytes.push(0);
+            },
+            Data::Byte(data) => bytes.push(*data),
+            Data::Float(data) => bytes.extend_from_slice(&data.to_le_bytes()),
+            Data::Double(data) => bytes.extend_from_slice(&data.to_le_bytes())
+        }
+    }
+
+    if bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+
+    bytes
+}
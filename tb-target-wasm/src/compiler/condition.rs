@@ -0,0 +1,69 @@
+use tb_core::{error::SourceError, types::{Condition, ConditionDiscriminant, Number}};
+
+use crate::{scope::WasmScope, types::{constant, type_name}, WasmApplicationContext};
+
+use super::{expression::WasmExpressionCompiler};
+
+pub struct WasmConditionCompiler;
+
+impl WasmConditionCompiler {
+    /// Pushes the result of the condition as i32, right side of And and Or is evaluated only if the left side does not decide the result
+    pub fn compile(condition: Condition, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let operation = ConditionDiscriminant::from(&condition);
+
+        match condition {
+            Condition::And { left, right } => {
+                Self::compile(*left, scope, context)?;
+                context.begin_block("if (result i32)".to_owned());
+                Self::compile(*right, scope, context)?;
+                context.add_else();
+                context.add_instruction(constant(Number::I32(0)));
+                context.end_block();
+            },
+            Condition::Or { left, right } => {
+                Self::compile(*left, scope, context)?;
+                context.begin_block("if (result i32)".to_owned());
+                context.add_instruction(constant(Number::I32(1)));
+                context.add_else();
+                Self::compile(*right, scope, context)?;
+                context.end_block();
+            },
+            Condition::Not { source } => {
+                Self::compile(*source, scope, context)?;
+                context.add_instruction("i32.eqz".to_owned());
+            },
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => {
+                let number_type = WasmExpressionCompiler::get_type(&left, scope)?.common(WasmExpressionCompiler::get_type(&right, scope)?);
+                WasmExpressionCompiler::compile(left, number_type, scope, context)?;
+                WasmExpressionCompiler::compile(right, number_type, scope, context)?;
+
+                // Float comparisons are false for NaN except not equal
+                let instruction = match (number_type.is_float(), number_type.is_signed(), operation) {
+                    (_, _, ConditionDiscriminant::Eq) => "eq",
+                    (_, _, ConditionDiscriminant::Ne) => "ne",
+                    (true, _, ConditionDiscriminant::Gr) => "gt",
+                    (true, _, ConditionDiscriminant::Ge) => "ge",
+                    (true, _, ConditionDiscriminant::Ls) => "lt",
+                    (true, _, _) => "le",
+                    (false, true, ConditionDiscriminant::Gr) => "gt_s",
+                    (false, true, ConditionDiscriminant::Ge) => "ge_s",
+                    (false, true, ConditionDiscriminant::Ls) => "lt_s",
+                    (false, true, _) => "le_s",
+                    (false, false, ConditionDiscriminant::Gr) => "gt_u",
+                    (false, false, ConditionDiscriminant::Ge) => "ge_u",
+                    (false, false, ConditionDiscriminant::Ls) => "lt_u",
+                    (false, false, _) => "le_u"
+                };
+
+                context.add_instruction(format!("{}.{}", type_name(number_type), instruction));
+            }
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use tb_core::{error::CodeGenerationError, scope::StatementCompiler, types::{Block, Definition, Number, NumberType, Parameter, Statement}};
+
+use crate::{scope::WasmScope, types::{constant, type_name}, WasmApplicationContext, WasmFunction, INDENT};
+
+use super::statement::WasmStatementCompiler;
+
+pub struct WasmDefinitionCompiler;
+
+impl WasmDefinitionCompiler {
+    pub fn compile(definition: Definition, context: &mut WasmApplicationContext) -> Result<(), CodeGenerationError> {
+        match definition {
+            Definition::Function { name, parameters, block } => Self::compile_function(name, parameters, block, context).map(|_| ())
+        }
+    }
+
+    /// Function without a returned value has no result, the others get a temporary type until the values are known.
+    /// main returns i32, the host uses it as the exit code.
+    pub fn signature(definition: &Definition) -> WasmFunction {
+        match definition {
+            Definition::Function { name, parameters, block } => WasmFunction {
+                parameters: parameters.iter().map(|parameter| parameter.param_type).collect(),
+                return_type: match (name == "main", Self::block_returns_value(block)) {
+                    (true, _) => Some(NumberType::I32),
+                    (false, true) => Some(NumberType::I64),
+                    (false, false) => None
+                }
+            }
+        }
+    }
+
+    /// Types of the returned values depend only on the function itself, it is compiled alone to find them.
+    /// Integers are extended to 64bit like the registers.
+    pub fn return_type(definition: Definition, context: &WasmApplicationContext) -> Result<Option<NumberType>, CodeGenerationError> {
+        let Definition::Function { name, parameters, block } = definition;
+        let mut function_context = WasmApplicationContext::new(context.target, context.datas.clone());
+        function_context.functions = context.functions.clone();
+
+        let return_types = Self::compile_function(name.clone(), parameters, block, &mut function_context)?.return_types;
+
+        Ok(match context.functions.get(&name).and_then(|function| function.return_type) {
+            None => None,
+            Some(_) if name == "main" => Some(NumberType::I32),
+            Some(_) if return_types.iter().all(|return_type| *return_type == NumberType::Float) => Some(NumberType::Float),
+            Some(_) if return_types.iter().any(|return_type| return_type.is_float()) => Some(NumberType::Double),
+            Some(_) => Some(NumberType::I64)
+        })
+    }
+
+    fn block_returns_value(block: &Block) -> bool {
+        block.items.iter().any(|item| match item {
+            Statement::Return(value) => value.is_some(),
+            Statement::If { true_block, false_block, .. } => Self::block_returns_value(true_block) || false_block.as_ref().map(Self::block_returns_value).unwrap_or_default(),
+            Statement::While { block, .. } |
+            Statement::Loop { block } => Self::block_returns_value(block),
+            _ => false
+        })
+    }
+
+    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut WasmApplicationContext) -> Result<WasmScope, CodeGenerationError> {
+        let mut scope = WasmScope::default();
+        scope.return_type = context.functions.get(&name).and_then(|function| function.return_type);
+
+        let parameters = arguments.into_iter().map(|parameter| scope.add_variable(&parameter.name, parameter.param_type).clone()).collect::<Vec<_>>();
+
+        // Function body is compiled first, the locals are declared before it
+        let body_position = context.lines.len();
+        let returns_at_end = matches!(block.items.last(), Some(Statement::Return(_)));
+        context.begin_function();
+
+        for (index, item) in block.items.into_iter().enumerate() {
+            WasmStatementCompiler::compile(item, &mut scope, context).map_err(|error| CodeGenerationError::new(&name, index, error))?;
+        }
+
+        // Like C, main returns zero if it reaches the end without a return, the other functions return zero too
+        if let (false, Some(return_type)) = (returns_at_end, scope.return_type) {
+            context.add_instruction(constant(Number::I32(0).convert(return_type)));
+        }
+
+        context.lines.push(format!("{})", INDENT));
+        context.lines.push(String::new());
+
+        let mut signature = vec![format!("(func ${}", name)];
+        if name == "main" {
+            signature.push("(export \"main\")".to_owned());
+        }
+        signature.extend(parameters.iter().map(|parameter| format!("(param {} {})", parameter.target_name, type_name(parameter.var_type))));
+        if let Some(return_type) = scope.return_type {
+            signature.push(format!("(result {})", type_name(return_type)));
+        }
+
+        let mut header = vec![format!("{}{}", INDENT, signature.join(" "))];
+        header.extend(scope.get_variables()[parameters.len()..].iter().map(|variable| format!("{}(local {} {})", INDENT.repeat(2), variable.target_name, type_name(variable.var_type))));
+        context.lines.splice(body_position..body_position, header);
+
+        Ok(scope)
+    }
+}
@@ -0,0 +1,203 @@
+use tb_core::{error::SourceError, types::{Expression, ExpressionDiscriminant, Number, NumberType, Value}};
+
+use crate::{scope::WasmScope, types::{constant, type_name}, WasmApplicationContext};
+
+use super::{condition::WasmConditionCompiler};
+
+pub struct WasmExpressionCompiler;
+
+impl WasmExpressionCompiler {
+    /// Pushes the value of the expression converted to the expected type
+    pub fn compile(expression: Expression, number_type: NumberType, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        // Constants are converted without an instruction
+        if let Expression::Value(Value::Number(num)) = expression {
+            context.add_instruction(constant(num.convert(number_type)));
+            return Ok(());
+        }
+
+        let expression_type = Self::get_type(&expression, scope)?;
+        Self::compile_expression(expression, expression_type, scope, context)?;
+        Self::convert(expression_type, number_type, context);
+        Ok(())
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    pub fn get_type(expression: &Expression, scope: &WasmScope) -> Result<NumberType, SourceError> {
+        NumberType::of_expression(expression, &|variable| scope.find_variable(variable).map(|item| item.var_type).ok_or(SourceError::VariableNotFound(variable.to_owned())))
+    }
+
+    /// Bool is a single bit, the other integers use their size
+    fn bits(number_type: NumberType) -> usize {
+        match number_type {
+            NumberType::Bool => 1,
+            number_type => number_type.size() as usize * 8
+        }
+    }
+
+    /// Every value of the source type could be written with the target type
+    fn fits(from: NumberType, to: NumberType) -> bool {
+        Self::bits(from) < Self::bits(to) && (!from.is_signed() || to.is_signed())
+    }
+
+    fn sign(signed: bool) -> &'static str {
+        match signed {
+            true => "s",
+            false => "u"
+        }
+    }
+
+    /// Integers smaller than 32bit are extended again after they are cut
+    fn narrow(to: NumberType, context: &mut WasmApplicationContext) {
+        let mask = match to {
+            NumberType::I8 => return context.add_instruction("i32.extend8_s".to_owned()),
+            NumberType::I16 => return context.add_instruction("i32.extend16_s".to_owned()),
+            NumberType::U8 => 0xFF,
+            NumberType::U16 => 0xFFFF,
+            _ => return
+        };
+
+        context.add_instruction(constant(Number::I32(mask)));
+        context.add_instruction("i32.and".to_owned());
+    }
+
+    /// Conversion with the C cast semantics, floating point values are truncated without a trap
+    pub fn convert(from: NumberType, to: NumberType, context: &mut WasmApplicationContext) {
+        let (from_name, to_name) = (type_name(from), type_name(to));
+
+        match (from, to) {
+            (from, to) if from == to => (),
+            (from, NumberType::Bool) => {
+                context.add_instruction(constant(Number::I32(0).convert(from)));
+                context.add_instruction(format!("{}.ne", from_name));
+            },
+            (NumberType::Float, NumberType::Double) => context.add_instruction("f64.promote_f32".to_owned()),
+            (NumberType::Double, NumberType::Float) => context.add_instruction("f32.demote_f64".to_owned()),
+            (from, to) if from.is_float() => {
+                // Small unsigned integers are cut from the signed value like the processor does
+                let signed = to.is_signed() || Self::bits(to) < 32;
+                context.add_instruction(format!("{}.trunc_sat_{}_{}", to_name, from_name, Self::sign(signed)));
+                Self::narrow(to, context);
+            },
+            (from, to) if to.is_float() => context.add_instruction(format!("{}.convert_{}_{}", to_name, from_name, Self::sign(from.is_signed()))),
+            (_, to) if from_name == "i64" && to_name == "i32" => {
+                context.add_instruction("i32.wrap_i64".to_owned());
+                Self::narrow(to, context);
+            },
+            (from, _) if from_name == "i32" && to_name == "i64" => context.add_instruction(format!("i64.extend_i32_{}", Self::sign(from.is_signed()))),
+            (from, to) if !Self::fits(from, to) => Self::narrow(to, context),
+            _ => ()
+        };
+    }
+
+    /// Pushes the value with the type of the expression
+    fn compile_expression(expression: Expression, number_type: NumberType, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let operation = ExpressionDiscriminant::from(&expression);
+
+        match expression {
+            Expression::Value(Value::Variable(variable)) => {
+                let variable = scope.find_variable(&variable).ok_or(SourceError::VariableNotFound(variable))?;
+                context.add_instruction(format!("local.get {}", variable.target_name));
+                Ok(())
+            },
+            Expression::Value(Value::Number(num)) => {
+                context.add_instruction(constant(num));
+                Ok(())
+            },
+
+            // Address of the string, like the assembly backends
+            Expression::Value(Value::String(data)) => {
+                let name = context.add_string(&data);
+                context.add_instruction(format!("global.get {}", name));
+                context.add_instruction("i64.extend_i32_u".to_owned());
+                Ok(())
+            },
+            Expression::Condition(condition) => WasmConditionCompiler::compile(*condition, scope, context),
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } |
+            Expression::Div { divider: right, divided: left } |
+            Expression::Modulo { divider: right, divided: left } => Self::compile_binary(operation, *left, *right, number_type, scope, context),
+            Expression::ShiftLeft { target, source } |
+            Expression::ShiftRight { target, source } => Self::compile_shift(operation, *target, *source, number_type, scope, context),
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => Self::compile_unary(operation, *source, number_type, scope, context)
+        }
+    }
+
+    /// Both operands are converted to the type of the operation
+    fn compile_binary(operation: ExpressionDiscriminant, left: Expression, right: Expression, number_type: NumberType, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let instruction = match (number_type.is_float(), operation) {
+            (true, ExpressionDiscriminant::Add) => "add",
+            (true, ExpressionDiscriminant::Sub) => "sub",
+            (true, ExpressionDiscriminant::Mul) => "mul",
+            (true, ExpressionDiscriminant::Div) => "div",
+            (true, _) => return Err(SourceError::UnsupportedFloatOperation),
+            (false, ExpressionDiscriminant::Add) => "add",
+            (false, ExpressionDiscriminant::Sub) => "sub",
+            (false, ExpressionDiscriminant::Mul) => "mul",
+            (false, ExpressionDiscriminant::BitwiseAnd) => "and",
+            (false, ExpressionDiscriminant::BitwiseOr) => "or",
+            (false, ExpressionDiscriminant::BitwiseXor) => "xor",
+            (false, ExpressionDiscriminant::Div) if number_type.is_signed() => "div_s",
+            (false, ExpressionDiscriminant::Div) => "div_u",
+            (false, _) if number_type.is_signed() => "rem_s",
+            (false, _) => "rem_u"
+        };
+
+        Self::compile(left, number_type, scope, context)?;
+        Self::compile(right, number_type, scope, context)?;
+        context.add_instruction(format!("{}.{}", type_name(number_type), instruction));
+        Ok(())
+    }
+
+    /// WebAssembly uses only the lower bits of the shift count like the processor, signed values are shifted arithmetically to right
+    fn compile_shift(operation: ExpressionDiscriminant, target: Expression, source: Expression, number_type: NumberType, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        if number_type.is_float() {
+            return Err(SourceError::UnsupportedFloatOperation);
+        }
+
+        Self::compile(target, number_type, scope, context)?;
+        Self::compile(source, number_type, scope, context)?;
+
+        let instruction = match (operation, number_type.is_signed()) {
+            (ExpressionDiscriminant::ShiftLeft, _) => "shl",
+            (_, true) => "shr_s",
+            (_, false) => "shr_u"
+        };
+
+        context.add_instruction(format!("{}.{}", type_name(number_type), instruction));
+        Ok(())
+    }
+
+    /// Negative value is subtracted from zero for the integers and the floating point values
+    fn compile_unary(operation: ExpressionDiscriminant, source: Expression, number_type: NumberType, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        if number_type.is_float() && operation == ExpressionDiscriminant::BitwiseNot {
+            return Err(SourceError::UnsupportedFloatOperation);
+        }
+
+        if operation == ExpressionDiscriminant::BitwiseNeg {
+            context.add_instruction(constant(Number::I32(0).convert(number_type)));
+        }
+
+        Self::compile(source, number_type, scope, context)?;
+
+        let (operand, instruction) = match operation {
+            ExpressionDiscriminant::BitwiseNot => (Some(Number::I32(-1)), "xor"),
+            ExpressionDiscriminant::BitwiseNeg => (None, "sub"),
+            ExpressionDiscriminant::Inc => (Some(Number::I32(1)), "add"),
+            _ => (Some(Number::I32(1)), "sub")
+        };
+
+        if let Some(operand) = operand {
+            context.add_instruction(constant(operand.convert(number_type)));
+        }
+
+        context.add_instruction(format!("{}.{}", type_name(number_type), instruction));
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+mod expression;
+mod statement;
+mod definition;
+mod condition;
+
+pub use expression::WasmExpressionCompiler;
+pub use statement::WasmStatementCompiler;
+pub use definition::WasmDefinitionCompiler;
+pub use condition::WasmConditionCompiler;
@@ -0,0 +1,258 @@
+use tb_core::{error::SourceError, scope::StatementCompiler, types::{Block, Condition, Expression, Number, NumberType, Statement, Value}};
+
+use crate::{scope::{WasmLoop, WasmScope}, types::{constant, type_name}, WasmApplicationContext, ARGUMENTS_NAME, ARGUMENT_SLOT_SIZE, PRINT_FUNCTION};
+
+use super::{condition::WasmConditionCompiler, expression::WasmExpressionCompiler};
+
+pub struct WasmStatementCompiler;
+
+impl StatementCompiler for WasmStatementCompiler {
+    type Scope = WasmScope;
+    type Context = WasmApplicationContext;
+    type Error = SourceError;
+
+    fn compile(statement: Statement, scope: &mut WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        match statement {
+            Statement::Assign { name, assigne } => Self::compile_assign(scope, name, assigne, context),
+            Statement::Declare { name, ty, init } => Self::compile_declare(scope, name, ty, init, context),
+            Statement::Call { name, arguments, assign, is_variadic } => Self::compile_call(scope, name, arguments, assign, is_variadic, context),
+            Statement::Print { format, arguments } => Self::compile_print(scope, format, arguments, context),
+            Statement::Return(expr) => Self::compile_return(scope, expr, context),
+            Statement::If { condition, true_block, false_block } => Self::compile_if(scope, condition, true_block, false_block, context),
+            Statement::While { condition, block } => Self::compile_while(scope, condition, block, context),
+            Statement::Loop { block } => Self::compile_loop(scope, block, context),
+            Statement::Break => Self::compile_break(scope, context),
+            Statement::Continue => Self::compile_continue(scope, context),
+        }
+    }
+}
+
+impl WasmStatementCompiler {
+    fn compile_assign(scope: &mut WasmScope, name: String, assigne: Expression, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        // Undeclared variable takes the type of the expression
+        let var_type = match scope.find_variable(&name) {
+            Some(variable) => variable.var_type,
+            None => WasmExpressionCompiler::get_type(&assigne, scope)?
+        };
+
+        WasmExpressionCompiler::compile(assigne, var_type, scope, context)?;
+
+        let wasm_name = match scope.find_variable(&name) {
+            Some(variable) => variable.target_name.clone(),
+            None => scope.add_variable(&name, var_type).target_name.clone()
+        };
+
+        context.add_instruction(format!("local.set {}", wasm_name));
+        Ok(())
+    }
+
+    fn compile_declare(scope: &mut WasmScope, name: String, var_type: NumberType, init: Option<Expression>, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        // Initial value could use the shadowed variable, so, calculate it before the declaration
+        if let Some(init) = init {
+            WasmExpressionCompiler::compile(init, var_type, scope, context)?;
+            let wasm_name = scope.add_variable(&name, var_type).target_name.clone();
+            context.add_instruction(format!("local.set {}", wasm_name));
+        } else {
+            scope.add_variable(&name, var_type);
+        }
+
+        Ok(())
+    }
+
+    /// Strings and the data labels are passed with their addresses
+    fn address(argument: &Value, scope: &WasmScope, context: &mut WasmApplicationContext) -> Option<String> {
+        match argument {
+            Value::String(data) => Some(context.add_string(data)),
+            Value::Variable(name) if scope.find_variable(name).is_none() && context.datas.items.iter().any(|item| item.label == *name) => Some(format!("${}", name)),
+            _ => None
+        }
+    }
+
+    /// Pushes the argument with the default argument promotions of C, integers are extended to 64bit like the registers
+    fn variadic_argument(argument: Value, scope: &WasmScope, context: &mut WasmApplicationContext) -> Result<NumberType, SourceError> {
+        if let Some(address) = Self::address(&argument, scope, context) {
+            context.add_instruction(format!("global.get {}", address));
+            context.add_instruction("i64.extend_i32_u".to_owned());
+            return Ok(NumberType::U64);
+        }
+
+        let argument = Expression::Value(argument);
+        let argument_type = match WasmExpressionCompiler::get_type(&argument, scope)? {
+            argument_type if argument_type.is_float() => NumberType::Double,
+            argument_type if argument_type.is_signed() => NumberType::I64,
+            _ => NumberType::U64
+        };
+
+        WasmExpressionCompiler::compile(argument, argument_type, scope, context)?;
+        Ok(argument_type)
+    }
+
+    fn compile_print(scope: &mut WasmScope, format: String, arguments: Vec<Value>, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let mut tmp_arguments = Vec::new();
+        tmp_arguments.push(Value::String(format));
+        tmp_arguments.extend(arguments);
+
+        Self::compile_call(scope, PRINT_FUNCTION.to_owned(), tmp_arguments, None, true, context)
+    }
+
+    /// Arguments after the format are written to the argument area, printf gets the address of the format and the area
+    fn compile_print_arguments(scope: &WasmScope, arguments: Vec<Value>, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let mut arguments = arguments.into_iter();
+        let format = arguments.next();
+
+        for (index, argument) in arguments.enumerate() {
+            context.add_instruction(format!("global.get {}", ARGUMENTS_NAME));
+            let argument_type = Self::variadic_argument(argument, scope, context)?;
+
+            let offset = match index {
+                0 => String::new(),
+                index => format!(" offset={}", index * ARGUMENT_SLOT_SIZE)
+            };
+            context.add_instruction(format!("{}.store{}", type_name(argument_type), offset));
+            context.argument_count = context.argument_count.max(index + 1);
+        }
+
+        match format.as_ref().and_then(|format| Self::address(format, scope, context)) {
+            Some(address) => context.add_instruction(format!("global.get {}", address)),
+            None => match format {
+                Some(format) => WasmExpressionCompiler::compile(Expression::Value(format), NumberType::U32, scope, context)?,
+                None => context.add_instruction(constant(Number::I32(0)))
+            }
+        };
+
+        context.add_instruction(format!("global.get {}", ARGUMENTS_NAME));
+        Ok(())
+    }
+
+    /// Arguments of the application functions are converted to the parameter types, the others are imported from the host
+    fn compile_call(scope: &mut WasmScope, name: String, arguments: Vec<Value>, assign: Option<String>, is_variadic: bool, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let (callee, return_type) = match context.functions.get(&name).cloned() {
+            Some(function) => {
+                for (index, argument) in arguments.into_iter().enumerate() {
+                    let argument = Expression::Value(argument);
+                    let argument_type = match function.parameters.get(index) {
+                        Some(parameter_type) => *parameter_type,
+                        None => WasmExpressionCompiler::get_type(&argument, scope)?
+                    };
+                    WasmExpressionCompiler::compile(argument, argument_type, scope, context)?;
+                }
+
+                (name.clone(), function.return_type)
+            },
+
+            // printf returns the number of the printed bytes, the others are expected to return a full register
+            None if is_variadic && name.trim_start_matches('_') == PRINT_FUNCTION => {
+                Self::compile_print_arguments(scope, arguments, context)?;
+                context.add_import(PRINT_FUNCTION, vec![NumberType::U32, NumberType::U32], NumberType::I32);
+                (PRINT_FUNCTION.to_owned(), Some(NumberType::I32))
+            },
+            None => {
+                let mut parameters = Vec::new();
+                for argument in arguments.into_iter() {
+                    parameters.push(Self::variadic_argument(argument, scope, context)?);
+                }
+
+                context.add_import(&name, parameters, NumberType::I64);
+                (name.clone(), Some(NumberType::I64))
+            }
+        };
+
+        context.add_instruction(format!("call ${}", callee));
+
+        match (assign, return_type) {
+            (Some(_), None) => return Err(SourceError::MissingReturnValue(name)),
+            (Some(assigned), Some(return_type)) => {
+                // Undeclared variable gets the full register
+                let (wasm_name, var_type) = match scope.find_variable(&assigned) {
+                    Some(variable) => (variable.target_name.clone(), variable.var_type),
+                    None => (scope.add_variable(&assigned, NumberType::I64).target_name.clone(), NumberType::I64)
+                };
+
+                WasmExpressionCompiler::convert(return_type, var_type, context);
+                context.add_instruction(format!("local.set {}", wasm_name));
+            },
+            (None, Some(_)) => context.add_instruction("drop".to_owned()),
+            (None, None) => ()
+        };
+
+        Ok(())
+    }
+
+    fn compile_if(scope: &mut WasmScope, condition: Condition, true_block: Block, false_block: Option<Block>, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        WasmConditionCompiler::compile(condition, scope, context)?;
+
+        context.begin_block("if".to_owned());
+        Self::compile_block(true_block, scope, context)?;
+
+        if let Some(false_block) = false_block {
+            context.add_else();
+            Self::compile_block(false_block, scope, context)?;
+        }
+
+        context.end_block();
+        Ok(())
+    }
+
+    /// Break jumps to the end of the outer block, continue jumps to the beginning of the loop
+    fn begin_loop(context: &mut WasmApplicationContext) -> WasmLoop {
+        let branches = WasmLoop { begin: context.new_label(), end: context.new_label() };
+        context.begin_block(format!("block {}", branches.end));
+        context.begin_block(format!("loop {}", branches.begin));
+        branches
+    }
+
+    fn compile_while(scope: &mut WasmScope, condition: Condition, block: Block, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let branches = Self::begin_loop(context);
+
+        // Exit from loop
+        WasmConditionCompiler::compile(condition, scope, context)?;
+        context.add_instruction("i32.eqz".to_owned());
+        context.add_instruction(format!("br_if {}", branches.end));
+
+        Self::compile_loop_body(scope, branches, block, context)
+    }
+
+    fn compile_loop(scope: &mut WasmScope, block: Block, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let branches = Self::begin_loop(context);
+        Self::compile_loop_body(scope, branches, block, context)
+    }
+
+    fn compile_loop_body(scope: &mut WasmScope, branches: WasmLoop, block: Block, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        scope.loops.push(branches.clone());
+        Self::compile_block(block, scope, context)?;
+        scope.loops.pop();
+
+        context.add_instruction(format!("br {}", branches.begin));
+        context.end_block();
+        context.end_block();
+        Ok(())
+    }
+
+    fn compile_break(scope: &mut WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let branches = scope.loops.last().ok_or(SourceError::OutsideOfLoop)?;
+        context.add_instruction(format!("br {}", branches.end));
+        Ok(())
+    }
+
+    fn compile_continue(scope: &mut WasmScope, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        let branches = scope.loops.last().ok_or(SourceError::OutsideOfLoop)?;
+        context.add_instruction(format!("br {}", branches.begin));
+        Ok(())
+    }
+
+    /// Value is converted to the return type of the function, the empty return gives zero if the function has a type
+    fn compile_return(scope: &mut WasmScope, expr: Option<Value>, context: &mut WasmApplicationContext) -> Result<(), SourceError> {
+        match (expr, scope.return_type) {
+            (Some(value), Some(return_type)) => {
+                let value = Expression::Value(value);
+                scope.return_types.push(WasmExpressionCompiler::get_type(&value, scope)?);
+                WasmExpressionCompiler::compile(value, return_type, scope, context)?;
+            },
+            (None, Some(return_type)) => context.add_instruction(constant(Number::I32(0).convert(return_type))),
+            (_, None) => ()
+        };
+
+        context.add_instruction("return".to_owned());
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use tb_core::{error::{CodeGenerationError, TBError}, syntax::Syntax, target::Target, types::{AssemblyGenerator, DataItemCollection, Definition}};
+
+use crate::{compiler::WasmDefinitionCompiler, types::{bytes_literal, data_bytes, type_name}, WasmApplicationContext, ARGUMENTS_NAME, ARGUMENT_SLOT_SIZE, DATA_START, HOST_MODULE, INDENT, PAGE_SIZE};
+
+/// WebAssembly module in the text format, it could be converted to the binary format with wat2wasm.
+/// The module imports printf from the host and exports its memory and main.
+#[derive(Debug, Clone, Default)]
+pub struct WasmTextGenerator {
+    target: Target
+}
+
+impl WasmTextGenerator {
+    /// Functions of the definitions before the module level declarations are added
+    pub fn compile(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<WasmApplicationContext, CodeGenerationError> {
+        let mut context = WasmApplicationContext::new(self.target, datas);
+        context.functions = definitions.iter().map(|definition| match definition {
+            Definition::Function { name, .. } => (name.clone(), WasmDefinitionCompiler::signature(definition))
+        }).collect();
+
+        // Calls could be compiled before the called function, so, the return types are found first
+        let mut return_types = Vec::new();
+        for definition in definitions.iter() {
+            let Definition::Function { name, .. } = definition;
+            return_types.push((name.clone(), WasmDefinitionCompiler::return_type(definition.clone(), &context)?));
+        }
+
+        for (name, return_type) in return_types.into_iter() {
+            if let Some(function) = context.functions.get_mut(&name) {
+                function.return_type = return_type;
+            }
+        }
+
+        for item in definitions.into_iter() {
+            WasmDefinitionCompiler::compile(item, &mut context)?;
+        }
+
+        Ok(context)
+    }
+
+    /// Strings and the data items are placed one after another, the argument area is after them
+    pub fn write(context: WasmApplicationContext) -> String {
+        let mut lines = vec!["(module".to_owned()];
+
+        if !context.imports.is_empty() {
+            lines.extend(context.imports.iter().map(|import| {
+                let parameters = match import.parameters.is_empty() {
+                    true => String::new(),
+                    false => format!(" (param {})", import.parameters.iter().map(|parameter| type_name(*parameter)).collect::<Vec<_>>().join(" "))
+                };
+                format!("{}(import \"{}\" \"{}\" (func ${}{} (result {})))", INDENT, HOST_MODULE, import.name, import.name, parameters, type_name(import.return_type))
+            }));
+            lines.push(String::new());
+        }
+
+        let strings = context.strings.iter().enumerate().map(|(index, data)| (format!("$.str.{}", index + 1), data.as_bytes().to_vec()));
+        let datas = context.datas.items.iter().map(|item| (format!("${}", item.label), data_bytes(&item.values)));
+
+        let mut globals = Vec::new();
+        let mut segments = Vec::new();
+        let mut offset = DATA_START;
+
+        for (name, bytes) in strings.chain(datas) {
+            globals.push(format!("{}(global {} i32 (i32.const {}))", INDENT, name, offset));
+            segments.push(format!("{}(data (i32.const {}) {})", INDENT, offset, bytes_literal(&bytes)));
+            offset += bytes.len() + 1;
+        }
+
+        if context.argument_count > 0 {
+            offset = offset.next_multiple_of(ARGUMENT_SLOT_SIZE);
+            globals.push(format!("{}(global {} i32 (i32.const {}))", INDENT, ARGUMENTS_NAME, offset));
+            offset += context.argument_count * ARGUMENT_SLOT_SIZE;
+        }
+
+        lines.push(format!("{}(memory (export \"memory\") {})", INDENT, offset.div_ceil(PAGE_SIZE).max(1)));
+        lines.extend(globals);
+        lines.extend(segments);
+        lines.push(String::new());
+
+        lines.extend(context.lines);
+        if lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+
+        lines.push(")".to_owned());
+        lines.into_iter().map(|line| format!("{}\r\n", line)).collect()
+    }
+}
+
+impl AssemblyGenerator for WasmTextGenerator {
+    fn new(target: Target) -> Self {
+        Self { target }
+    }
+
+    /// WebAssembly is the same for every target and it has only one text syntax, they are ignored
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, _: Syntax) -> Result<String, TBError> {
+        let context = self.compile(definitions, datas)?;
+        Ok(Self::write(context))
+    }
+}
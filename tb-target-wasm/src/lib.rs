@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+use tb_core::{target::Target, types::{DataItemCollection, NumberType}};
+
+pub mod compiler;
+pub mod generator;
+pub mod scope;
+pub mod types;
+
+pub const INDENT: &str = "  ";
+
+/// Print statements call the printf of the host, it gets the address of the format and the address of the arguments
+pub const PRINT_FUNCTION: &str = "printf";
+
+/// Module name of the imported functions
+pub const HOST_MODULE: &str = "env";
+
+/// Every variadic argument uses an 8 byte slot, integers are extended to 64bit and floats are written as double
+pub const ARGUMENT_SLOT_SIZE: usize = 8;
+
+/// Global with the address of the variadic argument area
+pub const ARGUMENTS_NAME: &str = "$.arguments";
+
+/// Data segment starts after the first slot, so, no string has the null address
+pub const DATA_START: usize = 8;
+
+pub const PAGE_SIZE: usize = 65536;
+
+#[derive(Debug, Clone)]
+pub struct WasmFunction {
+    pub parameters: Vec<NumberType>,
+
+    /// Function without a returned value does not have a type
+    pub return_type: Option<NumberType>
+}
+
+/// Function of the host, parameters are taken from the first call
+#[derive(Debug, Clone)]
+pub struct WasmImport {
+    pub name: String,
+    pub parameters: Vec<NumberType>,
+    pub return_type: NumberType
+}
+
+/// Instructions of the functions, the module level declarations are written after all functions are compiled
+#[derive(Debug, Default)]
+pub struct WasmApplicationContext {
+    pub target: Target,
+    pub datas: DataItemCollection,
+    pub functions: HashMap<String, WasmFunction>,
+    pub imports: Vec<WasmImport>,
+    pub strings: Vec<String>,
+
+    /// Biggest number of the variadic arguments, the argument area is allocated for it
+    pub argument_count: usize,
+    pub lines: Vec<String>,
+    indent: usize,
+    label_count: usize
+}
+
+impl WasmApplicationContext {
+    pub fn new(target: Target, datas: DataItemCollection) -> Self {
+        Self { target, datas, ..Default::default() }
+    }
+
+    /// Labels have a dot, so they could not be the same with the variables
+    pub fn new_label(&mut self) -> String {
+        self.label_count += 1;
+        format!("$.L{}", self.label_count)
+    }
+
+    pub fn begin_function(&mut self) {
+        self.label_count = 0;
+        self.indent = 0;
+    }
+
+    /// Instructions are written inside of the function
+    pub fn add_instruction(&mut self, instruction: String) {
+        self.lines.push(format!("{}{}", INDENT.repeat(self.indent + 2), instruction));
+    }
+
+    /// Structured instruction, following instructions are indented until its end
+    pub fn begin_block(&mut self, instruction: String) {
+        self.add_instruction(instruction);
+        self.indent += 1;
+    }
+
+    pub fn add_else(&mut self) {
+        self.indent -= 1;
+        self.add_instruction("else".to_owned());
+        self.indent += 1;
+    }
+
+    pub fn end_block(&mut self) {
+        self.indent -= 1;
+        self.add_instruction("end".to_owned());
+    }
+
+    pub fn add_import(&mut self, name: &str, parameters: Vec<NumberType>, return_type: NumberType) {
+        if !self.imports.iter().any(|import| import.name == name) {
+            self.imports.push(WasmImport { name: name.to_owned(), parameters, return_type });
+        }
+    }
+
+    /// Global with the address of the string, the same strings use the same global
+    pub fn add_string(&mut self, data: &str) -> String {
+        let index = match self.strings.iter().position(|item| item == data) {
+            Some(index) => index,
+            None => {
+                self.strings.push(data.to_owned());
+                self.strings.len() - 1
+            }
+        };
+        format!("$.str.{}", index + 1)
+    }
+}
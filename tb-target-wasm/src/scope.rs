@@ -0,0 +1,43 @@
+use tb_core::{scope::{Scope, ScopeNaming, ScopedVariable}, types::NumberType};
+
+/// Local names start with a dollar sign, shadowing declarations get a suffix
+#[derive(Debug, Clone)]
+pub struct WasmNaming;
+
+impl ScopeNaming for WasmNaming {
+    fn variable_name(name: &str, index: usize) -> String {
+        match index {
+            0 => format!("${}", name),
+            index => format!("${}.{}", name, index)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WasmLoop {
+    pub begin: String,
+    pub end: String
+}
+
+/// Variables of a function, every declaration has its own local
+#[derive(Debug, Default)]
+pub struct WasmScope {
+    variables: Scope<WasmNaming>,
+    pub loops: Vec<WasmLoop>,
+    pub return_type: Option<NumberType>,
+    pub return_types: Vec<NumberType>
+}
+
+impl WasmScope {
+    pub fn find_variable(&self, variable: &str) -> Option<&ScopedVariable> {
+        self.variables.find_variable(variable)
+    }
+
+    pub fn add_variable(&mut self, name: &str, var_type: NumberType) -> &ScopedVariable {
+        self.variables.add_variable(name, var_type)
+    }
+
+    pub fn get_variables(&self) -> &[ScopedVariable] {
+        self.variables.get_variables()
+    }
+}
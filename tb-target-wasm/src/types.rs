@@ -0,0 +1,79 @@
+use tb_core::{syntax::Data, types::{Number, NumberType}};
+
+/// Integers smaller than 64bit are kept in i32 with the extension of their type
+pub fn type_name(number_type: NumberType) -> &'static str {
+    match number_type {
+        NumberType::I64 | NumberType::U64 => "i64",
+        NumberType::Float => "f32",
+        NumberType::Double => "f64",
+        _ => "i32"
+    }
+}
+
+/// Instruction that pushes the constant
+pub fn constant(number: Number) -> String {
+    let value = match number {
+        Number::I8(num) => num.to_string(),
+        Number::U8(num) => num.to_string(),
+        Number::I16(num) => num.to_string(),
+        Number::U16(num) => num.to_string(),
+        Number::I32(num) => num.to_string(),
+        Number::U32(num) => num.to_string(),
+        Number::I64(num) => num.to_string(),
+        Number::U64(num) => num.to_string(),
+        Number::Float(num) => float_literal(num.is_nan(), num.is_sign_negative(), num.is_infinite(), format!("{:?}", num)),
+        Number::Double(num) => float_literal(num.is_nan(), num.is_sign_negative(), num.is_infinite(), format!("{:?}", num)),
+        Number::Bool(num) => (num as u8).to_string()
+    };
+
+    format!("{}.const {}", type_name(number.number_type()), value)
+}
+
+/// Shortest decimal form reads back the same value, NaN and infinity have their own names
+fn float_literal(nan: bool, negative: bool, infinite: bool, decimal: String) -> String {
+    let sign = match negative {
+        true => "-",
+        false => ""
+    };
+
+    match (nan, infinite) {
+        (true, _) => format!("{}nan", sign),
+        (_, true) => format!("{}inf", sign),
+        _ => decimal
+    }
+}
+
+/// String of the data segment with the zero terminator, the other characters are escaped with two hex digits
+pub fn bytes_literal(bytes: &[u8]) -> String {
+    let mut buffer = String::from("\"");
+    for byte in bytes.iter() {
+        match byte {
+            byte if (byte.is_ascii_graphic() || *byte == b' ') && *byte != b'\\' && *byte != b'"' => buffer.push(*byte as char),
+            byte => buffer.push_str(&format!("\\{:02x}", byte))
+        }
+    }
+    buffer.push_str("\\00\"");
+    buffer
+}
+
+/// Bytes of the data item, the terminator of the last string is added by the segment
+pub fn data_bytes(values: &[Data]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for data in values.iter() {
+        match data {
+            Data::String(data) => {
+                bytes.extend_from_slice(data.as_bytes());
+                bytes.push(0);
+            },
+            Data::Byte(data) => bytes.push(*data),
+            Data::Float(data) => bytes.extend_from_slice(&data.to_le_bytes()),
+            Data::Double(data) => bytes.extend_from_slice(&data.to_le_bytes())
+        }
+    }
+
+    if bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+
+    bytes
+}
@@ -1,14 +1,117 @@
-use tb_core::types::{Condition, ConditionDiscriminant, Value};
+use tb_core::types::{Condition, ConditionDiscriminant, Expression, Number, NumberType, RegisterSize};
 
-use crate::{instruction::X86Instruction, X86ApplicationContext, X86Store};
+use crate::{instruction::X86Instruction, X86ApplicationContext, X86Location, X86Store};
 
-use super::{error::X86Error, X86ValueCompiler};
+use super::{error::X86Error, X86ExpressionCompiler};
+
+/// Jump instructions depend on how the compared values are ordered
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum X86ComparisonType {
+    Signed,
+    Unsigned,
+    Float
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct X86Comparison {
+    pub condition_type: ConditionDiscriminant,
+    pub comparison_type: X86ComparisonType
+}
+
+/// What to do when the floating point comparison is unordered (NaN)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum X86Unordered {
+    Ignore,
+    Jump,
+    Skip
+}
+
+type X86JumpInstruction = fn(String) -> X86Instruction;
+type X86SetInstruction = fn(X86Location) -> X86Instruction;
 
 pub struct X86ConditionCompiler;
 
 
 impl X86ConditionCompiler {
-    pub fn compile(condition: Condition, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<ConditionDiscriminant, X86Error> {
+    /// Jump to the label if the condition result is same with the expected one.
+    /// Logical conditions are short-circuit, the right side is not evaluated if the left side decides the result.
+    pub fn compile_jump(condition: Condition, label: &str, jump_when: bool, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        match condition {
+            Condition::Not { source } => Self::compile_jump(*source, label, !jump_when, scope, context),
+
+            // Any side could finish the condition
+            Condition::And { left, right } if !jump_when => {
+                Self::compile_jump(*left, label, false, scope, context)?;
+                Self::compile_jump(*right, label, false, scope, context)
+            },
+            Condition::Or { left, right } if jump_when => {
+                Self::compile_jump(*left, label, true, scope, context)?;
+                Self::compile_jump(*right, label, true, scope, context)
+            },
+
+            // Left side could skip the right side, but only the right side decides the jump
+            Condition::And { left, right } => {
+                let skip = context.storage.create_branch();
+                Self::compile_jump(*left, &skip, false, scope, context)?;
+                Self::compile_jump(*right, label, true, scope, context)?;
+                context.instructions.add_branch(skip);
+                Ok(())
+            },
+            Condition::Or { left, right } => {
+                let skip = context.storage.create_branch();
+                Self::compile_jump(*left, &skip, true, scope, context)?;
+                Self::compile_jump(*right, label, false, scope, context)?;
+                context.instructions.add_branch(skip);
+                Ok(())
+            },
+            condition => {
+                let comparison = Self::compile_comparison(condition, scope, context)?;
+                Self::compile_comparison_jump(comparison, label, jump_when, context)
+            }
+        }
+    }
+
+    /// Boolean value of the condition in a new 8bit register
+    pub fn compile_value(condition: Condition, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match condition {
+            Condition::Not { source } => {
+                let result = Self::compile_value(*source, scope, context)?;
+                context.instructions.add_instruction(X86Instruction::Xor { source: X86Location::Imm(Number::U8(1)), target: result.clone(), comment: None });
+                Ok(result)
+            },
+            condition @ (Condition::And { .. } | Condition::Or { .. }) => {
+                let result = X86ExpressionCompiler::new_register(scope, RegisterSize::_8Bit);
+                let end = context.storage.create_branch();
+
+                context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(Number::U8(0)), target: result.clone(), comment: None });
+                Self::compile_jump(condition, &end, false, scope, context)?;
+                context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(Number::U8(1)), target: result.clone(), comment: None });
+                context.instructions.add_branch(end);
+                Ok(result)
+            },
+            condition => {
+                let comparison = Self::compile_comparison(condition, scope, context)?;
+                Self::compile_comparison_value(comparison, scope, context)
+            }
+        }
+    }
+
+    /// Sethi-Ullman number of the condition value
+    pub fn get_register_need(condition: &Condition) -> usize {
+        match condition {
+            Condition::Eq { left, right } |
+            Condition::Ne { left, right } |
+            Condition::Gr { left, right } |
+            Condition::Ge { left, right } |
+            Condition::Ls { left, right } |
+            Condition::Le { left, right } => X86ExpressionCompiler::get_register_need(&Expression::Sub { target: Box::new(right.clone()), source: Box::new(left.clone()) }),
+            Condition::And { left, right } |
+            Condition::Or { left, right } => std::cmp::max(Self::get_register_need(left), Self::get_register_need(right)) + 1,
+            Condition::Not { source } => Self::get_register_need(source)
+        }
+    }
+
+    fn compile_comparison(condition: Condition, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Comparison, X86Error> {
         match condition {
             Condition::Eq { left, right } => Self::compile_simple(scope, ConditionDiscriminant::Eq, left, right, context),
             Condition::Ne { left, right } => Self::compile_simple(scope, ConditionDiscriminant::Ne, left, right, context),
@@ -16,21 +119,134 @@ impl X86ConditionCompiler {
             Condition::Ge { left, right } => Self::compile_simple(scope, ConditionDiscriminant::Ge, left, right, context),
             Condition::Ls { left, right } => Self::compile_simple(scope, ConditionDiscriminant::Ls, left, right, context),
             Condition::Le { left, right } => Self::compile_simple(scope, ConditionDiscriminant::Le, left, right, context),
+            _ => Err(X86Error::UnexpectedInstruction)
         }
     }
-    
-    fn compile_simple(scope: &mut X86Store, condition_type: ConditionDiscriminant, left: Value, right: Value, context: &mut X86ApplicationContext) -> Result<ConditionDiscriminant, X86Error> {
-        let registers = scope.register_backup();
+
+    fn compile_simple(scope: &mut X86Store, condition_type: ConditionDiscriminant, left: Expression, right: Expression, context: &mut X86ApplicationContext) -> Result<X86Comparison, X86Error> {
+        // Both sides compared with the common type
+        let number_type = X86ExpressionCompiler::get_type(&left, scope)?.common(X86ExpressionCompiler::get_type(&right, scope)?);
 
         context.instructions.add_comment("Generate right value".to_owned());
-        let right = X86ValueCompiler::compile(right, context, scope, None)?;
-        
+        let right = X86ExpressionCompiler::materialize(right, number_type, scope, context)?;
+
         context.instructions.add_comment("Generate left value".to_owned());
-        let left = X86ValueCompiler::compile(left, context, scope, None)?;
+        let left = X86ExpressionCompiler::compile_operand(left, number_type, scope, context)?;
+
+        context.instructions.add_instruction(match number_type {
+            NumberType::Float => X86Instruction::Ucomiss { left, right, comment: None },
+            NumberType::Double => X86Instruction::Ucomisd { left, right, comment: None },
+            _ => X86Instruction::Cmp { left, right, comment: None }
+        });
+
+        let comparison_type = match (number_type.is_float(), number_type.is_signed()) {
+            (true, _) => X86ComparisonType::Float,
+            (false, true) => X86ComparisonType::Signed,
+            (false, false) => X86ComparisonType::Unsigned
+        };
+
+        Ok(X86Comparison { condition_type, comparison_type })
+    }
+
+    /// The flags are set by comparing right with left. Unordered floating point comparison (NaN) sets the parity flag,
+    /// it is false for all conditions except not equal.
+    fn compile_comparison_jump(comparison: X86Comparison, label: &str, jump_when: bool, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let (instruction, unordered): (X86JumpInstruction, X86Unordered) = match (comparison.comparison_type, comparison.condition_type, jump_when) {
+            (X86ComparisonType::Float, ConditionDiscriminant::Eq, false) => (X86Instruction::Jne, X86Unordered::Jump),
+            (X86ComparisonType::Float, ConditionDiscriminant::Eq, true) => (X86Instruction::Je, X86Unordered::Skip),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ne, false) => (X86Instruction::Je, X86Unordered::Skip),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ne, true) => (X86Instruction::Jne, X86Unordered::Jump),
+            (X86ComparisonType::Float, ConditionDiscriminant::Gr, false) => (X86Instruction::Jae, X86Unordered::Jump),
+            (X86ComparisonType::Float, ConditionDiscriminant::Gr, true) => (X86Instruction::Jb, X86Unordered::Skip),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ge, false) => (X86Instruction::Ja, X86Unordered::Jump),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ge, true) => (X86Instruction::Jbe, X86Unordered::Skip),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ls, false) => (X86Instruction::Jbe, X86Unordered::Ignore),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ls, true) => (X86Instruction::Ja, X86Unordered::Ignore),
+            (X86ComparisonType::Float, ConditionDiscriminant::Le, false) => (X86Instruction::Jb, X86Unordered::Ignore),
+            (X86ComparisonType::Float, ConditionDiscriminant::Le, true) => (X86Instruction::Jae, X86Unordered::Ignore),
+
+            (_, ConditionDiscriminant::Eq, false) => (X86Instruction::Jne, X86Unordered::Ignore),
+            (_, ConditionDiscriminant::Eq, true) => (X86Instruction::Je, X86Unordered::Ignore),
+            (_, ConditionDiscriminant::Ne, false) => (X86Instruction::Je, X86Unordered::Ignore),
+            (_, ConditionDiscriminant::Ne, true) => (X86Instruction::Jne, X86Unordered::Ignore),
+
+            (X86ComparisonType::Signed, ConditionDiscriminant::Gr, false) => (X86Instruction::Jge, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Gr, true) => (X86Instruction::Jl, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ge, false) => (X86Instruction::Jg, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ge, true) => (X86Instruction::Jle, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ls, false) => (X86Instruction::Jle, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ls, true) => (X86Instruction::Jg, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Le, false) => (X86Instruction::Jl, X86Unordered::Ignore),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Le, true) => (X86Instruction::Jge, X86Unordered::Ignore),
+
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Gr, false) => (X86Instruction::Jae, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Gr, true) => (X86Instruction::Jb, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ge, false) => (X86Instruction::Ja, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ge, true) => (X86Instruction::Jbe, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ls, false) => (X86Instruction::Jbe, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ls, true) => (X86Instruction::Ja, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Le, false) => (X86Instruction::Jb, X86Unordered::Ignore),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Le, true) => (X86Instruction::Jae, X86Unordered::Ignore),
+
+            _ => return Err(X86Error::UnexpectedInstruction)
+        };
 
-        context.instructions.add_instruction(X86Instruction::Cmp { left, right, comment: None });
-        scope.register_restore(registers);
+        match unordered {
+            X86Unordered::Ignore => { context.instructions.add_instruction(instruction(label.to_owned())); },
+            X86Unordered::Jump => {
+                context.instructions.add_instruction(instruction(label.to_owned()));
+                context.instructions.add_instruction(X86Instruction::Jp(label.to_owned()));
+            },
+            X86Unordered::Skip => {
+                let skip = context.storage.create_branch();
+                context.instructions.add_instruction(X86Instruction::Jp(skip.clone()));
+                context.instructions.add_instruction(instruction(label.to_owned()));
+                context.instructions.add_branch(skip);
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Same flags with the jumps, floating point conditions combine the result with the parity flag
+    fn compile_comparison_value(comparison: X86Comparison, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        let (instruction, unordered): (X86SetInstruction, Option<(X86SetInstruction, bool)>) = match (comparison.comparison_type, comparison.condition_type) {
+            (X86ComparisonType::Float, ConditionDiscriminant::Eq) => (X86Instruction::Sete, Some((X86Instruction::Setnp, false))),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ne) => (X86Instruction::Setne, Some((X86Instruction::Setp, true))),
+            (X86ComparisonType::Float, ConditionDiscriminant::Gr) => (X86Instruction::Setb, Some((X86Instruction::Setnp, false))),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ge) => (X86Instruction::Setbe, Some((X86Instruction::Setnp, false))),
+            (X86ComparisonType::Float, ConditionDiscriminant::Ls) => (X86Instruction::Seta, None),
+            (X86ComparisonType::Float, ConditionDiscriminant::Le) => (X86Instruction::Setae, None),
+
+            (_, ConditionDiscriminant::Eq) => (X86Instruction::Sete, None),
+            (_, ConditionDiscriminant::Ne) => (X86Instruction::Setne, None),
+
+            (X86ComparisonType::Signed, ConditionDiscriminant::Gr) => (X86Instruction::Setl, None),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ge) => (X86Instruction::Setle, None),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Ls) => (X86Instruction::Setg, None),
+            (X86ComparisonType::Signed, ConditionDiscriminant::Le) => (X86Instruction::Setge, None),
+
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Gr) => (X86Instruction::Setb, None),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ge) => (X86Instruction::Setbe, None),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Ls) => (X86Instruction::Seta, None),
+            (X86ComparisonType::Unsigned, ConditionDiscriminant::Le) => (X86Instruction::Setae, None),
+
+            _ => return Err(X86Error::UnexpectedInstruction)
+        };
+
+        let result = X86ExpressionCompiler::new_register(scope, RegisterSize::_8Bit);
+        context.instructions.add_instruction(instruction(result.clone()));
+
+        // Parity flag is combined with "and" for the ordered conditions and with "or" for not equal
+        if let Some((parity_instruction, combine_with_or)) = unordered {
+            let parity = X86ExpressionCompiler::new_register(scope, RegisterSize::_8Bit);
+            context.instructions.add_instruction(parity_instruction(parity.clone()));
+            context.instructions.add_instruction(match combine_with_or {
+                true => X86Instruction::Or { source: parity, target: result.clone(), comment: None },
+                false => X86Instruction::And { source: parity, target: result.clone(), comment: None }
+            });
+        }
 
-        Ok(condition_type)
+        Ok(result)
     }
 }
@@ -1,51 +1,118 @@
-use tb_core::types::{Block, Definition, Number, Parameter};
+use tb_core::{error::CodeGenerationError, types::{Block, CallingConventions, Definition, Number, NumberType, Parameter, RegisterTrait, Statement, Value}};
 
-use crate::{instruction::X86Instruction, register::Register, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
+use crate::{instruction::{X86Instruction, CALLEE_SAVED_REGISTERS, MICROSOFT_CALLEE_SAVED_REGISTERS}, register::Register, register_allocator::X86RegisterAllocator, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
 
-use super::{error::X86Error, statement::{X86StatementCompiler, CALL_CONVENTION}};
+use super::statement::{get_argument_registers, get_call_convention, X86StatementCompiler, SHADOW_SPACE_SIZE};
 
 const FUNCTION_CALL_STACK_SIZE: u16 = 16; //byte
 
 pub struct X86DefinitionCompiler;
 
 impl X86DefinitionCompiler {
-    pub fn compile(definition: Definition, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+    pub fn compile(definition: Definition, context: &mut X86ApplicationContext) -> Result<(), CodeGenerationError> {
         match definition {
             Definition::Function { name, parameters, block } => Self::compile_function(name, parameters, block, context),
         }
     }
 
-    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+    fn compile_function(name: String, arguments: Vec<Parameter>, block: Block, context: &mut X86ApplicationContext) -> Result<(), CodeGenerationError> {
         let mut scope = X86Store::default();
 
-        // Function name
-        context.instructions.add_branch(name.to_owned());
+        // Function name, entry point could have a different name on the target
+        let is_main = name == "main";
+        let label = match is_main {
+            true => context.os_specific_defs.main_function_name().to_owned(),
+            false => name.clone()
+        };
+        context.instructions.add_branch(label);
 
         // Function begin
         context.instructions.add_instruction(X86Instruction::Push(X86Location::Register(X86AddressingMode::Direct(Register::RBP))));
         context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), target: X86Location::Register(X86AddressingMode::Direct(Register::RBP)), comment: None });
 
-        for (index, parameter) in arguments.into_iter().enumerate().rev() {
-            let register = (*CALL_CONVENTION).get_register(index);
-            let variable = scope.add_variable(&parameter.name, parameter.param_type.size() as u8);
+        let argument_types = arguments.iter().map(|parameter| parameter.param_type).collect::<Vec<_>>();
+        let convention = get_call_convention(context);
+        let argument_registers = get_argument_registers(convention, &argument_types);
 
-            if let Some(reg) = register {
-                context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(reg)), target: X86Location::Register(X86AddressingMode::Based(-(variable.position as i32), Register::RBP)), comment: None });
-            }
-        }
+        // Stack arguments are after the return address and the saved RBP, Microsoft x64 has the shadow space before them
+        let mut stack_offset = match convention.convention {
+            CallingConventions::Microsoft => 16 + SHADOW_SPACE_SIZE as i32,
+            _ => 16
+        };
+
+        let callee_saved_registers: &[Register] = match convention.convention {
+            CallingConventions::Microsoft => &MICROSOFT_CALLEE_SAVED_REGISTERS,
+            _ => &CALLEE_SAVED_REGISTERS
+        };
 
+        // Stack is allocated before the parameters are stored, there is no red zone on Microsoft x64 and it is limited on System V
         context.instructions.add_instruction(X86Instruction::Sub { source: X86Location::Imm(Number::U16(0)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: None });
         let stack_pointer_position = context.instructions.last_instruction_position();
 
+        for (parameter, register) in arguments.into_iter().zip(argument_registers) {
+            let position = scope.add_variable(&parameter.name, parameter.param_type).position;
+            let variable = X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP));
+
+            match register {
+                Some(reg) => {
+                    context.instructions.add_instruction(X86Instruction::typed_movement(parameter.param_type, X86Location::Register(X86AddressingMode::Direct(reg.get_sized(parameter.param_type.size()))), variable, None));
+                },
+                None => {
+                    // Copy the bits of the stack argument into the local variable
+                    let register = X86Location::Register(X86AddressingMode::Direct(Register::RAX.get_sized(parameter.param_type.size())));
+                    context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Based(stack_offset, Register::RBP)), target: register.clone(), comment: Some(format!("parameter {}", parameter.name)) });
+                    context.instructions.add_instruction(X86Instruction::Mov { source: register, target: variable, comment: None });
+                    stack_offset += 8;
+                }
+            }
+        }
+
         context.instructions.add_comment("function body begin".to_owned());
-        
-        
-        for item in block.items.into_iter() {
-            X86StatementCompiler::compile(item, &mut scope, context)?;
+
+        let function_end = context.storage.create_branch();
+        context.storage.function_end = Some(function_end.clone());
+
+        // Like C, main returns zero if it reaches the end without a return
+        let returns_at_end = matches!(block.items.last(), Some(Statement::Return(_)));
+        let statement_count = block.items.len();
+
+        for (index, item) in block.items.into_iter().enumerate() {
+            X86StatementCompiler::compile(item, &mut scope, context).map_err(|error| CodeGenerationError::new(&name, index, error))?;
         }
 
+        // Implicit return is after the last statement
+        if is_main && !returns_at_end {
+            X86StatementCompiler::compile(Statement::Return(Some(Value::Number(Number::I32(0)))), &mut scope, context).map_err(|error| CodeGenerationError::new(&name, statement_count, error))?;
+        }
+
+        context.instructions.add_branch(function_end);
+        context.storage.function_end = None;
         context.instructions.add_comment("function body end".to_owned());
 
+        // Virtual registers are replaced after the whole body is generated, spilled values need stack slots
+        let callee_saved_registers = X86RegisterAllocator::allocate(&mut scope, context, stack_pointer_position + 1, callee_saved_registers);
+
+        // Used callee-saved registers are stored after the stack allocation and restored before the function end
+        for (index, register) in callee_saved_registers.into_iter().enumerate() {
+            // Whole vector register is preserved, it needs two slots
+            let position = match register.is_vector() {
+                true => {
+                    scope.add_temp_variable(NumberType::Double);
+                    scope.add_temp_variable(NumberType::Double).position
+                },
+                false => scope.add_temp_variable(NumberType::I64).position
+            };
+
+            let slot = X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP));
+            let (save, restore) = match register.is_vector() {
+                true => (X86Instruction::Movups { source: X86Location::Register(X86AddressingMode::Direct(register)), target: slot.clone(), comment: None }, X86Instruction::Movups { source: slot, target: X86Location::Register(X86AddressingMode::Direct(register)), comment: None }),
+                false => (X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(register)), target: slot.clone(), comment: None }, X86Instruction::Mov { source: slot, target: X86Location::Register(X86AddressingMode::Direct(register)), comment: None })
+            };
+
+            context.instructions.insert_instruction(save, stack_pointer_position + 1 + index);
+            context.instructions.add_instruction(restore);
+        }
+
         // Function end
         context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(Register::RBP)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: None });
         context.instructions.add_instruction(X86Instruction::Pop(X86Location::Register(X86AddressingMode::Direct(Register::RBP))));
@@ -59,6 +126,9 @@ impl X86DefinitionCompiler {
                 false => scope.get_last_position() as u16
             };
 
+            // Stack should be 16 byte aligned at the function call
+            let stack_size = stack_size.next_multiple_of(16);
+
             context.instructions.update_instruction(X86Instruction::Sub { source: X86Location::Imm(Number::U16(stack_size)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: None }, stack_pointer_position);
             
         } else {
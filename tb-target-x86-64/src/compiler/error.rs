@@ -1,6 +1,28 @@
-#[derive(Debug)]
+use thiserror::Error;
+
+#[derive(Error, Debug)]
 pub enum X86Error {
+    #[error("Variable not found ({0})")]
     VariableNotFound(String),
+
+    #[error("Unexpected instruction")]
     UnexpectedInstruction,
-    NoRegisterAvailable
+
+    #[error("Break or continue is outside of a loop")]
+    OutsideOfLoop,
+
+    #[error("Return is outside of a function")]
+    OutsideOfFunction,
+
+    #[error("Unsupported float operation")]
+    UnsupportedFloatOperation,
+
+    #[error("Unsupported operands ({0})")]
+    UnsupportedOperands(String),
+
+    #[error("Unknown label ({0})")]
+    UnknownLabel(String),
+
+    #[error("Unsupported format ({0})")]
+    UnsupportedFormat(String)
 }
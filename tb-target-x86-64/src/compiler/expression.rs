@@ -1,30 +1,30 @@
-use tb_core::{addressing_mode::AddressingMode, types::{Expression, RegisterSize, Value}};
+use std::cmp::max;
+
+use tb_core::{addressing_mode::AddressingMode, types::{Expression, Number, NumberType, RegisterSize, RegisterTrait, Value}};
 
 use crate::{instruction::{X86Instruction, X86InstructionType}, register::Register, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
 
-use super::{error::X86Error, value::X86ValueCompiler};
+use super::{condition::X86ConditionCompiler, error::X86Error, value::X86ValueCompiler};
 
-const SPECIAL_INSTRUCTION_CHECK: [X86InstructionType; 3] = [
-    X86InstructionType::IMul,
+const SPECIAL_INSTRUCTION_CHECK: [X86InstructionType; 2] = [
     X86InstructionType::Shl,
     X86InstructionType::Shr
 ];
-const SPECIAL_INSTRUCTION: [SpecialConfiguration; 3] = [
-    SpecialConfiguration { inst: X86InstructionType::IMul, fixed_source_type: None, fixed_target_type: Some(FixedType::AnyRegister) },
+const SPECIAL_INSTRUCTION: [SpecialConfiguration; 2] = [
     SpecialConfiguration { inst: X86InstructionType::Shl, fixed_source_type: Some(FixedType::Register(Register::ECX, Some(RegisterSize::_8Bit))), fixed_target_type: None },
     SpecialConfiguration { inst: X86InstructionType::Shr, fixed_source_type: Some(FixedType::Register(Register::ECX, Some(RegisterSize::_8Bit))), fixed_target_type: None },
 ];
 
 #[derive(Debug, Clone)]
 enum FixedType {
-    Register(Register, Option<RegisterSize>),
-    AnyRegister
+    Register(Register, Option<RegisterSize>)
 }
 
 #[derive(Debug)]
 struct SpecialConfiguration {
     #[allow(dead_code)]
     pub inst: X86InstructionType,
+    #[allow(dead_code)]
     pub fixed_target_type: Option<FixedType>,
     pub fixed_source_type: Option<FixedType>,
 }
@@ -33,152 +33,400 @@ pub struct X86ExpressionCompiler;
 
 impl X86ExpressionCompiler {
     pub fn compile(expression: Expression, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let location = Self::compile_expression(expression, scope, context)?;
+        scope.set_last_assigned_location(location);
+        Ok(())
+    }
+
+    /// Compile the expression tree and return the location of the result.
+    /// If the result is stored in a register, it is a new virtual register that is owned by the caller.
+    pub fn compile_expression(expression: Expression, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match expression {
+            Expression::Add { target, source } => Self::compile_simple(scope, X86InstructionType::Add, *target, *source, context),
+            Expression::Sub { target, source } => Self::compile_simple(scope, X86InstructionType::Sub, *target, *source, context),
+            Expression::Mul { target, source } => Self::compile_simple(scope, X86InstructionType::IMul, *target, *source, context),
+            Expression::Modulo { divider, divided } => Self::compile_div(scope, *divider, *divided, Register::EDX, context),
+            Expression::Div { divider, divided } => Self::compile_div(scope, *divider, *divided, Register::EAX, context),
+            Expression::ShiftLeft { target, source } => Self::compile_shift(scope, X86InstructionType::Shl, *target, *source, context),
+            Expression::ShiftRight { target, source } => Self::compile_shift(scope, X86InstructionType::Shr, *target, *source, context),
+            Expression::BitwiseNot { source } => Self::compile_single(scope, X86InstructionType::Not, *source, context),
+            Expression::BitwiseAnd { source, target } => Self::compile_simple(scope, X86InstructionType::And, *target, *source, context),
+            Expression::BitwiseOr { source, target } => Self::compile_simple(scope, X86InstructionType::Or, *target, *source, context),
+            Expression::BitwiseXor { source, target } => Self::compile_simple(scope, X86InstructionType::Xor, *target, *source, context),
+            Expression::BitwiseNeg { source } => Self::compile_single(scope, X86InstructionType::Neg, *source, context),
+            Expression::Dec { source } => Self::compile_single(scope, X86InstructionType::Dec, *source, context),
+            Expression::Inc { source } => Self::compile_single(scope, X86InstructionType::Inc, *source, context),
+            Expression::Condition(condition) => X86ConditionCompiler::compile_value(*condition, scope, context),
+            Expression::Value(val) => X86ValueCompiler::compile(val, context, scope, None),
+        }
+    }
+
+    /// Type of the expression result. Arithmetic results are promoted to at least I32.
+    pub fn get_type(expression: &Expression, scope: &X86Store) -> Result<NumberType, X86Error> {
+        NumberType::of_expression(expression, &|variable| scope.find_variable(variable).map(|item| item.var_type).ok_or(X86Error::VariableNotFound(variable.to_owned())))
+    }
+
+    /// Sethi-Ullman number of the expression: how many registers are required to evaluate it
+    pub fn get_register_need(expression: &Expression) -> usize {
         match expression {
-            Expression::Add { target, source } => Self::compile_simple(scope, X86InstructionType::Add, target, source, context),
-            Expression::Sub { target, source } => Self::compile_simple(scope, X86InstructionType::Sub, target, source, context),
-            Expression::Mul { target, source } => Self::compile_simple(scope, X86InstructionType::IMul, target, source, context),
-            Expression::Modulo { divider, divided } => Self::compile_div(scope, divider, divided, Register::EDX, context),
-            Expression::Div { divider, divided } => Self::compile_div(scope, divider, divided, Register::EAX, context),
-            Expression::ShiftLeft { target, source } => Self::compile_simple(scope, X86InstructionType::Shl, target, source, context),
-            Expression::ShiftRight { target, source } => Self::compile_simple(scope, X86InstructionType::Shr, target, source, context),
-            Expression::BitwiseNot { source } => Self::compile_single(scope, X86InstructionType::Not, source, context),
-            Expression::BitwiseAnd { source, target } => Self::compile_simple(scope, X86InstructionType::And, target, source, context),
-            Expression::BitwiseOr { source, target } => Self::compile_simple(scope, X86InstructionType::Or, target, source, context),
-            Expression::BitwiseXor { source, target } => Self::compile_simple(scope, X86InstructionType::Xor, target, source, context),
-            Expression::BitwiseNeg { source } => Self::compile_single(scope, X86InstructionType::Neg, source, context),
-            Expression::Dec { source } => Self::compile_single(scope, X86InstructionType::Dec, source, context),
-            Expression::Inc { source } => Self::compile_single(scope, X86InstructionType::Inc, source, context),
-            Expression::Value(val) => Self::compile_value(scope, val, context),
-        }
-    }
-
-    fn get_target_register(scope: &mut X86Store, inst_type: X86InstructionType, get_fixed_type: fn(special_info: &SpecialConfiguration) -> Option<FixedType>) -> Option<X86Location> {
-        match SPECIAL_INSTRUCTION_CHECK.iter().position(|item| *item == inst_type) {
-            Some(position) => {
-                let special_info = &SPECIAL_INSTRUCTION[position];
-                
-                // Get target type information
-                match get_fixed_type(special_info) {
-                    Some(FixedType::AnyRegister) => scope.lock_register(scope.get_last_size()).map(|reg| X86Location::Register(AddressingMode::Direct(reg))),
-                    Some(FixedType::Register(expected_register, expected_size)) => {
-                        // The register not used, so, we can use it
-                        if scope.is_free(expected_register) {
-                            scope.mark_register(expected_register);
-
-                            // The required register has different size
-                            if let Some(expected_size) = expected_size {
-                                let opcode_info = expected_register.get_info();
-
-                                let new_opcode = match expected_size {
-                                    RegisterSize::_8Bit => opcode_info._8bit_low,
-                                    RegisterSize::_16Bit => opcode_info._16bit,
-                                    RegisterSize::_32Bit => opcode_info._32bit,
-                                    RegisterSize::_64Bit => opcode_info._64bit,
-                                };
-
-                                Some(X86Location::Register(AddressingMode::Direct(new_opcode)))
-                            } else {
-                                Some(X86Location::Register(AddressingMode::Direct(expected_register)))
-                            }
-                        } else {
-                            None
-                        }
-                    },
-                    None => None,
+            Expression::Condition(condition) => X86ConditionCompiler::get_register_need(condition),
+            Expression::Value(Value::Variable(_)) => 0,
+            Expression::Value(_) => 1,
+            Expression::ShiftLeft { target: left, source: right } |
+            Expression::ShiftRight { target: left, source: right } |
+            Expression::Div { divider: left, divided: right } |
+            Expression::Modulo { divider: left, divided: right } |
+            Expression::Add { target: left, source: right } |
+            Expression::Sub { target: left, source: right } |
+            Expression::Mul { target: left, source: right } |
+            Expression::BitwiseAnd { target: left, source: right } |
+            Expression::BitwiseOr { target: left, source: right } |
+            Expression::BitwiseXor { target: left, source: right } => {
+                let left = max(Self::get_register_need(left), 1);
+                let right = Self::get_register_need(right);
+
+                match left == right {
+                    true => left + 1,
+                    false => max(left, right)
                 }
             },
-            None => None
+            Expression::BitwiseNot { source } |
+            Expression::BitwiseNeg { source } |
+            Expression::Inc { source } |
+            Expression::Dec { source } => max(Self::get_register_need(source), 1),
         }
     }
 
-    fn compile_simple(scope: &mut X86Store, inst_type: X86InstructionType, target: Value, source: Value, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
-        let registers = scope.register_backup();
+    /// Move the location into a register, if it is not already in a register.
+    pub fn load_to_register(scope: &mut X86Store, location: X86Location, size: RegisterSize, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match location {
+            X86Location::Register(AddressingMode::Direct(_)) | X86Location::Virtual(_) => Ok(location),
+            X86Location::Label(label) => {
+                let register = Self::new_register(scope, RegisterSize::_64Bit);
+                context.instructions.add_instruction(X86Instruction::Lea { source: X86Location::Label(label), target: register.clone(), comment: None });
+                Ok(register)
+            },
+            location => {
+                let register = Self::new_register(scope, size);
+                context.instructions.add_instruction(X86Instruction::Mov { source: location, target: register.clone(), comment: Some("Move address to reg for calculation".to_owned()) });
+                Ok(register)
+            }
+        }
+    }
 
-        context.instructions.add_comment("Generate source value".to_owned());
-        
-        // Some of the operatorlar need direct register.
-        let source_register = Self::get_target_register(scope, inst_type, |item| item.fixed_source_type.clone());
-        
-        let mut source = X86ValueCompiler::compile(source, context, scope, source_register)?;
-
-        // Some of the operatorlar need direct register.
-        let target_register = Self::get_target_register(scope, inst_type, |item| item.fixed_target_type.clone());
-        
-        context.instructions.add_comment("Generate target value".to_owned());
-        let target = X86ValueCompiler::compile(target, context, scope, target_register)?;
+    pub fn resize(location: X86Location, size: RegisterSize) -> X86Location {
+        match location {
+            X86Location::Register(AddressingMode::Direct(register)) => X86Location::Register(AddressingMode::Direct(register.get_sized(size))),
+            X86Location::Virtual(register) => X86Location::Virtual(register.get_sized(size)),
+            location => location
+        }
+    }
+
+    /// Size of the register, physical or virtual
+    pub fn get_register_size(location: &X86Location) -> Option<RegisterSize> {
+        match location {
+            X86Location::Register(AddressingMode::Direct(register)) => Some(register.get_register_size()),
+            X86Location::Virtual(register) => Some(register.size),
+            _ => None
+        }
+    }
+
+    pub fn is_vector_register(location: &X86Location) -> bool {
+        Self::get_register_size(location) == Some(RegisterSize::_128Bit)
+    }
+
+    /// Convert the value in the register to the other type. Narrowing just uses the lower part of the register.
+    /// Conversion between integer and floating point moves the value into the other kind of register.
+    pub fn convert_register(scope: &mut X86Store, location: X86Location, from: NumberType, to: NumberType, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        if Self::get_register_size(&location).is_none() {
+            return Ok(location);
+        }
 
-        if let Some(mode) = source.get_addressing_mode() {
-            if !mode.is_direct_register() {
-                let new_reg = scope.lock_register(scope.get_last_size()).ok_or(X86Error::NoRegisterAvailable)?;
-                context.instructions.add_instruction(X86Instruction::Mov { source, target: X86Location::Register(X86AddressingMode::Direct(new_reg)), comment: Some("Move address to reg for calculation".to_owned()) });
-                source = X86Location::Register(X86AddressingMode::Direct(new_reg));
+        match (from.is_float(), to.is_float()) {
+            (false, false) => Ok(Self::convert_integer_register(location, from, to, context)),
+            (true, true) => {
+                match (from, to) {
+                    (NumberType::Float, NumberType::Double) => { context.instructions.add_instruction(X86Instruction::Cvtss2sd { source: location.clone(), target: location.clone(), comment: None }); },
+                    (NumberType::Double, NumberType::Float) => { context.instructions.add_instruction(X86Instruction::Cvtsd2ss { source: location.clone(), target: location.clone(), comment: None }); },
+                    _ => ()
+                };
+                Ok(location)
+            },
+            (false, true) => {
+                // Integer extended to 64bit before the conversion
+                let extended_type = match from.is_signed() {
+                    true => NumberType::I64,
+                    false => NumberType::U64
+                };
+
+                let source = Self::convert_integer_register(location, from, extended_type, context);
+                let target = Self::new_register(scope, RegisterSize::_128Bit);
+
+                let convert = |source: X86Location| match to {
+                    NumberType::Float => X86Instruction::Cvtsi2ss { source, target: target.clone(), comment: None },
+                    _ => X86Instruction::Cvtsi2sd { source, target: target.clone(), comment: None }
+                };
+
+                if from != NumberType::U64 {
+                    context.instructions.add_instruction(convert(source));
+                    return Ok(target);
+                }
+
+                // Conversion instruction is signed, values with the highest bit are halved and doubled after the conversion.
+                // Lowest bit is kept to round the same way.
+                let (large, done) = (context.storage.create_branch(), context.storage.create_branch());
+                context.instructions.add_instruction(X86Instruction::Cmp { left: X86Location::Imm(Number::I32(0)), right: source.clone(), comment: None });
+                context.instructions.add_instruction(X86Instruction::Jl(large.clone()));
+                context.instructions.add_instruction(convert(source.clone()));
+                context.instructions.add_instruction(X86Instruction::Jmp(done.clone()));
+
+                context.instructions.add_branch(large);
+                let (half, lowest) = (Self::new_register(scope, RegisterSize::_64Bit), Self::new_register(scope, RegisterSize::_64Bit));
+                context.instructions.add_instruction(X86Instruction::Mov { source: source.clone(), target: half.clone(), comment: None });
+                context.instructions.add_instruction(X86Instruction::Shr { source: X86Location::Imm(Number::U8(1)), target: half.clone(), comment: None });
+                context.instructions.add_instruction(X86Instruction::Mov { source, target: lowest.clone(), comment: None });
+                context.instructions.add_instruction(X86Instruction::And { source: X86Location::Imm(Number::I32(1)), target: lowest.clone(), comment: None });
+                context.instructions.add_instruction(X86Instruction::Or { source: lowest, target: half.clone(), comment: None });
+                context.instructions.add_instruction(convert(half));
+                context.instructions.add_instruction(match to {
+                    NumberType::Float => X86Instruction::Addss { source: target.clone(), target: target.clone(), comment: None },
+                    _ => X86Instruction::Addsd { source: target.clone(), target: target.clone(), comment: None }
+                });
+
+                context.instructions.add_branch(done);
+                Ok(target)
+            },
+            (true, false) => {
+                let target = Self::new_register(scope, RegisterSize::_64Bit);
+
+                context.instructions.add_instruction(match from {
+                    NumberType::Float => X86Instruction::Cvttss2si { source: location, target: target.clone(), comment: None },
+                    _ => X86Instruction::Cvttsd2si { source: location, target: target.clone(), comment: None }
+                });
+
+                Ok(Self::resize(target, to.size()))
             }
         }
+    }
+
+    fn convert_integer_register(location: X86Location, from: NumberType, to: NumberType, context: &mut X86ApplicationContext) -> X86Location {
+        let (from_size, to_size) = (from.size(), to.size());
+        let target = Self::resize(location.clone(), to_size);
+
+        if from_size >= to_size {
+            return target;
+        }
 
-        let instruction = match inst_type {
-            X86InstructionType::Add => X86Instruction::Add { source, target: target.clone(), comment: None },
-            X86InstructionType::Sub => X86Instruction::Sub { source, target: target.clone(), comment: None },
-            X86InstructionType::IMul => X86Instruction::IMul { source, target: target.clone(), comment: None },
-            X86InstructionType::And => X86Instruction::And { source, target: target.clone(), comment: None },
-            X86InstructionType::Or => X86Instruction::Or { source, target: target.clone(), comment: None },
-            X86InstructionType::Xor => X86Instruction::Xor { source, target: target.clone(), comment: None },
-            X86InstructionType::Shl => X86Instruction::Shl { source, target: target.clone(), comment: None },
-            X86InstructionType::Shr => X86Instruction::Shr { source, target: target.clone(), comment: None },
-            _ => return Err(X86Error::UnexpectedInstruction)
+        let source = Self::resize(location, from_size);
+        let instruction = match (from.is_signed(), from_size) {
+            (true, _) => X86Instruction::Movsx { source, target: target.clone(), comment: None },
+
+            // 32bit move clears the upper part of the register
+            (false, RegisterSize::_32Bit) => X86Instruction::Mov { source: source.clone(), target: source, comment: None },
+            (false, _) => X86Instruction::Movzx { source, target: target.clone(), comment: None }
         };
 
         context.instructions.add_instruction(instruction);
-        scope.register_restore(registers);
-        scope.set_last_assigned_location(target.clone());
+        target
+    }
 
-        if let Some(register) = target.get_register() {
-            scope.mark_register(register);
+    /// Move the location into a register that fits to the type
+    fn load_typed_register(scope: &mut X86Store, location: X86Location, number_type: NumberType, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match (number_type.is_float(), location) {
+            (true, location) if Self::is_vector_register(&location) => Ok(location),
+            (true, location) => {
+                let register = Self::new_register(scope, RegisterSize::_128Bit);
+                context.instructions.add_instruction(X86Instruction::typed_movement(number_type, location, register.clone(), None));
+                Ok(register)
+            },
+            (false, location) => Self::load_to_register(scope, location, number_type.size(), context)
         }
+    }
 
-        Ok(())
+    /// Variable could be used directly as an operand if it has the same kind and it is wide enough
+    fn is_usable_operand(variable_type: NumberType, number_type: NumberType) -> bool {
+        match number_type.is_float() {
+            true => variable_type == number_type,
+            false => !variable_type.is_float() && variable_type.size() >= number_type.size()
+        }
     }
 
-    fn compile_div(scope: &mut X86Store, divider: Value, divided: Value, target_register: Register, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
-        let registers = scope.register_backup();
+    /// Number could be used as a 32bit immediate, it is sign extended by the instructions
+    pub fn is_immediate(num: &Number) -> bool {
+        match *num {
+            Number::I64(num) => i32::try_from(num).is_ok(),
+            Number::U64(num) => i32::try_from(num).is_ok(),
+            Number::U32(num) => i32::try_from(num).is_ok(),
+            Number::Float(_) | Number::Double(_) => false,
+            _ => true
+        }
+    }
 
-        context.instructions.add_comment("Generate divider value".to_owned());
-        let mut divider = X86ValueCompiler::compile(divider, context, scope, Some(X86Location::Register(X86AddressingMode::Direct(Register::ESI))))?;
+    /// Compile the expression into a register that is owned by the caller, the value converted to the expected type.
+    pub fn materialize(expression: Expression, number_type: NumberType, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        if let Expression::Value(Value::Number(num)) = expression {
+            if number_type.is_float() {
+                return X86ValueCompiler::compile(Value::Number(num.convert(number_type)), context, scope, None);
+            }
 
-        context.instructions.add_comment("Generate divided value".to_owned());
-        X86ValueCompiler::compile(divided, context, scope, Some(X86Location::Register(X86AddressingMode::Direct(Register::EAX))))?;
+            // Load the immediate with the final type, so the sign bits are correct
+            let register = Self::new_register(scope, number_type.size());
+            context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(num.convert(number_type)), target: register.clone(), comment: None });
+            return Ok(register);
+        }
 
-        if let Some(mode) = divider.get_addressing_mode() {
-            if !mode.is_direct_register() {
-                let new_reg = scope.lock_register(scope.get_last_size()).ok_or(X86Error::NoRegisterAvailable)?;
-                context.instructions.add_instruction(X86Instruction::Mov { source: divider, target: X86Location::Register(X86AddressingMode::Direct(new_reg)), comment: Some("Move address to reg for calculation".to_owned()) });
-                divider = X86Location::Register(X86AddressingMode::Direct(new_reg));
-            }
+        let expression_type = Self::get_type(&expression, scope)?;
+        let location = Self::compile_expression(expression, scope, context)?;
+        let location = Self::load_typed_register(scope, location, expression_type, context)?;
+        Self::convert_register(scope, location, expression_type, number_type, context)
+    }
+
+    /// Compile the expression as a source operand. Immediates and memory locations are used directly if it is possible.
+    pub fn compile_operand(expression: Expression, number_type: NumberType, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match expression {
+            Expression::Value(Value::Number(num)) if number_type.is_float() => Ok(X86ValueCompiler::compile_float_data(num.convert(number_type), context)),
+            Expression::Value(Value::Number(num)) if Self::is_immediate(&num.convert(number_type)) => Ok(X86Location::Imm(num.convert(number_type))),
+            Expression::Value(Value::Variable(variable)) if Self::is_usable_operand(Self::get_type(&Expression::Value(Value::Variable(variable.clone())), scope)?, number_type) => {
+                X86ValueCompiler::compile(Value::Variable(variable), context, scope, None)
+            },
+            expression => Self::materialize(expression, number_type, scope, context)
         }
+    }
 
-        context.instructions.add_instruction(X86Instruction::Cdq);
-        context.instructions.add_instruction(X86Instruction::IDiv { target: divider.clone(), comment: None });
-        scope.register_restore(registers);
-        scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Direct(target_register)));
+    fn get_fixed_register(inst_type: X86InstructionType, get_fixed_type: fn(special_info: &SpecialConfiguration) -> Option<FixedType>) -> Option<Register> {
+        let position = SPECIAL_INSTRUCTION_CHECK.iter().position(|item| *item == inst_type)?;
 
-        if let Some(register) = divider.get_register() {
-            scope.mark_register(register);
+        match get_fixed_type(&SPECIAL_INSTRUCTION[position]) {
+            Some(FixedType::Register(register, Some(size))) => Some(register.get_sized(size)),
+            Some(FixedType::Register(register, None)) => Some(register),
+            None => None
         }
+    }
 
-        Ok(())
+    fn compile_simple(scope: &mut X86Store, inst_type: X86InstructionType, target: Expression, source: Expression, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        let number_type = Self::get_type(&target, scope)?.common(Self::get_type(&source, scope)?);
+
+        // Evaluate the heavier side first, so the other side needs less register at the same time
+        let (target, source) = if Self::get_register_need(&source) > Self::get_register_need(&target) {
+            context.instructions.add_comment("Generate source value".to_owned());
+            let source = Self::compile_operand(source, number_type, scope, context)?;
+
+            context.instructions.add_comment("Generate target value".to_owned());
+            let target = Self::materialize(target, number_type, scope, context)?;
+            (target, source)
+        } else {
+            context.instructions.add_comment("Generate target value".to_owned());
+            let target = Self::materialize(target, number_type, scope, context)?;
+
+            context.instructions.add_comment("Generate source value".to_owned());
+            let source = Self::compile_operand(source, number_type, scope, context)?;
+            (target, source)
+        };
+
+        let instruction = match (inst_type, number_type) {
+            (inst_type, NumberType::Float | NumberType::Double) => Self::float_instruction(inst_type, number_type, source.clone(), target.clone())?,
+            (X86InstructionType::Add, _) => X86Instruction::Add { source: source.clone(), target: target.clone(), comment: None },
+            (X86InstructionType::Sub, _) => X86Instruction::Sub { source: source.clone(), target: target.clone(), comment: None },
+            (X86InstructionType::IMul, _) => X86Instruction::IMul { source: source.clone(), target: target.clone(), comment: None },
+            (X86InstructionType::And, _) => X86Instruction::And { source: source.clone(), target: target.clone(), comment: None },
+            (X86InstructionType::Or, _) => X86Instruction::Or { source: source.clone(), target: target.clone(), comment: None },
+            (X86InstructionType::Xor, _) => X86Instruction::Xor { source: source.clone(), target: target.clone(), comment: None },
+            _ => return Err(X86Error::UnexpectedInstruction)
+        };
+
+        context.instructions.add_instruction(instruction);
+        Ok(target)
     }
 
-    fn compile_single(scope: &mut X86Store, inst: X86InstructionType, source: Value, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
-        let registers = scope.register_backup();
+    fn compile_shift(scope: &mut X86Store, inst_type: X86InstructionType, target: Expression, source: Expression, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        let number_type = Self::get_type(&target, scope)?.promote();
+
+        if number_type.is_float() {
+            return Err(X86Error::UnsupportedFloatOperation);
+        }
+
+        context.instructions.add_comment("Generate target value".to_owned());
+        let target = Self::materialize(target, number_type, scope, context)?;
 
         context.instructions.add_comment("Generate source value".to_owned());
-        let mut source = X86ValueCompiler::compile(source, context, scope, None)?;
+        let count = match source {
+            Expression::Value(Value::Number(num)) => X86Location::Imm(num.convert(NumberType::U8)),
+            source => {
+                let source_type = Self::get_type(&source, scope)?.promote();
+                let source = Self::materialize(source, source_type, scope, context)?;
+
+                // Shift count should be in the fixed register (CL), the allocator keeps the other values away from it
+                let fixed_register = Self::get_fixed_register(inst_type, |item| item.fixed_source_type.clone()).ok_or(X86Error::UnexpectedInstruction)?;
+                context.instructions.add_instruction(X86Instruction::Mov { source, target: X86Location::Register(X86AddressingMode::Direct(fixed_register.get_sized(source_type.size()))), comment: None });
+                X86Location::Register(X86AddressingMode::Direct(fixed_register))
+            }
+        };
+
+        let instruction = match (inst_type, number_type.is_signed()) {
+            (X86InstructionType::Shl, _) => X86Instruction::Shl { source: count, target: target.clone(), comment: None },
+            (X86InstructionType::Shr, true) => X86Instruction::Sar { source: count, target: target.clone(), comment: None },
+            (X86InstructionType::Shr, false) => X86Instruction::Shr { source: count, target: target.clone(), comment: None },
+            _ => return Err(X86Error::UnexpectedInstruction)
+        };
+
+        context.instructions.add_instruction(instruction);
+        Ok(target)
+    }
+
+    fn compile_div(scope: &mut X86Store, divider: Expression, divided: Expression, target_register: Register, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        let number_type = Self::get_type(&divider, scope)?.common(Self::get_type(&divided, scope)?);
+        let size = number_type.size();
 
-        if let Some(mode) = source.get_addressing_mode() {
-            if !mode.is_direct_register() {
-                let new_reg = scope.lock_register(scope.get_last_size()).ok_or(X86Error::NoRegisterAvailable)?;
-                context.instructions.add_instruction(X86Instruction::Mov { source, target: X86Location::Register(X86AddressingMode::Direct(new_reg)), comment: Some("Move address to reg for calculation".to_owned()) });
-                source = X86Location::Register(X86AddressingMode::Direct(new_reg));
+        if number_type.is_float() {
+            // Floating point numbers have no remainder
+            if target_register != Register::EAX {
+                return Err(X86Error::UnsupportedFloatOperation);
             }
+
+            context.instructions.add_comment("Generate divided value".to_owned());
+            let divided = Self::materialize(divided, number_type, scope, context)?;
+
+            context.instructions.add_comment("Generate divider value".to_owned());
+            let divider = Self::compile_operand(divider, number_type, scope, context)?;
+
+            context.instructions.add_instruction(Self::float_instruction(X86InstructionType::IDiv, number_type, divider, divided.clone())?);
+            return Ok(divided);
+        }
+
+        context.instructions.add_comment("Generate divided value".to_owned());
+        let divided = Self::materialize(divided, number_type, scope, context)?;
+
+        context.instructions.add_comment("Generate divider value".to_owned());
+        let divider = Self::materialize(divider, number_type, scope, context)?;
+
+        // Divider lives while RAX and RDX are used, so the allocator does not place it in these registers
+        context.instructions.add_instruction(X86Instruction::Mov { source: divided.clone(), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX.get_sized(size))), comment: None });
+
+        match (number_type.is_signed(), size) {
+            (true, RegisterSize::_64Bit) => context.instructions.add_instruction(X86Instruction::Cqo),
+            (true, _) => context.instructions.add_instruction(X86Instruction::Cdq),
+            (false, _) => {
+                let rdx = X86Location::Register(X86AddressingMode::Direct(Register::EDX));
+                context.instructions.add_instruction(X86Instruction::Xor { source: rdx.clone(), target: rdx, comment: None })
+            }
+        };
+
+        context.instructions.add_instruction(match number_type.is_signed() {
+            true => X86Instruction::IDiv { target: divider, comment: None },
+            false => X86Instruction::Div { target: divider, comment: None }
+        });
+        context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(target_register.get_sized(size))), target: divided.clone(), comment: None });
+        Ok(divided)
+    }
+
+    fn compile_single(scope: &mut X86Store, inst: X86InstructionType, source: Expression, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        let number_type = Self::get_type(&source, scope)?.promote();
+
+        context.instructions.add_comment("Generate source value".to_owned());
+        let source = Self::materialize(source, number_type, scope, context)?;
+
+        if number_type.is_float() {
+            return Self::compile_float_single(scope, inst, number_type, source, context);
         }
 
         let instruction = match inst {
@@ -190,19 +438,50 @@ impl X86ExpressionCompiler {
         };
 
         context.instructions.add_instruction(instruction);
-        scope.register_restore(registers);
-        scope.set_last_assigned_location(source.clone());
+        Ok(source)
+    }
 
-        if let Some(register) = source.get_register() {
-            scope.mark_register(register);
+    fn compile_float_single(scope: &mut X86Store, inst: X86InstructionType, number_type: NumberType, source: X86Location, context: &mut X86ApplicationContext) -> Result<X86Location, X86Error> {
+        match inst {
+            X86InstructionType::Neg => {
+                // There is no negate instruction for SSE, subtract the value from zero
+                let target = Self::new_register(scope, RegisterSize::_128Bit);
+                context.instructions.add_instruction(X86Instruction::Xorps { source: target.clone(), target: target.clone(), comment: None });
+                context.instructions.add_instruction(Self::float_instruction(X86InstructionType::Sub, number_type, source, target.clone())?);
+                Ok(target)
+            },
+            X86InstructionType::Inc | X86InstructionType::Dec => {
+                let one = X86ValueCompiler::compile_float_data(Number::I32(1).convert(number_type), context);
+                let inst_type = match inst {
+                    X86InstructionType::Inc => X86InstructionType::Add,
+                    _ => X86InstructionType::Sub
+                };
+
+                context.instructions.add_instruction(Self::float_instruction(inst_type, number_type, one, source.clone())?);
+                Ok(source)
+            },
+            _ => Err(X86Error::UnsupportedFloatOperation)
         }
+    }
 
-        Ok(())
+    fn float_instruction(inst_type: X86InstructionType, number_type: NumberType, source: X86Location, target: X86Location) -> Result<X86Instruction, X86Error> {
+        let instruction = match (inst_type, number_type) {
+            (X86InstructionType::Add, NumberType::Float) => X86Instruction::Addss { source, target, comment: None },
+            (X86InstructionType::Add, _) => X86Instruction::Addsd { source, target, comment: None },
+            (X86InstructionType::Sub, NumberType::Float) => X86Instruction::Subss { source, target, comment: None },
+            (X86InstructionType::Sub, _) => X86Instruction::Subsd { source, target, comment: None },
+            (X86InstructionType::IMul, NumberType::Float) => X86Instruction::Mulss { source, target, comment: None },
+            (X86InstructionType::IMul, _) => X86Instruction::Mulsd { source, target, comment: None },
+            (X86InstructionType::IDiv, NumberType::Float) => X86Instruction::Divss { source, target, comment: None },
+            (X86InstructionType::IDiv, _) => X86Instruction::Divsd { source, target, comment: None },
+            _ => return Err(X86Error::UnsupportedFloatOperation)
+        };
+
+        Ok(instruction)
     }
 
-    pub fn compile_value(scope: &mut X86Store, value: Value, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
-        let value = X86ValueCompiler::compile(value, context, scope, None)?;
-        scope.set_last_assigned_location(value);
-        Ok(())
+    /// Physical register is decided later by the register allocator
+    pub fn new_register(scope: &mut X86Store, size: RegisterSize) -> X86Location {
+        X86Location::Virtual(scope.new_virtual_register(size))
     }
 }
@@ -4,6 +4,7 @@ mod statement;
 mod definition;
 mod condition;
 mod block;
+mod print;
 pub mod error;
 
 pub use value::X86ValueCompiler;
@@ -0,0 +1,124 @@
+use tb_core::types::{Expression, Number, NumberType, Statement, Value};
+
+use crate::{runtime::X86Runtime, X86ApplicationContext, X86Store};
+
+use super::{error::X86Error, statement::X86StatementCompiler};
+
+/// Temporary variable that has the argument with the type of the runtime function
+const ARGUMENT: &str = "__print_argument";
+
+pub struct X86PrintCompiler;
+
+impl X86PrintCompiler {
+    /// Format is known at the compile time, so it is split to the texts and the conversions. Every part is a call
+    /// to the runtime functions, only the conversions without the flags and the width are supported.
+    pub fn compile_freestanding(scope: &mut X86Store, format: String, arguments: Vec<Value>, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let unsupported = || X86Error::UnsupportedFormat(format.clone());
+
+        let mut statements = Vec::new();
+        let mut text = String::new();
+        let mut arguments = arguments.into_iter();
+        let mut chars = format.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            if char != '%' {
+                text.push(char);
+                continue;
+            }
+
+            if chars.next_if(|char| "-+ #0123456789".contains(*char)).is_some() {
+                return Err(unsupported());
+            }
+
+            let mut precision = None;
+            if chars.next_if_eq(&'.').is_some() {
+                let mut value = 0;
+                while let Some(digit) = chars.next_if(|char| char.is_ascii_digit()) {
+                    value = value * 10 + digit.to_digit(10).unwrap_or_default() as i64;
+                }
+                precision = Some(value);
+            }
+
+            let mut length = String::new();
+            while let Some(char) = chars.next_if(|char| "hlLqjzt".contains(*char)) {
+                length.push(char);
+            }
+
+            let conversion = chars.next().ok_or_else(unsupported)?;
+            if conversion == '%' {
+                text.push('%');
+                continue;
+            }
+
+            let argument = arguments.next().ok_or_else(unsupported)?;
+
+            // Strings are only literals, they are a part of the text
+            if let ('s', Value::String(value)) = (conversion, &argument) {
+                text.push_str(value);
+                continue;
+            }
+
+            if !text.is_empty() {
+                statements.push(Self::write(std::mem::take(&mut text)));
+            }
+
+            let bits = match length.as_str() {
+                "hh" => 8,
+                "h" => 16,
+                "" => 32,
+                _ => 64
+            };
+
+            match (conversion, precision) {
+                ('d' | 'i', None) => {
+                    statements.push(Self::declare(NumberType::I64, argument));
+                    statements.push(Self::call(X86Runtime::PRINT_SIGNED, vec![Value::Variable(ARGUMENT.to_owned()), Value::Number(Number::I64(bits))]));
+                },
+                ('u' | 'o' | 'x' | 'X', None) => {
+                    let (radix, digits) = match conversion {
+                        'o' => (8, "01234567"),
+                        'x' => (16, "0123456789abcdef"),
+                        'X' => (16, "0123456789ABCDEF"),
+                        _ => (10, "0123456789")
+                    };
+
+                    statements.push(Self::declare(NumberType::U64, argument));
+                    statements.push(Self::call(X86Runtime::PRINT_UNSIGNED, vec![Value::Variable(ARGUMENT.to_owned()), Value::Number(Number::I64(bits)), Value::Number(Number::I64(radix)), Value::String(digits.to_owned())]));
+                },
+                ('c', None) => {
+                    statements.push(Self::declare(NumberType::I64, argument));
+                    statements.push(Self::call(X86Runtime::PRINT_CHAR, vec![Value::Variable(ARGUMENT.to_owned())]));
+                },
+                ('f' | 'F', precision) => {
+                    statements.push(Self::declare(NumberType::Double, argument));
+                    statements.push(Self::call(X86Runtime::PRINT_DOUBLE, vec![Value::Variable(ARGUMENT.to_owned()), Value::Number(Number::I64(precision.unwrap_or(6)))]));
+                },
+                _ => return Err(unsupported())
+            };
+        }
+
+        if !text.is_empty() {
+            statements.push(Self::write(text));
+        }
+
+        for statement in statements.into_iter() {
+            X86StatementCompiler::compile(statement, scope, context)?;
+        }
+
+        Ok(())
+    }
+
+    /// Length is in bytes, the text is not terminated
+    fn write(text: String) -> Statement {
+        let length = text.len() as u64;
+        Self::call(X86Runtime::WRITE, vec![Value::String(text), Value::Number(Number::U64(length))])
+    }
+
+    fn call(name: &str, arguments: Vec<Value>) -> Statement {
+        Statement::Call { name: name.to_owned(), arguments, assign: None, is_variadic: false }
+    }
+
+    fn declare(ty: NumberType, argument: Value) -> Statement {
+        Statement::Declare { name: ARGUMENT.to_owned(), ty, init: Some(Expression::Value(argument)) }
+    }
+}
@@ -1,18 +1,57 @@
 use std::sync::LazyLock;
 
-use tb_core::{location::Location, types::{Block, CallingConventions, Condition, ConditionDiscriminant, Expression, Number, ProcedureCall, RegisterSize, Statement, Value}};
+use tb_core::{location::Location, types::{RegisterTrait, Block, NumberType, CallingConventions, Condition, Expression, Number, ProcedureCall, RegisterSize, Statement, Value}};
 
 use crate::{instruction::X86Instruction, register::Register, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
 
-use super::{block::X86BlockCompiler, condition::X86ConditionCompiler, error::X86Error, expression::X86ExpressionCompiler, X86ValueCompiler};
+use super::{block::X86BlockCompiler, condition::X86ConditionCompiler, error::X86Error, expression::X86ExpressionCompiler, print::X86PrintCompiler, X86ValueCompiler};
 
 pub static CALL_CONVENTION: LazyLock<ProcedureCall<Register>>= LazyLock::new(|| {
     ProcedureCall {
         convention: CallingConventions::Systemv,
-        registers: vec![Register::RDI, Register::RSI, Register::RDX, Register::RCX, Register::R8, Register::R9]
+        registers: vec![Register::RDI, Register::RSI, Register::RDX, Register::RCX, Register::R8, Register::R9],
+        float_registers: vec![Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3, Register::XMM4, Register::XMM5, Register::XMM6, Register::XMM7]
     }
 });
 
+pub static MICROSOFT_CALL_CONVENTION: LazyLock<ProcedureCall<Register>>= LazyLock::new(|| {
+    ProcedureCall {
+        convention: CallingConventions::Microsoft,
+        registers: vec![Register::RCX, Register::RDX, Register::R8, Register::R9],
+        float_registers: vec![Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3]
+    }
+});
+
+/// Microsoft x64 callers reserve this area above the stack arguments for the register arguments
+pub const SHADOW_SPACE_SIZE: u32 = 32;
+
+pub fn get_call_convention(context: &X86ApplicationContext) -> &'static ProcedureCall<Register> {
+    match context.target.calling_convention {
+        CallingConventions::Microsoft => &MICROSOFT_CALL_CONVENTION,
+        _ => &CALL_CONVENTION
+    }
+}
+
+/// System V counts the integer and floating point arguments separately, Microsoft x64 uses the position of the
+/// argument for both of them. The rest of the arguments are passed with stack.
+pub fn get_argument_registers(convention: &ProcedureCall<Register>, argument_types: &[NumberType]) -> Vec<Option<Register>> {
+    let mut integer_index = 0;
+    let mut float_index = 0;
+
+    argument_types.iter().enumerate().map(|(index, argument_type)| match (convention.convention, argument_type.is_float()) {
+        (CallingConventions::Microsoft, true) => convention.get_float_register(index),
+        (CallingConventions::Microsoft, false) => convention.get_register(index),
+        (_, true) => {
+            float_index += 1;
+            convention.get_float_register(float_index - 1)
+        },
+        (_, false) => {
+            integer_index += 1;
+            convention.get_register(integer_index - 1)
+        }
+    }).collect()
+}
+
 pub struct X86StatementCompiler;
 
 
@@ -20,110 +59,148 @@ impl X86StatementCompiler {
     pub fn compile(statement: Statement, scope: &mut X86Store, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
         match statement {
             Statement::Assign { name, assigne } => Self::compile_assign(scope, name, assigne, context),
+            Statement::Declare { name, ty, init } => Self::compile_declare(scope, name, ty, init, context),
             Statement::Call { name, arguments, assign, is_variadic } => Self::compile_call(scope, name, arguments, assign, is_variadic, context),
             Statement::Print { format, arguments } => Self::compile_print(scope, format, arguments, context),
             Statement::Return(expr) => Self::compile_return(scope, expr, context),
             Statement::If { condition, true_block, false_block } => Self::compile_if(scope, condition, true_block, false_block, context),
+            Statement::While { condition, block } => Self::compile_while(scope, condition, block, context),
+            Statement::Loop { block } => Self::compile_loop(scope, block, context),
+            Statement::Break => Self::compile_break(context),
+            Statement::Continue => Self::compile_continue(context),
         }
     }
 
-    fn get_jump_instruction(condition_type: ConditionDiscriminant, label: &str) -> X86Instruction {
-        match condition_type {
-            ConditionDiscriminant::Eq => X86Instruction::Jne(label.to_owned()),
-            ConditionDiscriminant::Ne => X86Instruction::Je(label.to_owned()),
-            ConditionDiscriminant::Gr => X86Instruction::Jnb(label.to_owned()),
-            ConditionDiscriminant::Ge => X86Instruction::Jnbe(label.to_owned()),
-            ConditionDiscriminant::Ls => X86Instruction::Jna(label.to_owned()),
-            ConditionDiscriminant::Le => X86Instruction::Jnae(label.to_owned()),
-        }
-    }
-    
     fn compile_assign(scope: &mut X86Store, name: String, assigne: Expression, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        // Undeclared variable takes the type of the expression
+        let var_type = match scope.find_variable(&name) {
+            Some(variable) => variable.var_type,
+            None => X86ExpressionCompiler::get_type(&assigne, scope)?
+        };
+
+        let location = X86ExpressionCompiler::materialize(assigne, var_type, scope, context)?;
+
         let position = match scope.find_variable(&name) {
             Some(variable) => variable.position,
-            None => scope.add_variable(&name, 8).position
+            None => scope.add_variable(&name, var_type).position
         };
 
-        let registers = scope.register_backup();
+        context.instructions.add_instruction(X86Instruction::typed_movement(var_type, location, X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)), Some(format!("assign {}", name))));
+        scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)));
+        Ok(())
+    }
 
-        X86ExpressionCompiler::compile(assigne, scope, context)?;
+    fn compile_declare(scope: &mut X86Store, name: String, var_type: NumberType, init: Option<Expression>, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        // Initial value could use the shadowed variable, so, calculate it before the declaration
+        let location = match init {
+            Some(init) => Some(X86ExpressionCompiler::materialize(init, var_type, scope, context)?),
+            None => None
+        };
 
-        if let Some(mode) = scope.get_last_assigned_location().get_addressing_mode() {
-            if !mode.is_direct_register() {
-                let new_reg = scope.lock_register(scope.get_last_size()).ok_or(X86Error::NoRegisterAvailable)?;
-                context.instructions.add_instruction(X86Instruction::Mov { source: scope.get_last_assigned_location(), target: X86Location::Register(X86AddressingMode::Direct(new_reg)), comment: Some("Move address to reg for calculation".to_owned()) });
-                scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Direct(new_reg)));
-            }
+        let position = scope.add_variable(&name, var_type).position;
+
+        if let Some(location) = location {
+            context.instructions.add_instruction(X86Instruction::typed_movement(var_type, location, X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)), Some(format!("declare {}", name))));
         }
 
-        context.instructions.add_instruction(X86Instruction::Mov { source: scope.get_last_assigned_location(), target: X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)), comment: Some(format!("assign {}", name)) });
-        scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)));
-        scope.register_restore(registers);
         Ok(())
     }
     
     fn compile_print(scope: &mut X86Store, format: String, arguments: Vec<Value>, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        if context.storage.freestanding {
+            return X86PrintCompiler::compile_freestanding(scope, format, arguments, context);
+        }
+
         let mut tmp_arguments = Vec::new();
         tmp_arguments.push(Value::String(format));
-        tmp_arguments.extend(arguments.into_iter());
+        tmp_arguments.extend(arguments);
 
         Self::compile_call(scope, context.os_specific_defs.print().to_owned(), tmp_arguments, None, true, context)
     }
 
     fn compile_call(scope: &mut X86Store, name: String, arguments: Vec<Value>, assign: Option<String>, is_variadic: bool, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
-        let registers = scope.register_backup();
-
-        // todo: save the register before used, if need it
         let mut total_stack_bytes = 0;
 
+        // Arguments of the application functions are converted to the parameter types
+        let parameters = context.storage.parameters.get(&name).cloned().unwrap_or_default();
+
+        let mut argument_types = Vec::new();
+        for (index, argument) in arguments.iter().enumerate() {
+            let argument_type = match (parameters.get(index), X86ExpressionCompiler::get_type(&Expression::Value(argument.clone()), scope)?) {
+                (Some(parameter_type), _) => *parameter_type,
+
+                // Variadic functions get float as double
+                (None, NumberType::Float) if is_variadic => NumberType::Double,
+                (None, argument_type) => argument_type
+            };
+            argument_types.push(argument_type);
+        }
+
+        let convention = get_call_convention(context);
+        let is_microsoft = convention.convention == CallingConventions::Microsoft;
+        let argument_registers = get_argument_registers(convention, &argument_types);
+
         // Float or double variable types
-        let mut vector_variable_count = 0;
+        let vector_variable_count = argument_registers.iter().filter(|register| register.map(|item| item.is_vector()).unwrap_or_default()).count();
+
+        // Stack should be 16 byte aligned at the call, odd number of stack arguments needs a padding before them
+        if argument_registers.iter().filter(|register| register.is_none()).count() % 2 == 1 {
+            total_stack_bytes += 8;
+            context.instructions.add_instruction(X86Instruction::Sub { source: X86Location::Imm(Number::U8(8)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: Some("stack alignment".to_owned()) });
+        }
+
+        for (index, ((argument, argument_type), register)) in arguments.into_iter().zip(argument_types).zip(argument_registers).enumerate().rev() {
+            let value_type = X86ExpressionCompiler::get_type(&Expression::Value(argument.clone()), scope)?;
 
-        for (index, argument) in arguments.into_iter().enumerate().rev() {
-            let register = (*CALL_CONVENTION).get_register(index);
             match register {
-                Some(reg) => {
+                // Integers are extended to the full register while they are loaded
+                Some(reg) if value_type == argument_type || !(value_type.is_float() || argument_type.is_float()) => {
                     X86ValueCompiler::compile(argument.clone(), context, scope, Some(X86Location::Register(X86AddressingMode::Direct(reg))))?;
                 },
+                Some(reg) => {
+                    // Value needs to be converted before the call
+                    let location = X86ExpressionCompiler::materialize(Expression::Value(argument), argument_type, scope, context)?;
+                    context.instructions.add_instruction(X86Instruction::typed_movement(argument_type, location.clone(), X86Location::Register(X86AddressingMode::Direct(reg.get_sized(argument_type.size()))), None));
+                },
                 None => {
                     total_stack_bytes += 8;
                     match argument {
-                        Value::Variable(variable) => {
-                            let variable_position = {
-                                let variable = scope.find_variable(&variable).ok_or(X86Error::VariableNotFound(variable.to_owned()))?;
-                                variable.position
-                            };
-                            context.instructions.add_instruction(X86Instruction::Push(X86Location::Register(X86AddressingMode::create_based(-(variable_position as i32), Register::RBP))));
-                        },
-                        Value::Number(num) => {
-
-                            // We will use this information if the function call is variadic
-                            match &num {
-                                Number::Float(_) | Number::Double(_) => vector_variable_count += 1,
-                                _ => ()
-                            };
-
+                        Value::Number(num) if !argument_type.is_float() && X86ExpressionCompiler::is_immediate(&num) => {
                             context.instructions.add_instruction(X86Instruction::Push(X86Location::Imm(num)));
                         },
-                        Value::String(string) => {
-                            let label = context.datas.create_label();
-                            context.datas.add_string_data(&label, &string);
-                            let tmp_register = scope.lock_register(RegisterSize::_64Bit).ok_or(X86Error::NoRegisterAvailable)?;
-                            let variable = scope.add_temp_variable(8);
-                            context.instructions.add_instruction(X86Instruction::Lea { source: X86Location::Label(label), target: X86Location::Register(X86AddressingMode::Direct(tmp_register)), comment: None });
-                            context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(tmp_register)), target: X86Location::Register(X86AddressingMode::Based(-(variable.position as i32), Register::RBP)), comment: None });
-                            scope.release_register(tmp_register);
+                        argument if argument_type.is_float() => {
+                            // There is no push for SSE registers
+                            let location = X86ExpressionCompiler::materialize(Expression::Value(argument), argument_type, scope, context)?;
+                            context.instructions.add_instruction(X86Instruction::Sub { source: X86Location::Imm(Number::U8(8)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: None });
+                            context.instructions.add_instruction(X86Instruction::typed_movement(argument_type, location, X86Location::Register(X86AddressingMode::Indirect(Register::RSP)), None));
+                        },
+                        argument => {
+                            // Strings are passed with their address, variables could be smaller than the stack slot, extend them before push
+                            let location = X86ExpressionCompiler::materialize(Expression::Value(argument), NumberType::I64, scope, context)?;
+                            context.instructions.add_instruction(X86Instruction::Push(X86ExpressionCompiler::resize(location, RegisterSize::_64Bit)));
                         }
                     };
                 },
             };
+
+            // Variadic functions read the floating point arguments from the integer registers
+            if let (true, true, Some(register), Some(integer_register)) = (is_microsoft && is_variadic, argument_type.is_float(), register, convention.get_register(index)) {
+                context.instructions.add_instruction(X86Instruction::Movq { source: X86Location::Register(X86AddressingMode::Direct(register)), target: X86Location::Register(X86AddressingMode::Direct(integer_register)), comment: None });
+            }
+        }
+
+        // Vector register count is only used by the System V variadic functions
+        if is_variadic && !is_microsoft {
+            context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(Number::U8(vector_variable_count as u8)), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), comment: None });
         }
 
-        if is_variadic {
-            scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Direct(Register::RAX))); // call result is in RAX register
-            context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(Number::U8(vector_variable_count)), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), comment: None });
+        // Called function could store the register arguments into the shadow space
+        if is_microsoft {
+            total_stack_bytes += SHADOW_SPACE_SIZE;
+            context.instructions.add_instruction(X86Instruction::Sub { source: X86Location::Imm(Number::U8(SHADOW_SPACE_SIZE as u8)), target: X86Location::Register(X86AddressingMode::Direct(Register::RSP)), comment: Some("shadow space".to_owned()) });
         }
 
+        // Call clobbers the caller-saved registers, the register allocator does not keep any value in them across the call
         context.instructions.add_instruction(X86Instruction::Call(name));
         scope.set_last_assigned_location(X86Location::Register(X86AddressingMode::Direct(Register::RAX))); // call result is in RAX register
 
@@ -136,54 +213,95 @@ impl X86StatementCompiler {
         }
 
         if let Some(assigned) = assign {
-            let position = match scope.find_variable(&assigned) {
-                Some(variable) => variable.position,
-                None => scope.add_variable(&assigned, 8).position
+            let (position, var_type) = match scope.find_variable(&assigned) {
+                Some(variable) => (variable.position, variable.var_type),
+                None => (scope.add_variable(&assigned, NumberType::I64).position, NumberType::I64)
             };
-            context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), target: X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)), comment: None });
+
+            // Floating point result is in XMM0
+            let result = match var_type.is_float() {
+                true => Register::XMM0,
+                false => Register::RAX.get_sized(var_type.size())
+            };
+            context.instructions.add_instruction(X86Instruction::typed_movement(var_type, X86Location::Register(X86AddressingMode::Direct(result)), X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP)), None));
         }
 
-        // todo: restore the register before used, if need it
-        scope.register_restore(registers);
         Ok(())
     }
 
     fn compile_if(scope: &mut X86Store, condition: Condition, true_block: Block, false_block: Option<Block>, context: &mut X86ApplicationContext) -> Result<(), X86Error> {        
-        let condition_type = X86ConditionCompiler::compile(condition, scope, context)?;
-
-        let fist_jump_location = context.instructions.add_instruction(Self::get_jump_instruction(condition_type, ""));
+        let false_branch = context.storage.create_branch();
+        X86ConditionCompiler::compile_jump(condition, &false_branch, false, scope, context)?;
 
         X86BlockCompiler::compile(true_block, scope, context)?;
 
         if let Some(false_block) = false_block {
-            let jmp_location = context.instructions.add_instruction(X86Instruction::Jne("".to_owned()));
+            let end_branch = context.storage.create_branch();
+            context.instructions.add_instruction(X86Instruction::Jmp(end_branch.clone()));
 
-            let else_branch = context.storage.create_branch();
-            context.instructions.add_branch(else_branch.clone());
+            // Jump to else block
+            context.instructions.add_branch(false_branch);
             X86BlockCompiler::compile(false_block, scope, context)?;
-
-            let end_branch = context.storage.create_branch();
-            context.instructions.add_branch(end_branch.clone());
-            context.instructions.update_instruction(Self::get_jump_instruction(condition_type, &else_branch), fist_jump_location); // Jump to else block
-            context.instructions.update_instruction(X86Instruction::Jmp(end_branch.to_owned()), jmp_location);
+            context.instructions.add_branch(end_branch);
 
         } else {
-            let end_branch = context.storage.create_branch();
-            context.instructions.add_branch(end_branch.clone());
-            context.instructions.update_instruction(Self::get_jump_instruction(condition_type, &end_branch), fist_jump_location);
+            context.instructions.add_branch(false_branch);
         }
 
         Ok(())
     }
 
+    fn compile_while(scope: &mut X86Store, condition: Condition, block: Block, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let branches = context.storage.begin_loop();
+
+        context.instructions.add_branch(branches.begin.clone());
+        X86ConditionCompiler::compile_jump(condition, &branches.end, false, scope, context)?; // Exit from loop
+
+        X86BlockCompiler::compile(block, scope, context)?;
+        context.instructions.add_instruction(X86Instruction::Jmp(branches.begin.clone()));
+        context.instructions.add_branch(branches.end.clone());
+
+        context.storage.end_loop();
+        Ok(())
+    }
+
+    fn compile_loop(scope: &mut X86Store, block: Block, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let branches = context.storage.begin_loop();
+
+        context.instructions.add_branch(branches.begin.clone());
+        X86BlockCompiler::compile(block, scope, context)?;
+        context.instructions.add_instruction(X86Instruction::Jmp(branches.begin.clone()));
+        context.instructions.add_branch(branches.end.clone());
+
+        context.storage.end_loop();
+        Ok(())
+    }
+
+    fn compile_break(context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let branches = context.storage.current_loop().ok_or(X86Error::OutsideOfLoop)?;
+        context.instructions.add_instruction(X86Instruction::Jmp(branches.end.clone()));
+        Ok(())
+    }
+
+    fn compile_continue(context: &mut X86ApplicationContext) -> Result<(), X86Error> {
+        let branches = context.storage.current_loop().ok_or(X86Error::OutsideOfLoop)?;
+        context.instructions.add_instruction(X86Instruction::Jmp(branches.begin.clone()));
+        Ok(())
+    }
+
     fn compile_return(scope: &mut X86Store, expr: Option<Value>, context: &mut X86ApplicationContext) -> Result<(), X86Error> {
         match expr {
             Some(Value::Variable(variable)) => {
-                if let Some(variable) = scope.find_variable(&variable) {
-                    context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Register(X86AddressingMode::Based(-(variable.position as i32), Register::RBP)), target: X86Location::Register(X86AddressingMode::Direct(Register::EAX)), comment: Some(format!("return {}", variable.name)) });
-                } else {
-                    return Err(X86Error::VariableNotFound(variable.clone()));
-                }
+                let target = match X86ExpressionCompiler::get_type(&Expression::Value(Value::Variable(variable.clone())), scope)?.is_float() {
+                    true => Register::XMM0,
+                    false => Register::RAX
+                };
+
+                // Value extended to the full register
+                X86ValueCompiler::compile(Value::Variable(variable), context, scope, Some(X86Location::Register(X86AddressingMode::Direct(target))))?;
+            },
+            Some(Value::Number(number)) if number.number_type().is_float() => {
+                X86ValueCompiler::compile(Value::Number(number), context, scope, Some(X86Location::Register(X86AddressingMode::Direct(Register::XMM0))))?;
             },
             Some(Value::Number(number)) => {
                 context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(number), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), comment: Some(format!("return {}", number)) });
@@ -191,12 +309,14 @@ impl X86StatementCompiler {
             Some(Value::String(data)) => {
                 let label = context.datas.create_label();
                 context.datas.add_string_data(&label, &data);
-                context.instructions.add_instruction(X86Instruction::Lea { source: Location::Label(label), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), comment: Some(format!("return \"{}\"", data)) });
+                context.instructions.add_instruction(X86Instruction::Lea { source: Location::Label(label), target: X86Location::Register(X86AddressingMode::Direct(Register::RAX)), comment: Some(format!("return {:?}", data)) });
                 
             }
             None => ()
         };
 
+        let function_end = context.storage.function_end.clone().ok_or(X86Error::OutsideOfFunction)?;
+        context.instructions.add_instruction(X86Instruction::Jmp(function_end));
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,8 +1,8 @@
-use tb_core::types::Value;
+use tb_core::types::{Number, NumberType, RegisterSize, Value};
 
 use crate::{instruction::X86Instruction, register::Register, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
 
-use super::error::X86Error;
+use super::{error::X86Error, X86ExpressionCompiler};
 
 pub struct X86ValueCompiler;
 
@@ -10,31 +10,69 @@ impl X86ValueCompiler {
     pub fn compile(variable: Value, context: &mut X86ApplicationContext, scope: &mut X86Store, target: Option<X86Location>) -> Result<X86Location, X86Error> {
         match variable {
             Value::Variable(variable) => {
-                let (variable_size, variable_position) = {
+                let (variable_type, variable_position) = {
                     let variable = scope.find_variable(&variable).ok_or(X86Error::VariableNotFound(variable.to_owned()))?;
-                    (variable.size, variable.position)
+                    (variable.var_type, variable.position)
                 };
 
-                scope.set_last_size(variable_size.into());
-
                 if let Some(target) = target {
-                    // Copy value from stack to new location
-                    context.instructions.add_instruction(X86Instruction::Mov {
-                        source: X86Location::Register(X86AddressingMode::create_based(-(variable_position as i32), Register::RBP)),
-                        target: target.clone(),
-                        comment: None
-                    });
-
-                    // Return expected target
-                    Ok(target)
+                    let source = X86Location::Register(X86AddressingMode::create_based(-(variable_position as i32), Register::RBP));
+
+                    match X86ExpressionCompiler::get_register_size(&target) {
+                        Some(_) if variable_type.is_float() => {
+                            context.instructions.add_instruction(X86Instruction::typed_movement(variable_type, source, target.clone(), None));
+                            Ok(target)
+                        },
+                        Some(register_size) => {
+                            // Copy value from stack to register and extend it to the register size
+                            let target_type = match variable_type.is_signed() {
+                                true => NumberType::I64,
+                                false => NumberType::U64
+                            };
+
+                            context.instructions.add_instruction(X86Instruction::Mov {
+                                source,
+                                target: X86ExpressionCompiler::resize(target.clone(), variable_type.size()),
+                                comment: None
+                            });
+
+                            let location = X86ExpressionCompiler::convert_register(scope, target, variable_type, target_type, context)?;
+                            Ok(X86ExpressionCompiler::resize(location, register_size))
+                        },
+                        _ => {
+                            // Copy value from stack to new location
+                            context.instructions.add_instruction(X86Instruction::Mov { source, target: target.clone(), comment: None });
+
+                            // Return expected target
+                            Ok(target)
+                        }
+                    }
                 } else {
                     Ok(X86Location::Register(X86AddressingMode::create_based(-(variable_position as i32), Register::RBP)))
                 }
             },
 
+            Value::Number(num) if num.number_type().is_float() => {
+                let number_type = num.number_type();
+                let source = Self::compile_float_data(num, context);
+
+                let target = match target {
+                    Some(target) => target,
+                    None => X86ExpressionCompiler::new_register(scope, RegisterSize::_128Bit)
+                };
+
+                // General purpose register gets the bit pattern of the number
+                let instruction = match X86ExpressionCompiler::is_vector_register(&target) {
+                    true => X86Instruction::typed_movement(number_type, source, target.clone(), None),
+                    false => X86Instruction::Mov { source, target: target.clone(), comment: None }
+                };
+
+                context.instructions.add_instruction(instruction);
+                Ok(target)
+            },
+
             Value::Number(num) => {
                 let num_size = num.size();
-                scope.set_last_size(num_size);
 
                 match target {
                     Some(target) => {
@@ -42,9 +80,9 @@ impl X86ValueCompiler {
                         Ok(target)
                     },
                     None => {
-                        let register = scope.lock_register(num_size).ok_or(X86Error::NoRegisterAvailable)?;
-                        context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(num), target: X86Location::Register(X86AddressingMode::Direct(register)), comment: None });
-                        Ok(X86Location::Register(X86AddressingMode::Direct(register)))
+                        let register = X86ExpressionCompiler::new_register(scope, num_size);
+                        context.instructions.add_instruction(X86Instruction::Mov { source: X86Location::Imm(num), target: register.clone(), comment: None });
+                        Ok(register)
                     }
                 }
             },
@@ -61,4 +99,17 @@ impl X86ValueCompiler {
             }
         }
     }
+
+    /// Floating point numbers can not be used as an immediate, they are stored in the readonly data
+    pub fn compile_float_data(num: Number, context: &mut X86ApplicationContext) -> X86Location {
+        let label = context.datas.create_label();
+
+        match num {
+            Number::Float(num) => context.datas.add_float_data(&label, num),
+            Number::Double(num) => context.datas.add_double_data(&label, num),
+            num => return Self::compile_float_data(num.convert(NumberType::Double), context)
+        };
+
+        X86Location::Label(label)
+    }
 }
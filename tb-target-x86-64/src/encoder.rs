@@ -0,0 +1,695 @@
+//! Machine code of the instructions without an external assembler. Operand sizes are decided like the AT&T
+//! syntax generator does, so the encoded bytes are the same as the assembled output of the generated text.
+
+use std::collections::HashMap;
+
+use tb_core::{addressing_mode::AddressingMode, location::Location, syntax::AsmStructure, types::{Number, RegisterSize, RegisterTrait}};
+
+use crate::{compiler::error::X86Error, instruction::X86Instruction, register::Register, X86Location};
+
+/// Register numbers that are used in the instruction encoding
+const REGISTER_CODES: [Register; 16] = [
+    Register::RAX, Register::RCX, Register::RDX, Register::RBX, Register::RSP, Register::RBP, Register::RSI, Register::RDI,
+    Register::R8, Register::R9, Register::R10, Register::R11, Register::R12, Register::R13, Register::R14, Register::R15
+];
+
+/// 32bit PC relative reference to a symbol that is not in the code, the value is `symbol + addend - offset`
+#[derive(Debug, Clone, PartialEq)]
+pub struct X86Relocation {
+    pub offset: usize,
+    pub symbol: String,
+    pub addend: i64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct X86Symbol {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct X86MachineCode {
+    pub code: Vec<u8>,
+    pub functions: Vec<X86Symbol>,
+    pub labels: HashMap<String, usize>,
+    pub relocations: Vec<X86Relocation>
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Memory {
+    Based(Register, i32),
+    Label(String),
+    Absolute(i32)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Register(Register),
+    Memory(Memory),
+    Immediate(i64)
+}
+
+#[derive(Debug, Clone)]
+enum Rm {
+    Register(Register),
+    Memory(Memory)
+}
+
+/// Relocation inside of the instruction bytes: position, symbol and addend
+type PendingRelocation = (usize, String, i64);
+
+/// Encoded instruction before the layout, jumps are sized after the label positions are known
+enum Fragment {
+    Bytes { bytes: Vec<u8>, relocation: Option<PendingRelocation> },
+    Jump { condition: Option<u8>, label: String, short: bool },
+    Call(String),
+    Label(String),
+    FunctionBegin(String),
+    FunctionEnd
+}
+
+impl Fragment {
+    fn size(&self) -> usize {
+        match self {
+            Fragment::Bytes { bytes, .. } => bytes.len(),
+            Fragment::Jump { short: true, .. } => 2,
+            Fragment::Jump { condition: None, .. } | Fragment::Call(_) => 5,
+            Fragment::Jump { .. } => 6,
+            _ => 0
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Encoding {
+    operand_size_prefix: bool,
+    mandatory_prefix: Option<u8>,
+    rex_w: bool,
+    opcode: Vec<u8>,
+
+    // Register or the opcode extension for the reg field of ModRM
+    reg: Option<Register>,
+    extension: u8,
+    rm: Option<Rm>,
+
+    // Register is added to the last byte of the opcode
+    opcode_register: Option<Register>,
+    immediate: Option<(i64, usize)>
+}
+
+impl Encoding {
+    fn new(opcode: &[u8]) -> Self {
+        Self { opcode: opcode.to_vec(), ..Default::default() }
+    }
+
+    fn sized(mut self, size: RegisterSize) -> Self {
+        self.operand_size_prefix = size == RegisterSize::_16Bit;
+        self.rex_w = size == RegisterSize::_64Bit;
+        self
+    }
+
+    fn prefix(mut self, prefix: Option<u8>) -> Self {
+        self.mandatory_prefix = prefix;
+        self
+    }
+
+    fn reg(mut self, register: Register) -> Self {
+        self.reg = Some(register);
+        self
+    }
+
+    fn extension(mut self, extension: u8) -> Self {
+        self.extension = extension;
+        self
+    }
+
+    fn rm(mut self, operand: &Operand) -> Result<Self, X86Error> {
+        self.rm = Some(match operand {
+            Operand::Register(register) => Rm::Register(*register),
+            Operand::Memory(memory) => Rm::Memory(memory.clone()),
+            Operand::Immediate(_) => return Err(X86Error::UnsupportedOperands("immediate is not a register or memory".to_owned()))
+        });
+        Ok(self)
+    }
+
+    fn immediate(mut self, value: i64, size: usize) -> Self {
+        self.immediate = Some((value, size));
+        self
+    }
+
+    fn bytes(self) -> Result<(Vec<u8>, Option<PendingRelocation>), X86Error> {
+        let mut registers = self.reg.iter().chain(self.opcode_register.iter()).copied().collect::<Vec<_>>();
+        match &self.rm {
+            Some(Rm::Register(register)) | Some(Rm::Memory(Memory::Based(register, _))) => registers.push(*register),
+            _ => ()
+        };
+
+        let reg = self.reg.map(register_code).transpose()?.unwrap_or(self.extension);
+        let base = match (&self.rm, &self.opcode_register) {
+            (Some(Rm::Register(register)), _) | (Some(Rm::Memory(Memory::Based(register, _))), _) | (None, Some(register)) => register_code(*register)?,
+            _ => 0
+        };
+
+        let rex = 0x40 | (self.rex_w as u8) << 3 | (reg >> 3) << 2 | (base >> 3);
+
+        // Lowest byte of SP, BP, SI and DI is only accessible with REX, high bytes are not accessible with it
+        let needs_rex = registers.iter().any(|register| matches!(register, Register::SPL | Register::BPL | Register::SIL | Register::DIL));
+        let high_byte = registers.iter().any(|register| matches!(register, Register::AH | Register::BH | Register::CH | Register::DH));
+
+        let mut bytes = Vec::new();
+        if self.operand_size_prefix {
+            bytes.push(0x66);
+        }
+
+        if let Some(prefix) = self.mandatory_prefix {
+            bytes.push(prefix);
+        }
+
+        if rex != 0x40 || needs_rex {
+            if high_byte {
+                return Err(X86Error::UnsupportedOperands("high byte register could not be used with REX".to_owned()));
+            }
+            bytes.push(rex);
+        }
+
+        bytes.extend_from_slice(&self.opcode);
+        if self.opcode_register.is_some() {
+            *bytes.last_mut().unwrap() += base & 7;
+        }
+
+        let mut relocation = None;
+        let immediate_size = self.immediate.map(|(_, size)| size).unwrap_or_default();
+
+        match self.rm {
+            Some(Rm::Register(_)) => bytes.push(0xC0 | (reg & 7) << 3 | (base & 7)),
+            Some(Rm::Memory(Memory::Based(register, displacement))) => {
+                if register.get_register_size() != RegisterSize::_64Bit {
+                    return Err(X86Error::UnsupportedOperands(format!("{} could not be used as address", register)));
+                }
+
+                // RBP and R13 without displacement means RIP relative or absolute address
+                let mode = match displacement {
+                    0 if base & 7 != 5 => 0x00,
+                    displacement if i8::try_from(displacement).is_ok() => 0x40,
+                    _ => 0x80
+                };
+
+                bytes.push(mode | (reg & 7) << 3 | (base & 7));
+
+                // RSP and R12 need SIB byte without index
+                if base & 7 == 4 {
+                    bytes.push(0x24);
+                }
+
+                match mode {
+                    0x40 => bytes.push(displacement as u8),
+                    0x80 => bytes.extend_from_slice(&displacement.to_le_bytes()),
+                    _ => ()
+                };
+            },
+            Some(Rm::Memory(Memory::Label(label))) => {
+                bytes.push((reg & 7) << 3 | 0b101);
+                relocation = Some((bytes.len(), label, -4 - immediate_size as i64));
+                bytes.extend_from_slice(&[0; 4]);
+            },
+            Some(Rm::Memory(Memory::Absolute(address))) => {
+                bytes.push((reg & 7) << 3 | 0b100);
+                bytes.push(0x25);
+                bytes.extend_from_slice(&address.to_le_bytes());
+            },
+            None => ()
+        };
+
+        if let Some((value, size)) = self.immediate {
+            bytes.extend_from_slice(&value.to_le_bytes()[..size]);
+        }
+
+        Ok((bytes, relocation))
+    }
+}
+
+/// Number of the register in ModRM, SIB and REX
+fn register_code(register: Register) -> Result<u8, X86Error> {
+    match register {
+        Register::AH => Ok(4),
+        Register::CH => Ok(5),
+        Register::DH => Ok(6),
+        Register::BH => Ok(7),
+        register if register.is_vector() => Ok(register as u8 - Register::XMM0 as u8),
+        register => REGISTER_CODES.iter().position(|item| *item == register.get_info()._64bit).map(|code| code as u8)
+            .ok_or_else(|| X86Error::UnsupportedOperands(format!("{} could not be encoded", register)))
+    }
+}
+
+/// Immediate as it is written to the instruction, the value is truncated to the operand size
+fn sign_extend(value: i64, size: RegisterSize) -> i64 {
+    match size {
+        RegisterSize::_8Bit => value as i8 as i64,
+        RegisterSize::_16Bit => value as i16 as i64,
+        RegisterSize::_32Bit => value as i32 as i64,
+        _ => value
+    }
+}
+
+fn immediate_size(size: RegisterSize) -> usize {
+    match size {
+        RegisterSize::_8Bit => 1,
+        RegisterSize::_16Bit => 2,
+        _ => 4
+    }
+}
+
+fn is_general(operand: &Operand) -> bool {
+    matches!(operand, Operand::Register(register) if !register.is_vector())
+}
+
+fn is_vector(operand: &Operand) -> bool {
+    matches!(operand, Operand::Register(register) if register.is_vector())
+}
+
+pub struct X86Encoder;
+
+impl X86Encoder {
+    /// Encode the instructions, jumps use 8bit displacement if the label is close enough
+    pub fn encode(items: &[AsmStructure<X86Instruction>]) -> Result<X86MachineCode, X86Error> {
+        let mut fragments = Vec::new();
+        let mut in_function = false;
+
+        for item in items.iter() {
+            match item {
+                // Branch outside of the function is the function itself
+                AsmStructure::Branch(name) => match in_function {
+                    true => fragments.push(Fragment::Label(name.clone())),
+                    false => {
+                        fragments.push(Fragment::FunctionBegin(name.clone()));
+                        in_function = true;
+                    }
+                },
+                AsmStructure::BranchFinished => {
+                    if in_function {
+                        fragments.push(Fragment::FunctionEnd);
+                    }
+                    in_function = false;
+                },
+                AsmStructure::Comment(_) => (),
+                AsmStructure::Instruction(instruction) => fragments.push(Self::instruction(instruction)?)
+            }
+        }
+
+        // Jumps only grow, the layout is stable when all of the jumps fit
+        let (offsets, labels) = loop {
+            let (offsets, labels) = Self::layout(&fragments);
+            let mut changed = false;
+
+            for (index, fragment) in fragments.iter_mut().enumerate() {
+                if let Fragment::Jump { label, short, .. } = fragment {
+                    let target = *labels.get(label).ok_or_else(|| X86Error::UnknownLabel(label.clone()))?;
+                    let displacement = target as i64 - (offsets[index] + 2) as i64;
+
+                    if *short && i8::try_from(displacement).is_err() {
+                        *short = false;
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break (offsets, labels);
+            }
+        };
+
+        let mut machine_code = X86MachineCode { labels, ..Default::default() };
+        for (fragment, offset) in fragments.into_iter().zip(offsets) {
+            let code = &mut machine_code.code;
+            match fragment {
+                Fragment::Bytes { bytes, relocation } => {
+                    if let Some((position, symbol, addend)) = relocation {
+                        machine_code.relocations.push(X86Relocation { offset: offset + position, symbol, addend });
+                    }
+                    code.extend(bytes);
+                },
+                Fragment::Jump { condition, label, short } => {
+                    let target = machine_code.labels[&label] as i64;
+                    match (short, condition) {
+                        (true, None) => code.push(0xEB),
+                        (true, Some(condition)) => code.push(0x70 + condition),
+                        (false, None) => code.push(0xE9),
+                        (false, Some(condition)) => code.extend_from_slice(&[0x0F, 0x80 + condition])
+                    };
+
+                    let end = (offset + Fragment::Jump { condition, label, short }.size()) as i64;
+                    match short {
+                        true => code.push((target - end) as u8),
+                        false => code.extend_from_slice(&((target - end) as i32).to_le_bytes())
+                    };
+                },
+                Fragment::Call(name) => {
+                    code.push(0xE8);
+
+                    // Functions that are not in the code are resolved by the linker
+                    let displacement = match machine_code.labels.get(&name) {
+                        Some(target) => *target as i64 - (offset + 5) as i64,
+                        None => {
+                            machine_code.relocations.push(X86Relocation { offset: offset + 1, symbol: name, addend: -4 });
+                            0
+                        }
+                    };
+                    code.extend_from_slice(&(displacement as i32).to_le_bytes());
+                },
+                Fragment::FunctionBegin(name) => machine_code.functions.push(X86Symbol { name, offset, size: 0 }),
+                Fragment::FunctionEnd => {
+                    if let Some(function) = machine_code.functions.last_mut() {
+                        function.size = offset - function.offset;
+                    }
+                },
+                Fragment::Label(_) => ()
+            }
+        }
+
+        Ok(machine_code)
+    }
+
+    fn layout(fragments: &[Fragment]) -> (Vec<usize>, HashMap<String, usize>) {
+        let mut offsets = Vec::with_capacity(fragments.len());
+        let mut labels = HashMap::new();
+        let mut offset = 0;
+
+        for fragment in fragments.iter() {
+            if let Fragment::Label(name) | Fragment::FunctionBegin(name) = fragment {
+                labels.insert(name.clone(), offset);
+            }
+
+            offsets.push(offset);
+            offset += fragment.size();
+        }
+
+        (offsets, labels)
+    }
+
+    fn operand(location: &X86Location) -> Result<Operand, X86Error> {
+        match location {
+            Location::Register(AddressingMode::Direct(register)) => Ok(Operand::Register(*register)),
+            Location::Register(AddressingMode::Indirect(register)) => Ok(Operand::Memory(Memory::Based(*register, 0))),
+            Location::Register(AddressingMode::Based(displacement, register)) => Ok(Operand::Memory(Memory::Based(*register, *displacement))),
+            Location::Label(label) => Ok(Operand::Memory(Memory::Label(label.clone()))),
+            Location::Memory(address) => i32::try_from(*address).map(|address| Operand::Memory(Memory::Absolute(address)))
+                .map_err(|_| X86Error::UnsupportedOperands(format!("address {} is out of range", address))),
+            Location::Imm(number) => Ok(Operand::Immediate(match *number {
+                Number::I8(number) => number as i64,
+                Number::U8(number) => number as i64,
+                Number::I16(number) => number as i64,
+                Number::U16(number) => number as i64,
+                Number::I32(number) => number as i64,
+                Number::U32(number) => number as i64,
+                Number::I64(number) => number,
+                Number::U64(number) => number as i64,
+                Number::Bool(number) => number as i64,
+                Number::Float(_) | Number::Double(_) => return Err(X86Error::UnsupportedOperands(format!("{} is not an integer", number)))
+            })),
+            Location::Virtual(_) => Err(X86Error::UnsupportedOperands("virtual register is not allocated".to_owned()))
+        }
+    }
+
+    /// Size comes from the register operand, otherwise from the register of the address
+    fn operation_size(operands: &[&Operand]) -> RegisterSize {
+        let register = operands.iter().find_map(|operand| match operand {
+            Operand::Register(register) if !register.is_vector() => Some(*register),
+            _ => None
+        });
+
+        let base = operands.iter().find_map(|operand| match operand {
+            Operand::Memory(Memory::Based(register, _)) => Some(*register),
+            _ => None
+        });
+
+        register.or(base).map(|register| register.get_register_size()).unwrap_or(RegisterSize::_64Bit)
+    }
+
+    fn instruction(instruction: &X86Instruction) -> Result<Fragment, X86Error> {
+        let unsupported = || X86Error::UnsupportedOperands(format!("{:?}", instruction));
+
+        let encoding = match instruction {
+            X86Instruction::Add { source, target, .. } => Self::arithmetic(0, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Or { source, target, .. } => Self::arithmetic(1, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::And { source, target, .. } => Self::arithmetic(4, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Sub { source, target, .. } => Self::arithmetic(5, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Xor { source, target, .. } => Self::arithmetic(6, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cmp { left, right, .. } => Self::arithmetic(7, &Self::operand(left)?, &Self::operand(right)?),
+            X86Instruction::Mov { source, target, .. } => Self::mov(&Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Movsx { source, target, .. } => Self::extend(true, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Movzx { source, target, .. } => Self::extend(false, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Lea { source, target, .. } => match (Self::operand(source)?, Self::operand(target)?) {
+                (source @ Operand::Memory(_), Operand::Register(target)) => Encoding::new(&[0x8D]).sized(target.get_register_size()).reg(target).rm(&source),
+                _ => Err(unsupported())
+            },
+            X86Instruction::IMul { source, target, .. } => match (Self::operand(source)?, Self::operand(target)?) {
+                (Operand::Immediate(value), target @ Operand::Register(register)) => {
+                    let size = register.get_register_size();
+                    let value = sign_extend(value, size);
+                    match i8::try_from(value).is_ok() {
+                        true => Encoding::new(&[0x6B]).sized(size).reg(register).rm(&target).map(|encoding| encoding.immediate(value, 1)),
+                        false => Encoding::new(&[0x69]).sized(size).reg(register).rm(&target).map(|encoding| encoding.immediate(value, immediate_size(size)))
+                    }
+                },
+                (source, Operand::Register(register)) => Encoding::new(&[0x0F, 0xAF]).sized(register.get_register_size()).reg(register).rm(&source),
+                _ => Err(unsupported())
+            },
+            X86Instruction::Not { source, .. } => Self::unary(&[0xF6, 0xF7], 2, &Self::operand(source)?),
+            X86Instruction::Neg { source, .. } => Self::unary(&[0xF6, 0xF7], 3, &Self::operand(source)?),
+            X86Instruction::Div { target, .. } => Self::unary(&[0xF6, 0xF7], 6, &Self::operand(target)?),
+            X86Instruction::IDiv { target, .. } => Self::unary(&[0xF6, 0xF7], 7, &Self::operand(target)?),
+            X86Instruction::Inc { source, .. } => Self::unary(&[0xFE, 0xFF], 0, &Self::operand(source)?),
+            X86Instruction::Dec { source, .. } => Self::unary(&[0xFE, 0xFF], 1, &Self::operand(source)?),
+            X86Instruction::Shl { source, target, .. } => Self::shift(4, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Shr { source, target, .. } => Self::shift(5, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Sar { source, target, .. } => Self::shift(7, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Push(target) => match Self::operand(target)? {
+                Operand::Register(register) if register.get_register_size() == RegisterSize::_64Bit => Ok(Encoding { opcode_register: Some(register), ..Encoding::new(&[0x50]) }),
+                Operand::Immediate(value) if i8::try_from(value).is_ok() => Ok(Encoding::new(&[0x6A]).immediate(value, 1)),
+                Operand::Immediate(value) if i32::try_from(value).is_ok() => Ok(Encoding::new(&[0x68]).immediate(value, 4)),
+                operand @ Operand::Memory(_) => Encoding::new(&[0xFF]).extension(6).rm(&operand),
+                _ => Err(unsupported())
+            },
+            X86Instruction::Pop(target) => match Self::operand(target)? {
+                Operand::Register(register) if register.get_register_size() == RegisterSize::_64Bit => Ok(Encoding { opcode_register: Some(register), ..Encoding::new(&[0x58]) }),
+                operand @ Operand::Memory(_) => Encoding::new(&[0x8F]).rm(&operand),
+                _ => Err(unsupported())
+            },
+            X86Instruction::Movss { source, target, .. } => Self::vector_move(Some(0xF3), &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Movsd { source, target, .. } => Self::vector_move(Some(0xF2), &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Movq { source, target, .. } => Self::vector_bits(&Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Movups { source, target, .. } => Self::vector_move(None, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Addss { source, target, .. } => Self::vector(Some(0xF3), 0x58, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Addsd { source, target, .. } => Self::vector(Some(0xF2), 0x58, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Subss { source, target, .. } => Self::vector(Some(0xF3), 0x5C, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Subsd { source, target, .. } => Self::vector(Some(0xF2), 0x5C, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Mulss { source, target, .. } => Self::vector(Some(0xF3), 0x59, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Mulsd { source, target, .. } => Self::vector(Some(0xF2), 0x59, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Divss { source, target, .. } => Self::vector(Some(0xF3), 0x5E, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Divsd { source, target, .. } => Self::vector(Some(0xF2), 0x5E, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Xorps { source, target, .. } => Self::vector(None, 0x57, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cvtss2sd { source, target, .. } => Self::vector(Some(0xF3), 0x5A, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cvtsd2ss { source, target, .. } => Self::vector(Some(0xF2), 0x5A, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Ucomiss { left, right, .. } => Self::vector(None, 0x2E, &Self::operand(left)?, &Self::operand(right)?),
+            X86Instruction::Ucomisd { left, right, .. } => Self::vector(Some(0x66), 0x2E, &Self::operand(left)?, &Self::operand(right)?),
+            X86Instruction::Cvtsi2ss { source, target, .. } => Self::integer_to_float(0xF3, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cvtsi2sd { source, target, .. } => Self::integer_to_float(0xF2, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cvttss2si { source, target, .. } => Self::float_to_integer(0xF3, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Cvttsd2si { source, target, .. } => Self::float_to_integer(0xF2, &Self::operand(source)?, &Self::operand(target)?),
+            X86Instruction::Sete(target) => Self::set(0x4, &Self::operand(target)?),
+            X86Instruction::Setne(target) => Self::set(0x5, &Self::operand(target)?),
+            X86Instruction::Setg(target) => Self::set(0xF, &Self::operand(target)?),
+            X86Instruction::Setge(target) => Self::set(0xD, &Self::operand(target)?),
+            X86Instruction::Setl(target) => Self::set(0xC, &Self::operand(target)?),
+            X86Instruction::Setle(target) => Self::set(0xE, &Self::operand(target)?),
+            X86Instruction::Seta(target) => Self::set(0x7, &Self::operand(target)?),
+            X86Instruction::Setae(target) => Self::set(0x3, &Self::operand(target)?),
+            X86Instruction::Setb(target) => Self::set(0x2, &Self::operand(target)?),
+            X86Instruction::Setbe(target) => Self::set(0x6, &Self::operand(target)?),
+            X86Instruction::Setp(target) => Self::set(0xA, &Self::operand(target)?),
+            X86Instruction::Setnp(target) => Self::set(0xB, &Self::operand(target)?),
+            X86Instruction::Cdq => Ok(Encoding::new(&[0x99])),
+            X86Instruction::Cqo => Ok(Encoding::new(&[0x99]).sized(RegisterSize::_64Bit)),
+            X86Instruction::Ret => Ok(Encoding::new(&[0xC3])),
+            X86Instruction::Syscall => Ok(Encoding::new(&[0x0F, 0x05])),
+            X86Instruction::Call(name) => return Ok(Fragment::Call(name.clone())),
+            X86Instruction::Jmp(label) => return Ok(Fragment::Jump { condition: None, label: label.clone(), short: true }),
+            jump => {
+                let (condition, label) = Self::condition(jump).ok_or_else(unsupported)?;
+                return Ok(Fragment::Jump { condition: Some(condition), label: label.clone(), short: true });
+            }
+        };
+
+        let (bytes, relocation) = encoding?.bytes()?;
+        Ok(Fragment::Bytes { bytes, relocation })
+    }
+
+    fn condition(instruction: &X86Instruction) -> Option<(u8, &String)> {
+        Some(match instruction {
+            X86Instruction::Jo(label) => (0x0, label),
+            X86Instruction::Jno(label) => (0x1, label),
+            X86Instruction::Jb(label) | X86Instruction::Jc(label) | X86Instruction::Jnae(label) => (0x2, label),
+            X86Instruction::Jae(label) | X86Instruction::Jnb(label) | X86Instruction::Jnc(label) => (0x3, label),
+            X86Instruction::Je(label) | X86Instruction::Jz(label) => (0x4, label),
+            X86Instruction::Jne(label) | X86Instruction::Jnz(label) => (0x5, label),
+            X86Instruction::Jbe(label) | X86Instruction::Jna(label) => (0x6, label),
+            X86Instruction::Ja(label) | X86Instruction::Jnbe(label) => (0x7, label),
+            X86Instruction::Js(label) => (0x8, label),
+            X86Instruction::Jns(label) => (0x9, label),
+            X86Instruction::Jp(label) | X86Instruction::Jpe(label) => (0xA, label),
+            X86Instruction::Jnp(label) | X86Instruction::Jpo(label) => (0xB, label),
+            X86Instruction::Jl(label) | X86Instruction::Jnge(label) => (0xC, label),
+            X86Instruction::Jge(label) | X86Instruction::Jnl(label) => (0xD, label),
+            X86Instruction::Jle(label) | X86Instruction::Jng(label) => (0xE, label),
+            X86Instruction::Jg(label) | X86Instruction::Jnle(label) => (0xF, label),
+            _ => return None
+        })
+    }
+
+    /// ADD, OR, AND, SUB, XOR and CMP share the encoding, the operation is in the reg field or in the opcode
+    fn arithmetic(operation: u8, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        let size = Self::operation_size(&[target, source]);
+        let byte = size == RegisterSize::_8Bit;
+        let accumulator = matches!(target, Operand::Register(Register::AL | Register::AX | Register::EAX | Register::RAX));
+
+        match (source, target) {
+            (Operand::Immediate(value), target) => {
+                let value = sign_extend(*value, size);
+                if i32::try_from(value).is_err() {
+                    return Err(X86Error::UnsupportedOperands(format!("{} does not fit to the instruction", value)));
+                }
+
+                match (byte, accumulator, i8::try_from(value).is_ok()) {
+                    (true, true, _) => Ok(Encoding::new(&[operation * 8 + 4]).immediate(value, 1)),
+                    (true, false, _) => Encoding::new(&[0x80]).extension(operation).rm(target).map(|encoding| encoding.immediate(value, 1)),
+                    (false, _, true) => Encoding::new(&[0x83]).sized(size).extension(operation).rm(target).map(|encoding| encoding.immediate(value, 1)),
+                    (false, true, false) => Ok(Encoding::new(&[operation * 8 + 5]).sized(size).immediate(value, immediate_size(size))),
+                    (false, false, false) => Encoding::new(&[0x81]).sized(size).extension(operation).rm(target).map(|encoding| encoding.immediate(value, immediate_size(size)))
+                }
+            },
+            (Operand::Register(register), target) if is_general(source) => Encoding::new(&[operation * 8 + !byte as u8]).sized(size).reg(*register).rm(target),
+            (source @ Operand::Memory(_), Operand::Register(register)) if is_general(target) => Encoding::new(&[operation * 8 + 2 + !byte as u8]).sized(size).reg(*register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+
+    fn mov(source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        let size = Self::operation_size(&[target, source]);
+        let byte = size == RegisterSize::_8Bit;
+
+        match (source, target) {
+            (Operand::Immediate(value), Operand::Register(register)) if is_general(target) => {
+                let value = sign_extend(*value, size);
+                let encoding = Encoding { opcode_register: Some(*register), ..Encoding::new(&[if byte { 0xB0 } else { 0xB8 }]) }.sized(size);
+
+                // 64bit immediate is only used if it does not fit to the sign extended 32bit immediate
+                match (size, i32::try_from(value).is_ok()) {
+                    (RegisterSize::_64Bit, true) => Encoding::new(&[0xC7]).sized(size).rm(target).map(|encoding| encoding.immediate(value, 4)),
+                    (RegisterSize::_64Bit, false) => Ok(encoding.immediate(value, 8)),
+                    _ => Ok(encoding.immediate(value, immediate_size(size)))
+                }
+            },
+            (Operand::Immediate(value), Operand::Memory(_)) => {
+                let value = sign_extend(*value, size);
+                if i32::try_from(value).is_err() {
+                    return Err(X86Error::UnsupportedOperands(format!("{} does not fit to the instruction", value)));
+                }
+                Encoding::new(&[if byte { 0xC6 } else { 0xC7 }]).sized(size).rm(target).map(|encoding| encoding.immediate(value, immediate_size(size)))
+            },
+
+            (Operand::Register(register), target) if is_general(source) => Encoding::new(&[if byte { 0x88 } else { 0x89 }]).sized(size).reg(*register).rm(target),
+            (source @ Operand::Memory(_), Operand::Register(register)) if is_general(target) => Encoding::new(&[if byte { 0x8A } else { 0x8B }]).sized(size).reg(*register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+
+    fn extend(signed: bool, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        let (source_size, target) = match (source, target) {
+            (Operand::Register(source), Operand::Register(target)) => (source.get_register_size(), *target),
+            _ => return Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        };
+
+        let opcode: &[u8] = match (signed, source_size) {
+            (true, RegisterSize::_8Bit) => &[0x0F, 0xBE],
+            (true, RegisterSize::_16Bit) => &[0x0F, 0xBF],
+            (true, RegisterSize::_32Bit) => &[0x63],
+            (false, RegisterSize::_8Bit) => &[0x0F, 0xB6],
+            (false, RegisterSize::_16Bit) => &[0x0F, 0xB7],
+            _ => return Err(X86Error::UnsupportedOperands(format!("{:?}", source)))
+        };
+
+        Encoding::new(opcode).sized(target.get_register_size()).reg(target).rm(source)
+    }
+
+    fn unary(opcodes: &[u8; 2], extension: u8, target: &Operand) -> Result<Encoding, X86Error> {
+        let size = Self::operation_size(&[target]);
+        let opcode = match size {
+            RegisterSize::_8Bit => opcodes[0],
+            _ => opcodes[1]
+        };
+
+        Encoding::new(&[opcode]).sized(size).extension(extension).rm(target)
+    }
+
+    /// Shift count is an immediate or CL
+    fn shift(extension: u8, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        let size = Self::operation_size(&[target]);
+        let byte = (size == RegisterSize::_8Bit) as u8;
+
+        let encoding = match source {
+            Operand::Immediate(1) => Encoding::new(&[0xD1 - byte]),
+            Operand::Immediate(value) => Encoding::new(&[0xC1 - byte]).immediate(*value as u8 as i64, 1),
+            Operand::Register(Register::CL) => Encoding::new(&[0xD3 - byte]),
+            _ => return Err(X86Error::UnsupportedOperands(format!("shift count {:?}", source)))
+        };
+
+        encoding.sized(size).extension(extension).rm(target)
+    }
+
+    fn set(condition: u8, target: &Operand) -> Result<Encoding, X86Error> {
+        Encoding::new(&[0x0F, 0x90 + condition]).rm(target)
+    }
+
+    /// Target is a vector register, source is a vector register or memory
+    fn vector(prefix: Option<u8>, opcode: u8, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        match (source, target) {
+            (source, Operand::Register(register)) if is_vector(target) && (is_vector(source) || matches!(source, Operand::Memory(_))) =>
+                Encoding::new(&[0x0F, opcode]).prefix(prefix).reg(*register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+
+    /// Store uses the other opcode, the register is always in the reg field
+    fn vector_move(prefix: Option<u8>, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        match (source, target) {
+            (Operand::Register(register), Operand::Memory(_)) if is_vector(source) => Encoding::new(&[0x0F, 0x11]).prefix(prefix).reg(*register).rm(target),
+            _ => Self::vector(prefix, 0x10, source, target)
+        }
+    }
+
+    /// 64bit move between the general purpose and the vector registers, the vector register is in the reg field
+    fn vector_bits(source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        match (source, target) {
+            (Operand::Register(register), target) if is_vector(source) && is_general(target) => Encoding::new(&[0x0F, 0x7E]).prefix(Some(0x66)).sized(RegisterSize::_64Bit).reg(*register).rm(target),
+            (source, Operand::Register(register)) if is_general(source) && is_vector(target) => Encoding::new(&[0x0F, 0x6E]).prefix(Some(0x66)).sized(RegisterSize::_64Bit).reg(*register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+
+    fn integer_to_float(prefix: u8, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        match (source, target) {
+            (Operand::Register(register), Operand::Register(target_register)) if is_general(source) && is_vector(target) =>
+                Encoding::new(&[0x0F, 0x2A]).prefix(Some(prefix)).sized(register.get_register_size()).reg(*target_register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+
+    fn float_to_integer(prefix: u8, source: &Operand, target: &Operand) -> Result<Encoding, X86Error> {
+        match (source, target) {
+            (source, Operand::Register(register)) if is_general(target) && (is_vector(source) || matches!(source, Operand::Memory(_))) =>
+                Encoding::new(&[0x0F, 0x2C]).prefix(Some(prefix)).sized(register.get_register_size()).reg(*register).rm(source),
+            _ => Err(X86Error::UnsupportedOperands(format!("{:?}, {:?}", source, target)))
+        }
+    }
+}
@@ -0,0 +1,25 @@
+//! Static executable of the freestanding program, the code and the data are in the same segment, so every
+//! relocation is resolved before the file is written.
+
+use tb_core::{elf::{self, EM_X86_64}, error::TBError, types::DataItemCollection};
+
+use crate::{encoder::X86MachineCode, runtime::X86Runtime};
+
+pub fn write(machine_code: X86MachineCode, datas: &DataItemCollection) -> Result<Vec<u8>, TBError> {
+    let (data, data_labels) = datas.to_bytes();
+
+    let data_offset = machine_code.code.len().next_multiple_of(16);
+    let mut image = machine_code.code;
+    image.resize(data_offset, 0);
+    image.extend_from_slice(&data);
+
+    // There is no other object to link, calls to unknown functions could not be resolved
+    for relocation in machine_code.relocations.iter() {
+        let target = data_labels.get(&relocation.symbol).ok_or_else(|| TBError::LinkFailed(format!("symbol not found: {}", relocation.symbol)))?;
+        let value = (data_offset + target) as i64 + relocation.addend - relocation.offset as i64;
+        image[relocation.offset..relocation.offset + 4].copy_from_slice(&(value as i32).to_le_bytes());
+    }
+
+    let entry = machine_code.labels.get(X86Runtime::START).ok_or_else(|| TBError::LinkFailed(format!("symbol not found: {}", X86Runtime::START)))?;
+    Ok(elf::executable(EM_X86_64, &image, *entry))
+}
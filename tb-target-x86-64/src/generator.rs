@@ -1,20 +1,85 @@
-use tb_core::{syntax::{SyntaxGeneratorTrait, TBSyntaxGenerator}, types::{AssemblyGenerator, DataItemCollection, Definition}};
+use tb_core::{error::{CodeGenerationError, TBError}, syntax::{Syntax, TBSyntaxGenerator}, target::{ObjectFormat, OperatingSystem, Target}, types::{AssemblyGenerator, DataItemCollection, Definition, ExecutableGenerator, ObjectGenerator}};
 
-use crate::{compiler::X86DefinitionCompiler, instruction::X86Instruction, X86ApplicationContext};
+use crate::{compiler::X86DefinitionCompiler, encoder::X86Encoder, runtime::X86Runtime, X86ApplicationContext};
+
+#[cfg(unix)]
+use tb_core::jit::{Jit, JitError, JitGenerator};
 
 
 #[derive(Debug, Clone, Default)]
-pub struct X86AssemblyGenerator;
+pub struct X86AssemblyGenerator {
+    target: Target
+}
+
+impl X86AssemblyGenerator {
+    /// Instructions of the definitions before they are printed or encoded
+    pub fn compile(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<X86ApplicationContext, CodeGenerationError> {
+        let mut context = X86ApplicationContext::new(self.target, datas);
+        context.storage.parameters = Definition::parameter_types(&definitions);
 
-impl AssemblyGenerator for X86AssemblyGenerator {
-    fn generate(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> String {
-        let mut context = X86ApplicationContext { datas, ..Default::default() };
-        
         for item in definitions.into_iter() {
-            X86DefinitionCompiler::compile(item, &mut context).unwrap();
+            X86DefinitionCompiler::compile(item, &mut context)?;
         }
 
-        let syntax_generator = TBSyntaxGenerator::get_generator::<X86Instruction>().unwrap();
-        syntax_generator.generate(&mut context)
+        Ok(context)
+    }
+
+    /// Same as compile, but the program does not use the C library and it has its own entry point
+    pub fn compile_freestanding(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<X86ApplicationContext, CodeGenerationError> {
+        let mut context = X86ApplicationContext::new(self.target, datas);
+        context.storage.parameters = Definition::parameter_types(&definitions);
+        context.storage.freestanding = true;
+
+        for item in definitions.into_iter() {
+            X86DefinitionCompiler::compile(item, &mut context)?;
+        }
+
+        X86Runtime::add(&mut context);
+        Ok(context)
+    }
+}
+
+impl AssemblyGenerator for X86AssemblyGenerator {
+    fn new(target: Target) -> Self {
+        Self { target }
+    }
+
+    fn generate_with_syntax(&self, definitions: Vec<Definition>, datas: DataItemCollection, syntax: Syntax) -> Result<String, TBError> {
+        let mut context = self.compile(definitions, datas)?;
+        Ok(TBSyntaxGenerator::generate(syntax, &mut context))
+    }
+}
+
+impl ObjectGenerator for X86AssemblyGenerator {
+    fn generate_object(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Vec<u8>, TBError> {
+        if self.target.object_format != ObjectFormat::Elf {
+            return Err(TBError::UnsupportedTarget(self.target.to_string()));
+        }
+
+        let context = self.compile(definitions, datas)?;
+        let machine_code = X86Encoder::encode(&context.instructions.items).map_err(|error| TBError::EncodeFailed(Box::new(error)))?;
+        Ok(crate::object::write(machine_code, &context.datas))
+    }
+}
+
+impl ExecutableGenerator for X86AssemblyGenerator {
+    fn generate_executable(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Vec<u8>, TBError> {
+        // Runtime uses the Linux system calls
+        if self.target.operating_system != OperatingSystem::Linux {
+            return Err(TBError::UnsupportedTarget(self.target.to_string()));
+        }
+
+        let context = self.compile_freestanding(definitions, datas)?;
+        let machine_code = X86Encoder::encode(&context.instructions.items).map_err(|error| TBError::EncodeFailed(Box::new(error)))?;
+        crate::executable::write(machine_code, &context.datas)
+    }
+}
+
+#[cfg(unix)]
+impl JitGenerator for X86AssemblyGenerator {
+    fn jit(&self, definitions: Vec<Definition>, datas: DataItemCollection) -> Result<Jit, JitError> {
+        let context = self.compile(definitions, datas)?;
+        let machine_code = X86Encoder::encode(&context.instructions.items).map_err(|error| JitError::EncodeFailed(Box::new(error)))?;
+        crate::jit::link(machine_code, &context.datas)
     }
 }
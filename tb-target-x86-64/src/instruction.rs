@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use strum_macros::EnumDiscriminants;
 use strum_macros::Display;
 use tb_core::instruction::InstructionTrait;
+use tb_core::types::NumberType;
 use tb_core::types::Value;
 use tb_core::types::ValueType;
 
@@ -10,6 +11,42 @@ use crate::register::Register;
 use crate::X86AbstractInstruction;
 use crate::X86Location;
 
+/// Registers that could hold an argument at the function call (RAX has the vector register count for variadic functions)
+pub const CALL_ARGUMENT_REGISTERS: [Register; 15] = [
+    Register::RDI, Register::RSI, Register::RDX, Register::RCX, Register::R8, Register::R9, Register::RAX,
+    Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3, Register::XMM4, Register::XMM5, Register::XMM6, Register::XMM7
+];
+
+/// Registers that are not preserved by the called function
+pub const CALLER_SAVED_REGISTERS: [Register; 25] = [
+    Register::RAX, Register::RCX, Register::RDX, Register::RSI, Register::RDI, Register::R8, Register::R9, Register::R10, Register::R11,
+    Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3, Register::XMM4, Register::XMM5, Register::XMM6, Register::XMM7,
+    Register::XMM8, Register::XMM9, Register::XMM10, Register::XMM11, Register::XMM12, Register::XMM13, Register::XMM14, Register::XMM15
+];
+
+/// Registers that have to be preserved by the called function (RBP is saved with the stack frame)
+pub const CALLEE_SAVED_REGISTERS: [Register; 5] = [
+    Register::RBX, Register::R12, Register::R13, Register::R14, Register::R15
+];
+
+/// Microsoft x64 convention also preserves RSI, RDI and the upper vector registers
+pub const MICROSOFT_CALLEE_SAVED_REGISTERS: [Register; 17] = [
+    Register::RBX, Register::RSI, Register::RDI, Register::R12, Register::R13, Register::R14, Register::R15,
+    Register::XMM6, Register::XMM7, Register::XMM8, Register::XMM9, Register::XMM10, Register::XMM11, Register::XMM12, Register::XMM13, Register::XMM14, Register::XMM15
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum X86OperandAccess {
+    Read,
+    Write,
+    ReadWrite
+}
+
+pub struct X86Operands<'a> {
+    pub source: Option<&'a mut X86Location>,
+    pub target: Option<(&'a mut X86Location, X86OperandAccess)>
+}
+
 #[derive(Debug, Clone, Display, EnumDiscriminants)]
 #[strum_discriminants(name(X86InstructionType))]
 #[strum_discriminants(derive(Display))]
@@ -33,6 +70,10 @@ pub enum X86Instruction {
         target: X86Location,
         comment: Option<String>
     },
+    Div {
+        target: X86Location,
+        comment: Option<String>
+    },
     Not {
         source: X86Location,
         comment: Option<String>
@@ -54,6 +95,16 @@ pub enum X86Instruction {
         target: X86Location,
         comment: Option<String>
     },
+    Movsx {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Movzx {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
     Lea {
         source: X86Location,
         target: X86Location,
@@ -84,11 +135,125 @@ pub enum X86Instruction {
         target: X86Location,
         comment: Option<String>
     },
+    Sar {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
     Cmp {
         left: X86Location,
         right: X86Location,
         comment: Option<String>
     },
+    Movss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Movsd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+
+    /// Bits of the general purpose register and the vector register
+    Movq {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+
+    /// Whole vector register
+    Movups {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Addss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Addsd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Subss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Subsd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Mulss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Mulsd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Divss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Divsd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Xorps {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvtsi2ss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvtsi2sd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvttss2si {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvttsd2si {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvtss2sd {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Cvtsd2ss {
+        source: X86Location,
+        target: X86Location,
+        comment: Option<String>
+    },
+    Ucomiss {
+        left: X86Location,
+        right: X86Location,
+        comment: Option<String>
+    },
+    Ucomisd {
+        left: X86Location,
+        right: X86Location,
+        comment: Option<String>
+    },
     Call(String),
     Jne(String),
     Je(String),
@@ -96,11 +261,49 @@ pub enum X86Instruction {
     Jnbe(String),
     Jna(String),
     Jnae(String),
+    Ja(String),
+    Jae(String),
+    Jb(String),
+    Jbe(String),
+    Jc(String),
+    Jnc(String),
+    Jg(String),
+    Jge(String),
+    Jl(String),
+    Jle(String),
+    Jng(String),
+    Jnge(String),
+    Jnl(String),
+    Jnle(String),
+    Jo(String),
+    Jno(String),
+    Jp(String),
+    Jnp(String),
+    Jpe(String),
+    Jpo(String),
+    Js(String),
+    Jns(String),
+    Jz(String),
+    Jnz(String),
     Jmp(String),
     Cdq,
+    Cqo,
+    Sete(X86Location),
+    Setne(X86Location),
+    Setg(X86Location),
+    Setge(X86Location),
+    Setl(X86Location),
+    Setle(X86Location),
+    Seta(X86Location),
+    Setae(X86Location),
+    Setb(X86Location),
+    Setbe(X86Location),
+    Setp(X86Location),
+    Setnp(X86Location),
     Push(X86Location),
     Pop(X86Location),
-    Ret
+    Ret,
+    Syscall
 }
 
 impl X86Instruction {
@@ -113,6 +316,135 @@ impl X86Instruction {
             X86Instruction::Mov { source, target, comment: None }
         }
     }
+
+    /// Move instruction for the number type, floating point values are moved with SSE instructions
+    pub fn typed_movement(number_type: NumberType, source: X86Location, target: X86Location, comment: Option<String>) -> X86Instruction {
+        match number_type {
+            NumberType::Float => X86Instruction::Movss { source, target, comment },
+            NumberType::Double => X86Instruction::Movsd { source, target, comment },
+            _ => X86Instruction::Mov { source, target, comment }
+        }
+    }
+
+    /// Explicit operands of the instruction. Source is always read, target could be read, written or both.
+    pub fn operands_mut(&mut self) -> X86Operands<'_> {
+        match self {
+            X86Instruction::Add { source, target, .. } |
+            X86Instruction::Sub { source, target, .. } |
+            X86Instruction::IMul { source, target, .. } |
+            X86Instruction::And { source, target, .. } |
+            X86Instruction::Or { source, target, .. } |
+            X86Instruction::Xor { source, target, .. } |
+            X86Instruction::Shl { source, target, .. } |
+            X86Instruction::Shr { source, target, .. } |
+            X86Instruction::Sar { source, target, .. } |
+            X86Instruction::Addss { source, target, .. } |
+            X86Instruction::Addsd { source, target, .. } |
+            X86Instruction::Subss { source, target, .. } |
+            X86Instruction::Subsd { source, target, .. } |
+            X86Instruction::Mulss { source, target, .. } |
+            X86Instruction::Mulsd { source, target, .. } |
+            X86Instruction::Divss { source, target, .. } |
+            X86Instruction::Divsd { source, target, .. } |
+            X86Instruction::Xorps { source, target, .. } => X86Operands { source: Some(source), target: Some((target, X86OperandAccess::ReadWrite)) },
+            X86Instruction::Mov { source, target, .. } |
+            X86Instruction::Movsx { source, target, .. } |
+            X86Instruction::Movzx { source, target, .. } |
+            X86Instruction::Lea { source, target, .. } |
+            X86Instruction::Movss { source, target, .. } |
+            X86Instruction::Movsd { source, target, .. } |
+            X86Instruction::Movq { source, target, .. } |
+            X86Instruction::Movups { source, target, .. } |
+            X86Instruction::Cvtsi2ss { source, target, .. } |
+            X86Instruction::Cvtsi2sd { source, target, .. } |
+            X86Instruction::Cvttss2si { source, target, .. } |
+            X86Instruction::Cvttsd2si { source, target, .. } |
+            X86Instruction::Cvtss2sd { source, target, .. } |
+            X86Instruction::Cvtsd2ss { source, target, .. } => X86Operands { source: Some(source), target: Some((target, X86OperandAccess::Write)) },
+            X86Instruction::Not { source, .. } |
+            X86Instruction::Neg { source, .. } |
+            X86Instruction::Inc { source, .. } |
+            X86Instruction::Dec { source, .. } => X86Operands { source: None, target: Some((source, X86OperandAccess::ReadWrite)) },
+            X86Instruction::Cmp { left, right, .. } |
+            X86Instruction::Ucomiss { left, right, .. } |
+            X86Instruction::Ucomisd { left, right, .. } => X86Operands { source: Some(left), target: Some((right, X86OperandAccess::Read)) },
+            X86Instruction::IDiv { target, .. } |
+            X86Instruction::Div { target, .. } |
+            X86Instruction::Push(target) => X86Operands { source: None, target: Some((target, X86OperandAccess::Read)) },
+            X86Instruction::Pop(target) |
+            X86Instruction::Sete(target) |
+            X86Instruction::Setne(target) |
+            X86Instruction::Setg(target) |
+            X86Instruction::Setge(target) |
+            X86Instruction::Setl(target) |
+            X86Instruction::Setle(target) |
+            X86Instruction::Seta(target) |
+            X86Instruction::Setae(target) |
+            X86Instruction::Setb(target) |
+            X86Instruction::Setbe(target) |
+            X86Instruction::Setp(target) |
+            X86Instruction::Setnp(target) => X86Operands { source: None, target: Some((target, X86OperandAccess::Write)) },
+            X86Instruction::Call(_) |
+            X86Instruction::Jne(_) |
+            X86Instruction::Je(_) |
+            X86Instruction::Jnb(_) |
+            X86Instruction::Jnbe(_) |
+            X86Instruction::Jna(_) |
+            X86Instruction::Jnae(_) |
+            X86Instruction::Ja(_) |
+            X86Instruction::Jae(_) |
+            X86Instruction::Jb(_) |
+            X86Instruction::Jbe(_) |
+            X86Instruction::Jc(_) |
+            X86Instruction::Jnc(_) |
+            X86Instruction::Jg(_) |
+            X86Instruction::Jge(_) |
+            X86Instruction::Jl(_) |
+            X86Instruction::Jle(_) |
+            X86Instruction::Jng(_) |
+            X86Instruction::Jnge(_) |
+            X86Instruction::Jnl(_) |
+            X86Instruction::Jnle(_) |
+            X86Instruction::Jo(_) |
+            X86Instruction::Jno(_) |
+            X86Instruction::Jp(_) |
+            X86Instruction::Jnp(_) |
+            X86Instruction::Jpe(_) |
+            X86Instruction::Jpo(_) |
+            X86Instruction::Js(_) |
+            X86Instruction::Jns(_) |
+            X86Instruction::Jz(_) |
+            X86Instruction::Jnz(_) |
+            X86Instruction::Jmp(_) |
+            X86Instruction::Cdq |
+            X86Instruction::Cqo |
+            X86Instruction::Ret |
+            X86Instruction::Syscall => X86Operands { source: None, target: None },
+        }
+    }
+
+    /// Registers that are read by the instruction without being an operand
+    pub fn implicit_reads(&self) -> &'static [Register] {
+        match self {
+            X86Instruction::Cdq | X86Instruction::Cqo => &[Register::RAX],
+            X86Instruction::IDiv { .. } | X86Instruction::Div { .. } => &[Register::RAX, Register::RDX],
+            X86Instruction::Call(_) => &CALL_ARGUMENT_REGISTERS,
+            X86Instruction::Ret => &[Register::RAX, Register::XMM0],
+            X86Instruction::Syscall => &[Register::RAX, Register::RDI, Register::RSI, Register::RDX],
+            _ => &[]
+        }
+    }
+
+    /// Registers that are written by the instruction without being an operand
+    pub fn implicit_writes(&self) -> &'static [Register] {
+        match self {
+            X86Instruction::Cdq | X86Instruction::Cqo => &[Register::RDX],
+            X86Instruction::IDiv { .. } | X86Instruction::Div { .. } => &[Register::RAX, Register::RDX],
+            X86Instruction::Call(_) => &CALLER_SAVED_REGISTERS,
+            X86Instruction::Syscall => &[Register::RAX, Register::RCX, Register::R11],
+            _ => &[]
+        }
+    }
 }
 
 impl From<X86Instruction> for X86AbstractInstruction {
@@ -130,6 +462,7 @@ impl InstructionTrait for X86Instruction {
             X86Instruction::Add { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Sub { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::IDiv { target, comment } => X86AbstractInstruction::target_with_comment(self, target, comment),
+            X86Instruction::Div { target, comment } => X86AbstractInstruction::target_with_comment(self, target, comment),
             X86Instruction::IMul { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Not { source, comment } => X86AbstractInstruction::target_with_comment(self, source, comment),
             X86Instruction::Neg { source, comment } => X86AbstractInstruction::target_with_comment(self, source, comment),
@@ -140,11 +473,47 @@ impl InstructionTrait for X86Instruction {
             X86Instruction::Xor { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Shl { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Shr { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Sar { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Mov { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Lea { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Movsx { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Movzx { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
             X86Instruction::Cmp { left, right, comment } => X86AbstractInstruction::target_source_with_comment(self, right, left, comment),
+            X86Instruction::Movss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Movsd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Movq { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Movups { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Addss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Addsd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Subss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Subsd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Mulss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Mulsd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Divss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Divsd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Xorps { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvtsi2ss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvtsi2sd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvttss2si { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvttsd2si { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvtss2sd { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Cvtsd2ss { source, target, comment } => X86AbstractInstruction::target_source_with_comment(self, target, source, comment),
+            X86Instruction::Ucomiss { left, right, comment } => X86AbstractInstruction::target_source_with_comment(self, right, left, comment),
+            X86Instruction::Ucomisd { left, right, comment } => X86AbstractInstruction::target_source_with_comment(self, right, left, comment),
             X86Instruction::Push(target) => X86AbstractInstruction::target(self, target),
             X86Instruction::Pop(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Sete(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setne(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setg(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setge(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setl(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setle(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Seta(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setae(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setb(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setbe(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setp(target) => X86AbstractInstruction::target(self, target),
+            X86Instruction::Setnp(target) => X86AbstractInstruction::target(self, target),
             X86Instruction::Call(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Jne(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Je(label) => X86AbstractInstruction::label(self, label),
@@ -152,9 +521,35 @@ impl InstructionTrait for X86Instruction {
             X86Instruction::Jnbe(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Jna(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Jnae(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Ja(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jae(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jb(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jbe(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jc(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnc(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jg(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jge(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jl(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jle(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jng(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnge(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnl(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnle(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jo(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jno(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jp(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnp(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jpe(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jpo(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Js(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jns(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jz(label) => X86AbstractInstruction::label(self, label),
+            X86Instruction::Jnz(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Jmp(label) => X86AbstractInstruction::label(self, label),
             X86Instruction::Ret => X86AbstractInstruction::simple(self),
-            X86Instruction::Cdq => X86AbstractInstruction::simple(self)
+            X86Instruction::Syscall => X86AbstractInstruction::simple(self),
+            X86Instruction::Cdq => X86AbstractInstruction::simple(self),
+            X86Instruction::Cqo => X86AbstractInstruction::simple(self)
         }
     }
     
@@ -0,0 +1,59 @@
+//! Links the encoded functions in the executable memory. Calls to the process functions go through the
+//! stubs after the code, because the shared libraries are usually not in the 32bit range of the code.
+
+use std::collections::HashMap;
+
+use tb_core::{jit::{resolve_symbol, ExecutableMemory, Jit, JitError}, types::DataItemCollection};
+
+use crate::encoder::X86MachineCode;
+
+/// jmp *0(%rip) followed by the absolute address
+const STUB_SIZE: usize = 16;
+
+pub fn link(machine_code: X86MachineCode, datas: &DataItemCollection) -> Result<Jit, JitError> {
+    let (data, data_labels) = datas.to_bytes();
+
+    // Functions that are not in the code need a stub
+    let mut externals = Vec::new();
+    for relocation in machine_code.relocations.iter() {
+        let symbol = &relocation.symbol;
+        if !machine_code.labels.contains_key(symbol) && !data_labels.contains_key(symbol) && !externals.contains(symbol) {
+            externals.push(symbol.clone());
+        }
+    }
+
+    // Code, stubs and the data
+    let stubs_offset = machine_code.code.len().next_multiple_of(STUB_SIZE);
+    let data_offset = stubs_offset + externals.len() * STUB_SIZE;
+
+    let mut memory = ExecutableMemory::new(data_offset + data.len())?;
+    let base = memory.address();
+    let buffer = memory.as_mut_slice();
+
+    buffer[..machine_code.code.len()].copy_from_slice(&machine_code.code);
+    buffer[data_offset..data_offset + data.len()].copy_from_slice(&data);
+
+    let mut symbols = HashMap::new();
+    for (index, name) in externals.into_iter().enumerate() {
+        let address = resolve_symbol(&name).ok_or_else(|| JitError::SymbolNotFound(name.clone()))?;
+        let offset = stubs_offset + index * STUB_SIZE;
+
+        buffer[offset..offset + 6].copy_from_slice(&[0xFF, 0x25, 0x00, 0x00, 0x00, 0x00]);
+        buffer[offset + 6..offset + 14].copy_from_slice(&(address as u64).to_le_bytes());
+        symbols.insert(name, offset);
+    }
+
+    symbols.extend(machine_code.labels.iter().map(|(name, offset)| (name.clone(), *offset)));
+    symbols.extend(data_labels.into_iter().map(|(name, offset)| (name, data_offset + offset)));
+
+    for relocation in machine_code.relocations.iter() {
+        let target = symbols[&relocation.symbol] as i64;
+        let value = i32::try_from(target + relocation.addend - relocation.offset as i64).map_err(|_| JitError::RelocationOutOfRange(relocation.symbol.clone()))?;
+        buffer[relocation.offset..relocation.offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    memory.make_executable()?;
+
+    let functions = machine_code.functions.into_iter().map(|function| (function.name, base + function.offset)).collect();
+    Ok(Jit::new(memory, functions))
+}
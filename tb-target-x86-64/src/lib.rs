@@ -8,13 +8,29 @@ pub mod compiler;
 pub mod instruction;
 pub mod generator;
 pub mod storage;
+pub mod register_allocator;
+pub mod encoder;
+pub mod object;
+pub mod executable;
+pub mod runtime;
+#[cfg(unix)]
+pub mod jit;
 
 #[derive(Debug, Clone, Default)]
 pub struct X86StoreDefaultRegisters;
 
 impl StoreDefaultRegisters<Register> for X86StoreDefaultRegisters {
-    fn initialize() -> Vec<(Register, bool)> {
-        vec![(Register::RDX, true), (Register::RCX, true), (Register::R8, true), (Register::R9, true), (Register::R10, true), (Register::RDI, true)]
+    fn initialize() -> Vec<Register> {
+        vec![
+            Register::RDX, Register::RCX, Register::R8, Register::R9, Register::R10, Register::RDI, Register::RSI, Register::RAX,
+
+            // Callee-saved registers are used last, the function has to save them before using
+            Register::RBX, Register::R12, Register::R13, Register::R14, Register::R15,
+
+            // R11 and XMM15 are used for the spilled registers
+            Register::XMM8, Register::XMM9, Register::XMM10, Register::XMM11, Register::XMM12, Register::XMM13, Register::XMM14,
+            Register::XMM0, Register::XMM1, Register::XMM2, Register::XMM3, Register::XMM4, Register::XMM5, Register::XMM6, Register::XMM7
+        ]
     }
 }
 
@@ -0,0 +1,30 @@
+//! Relocatable ELF object of the encoded functions, it is linked like the assembled output.
+
+use tb_core::{elf::{ElfObject, ElfRelocation, EM_X86_64, R_X86_64_PC32, R_X86_64_PLT32}, types::DataItemCollection};
+
+use crate::encoder::X86MachineCode;
+
+pub fn write(machine_code: X86MachineCode, datas: &DataItemCollection) -> Vec<u8> {
+    let (rodata, labels) = datas.to_bytes();
+    let mut object = ElfObject::new(EM_X86_64, machine_code.code, rodata);
+
+    for function in machine_code.functions.iter() {
+        object.add_function(&function.name, function.offset, function.size);
+    }
+
+    // Data labels have the same names in the assembly
+    for item in datas.items.iter() {
+        object.add_data(&format!(".{}", item.label), labels[&item.label]);
+    }
+
+    // Other symbols are the functions of the other objects
+    for relocation in machine_code.relocations.into_iter() {
+        let relocation = match labels.contains_key(&relocation.symbol) {
+            true => ElfRelocation { offset: relocation.offset, symbol: format!(".{}", relocation.symbol), kind: R_X86_64_PC32, addend: relocation.addend },
+            false => ElfRelocation { offset: relocation.offset, symbol: relocation.symbol, kind: R_X86_64_PLT32, addend: relocation.addend }
+        };
+        object.add_relocation(relocation);
+    }
+
+    object.to_bytes()
+}
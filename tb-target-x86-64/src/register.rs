@@ -3,15 +3,20 @@ use std::fmt::Display;
 use tb_core::types::{RegisterTrait, RegisterSize};
 
 #[repr(usize)]
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Register {
     AL, BL, CL, DL, AH, BH, CH, DH, DIL, SIL, BPL, SPL, R8B, R9B, R10B, R11B, R12B, R13B, R14B, R15B, // Byte Registers
     AX, BX, CX, DX, DI, SI, BP, SP, R8W, R9W, R10W, R11W, R12W, R13W, R14W, R15W, // Word Registers
     EAX, EBX, ECX, EDX, ESI, EDI, EBP, ESP, R8D, R9D, R10D, R11D, R12D, R13D, R14D, R15D, // Doubleword Registers
     RAX, RBX, RCX, RDX, RSI, RDI, RBP, RSP, R8, R9, R10, R11, R12, R13, R14, R15, // Quadword Registers
+    XMM0, XMM1, XMM2, XMM3, XMM4, XMM5, XMM6, XMM7, XMM8, XMM9, XMM10, XMM11, XMM12, XMM13, XMM14, XMM15, // SSE Registers
 }
 
 impl Register {    
+    pub fn is_vector(&self) -> bool {
+        self.get_register_size() == RegisterSize::_128Bit
+    }
+
     pub fn get_info(&self) -> &'static RegisterSizes {
         match *self {
             Register::RAX | Register::EAX | Register::AX | Register::AH | Register::AL => &OPCODE_TYPES[0],
@@ -30,7 +35,22 @@ impl Register {
             Register::R13 | Register::R13D | Register::R13W | Register::R13B => &OPCODE_TYPES[13],
             Register::R14 | Register::R14D | Register::R14W | Register::R14B => &OPCODE_TYPES[14],
             Register::R15 | Register::R15D | Register::R15W | Register::R15B => &OPCODE_TYPES[15],
-
+            Register::XMM0 => &OPCODE_TYPES[16],
+            Register::XMM1 => &OPCODE_TYPES[17],
+            Register::XMM2 => &OPCODE_TYPES[18],
+            Register::XMM3 => &OPCODE_TYPES[19],
+            Register::XMM4 => &OPCODE_TYPES[20],
+            Register::XMM5 => &OPCODE_TYPES[21],
+            Register::XMM6 => &OPCODE_TYPES[22],
+            Register::XMM7 => &OPCODE_TYPES[23],
+            Register::XMM8 => &OPCODE_TYPES[24],
+            Register::XMM9 => &OPCODE_TYPES[25],
+            Register::XMM10 => &OPCODE_TYPES[26],
+            Register::XMM11 => &OPCODE_TYPES[27],
+            Register::XMM12 => &OPCODE_TYPES[28],
+            Register::XMM13 => &OPCODE_TYPES[29],
+            Register::XMM14 => &OPCODE_TYPES[30],
+            Register::XMM15 => &OPCODE_TYPES[31],
         }
     }
 }
@@ -43,7 +63,7 @@ pub struct RegisterSizes {
     pub _8bit_high: Option<Register>,
 }
 
-pub const OPCODE_TYPES: [RegisterSizes; 16] = [
+pub const OPCODE_TYPES: [RegisterSizes; 32] = [
     RegisterSizes { _64bit: Register::RAX, _32bit: Register::EAX, _16bit: Register::AX, _8bit_high: Some(Register::AH), _8bit_low: Register::AL },
     RegisterSizes { _64bit: Register::RBX, _32bit: Register::EBX, _16bit: Register::BX, _8bit_high: Some(Register::BH), _8bit_low: Register::BL },
     RegisterSizes { _64bit: Register::RCX, _32bit: Register::ECX, _16bit: Register::CX, _8bit_high: Some(Register::CH), _8bit_low: Register::CL },
@@ -60,6 +80,24 @@ pub const OPCODE_TYPES: [RegisterSizes; 16] = [
     RegisterSizes { _64bit: Register::R13, _32bit: Register::R13D, _16bit: Register::R13W, _8bit_high: None, _8bit_low: Register::R13B },
     RegisterSizes { _64bit: Register::R14, _32bit: Register::R14D, _16bit: Register::R14W, _8bit_high: None, _8bit_low: Register::R14B },
     RegisterSizes { _64bit: Register::R15, _32bit: Register::R15D, _16bit: Register::R15W, _8bit_high: None, _8bit_low: Register::R15B },
+
+    // SSE registers have no sized views
+    RegisterSizes { _64bit: Register::XMM0, _32bit: Register::XMM0, _16bit: Register::XMM0, _8bit_high: None, _8bit_low: Register::XMM0 },
+    RegisterSizes { _64bit: Register::XMM1, _32bit: Register::XMM1, _16bit: Register::XMM1, _8bit_high: None, _8bit_low: Register::XMM1 },
+    RegisterSizes { _64bit: Register::XMM2, _32bit: Register::XMM2, _16bit: Register::XMM2, _8bit_high: None, _8bit_low: Register::XMM2 },
+    RegisterSizes { _64bit: Register::XMM3, _32bit: Register::XMM3, _16bit: Register::XMM3, _8bit_high: None, _8bit_low: Register::XMM3 },
+    RegisterSizes { _64bit: Register::XMM4, _32bit: Register::XMM4, _16bit: Register::XMM4, _8bit_high: None, _8bit_low: Register::XMM4 },
+    RegisterSizes { _64bit: Register::XMM5, _32bit: Register::XMM5, _16bit: Register::XMM5, _8bit_high: None, _8bit_low: Register::XMM5 },
+    RegisterSizes { _64bit: Register::XMM6, _32bit: Register::XMM6, _16bit: Register::XMM6, _8bit_high: None, _8bit_low: Register::XMM6 },
+    RegisterSizes { _64bit: Register::XMM7, _32bit: Register::XMM7, _16bit: Register::XMM7, _8bit_high: None, _8bit_low: Register::XMM7 },
+    RegisterSizes { _64bit: Register::XMM8, _32bit: Register::XMM8, _16bit: Register::XMM8, _8bit_high: None, _8bit_low: Register::XMM8 },
+    RegisterSizes { _64bit: Register::XMM9, _32bit: Register::XMM9, _16bit: Register::XMM9, _8bit_high: None, _8bit_low: Register::XMM9 },
+    RegisterSizes { _64bit: Register::XMM10, _32bit: Register::XMM10, _16bit: Register::XMM10, _8bit_high: None, _8bit_low: Register::XMM10 },
+    RegisterSizes { _64bit: Register::XMM11, _32bit: Register::XMM11, _16bit: Register::XMM11, _8bit_high: None, _8bit_low: Register::XMM11 },
+    RegisterSizes { _64bit: Register::XMM12, _32bit: Register::XMM12, _16bit: Register::XMM12, _8bit_high: None, _8bit_low: Register::XMM12 },
+    RegisterSizes { _64bit: Register::XMM13, _32bit: Register::XMM13, _16bit: Register::XMM13, _8bit_high: None, _8bit_low: Register::XMM13 },
+    RegisterSizes { _64bit: Register::XMM14, _32bit: Register::XMM14, _16bit: Register::XMM14, _8bit_high: None, _8bit_low: Register::XMM14 },
+    RegisterSizes { _64bit: Register::XMM15, _32bit: Register::XMM15, _16bit: Register::XMM15, _8bit_high: None, _8bit_low: Register::XMM15 },
 ];
 
 impl RegisterTrait for Register {
@@ -74,11 +112,15 @@ impl RegisterTrait for Register {
             RegisterSize::_16Bit => info._16bit,
             RegisterSize::_32Bit => info._32bit,
             RegisterSize::_64Bit => info._64bit,
+            RegisterSize::_128Bit => match self.is_vector() {
+                true => self,
+                false => info._64bit
+            },
         }
     }
 }
 
-pub const REGISTER_SIZES: [RegisterSize; 68] = [
+pub const REGISTER_SIZES: [RegisterSize; 84] = [
     // Byte Registers
     RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit, RegisterSize::_8Bit,
 
@@ -90,6 +132,9 @@ pub const REGISTER_SIZES: [RegisterSize; 68] = [
     
     // Quadword Registers
     RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit, RegisterSize::_64Bit,  RegisterSize::_64Bit,
+
+    // SSE Registers
+    RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit, RegisterSize::_128Bit,  RegisterSize::_128Bit,
 ];
 
 impl Display for Register {
@@ -103,4 +148,4 @@ pub fn get_register_type(register: Register) -> RegisterSize {
 }
 
 // Compile time checks
-const _: () = assert!(REGISTER_SIZES.len() == Register::R15 as usize + 1, "Missing register types");
+const _: () = assert!(REGISTER_SIZES.len() == Register::XMM15 as usize + 1, "Missing register types");
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use tb_core::{register_allocator::{Allocation, FixedRanges, LinearScanAllocator, LiveInterval, VirtualRegister}, syntax::AsmStructure, types::{NumberType, RegisterSize, RegisterTrait}};
+
+use crate::{instruction::{X86Instruction, X86OperandAccess}, register::Register, X86AddressingMode, X86ApplicationContext, X86Location, X86Store};
+
+/// Spilled values are moved through these registers, so they are never given to the allocator
+const GENERAL_SCRATCH_REGISTER: Register = Register::R11;
+const VECTOR_SCRATCH_REGISTER: Register = Register::XMM15;
+
+/// Physical register range that is not closed yet
+#[derive(Debug, Clone, Copy)]
+struct OpenRange {
+    start: usize,
+    end: usize,
+    explicit: bool
+}
+
+pub struct X86RegisterAllocator;
+
+impl X86RegisterAllocator {
+    /// Replace the virtual registers of the instructions after the position with the physical registers.
+    /// Values are not kept in registers between the statements, so the intervals never cross a loop edge
+    /// and the instruction order is enough to find the liveness.
+    /// Calls clobber the caller-saved registers. Instead of saving and restoring them around every call, the
+    /// values that live across a call are never given a caller-saved register, they get a callee-saved register
+    /// or a stack slot. Callee-saved registers are stored once in the prologue, so the calls in a loop cost nothing.
+    /// Returns the callee-saved registers that the function writes, so it needs to preserve them.
+    pub fn allocate(scope: &mut X86Store, context: &mut X86ApplicationContext, start: usize, callee_saved_registers: &[Register]) -> Vec<Register> {
+        let items = context.instructions.items.split_off(start);
+        let (intervals, fixed_ranges) = Self::build_intervals(&items);
+
+        let vectors = intervals.iter().map(|interval| (interval.register, interval.vector)).collect::<HashMap<_, _>>();
+        let allocations = LinearScanAllocator::new(scope.get_registers()).allocate(intervals, &fixed_ranges);
+
+        // Every spilled register gets its own stack slot
+        let mut registers = allocations.keys().cloned().collect::<Vec<_>>();
+        registers.sort();
+
+        let mut slots = HashMap::new();
+        for register in registers.into_iter().filter(|register| allocations[register] == Allocation::Spill) {
+            let slot_type = match vectors[&register] {
+                true => NumberType::Double,
+                false => NumberType::I64
+            };
+            slots.insert(register, scope.add_temp_variable(slot_type).position);
+        }
+
+        for item in items.into_iter() {
+            match item {
+                AsmStructure::Instruction(instruction) => Self::rewrite(*instruction, &allocations, &slots, context),
+                item => context.instructions.items.push(item)
+            }
+        }
+
+        // Calls are not counted, the called function preserves the same registers
+        let mut written = Vec::new();
+        for item in context.instructions.items[start..].iter() {
+            if let AsmStructure::Instruction(instruction) = item {
+                let mut instruction = instruction.as_ref().clone();
+                if let Some((target, access)) = instruction.operands_mut().target {
+                    if let (true, Some(register)) = (access != X86OperandAccess::Read, Self::physical_register(target)) {
+                        written.push(Self::full_register(register));
+                    }
+                }
+
+                if !matches!(instruction, X86Instruction::Call(_)) {
+                    written.extend(instruction.implicit_writes().iter().map(|register| Self::full_register(*register)));
+                }
+            }
+        }
+
+        callee_saved_registers.iter().filter(|register| written.contains(register)).cloned().collect()
+    }
+
+    /// General purpose registers are compared with their 64bit names
+    fn full_register(register: Register) -> Register {
+        match register.is_vector() {
+            true => register,
+            false => register.get_sized(RegisterSize::_64Bit)
+        }
+    }
+
+    fn build_intervals(items: &[AsmStructure<X86Instruction>]) -> (Vec<LiveInterval>, FixedRanges<Register>) {
+        let mut intervals: HashMap<usize, LiveInterval> = HashMap::new();
+        let mut fixed_ranges = FixedRanges::default();
+        let mut open_ranges: HashMap<Register, OpenRange> = HashMap::new();
+
+        for (position, item) in items.iter().enumerate() {
+            let mut instruction = match item {
+                AsmStructure::Instruction(instruction) => instruction.as_ref().clone(),
+                _ => continue
+            };
+
+            let implicit_reads = instruction.implicit_reads();
+            let implicit_writes = instruction.implicit_writes();
+            let weak_reads = matches!(instruction, X86Instruction::Call(_) | X86Instruction::Ret);
+            let zeroing = Self::is_zeroing(&instruction);
+
+            let operands = instruction.operands_mut();
+            let mut reads = Vec::new();
+            let mut writes = Vec::new();
+
+            if let Some(source) = operands.source {
+                if !zeroing {
+                    reads.push(source.clone());
+                }
+            }
+
+            if let Some((target, access)) = operands.target {
+                if access != X86OperandAccess::Write && !zeroing {
+                    reads.push(target.clone());
+                }
+
+                if access != X86OperandAccess::Read {
+                    writes.push(target.clone());
+                }
+            }
+
+            for location in reads.iter().chain(writes.iter()) {
+                if let X86Location::Virtual(register) = location {
+                    let interval = intervals.entry(register.index).or_insert(LiveInterval { register: register.index, vector: register.is_vector(), start: position, end: position });
+                    interval.end = position;
+                }
+            }
+
+            // Reads are done before the writes
+            for register in reads.iter().filter_map(Self::physical_register) {
+                Self::read_register(&mut open_ranges, register, position, true, false);
+            }
+
+            for register in implicit_reads.iter() {
+                Self::read_register(&mut open_ranges, *register, position, false, weak_reads);
+            }
+
+            for register in writes.iter().filter_map(Self::physical_register) {
+                Self::write_register(&mut open_ranges, &mut fixed_ranges, register, position, true);
+            }
+
+            for register in implicit_writes.iter() {
+                Self::write_register(&mut open_ranges, &mut fixed_ranges, *register, position, false);
+            }
+        }
+
+        for (register, range) in open_ranges.into_iter() {
+            fixed_ranges.add(register, range.start, range.end);
+        }
+
+        (intervals.into_values().collect(), fixed_ranges)
+    }
+
+    fn read_register(open_ranges: &mut HashMap<Register, OpenRange>, register: Register, position: usize, explicit: bool, weak: bool) {
+        let register = register.get_sized(RegisterSize::_64Bit);
+
+        match open_ranges.get_mut(&register) {
+            // Call and return only might read the register, so only the values that are placed on purpose are kept
+            Some(range) if !weak || range.explicit => range.end = position,
+            Some(_) => (),
+
+            // Register has a value from the caller (function parameter)
+            None if explicit => { open_ranges.insert(register, OpenRange { start: 0, end: position, explicit }); },
+            None => ()
+        };
+    }
+
+    fn write_register(open_ranges: &mut HashMap<Register, OpenRange>, fixed_ranges: &mut FixedRanges<Register>, register: Register, position: usize, explicit: bool) {
+        let register = register.get_sized(RegisterSize::_64Bit);
+
+        if let Some(range) = open_ranges.insert(register, OpenRange { start: position, end: position, explicit }) {
+            fixed_ranges.add(register, range.start, range.end);
+        }
+    }
+
+    fn physical_register(location: &X86Location) -> Option<Register> {
+        match location {
+            X86Location::Register(X86AddressingMode::Direct(register)) if !matches!(register.get_sized(RegisterSize::_64Bit), Register::RSP | Register::RBP) => Some(*register),
+            _ => None
+        }
+    }
+
+    /// xor with the same register clears it, the old value is not used
+    fn is_zeroing(instruction: &X86Instruction) -> bool {
+        match instruction {
+            X86Instruction::Xor { source, target, .. } |
+            X86Instruction::Xorps { source, target, .. } => match (source, target) {
+                (X86Location::Register(X86AddressingMode::Direct(source)), X86Location::Register(X86AddressingMode::Direct(target))) => source == target,
+                (X86Location::Virtual(source), X86Location::Virtual(target)) => source.index == target.index,
+                _ => false
+            },
+            _ => false
+        }
+    }
+
+    /// Spilled source could stay in the memory if the instruction supports it
+    fn accepts_memory_source(instruction: &X86Instruction) -> bool {
+        matches!(instruction,
+            X86Instruction::Add { .. } | X86Instruction::Sub { .. } | X86Instruction::IMul { .. } | X86Instruction::And { .. } | X86Instruction::Or { .. } | X86Instruction::Xor { .. } |
+            X86Instruction::Mov { .. } | X86Instruction::Cmp { .. } | X86Instruction::Movss { .. } | X86Instruction::Movsd { .. } |
+            X86Instruction::Addss { .. } | X86Instruction::Addsd { .. } | X86Instruction::Subss { .. } | X86Instruction::Subsd { .. } |
+            X86Instruction::Mulss { .. } | X86Instruction::Mulsd { .. } | X86Instruction::Divss { .. } | X86Instruction::Divsd { .. } |
+            X86Instruction::Ucomiss { .. } | X86Instruction::Ucomisd { .. } | X86Instruction::Cvtss2sd { .. } | X86Instruction::Cvtsd2ss { .. } |
+            X86Instruction::Cvttss2si { .. } | X86Instruction::Cvttsd2si { .. })
+    }
+
+    fn rewrite(mut instruction: X86Instruction, allocations: &HashMap<usize, Allocation<Register>>, slots: &HashMap<usize, usize>, context: &mut X86ApplicationContext) {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        let accepts_memory_source = Self::accepts_memory_source(&instruction);
+        let accepts_memory_target = matches!(instruction, X86Instruction::Push(_));
+        let operands = instruction.operands_mut();
+
+        let mut target_is_register = false;
+
+        // Spilled target register index and it is already loaded or not
+        let mut target_in_scratch = None;
+
+        if let Some((target, access)) = operands.target {
+            match target.clone() {
+                X86Location::Virtual(register) => match &allocations[&register.index] {
+                    Allocation::Register(physical) => {
+                        *target = X86Location::Register(X86AddressingMode::Direct(physical.get_sized(register.size)));
+                        target_is_register = true;
+                    },
+                    Allocation::Spill if accepts_memory_target => *target = Self::slot_location(slots[&register.index]),
+                    Allocation::Spill => {
+                        let slot = Self::slot_location(slots[&register.index]);
+
+                        let loaded = access != X86OperandAccess::Write;
+                        if loaded {
+                            before.push(Self::slot_movement(register, slot.clone(), Self::scratch_location(register)));
+                        }
+
+                        if access != X86OperandAccess::Read {
+                            after.push(Self::slot_movement(register, Self::scratch_location(register), slot));
+                        }
+
+                        *target = Self::scratch_location(register);
+                        target_is_register = true;
+                        target_in_scratch = Some((register.index, loaded));
+                    }
+                },
+                X86Location::Register(X86AddressingMode::Direct(_)) => target_is_register = true,
+                _ => ()
+            }
+        }
+
+        if let Some(source) = operands.source {
+            if let X86Location::Virtual(register) = source.clone() {
+                match &allocations[&register.index] {
+                    Allocation::Register(physical) => *source = X86Location::Register(X86AddressingMode::Direct(physical.get_sized(register.size))),
+                    Allocation::Spill if accepts_memory_source && target_is_register && target_in_scratch.map(|(index, _)| index) != Some(register.index) => *source = Self::slot_location(slots[&register.index]),
+                    Allocation::Spill => {
+                        // Source is read before the target is written, so it could share the scratch register with a write-only target
+                        if target_in_scratch != Some((register.index, true)) {
+                            before.push(Self::slot_movement(register, Self::slot_location(slots[&register.index]), Self::scratch_location(register)));
+                        }
+
+                        *source = Self::scratch_location(register);
+                    }
+                }
+            }
+        }
+
+        for item in before.into_iter().chain(std::iter::once(instruction)).chain(after) {
+            context.instructions.add_instruction(item);
+        }
+    }
+
+    fn scratch_location(register: VirtualRegister) -> X86Location {
+        let scratch = match register.is_vector() {
+            true => VECTOR_SCRATCH_REGISTER,
+            false => GENERAL_SCRATCH_REGISTER.get_sized(register.size)
+        };
+
+        X86Location::Register(X86AddressingMode::Direct(scratch))
+    }
+
+    fn slot_location(position: usize) -> X86Location {
+        X86Location::Register(X86AddressingMode::Based(-(position as i32), Register::RBP))
+    }
+
+    /// Whole slot is moved, the value is always in the lower part
+    fn slot_movement(register: VirtualRegister, source: X86Location, target: X86Location) -> X86Instruction {
+        let resize = |location: X86Location| match location {
+            X86Location::Register(X86AddressingMode::Direct(register)) => X86Location::Register(X86AddressingMode::Direct(register.get_sized(RegisterSize::_64Bit))),
+            location => location
+        };
+
+        match register.is_vector() {
+            true => X86Instruction::Movsd { source, target, comment: None },
+            false => X86Instruction::Mov { source: resize(source), target: resize(target), comment: None }
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! Functions of the freestanding executables. There is no C library, so the program starts at `_start` and the
+//! print statements are written with the Linux system calls.
+
+use tb_core::types::Number;
+
+use crate::{instruction::X86Instruction, register::Register, X86AddressingMode, X86ApplicationContext, X86Location};
+
+const SYSCALL_WRITE: i32 = 1;
+const SYSCALL_EXIT: i32 = 60;
+const STDOUT: i32 = 1;
+
+fn register(register: Register) -> X86Location {
+    X86Location::Register(X86AddressingMode::Direct(register))
+}
+
+fn memory(offset: i32, register: Register) -> X86Location {
+    X86Location::Register(X86AddressingMode::Based(offset, register))
+}
+
+fn indirect(register: Register) -> X86Location {
+    X86Location::Register(X86AddressingMode::Indirect(register))
+}
+
+fn imm(number: i32) -> X86Location {
+    X86Location::Imm(Number::I32(number))
+}
+
+pub struct X86Runtime;
+
+impl X86Runtime {
+    pub const START: &'static str = "_start";
+
+    /// write(pointer, length)
+    pub const WRITE: &'static str = "__tb_write";
+
+    /// print_signed(value, bits), value is truncated to the bits before it is printed
+    pub const PRINT_SIGNED: &'static str = "__tb_print_signed";
+
+    /// print_unsigned(value, bits, radix, digits)
+    pub const PRINT_UNSIGNED: &'static str = "__tb_print_unsigned";
+
+    /// print_char(value)
+    pub const PRINT_CHAR: &'static str = "__tb_print_char";
+
+    /// print_double(value, precision), the scaled value should fit in 64bit, infinity and NaN are not printed
+    pub const PRINT_DOUBLE: &'static str = "__tb_print_double";
+
+    pub fn add(context: &mut X86ApplicationContext) {
+        Self::start(context);
+        Self::write(context);
+        Self::print_signed(context);
+        Self::print_unsigned(context);
+        Self::print_char(context);
+        Self::print_double(context);
+    }
+
+    fn function(name: &str, instructions: Vec<X86Instruction>, context: &mut X86ApplicationContext) {
+        Self::function_with_branches(vec![(name.to_owned(), instructions)], context);
+    }
+
+    /// First branch is the function itself
+    fn function_with_branches(branches: Vec<(String, Vec<X86Instruction>)>, context: &mut X86ApplicationContext) {
+        for (branch, instructions) in branches.into_iter() {
+            context.instructions.add_branch(branch);
+            for instruction in instructions.into_iter() {
+                context.instructions.add_instruction(instruction);
+            }
+        }
+        context.instructions.add_close_branch();
+    }
+
+    fn string(value: &str, context: &mut X86ApplicationContext) -> X86Location {
+        let label = context.datas.create_label();
+        context.datas.add_string_data(&label, value);
+        X86Location::Label(label)
+    }
+
+    /// Main is called with the aligned stack and its result is the exit code
+    fn start(context: &mut X86ApplicationContext) {
+        let main = context.os_specific_defs.main_function_name().to_owned();
+
+        Self::function(Self::START, vec![
+                X86Instruction::Xor { source: register(Register::EBP), target: register(Register::EBP), comment: None },
+                X86Instruction::Call(main),
+                X86Instruction::Mov { source: register(Register::EAX), target: register(Register::EDI), comment: None },
+                X86Instruction::Mov { source: imm(SYSCALL_EXIT), target: register(Register::EAX), comment: None },
+                X86Instruction::Syscall
+        ], context);
+    }
+
+    fn write(context: &mut X86ApplicationContext) {
+        Self::function(Self::WRITE, vec![
+            X86Instruction::Mov { source: register(Register::RSI), target: register(Register::RDX), comment: None },
+            X86Instruction::Mov { source: register(Register::RDI), target: register(Register::RSI), comment: None },
+            X86Instruction::Mov { source: imm(STDOUT), target: register(Register::EDI), comment: None },
+            X86Instruction::Mov { source: imm(SYSCALL_WRITE), target: register(Register::EAX), comment: None },
+            X86Instruction::Syscall,
+            X86Instruction::Ret
+        ], context);
+    }
+
+    /// Sign is printed first, the absolute value is printed as unsigned
+    fn print_signed(context: &mut X86ApplicationContext) {
+        let positive = context.storage.create_branch();
+        let minus = Self::string("-", context);
+        let digits = Self::string("0123456789", context);
+
+        Self::function_with_branches(vec![
+            (Self::PRINT_SIGNED.to_owned(), vec![
+                X86Instruction::Mov { source: imm(64), target: register(Register::ECX), comment: None },
+                X86Instruction::Sub { source: register(Register::ESI), target: register(Register::ECX), comment: None },
+                X86Instruction::Shl { source: register(Register::CL), target: register(Register::RDI), comment: None },
+                X86Instruction::Sar { source: register(Register::CL), target: register(Register::RDI), comment: None },
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RDI), comment: None },
+                X86Instruction::Jge(positive.clone()),
+                X86Instruction::Neg { source: register(Register::RDI), comment: None },
+                X86Instruction::Push(register(Register::RDI)),
+                X86Instruction::Lea { source: minus, target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: imm(1), target: register(Register::ESI), comment: None },
+                X86Instruction::Call(Self::WRITE.to_owned()),
+                X86Instruction::Pop(register(Register::RDI))
+            ]),
+            (positive, vec![
+                X86Instruction::Mov { source: imm(64), target: register(Register::ESI), comment: None },
+                X86Instruction::Mov { source: imm(10), target: register(Register::EDX), comment: None },
+                X86Instruction::Lea { source: digits, target: register(Register::RCX), comment: None },
+                X86Instruction::Jmp(Self::PRINT_UNSIGNED.to_owned())
+            ])
+        ], context);
+    }
+
+    /// Digits are written backward to the stack buffer
+    fn print_unsigned(context: &mut X86ApplicationContext) {
+        let next_digit = context.storage.create_branch();
+
+        Self::function_with_branches(vec![
+            (Self::PRINT_UNSIGNED.to_owned(), vec![
+                X86Instruction::Push(register(Register::RBP)),
+                X86Instruction::Mov { source: register(Register::RSP), target: register(Register::RBP), comment: None },
+                X86Instruction::Sub { source: imm(80), target: register(Register::RSP), comment: None },
+                X86Instruction::Mov { source: register(Register::RCX), target: register(Register::R8), comment: Some("digits".to_owned()) },
+                X86Instruction::Mov { source: register(Register::RDX), target: register(Register::R9), comment: Some("radix".to_owned()) },
+                X86Instruction::Mov { source: imm(64), target: register(Register::ECX), comment: None },
+                X86Instruction::Sub { source: register(Register::ESI), target: register(Register::ECX), comment: None },
+                X86Instruction::Shl { source: register(Register::CL), target: register(Register::RDI), comment: None },
+                X86Instruction::Shr { source: register(Register::CL), target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: register(Register::RDI), target: register(Register::RAX), comment: None },
+                X86Instruction::Mov { source: register(Register::RBP), target: register(Register::R10), comment: Some("position".to_owned()) }
+            ]),
+            (next_digit.clone(), vec![
+                X86Instruction::Xor { source: register(Register::EDX), target: register(Register::EDX), comment: None },
+                X86Instruction::Div { target: register(Register::R9), comment: None },
+                X86Instruction::Dec { source: register(Register::R10), comment: None },
+                X86Instruction::Mov { source: register(Register::RDX), target: register(Register::R11), comment: None },
+                X86Instruction::Add { source: register(Register::R8), target: register(Register::R11), comment: None },
+                X86Instruction::Mov { source: indirect(Register::R11), target: register(Register::CL), comment: None },
+                X86Instruction::Mov { source: register(Register::CL), target: indirect(Register::R10), comment: None },
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RAX), comment: None },
+                X86Instruction::Jne(next_digit),
+                X86Instruction::Mov { source: register(Register::R10), target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: register(Register::RBP), target: register(Register::RSI), comment: None },
+                X86Instruction::Sub { source: register(Register::R10), target: register(Register::RSI), comment: None },
+                X86Instruction::Call(Self::WRITE.to_owned()),
+                X86Instruction::Mov { source: register(Register::RBP), target: register(Register::RSP), comment: None },
+                X86Instruction::Pop(register(Register::RBP)),
+                X86Instruction::Ret
+            ])
+        ], context);
+    }
+
+    fn print_char(context: &mut X86ApplicationContext) {
+        Self::function(Self::PRINT_CHAR, vec![
+            X86Instruction::Push(register(Register::RBP)),
+            X86Instruction::Mov { source: register(Register::RSP), target: register(Register::RBP), comment: None },
+            X86Instruction::Sub { source: imm(16), target: register(Register::RSP), comment: None },
+            X86Instruction::Mov { source: register(Register::DIL), target: memory(-1, Register::RBP), comment: None },
+            X86Instruction::Lea { source: memory(-1, Register::RBP), target: register(Register::RDI), comment: None },
+            X86Instruction::Mov { source: imm(1), target: register(Register::ESI), comment: None },
+            X86Instruction::Call(Self::WRITE.to_owned()),
+            X86Instruction::Mov { source: register(Register::RBP), target: register(Register::RSP), comment: None },
+            X86Instruction::Pop(register(Register::RBP)),
+            X86Instruction::Ret
+        ], context);
+    }
+
+    /// Value is rounded to an integer with the precision digits, the integer part and the fraction digits are printed separately
+    fn print_double(context: &mut X86ApplicationContext) {
+        let positive = context.storage.create_branch();
+        let next_power = context.storage.create_branch();
+        let scaled = context.storage.create_branch();
+        let next_digit = context.storage.create_branch();
+        let done = context.storage.create_branch();
+
+        let minus = Self::string("-", context);
+        let dot = Self::string(".", context);
+        let digits = Self::string("0123456789", context);
+
+        let half = context.datas.create_label();
+        context.datas.add_double_data(&half, 0.5);
+
+        let value = memory(-24, Register::RBP);
+        let fraction = memory(-32, Register::RBP);
+
+        Self::function_with_branches(vec![
+            (Self::PRINT_DOUBLE.to_owned(), vec![
+                X86Instruction::Push(register(Register::RBP)),
+                X86Instruction::Mov { source: register(Register::RSP), target: register(Register::RBP), comment: None },
+                X86Instruction::Push(register(Register::RBX)),
+                X86Instruction::Push(register(Register::R12)),
+                X86Instruction::Sub { source: imm(16), target: register(Register::RSP), comment: None },
+                X86Instruction::Mov { source: register(Register::RDI), target: register(Register::RBX), comment: Some("precision".to_owned()) },
+                X86Instruction::Movsd { source: register(Register::XMM0), target: value.clone(), comment: None },
+                X86Instruction::Mov { source: value.clone(), target: register(Register::RAX), comment: None },
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RAX), comment: None },
+                X86Instruction::Jge(positive.clone()),
+
+                // Sign bit is cleared
+                X86Instruction::Shl { source: imm(1), target: register(Register::RAX), comment: None },
+                X86Instruction::Shr { source: imm(1), target: register(Register::RAX), comment: None },
+                X86Instruction::Mov { source: register(Register::RAX), target: value.clone(), comment: None },
+                X86Instruction::Lea { source: minus, target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: imm(1), target: register(Register::ESI), comment: None },
+                X86Instruction::Call(Self::WRITE.to_owned())
+            ]),
+            (positive, vec![
+                X86Instruction::Mov { source: imm(1), target: register(Register::R12), comment: Some("scale".to_owned()) },
+                X86Instruction::Mov { source: register(Register::RBX), target: register(Register::RCX), comment: None }
+            ]),
+            (next_power.clone(), vec![
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RCX), comment: None },
+                X86Instruction::Je(scaled.clone()),
+                X86Instruction::IMul { source: imm(10), target: register(Register::R12), comment: None },
+                X86Instruction::Dec { source: register(Register::RCX), comment: None },
+                X86Instruction::Jmp(next_power)
+            ]),
+            (scaled, vec![
+                X86Instruction::Movsd { source: value, target: register(Register::XMM0), comment: None },
+                X86Instruction::Cvtsi2sd { source: register(Register::R12), target: register(Register::XMM1), comment: None },
+                X86Instruction::Mulsd { source: register(Register::XMM1), target: register(Register::XMM0), comment: None },
+                X86Instruction::Addsd { source: X86Location::Label(half), target: register(Register::XMM0), comment: None },
+                X86Instruction::Cvttsd2si { source: register(Register::XMM0), target: register(Register::RAX), comment: None },
+                X86Instruction::Xor { source: register(Register::EDX), target: register(Register::EDX), comment: None },
+                X86Instruction::Div { target: register(Register::R12), comment: None },
+                X86Instruction::Mov { source: register(Register::RDX), target: fraction.clone(), comment: None },
+                X86Instruction::Mov { source: register(Register::RAX), target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: imm(64), target: register(Register::ESI), comment: None },
+                X86Instruction::Mov { source: imm(10), target: register(Register::EDX), comment: None },
+                X86Instruction::Lea { source: digits, target: register(Register::RCX), comment: None },
+                X86Instruction::Call(Self::PRINT_UNSIGNED.to_owned()),
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RBX), comment: None },
+                X86Instruction::Je(done.clone()),
+                X86Instruction::Lea { source: dot, target: register(Register::RDI), comment: None },
+                X86Instruction::Mov { source: imm(1), target: register(Register::ESI), comment: None },
+                X86Instruction::Call(Self::WRITE.to_owned())
+            ]),
+
+            // Leading zeros of the fraction are printed too
+            (next_digit.clone(), vec![
+                X86Instruction::Mov { source: register(Register::R12), target: register(Register::RAX), comment: None },
+                X86Instruction::Xor { source: register(Register::EDX), target: register(Register::EDX), comment: None },
+                X86Instruction::Mov { source: imm(10), target: register(Register::ECX), comment: None },
+                X86Instruction::Div { target: register(Register::RCX), comment: None },
+                X86Instruction::Mov { source: register(Register::RAX), target: register(Register::R12), comment: None },
+                X86Instruction::Mov { source: fraction.clone(), target: register(Register::RAX), comment: None },
+                X86Instruction::Xor { source: register(Register::EDX), target: register(Register::EDX), comment: None },
+                X86Instruction::Div { target: register(Register::R12), comment: None },
+                X86Instruction::Mov { source: register(Register::RDX), target: fraction, comment: None },
+                X86Instruction::Add { source: imm('0' as i32), target: register(Register::RAX), comment: None },
+                X86Instruction::Mov { source: register(Register::RAX), target: register(Register::RDI), comment: None },
+                X86Instruction::Call(Self::PRINT_CHAR.to_owned()),
+                X86Instruction::Dec { source: register(Register::RBX), comment: None },
+                X86Instruction::Cmp { left: imm(0), right: register(Register::RBX), comment: None },
+                X86Instruction::Jne(next_digit)
+            ]),
+            (done, vec![
+                X86Instruction::Add { source: imm(16), target: register(Register::RSP), comment: None },
+                X86Instruction::Pop(register(Register::R12)),
+                X86Instruction::Pop(register(Register::RBX)),
+                X86Instruction::Pop(register(Register::RBP)),
+                X86Instruction::Ret
+            ])
+        ], context);
+    }
+}
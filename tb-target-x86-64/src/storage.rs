@@ -1,8 +1,26 @@
-use tb_core::instruction::StorageTrait;
+use std::collections::HashMap;
+
+use tb_core::{instruction::StorageTrait, types::NumberType};
 
 #[derive(Debug, Default)]
 pub struct X86Storage {
-    pub branch_counter: usize
+    pub branch_counter: usize,
+    pub loops: Vec<X86LoopBranches>,
+
+    // Return statements jump to the end of the current function
+    pub function_end: Option<String>,
+
+    // Print is written with the system calls instead of printf
+    pub freestanding: bool,
+
+    // Parameter types of the application functions, the arguments are converted to them
+    pub parameters: HashMap<String, Vec<NumberType>>
+}
+
+#[derive(Debug, Clone)]
+pub struct X86LoopBranches {
+    pub begin: String,
+    pub end: String
 }
 
 impl X86Storage {
@@ -11,6 +29,20 @@ impl X86Storage {
         self.branch_counter += 1;
         name
     }
+
+    pub fn begin_loop(&mut self) -> X86LoopBranches {
+        let branches = X86LoopBranches { begin: self.create_branch(), end: self.create_branch() };
+        self.loops.push(branches.clone());
+        branches
+    }
+
+    pub fn end_loop(&mut self) {
+        self.loops.pop();
+    }
+
+    pub fn current_loop(&self) -> Option<&X86LoopBranches> {
+        self.loops.last()
+    }
 }
 
 impl StorageTrait for X86Storage {
@@ -61,7 +61,7 @@ fn main() {
     //application_type.add_string_data("string2", "This is a string2.");
     //application_type.add_byte_data("string2", 2);
 
-    let buffer = application_type.build::<X86AssemblyGenerator>();
+    let buffer = application_type.build::<X86AssemblyGenerator>().unwrap();
     // println!("{}", &buffer);
 
     let mut file = File::create("out.s").unwrap();
@@ -0,0 +1,188 @@
+
+.text
+.section	.rodata
+.p2align 3
+.LC1:
+    .xword 0x4004000000000000 // 2.5
+
+.text
+.section	.rodata
+.p2align 3
+.LC2:
+    .string "x: %d\012"
+
+.text
+.section	.rodata
+.p2align 3
+.LC3:
+    .string "tab\011\"quote\" \\ \001"
+
+.text
+.section	.rodata
+.p2align 3
+.LC4:
+    .xword 0x3ff8000000000000 // 1.5
+
+.text
+.section	.rodata
+.p2align 3
+.LC5:
+    .xword 0xfe37e43c8800759c // -1000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000
+
+.text
+.section	.rodata
+.p2align 3
+.LC6:
+    .string "text"
+
+.text
+.globl calculate
+calculate:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    str d0, [x29, #-16]
+    strb w1, [x29, #-17]
+    // function body begin
+    ldr x9, [x29, #-8]
+    add x9, x9, #1
+    scvtf d16, x9
+    ldr d17, [x29, #-16]
+    adrp x16, .LC1
+    add x16, x16, :lo12:.LC1
+    ldr d18, [x16]
+    fsub d17, d17, d18
+    fmul d16, d16, d17
+    fcvtzs x9, d16
+    str w9, [x29, #-24] // declare x
+    ldr w9, [x29, #-24]
+    cmp w9, #3
+    b.ge L1
+    ldrb w9, [x29, #-17]
+    cmp w9, #200
+    b.eq L1
+    mov w9, #1
+    b L2
+L1:
+    mov w9, #0
+L2:
+    strb w9, [x29, #-26] // declare flag
+    ldrb w9, [x29, #-17]
+    lsl w9, w9, #2
+    ldrb w10, [x29, #-17]
+    asr w10, w10, #1
+    orr w9, w9, w10
+    ldrb w10, [x29, #-17]
+    mvn w10, w10
+    mov w11, #15
+    and w10, w10, w11
+    eor w9, w9, w10
+    uxtb w9, w9
+    strb w9, [x29, #-25] // assign y
+    ldr w9, [x29, #-24]
+    mov w10, #-3
+    sdiv w9, w9, w10
+    mov w10, #7
+    neg w10, w10
+    ldr w11, [x29, #-24]
+    neg w11, w11
+    add w10, w10, w11
+    sdiv w17, w9, w10
+    mul w17, w17, w10
+    sub w9, w9, w17
+    str w9, [x29, #-24] // assign x
+    ldr w9, [x29, #-24]
+    add w9, w9, #1
+    sxtw x9, w9
+    ldr x10, [x29, #-8]
+    sub x10, x10, #1
+    add x9, x9, x10
+    str w9, [x29, #-24] // assign x
+    ldr w9, [x29, #-24]
+    ldr x16, [x29, #-8]
+    scvtf d17, x16
+    ldr d18, [x29, #-16]
+    fcmp d17, d18
+    cset w10, ls
+    cmp w9, w10
+    cset w9, ge
+    strb w9, [x29, #-26] // assign flag
+    ldr w9, [x29, #-24]
+    cmp w9, #0
+    b.gt L4
+    ldr x16, [x29, #-8]
+    scvtf d16, x16
+    ldr d17, [x29, #-16]
+    fcmp d16, d17
+    b.eq L3
+    ldrb w9, [x29, #-17]
+    cmp w9, #0
+    b.ne L3
+L4:
+    adrp x0, .LC2
+    add x0, x0, :lo12:.LC2
+    ldr w1, [x29, #-24]
+    sxtw x1, w1
+    bl printf
+    b L5
+L3:
+    ldr w9, [x29, #-24]
+    mov w10, #-1
+    cmp w9, w10
+    b.ne L6
+    adrp x0, .LC3
+    add x0, x0, :lo12:.LC3
+    ldr w1, [x29, #-24]
+    sxtw x1, w1
+    adrp x16, .LC4
+    add x16, x16, :lo12:.LC4
+    ldr d0, [x16]
+    mov x2, #-2
+    mov x3, #1
+    bl printf
+    b L7
+L6:
+    ldr x0, [x29, #-8]
+    adrp x16, .LC5
+    add x16, x16, :lo12:.LC5
+    ldr d0, [x16]
+    adrp x1, .LC6
+    add x1, x1, :lo12:.LC6
+    bl pick
+    strb w0, [x29, #-25]
+L7:
+L5:
+L8:
+    ldr w9, [x29, #-24]
+    cmp w9, #10
+    b.gt L9
+    ldr w9, [x29, #-24]
+    add w9, w9, #1
+    str w9, [x29, #-24] // assign x
+L10:
+    b L11
+    b L10
+L11:
+    b L8
+    b L8
+L9:
+    ldr w0, [x29, #-24]
+    sxtw x0, w0
+    b L0
+L0:
+    // function body end
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.globl empty
+empty:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    // function body begin
+    b L12
+L12:
+    // function body end
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
@@ -0,0 +1,104 @@
+
+.section    __TEXT,__cstring,cstring_literals
+.p2align 3
+.LC1:
+    .string "%lld %d\012"
+
+.text
+.globl steps
+steps:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    str x0, [x29, #-8]
+    // function body begin
+    ldr x9, [x29, #-8]
+    str x9, [x29, #-16] // declare value
+    mov w9, #0
+    str w9, [x29, #-20] // declare count
+L1:
+    ldr x9, [x29, #-16]
+    cmp x9, #1
+    b.eq L2
+    ldr x9, [x29, #-16]
+    mov x10, #2
+    sdiv x17, x9, x10
+    mul x17, x17, x10
+    sub x9, x9, x17
+    cmp x9, #0
+    b.ne L3
+    ldr x9, [x29, #-16]
+    mov x10, #2
+    sdiv x9, x9, x10
+    str x9, [x29, #-16] // assign value
+    b L4
+L3:
+    ldr x9, [x29, #-16]
+    mov x10, #3
+    mul x9, x9, x10
+    add x9, x9, #1
+    str x9, [x29, #-16] // assign value
+L4:
+    ldr w9, [x29, #-20]
+    add w9, w9, #1
+    str w9, [x29, #-20] // assign count
+    b L1
+L2:
+    ldr w0, [x29, #-20]
+    sxtw x0, w0
+    b L0
+L0:
+    // function body end
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
+.globl _main
+_main:
+    stp x29, x30, [sp, #-16]!
+    mov x29, sp
+    sub sp, sp, #32
+    // function body begin
+    mov w9, #0
+    str w9, [x29, #-4] // declare best
+    mov x9, #0
+    str x9, [x29, #-16] // declare best_start
+    mov x9, #1
+    str x9, [x29, #-24] // declare start
+L6:
+    ldr x9, [x29, #-24]
+    cmp x9, #30
+    b.ge L7
+    ldr x0, [x29, #-24]
+    bl steps
+    str w0, [x29, #-28]
+    ldr w9, [x29, #-28]
+    ldr w10, [x29, #-4]
+    cmp w9, w10
+    b.le L8
+    ldr w9, [x29, #-28]
+    str w9, [x29, #-4] // assign best
+    ldr x9, [x29, #-24]
+    str x9, [x29, #-16] // assign best_start
+L8:
+    ldr x9, [x29, #-24]
+    add x9, x9, #1
+    str x9, [x29, #-24] // assign start
+    b L6
+L7:
+    sub sp, sp, #16 // stack arguments
+    ldr x9, [x29, #-16]
+    str x9, [sp]
+    ldr w9, [x29, #-4]
+    sxtw x9, w9
+    str x9, [sp, #8]
+    adrp x0, .LC1@PAGE
+    add x0, x0, .LC1@PAGEOFF
+    bl _printf
+    add sp, sp, #16
+    mov x0, #0
+    b L5
+L5:
+    // function body end
+    mov sp, x29
+    ldp x29, x30, [sp], #16
+    ret
@@ -0,0 +1,173 @@
+use core::str;
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::{ApplicationType, BlockType, FunctionType};
+use tb_core::ir;
+use tb_core::target::Target;
+use tb_core::types::{Definition, NumberType, RegisterSize, RegisterTrait, Value};
+use tb_target_aarch64::generator::AArch64AssemblyGenerator;
+use tb_target_aarch64::register::Register;
+
+use super::differential::compare_backend;
+use super::ir_test::ALL_STATEMENTS;
+use super::{application, collatz, program_output};
+
+fn source(definitions: Vec<Definition>, target: Target) -> String {
+    let mut application = application(definitions);
+    application.set_target(target);
+    super::source::<AArch64AssemblyGenerator>(application)
+}
+
+fn scale_application() -> Vec<Definition> {
+    let mut scale_func = FunctionType::main();
+    scale_func.set_name("scale");
+    for name in ["a", "b", "c", "d", "e", "f", "g", "h", "i"] {
+        scale_func.add_parameter(name, NumberType::I64);
+    }
+    scale_func.add_parameter("x", NumberType::Float);
+    scale_func.add_parameter("y", NumberType::Double);
+
+    let mut scale_func_block = BlockType::default();
+    scale_func_block.add_return_variable("i");
+    scale_func.set_body(scale_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+    main_func_block.add_call_and_assign("scale".to_owned(), vec![1.into(), 2.into(), 3.into(), 4.into(), 5.into(), 6.into(), 7.into(), 8.into(), 9.into(), 1.5f32.into(), 2.5.into()], "total".to_owned());
+    main_func_block.add_print("%lld %s %f\n".to_owned(), vec![Value::Variable("total".to_owned()), "text".into(), 0.5.into()]);
+    main_func.set_body(main_func_block);
+
+    let mut application = ApplicationType::default();
+    application.add_function(main_func);
+    application.add_function(scale_func);
+    ir::parse(&application.to_ir()).unwrap()
+}
+
+#[test]
+fn aarch64_registers() {
+    assert_eq!(Register::W9, Register::X9.get_sized(RegisterSize::_32Bit));
+    assert_eq!(Register::W9, Register::X9.get_sized(RegisterSize::_8Bit));
+    assert_eq!(Register::X30, Register::W30.get_sized(RegisterSize::_64Bit));
+    assert_eq!(Register::S16, Register::D16.get_sized(RegisterSize::_32Bit));
+    assert_eq!(Register::WZR, Register::XZR.get_sized(RegisterSize::_32Bit));
+    assert_eq!(RegisterSize::_32Bit, Register::W0.get_register_size());
+    assert_eq!(RegisterSize::_64Bit, Register::D31.get_register_size());
+    assert!(Register::S0.is_vector());
+    assert!(!Register::X0.is_vector());
+}
+
+#[test]
+fn aarch64_collatz() {
+    assert_eq!(include_str!("aarch64/collatz.s"), source(collatz(), Target::AARCH64_LINUX));
+}
+
+#[test]
+fn aarch64_all_statements() {
+    assert_eq!(include_str!("aarch64/all_statements.s"), source(ir::parse(ALL_STATEMENTS).unwrap(), Target::AARCH64_LINUX));
+}
+
+#[test]
+fn aarch64_macos() {
+    assert_eq!(include_str!("aarch64/collatz_macos.s"), source(collatz(), Target::AARCH64_MACOS));
+}
+
+#[test]
+fn aarch64_arguments() {
+    let source = source(scale_application(), Target::AARCH64_LINUX);
+
+    // Integer and floating point arguments are counted separately, ninth integer is after the frame record
+    assert!(source.contains("    str x7, [x29, #-64]\n    ldr x16, [x29, #16] // parameter i\n    str x16, [x29, #-72]\n    str s0, [x29, #-76]\n    str d1, [x29, #-88]\n"), "{}", source);
+    assert!(source.contains("    sub sp, sp, #16 // stack arguments\n    mov x9, #9\n    str x9, [sp]\n    mov x0, #1\n"), "{}", source);
+    assert!(source.contains("    ldr s0, [x16]\n    adrp x16, .LC2\n    add x16, x16, :lo12:.LC2\n    ldr d1, [x16]\n    bl scale\n    add sp, sp, #16\n    str x0, [x29, #-8]\n"), "{}", source);
+
+    // Variadic arguments use the registers
+    assert!(source.contains("    ldr x1, [x29, #-8]\n    adrp x2, .LC4\n    add x2, x2, :lo12:.LC4\n    adrp x16, .LC5\n    add x16, x16, :lo12:.LC5\n    ldr d0, [x16]\n    bl printf\n"), "{}", source);
+}
+
+#[test]
+fn aarch64_macos_variadic_arguments() {
+    let source = source(scale_application(), Target::AARCH64_MACOS);
+
+    // Apple passes the variadic arguments with stack
+    assert!(source.contains("    sub sp, sp, #32 // stack arguments\n    ldr x9, [x29, #-8]\n    str x9, [sp]\n    adrp x9, .LC3@PAGE\n    add x9, x9, .LC3@PAGEOFF\n    str x9, [sp, #8]\n"), "{}", source);
+    assert!(source.contains("    str d16, [sp, #16]\n    adrp x0, .LC5@PAGE\n    add x0, x0, .LC5@PAGEOFF\n    bl _printf\n    add sp, sp, #32\n"), "{}", source);
+}
+
+#[test]
+fn aarch64_conditions() {
+    let definitions = ir::parse(r#"fn main() {
+    let a: i32 = -1;
+    let b: u32 = 1u32;
+    let c: f64 = 0.5;
+    if a > 0 {
+        print "signed\n";
+    }
+    if b <= 7u32 {
+        print "unsigned\n";
+    }
+    if c < 1.0 {
+        print "float\n";
+    }
+    let d: bool = c >= 0.0;
+    return d;
+}
+"#).unwrap();
+
+    let source = source(definitions, Target::AARCH64_LINUX);
+
+    // Jumps are taken when the condition is false, inverted float conditions are true for NaN
+    assert!(source.contains("    cmp w9, #0\n    b.le L1\n"), "{}", source);
+    assert!(source.contains("    cmp w9, #7\n    b.hi L2\n"), "{}", source);
+    assert!(source.contains("    fcmp d16, d17\n    b.pl L3\n"), "{}", source);
+    assert!(source.contains("    fcmp d16, d17\n    cset w9, ge\n"), "{}", source);
+}
+
+/// Tools of the cross compilation are not installed everywhere, tests using them are ignored by default
+fn run_with_qemu(application: ApplicationType, file_name: &str) -> (String, i32) {
+    let mut application = application;
+    let mut source_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+
+    source_file_name.push(format!("{}.s", file_name));
+    executable_name.push(format!("{}.exe", file_name));
+    application.set_target(Target::AARCH64_LINUX);
+    fs::write(&source_file_name, application.build::<AArch64AssemblyGenerator>().unwrap()).unwrap();
+
+    // Static executable does not need the libraries of the target
+    let output = Command::new("aarch64-linux-gnu-gcc").arg("-static").arg(&source_file_name).arg("-o").arg(&executable_name).output().expect("aarch64-linux-gnu-gcc is not found");
+    assert!(output.status.success(), "{}", str::from_utf8(&output.stderr).unwrap());
+
+    program_output(Command::new("qemu-aarch64").arg(&executable_name).output().expect("qemu-aarch64 is not found"))
+}
+
+#[test]
+#[ignore = "needs aarch64-linux-gnu-gcc and qemu-aarch64"]
+fn aarch64_qemu_collatz() {
+    assert_eq!(("27 111\n".to_owned(), 0), run_with_qemu(application(collatz()), "aarch64_collatz"));
+}
+
+#[test]
+#[ignore = "needs aarch64-linux-gnu-gcc and qemu-aarch64"]
+fn aarch64_qemu_differential() {
+    compare_backend("aarch64_differential", run_with_qemu);
+}
+
+#[test]
+fn aarch64_argument_conversion() {
+    let definitions = ir::parse(r#"fn show(a: f64, b: f32, c: i64) {
+    print "%f %f %lld\n", a, b, c;
+}
+
+fn main() {
+    let x: f32 = 0.5f32;
+    let y: i32 = -7;
+    call show(x, y, y);
+    return 0;
+}"#).unwrap();
+    let source = source(definitions, Target::AARCH64_LINUX);
+
+    // Arguments are converted to the parameter types before the call
+    assert!(source.contains("    ldr s0, [x29, #-4]\n    fcvt d0, s0\n    ldr w16, [x29, #-8]\n    scvtf s1, w16\n    ldr w0, [x29, #-8]\n    sxtw x0, w0\n    bl show\n"), "{}", source);
+}
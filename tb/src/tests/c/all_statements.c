@@ -0,0 +1,42 @@
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+
+int64_t pick();
+
+int64_t calculate(int64_t a, double b, uint8_t c);
+void empty(void);
+
+int64_t calculate(int64_t a, double b, uint8_t c) {
+    int32_t x;
+    uint8_t y;
+    bool flag;
+    x = (int32_t)((double)(int64_t)((uint64_t)a + (uint64_t)(int64_t)1) * (b - 2.5));
+    flag = (bool)((x < 3) && !((int32_t)c == (int32_t)200));
+    y = (uint8_t)(((int32_t)((uint32_t)(int32_t)c << ((uint32_t)2 & 31)) | ((int32_t)c >> ((uint32_t)1 & 31))) ^ ((~(int32_t)c) & (int32_t)15));
+    x = ((x / -3) % (int32_t)((uint32_t)((int32_t)-(uint32_t)(int32_t)7) + (uint32_t)((int32_t)-(uint32_t)x)));
+    x = (int32_t)(int64_t)((uint64_t)(int64_t)(int32_t)((uint32_t)x + 1u) + (uint64_t)(int64_t)((uint64_t)a - 1u));
+    flag = (bool)(x >= (int32_t)(bool)((double)a <= b));
+    if ((x > 0) || (((double)a != b) && !!((int32_t)c == (int32_t)0))) {
+        printf("x: %d\n", x);
+    } else {
+        if (x == -1) {
+            printf("tab\t\"quote\" \\ \001", x, 1.5f, -2, true);
+        } else {
+            y = (uint8_t)pick(a, -1e300, "text");
+        }
+    }
+    while (!(x > 10)) {
+        x = (int32_t)((uint32_t)x + (uint32_t)1);
+        for (;;) {
+            break;
+        }
+        continue;
+    }
+    return x;
+}
+
+void empty(void) {
+    return;
+}
+
@@ -0,0 +1,42 @@
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+
+int64_t steps(int64_t start);
+
+int64_t steps(int64_t start) {
+    int64_t value;
+    int32_t count;
+    value = start;
+    count = 0;
+    while (value != INT64_C(1)) {
+        if ((value % INT64_C(2)) == INT64_C(0)) {
+            value = (value / INT64_C(2));
+        } else {
+            value = (int64_t)((uint64_t)(int64_t)((uint64_t)value * (uint64_t)INT64_C(3)) + (uint64_t)INT64_C(1));
+        }
+        count = (int32_t)((uint32_t)count + (uint32_t)1);
+    }
+    return count;
+}
+
+int main(void) {
+    int32_t best;
+    int64_t best_start;
+    int64_t start;
+    int32_t count;
+    best = 0;
+    best_start = INT64_C(0);
+    start = INT64_C(1);
+    while (start < INT64_C(30)) {
+        count = (int32_t)steps(start);
+        if (count > best) {
+            best = count;
+            best_start = start;
+        }
+        start = (int64_t)((uint64_t)start + 1u);
+    }
+    printf("%lld %d\n", best_start, best);
+    return 0;
+}
+
@@ -0,0 +1,80 @@
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::ApplicationType;
+use tb_core::compiler::{CompilerTrait, TBCompiler};
+use tb_core::ir;
+use tb_target_c::generator::CSourceGenerator;
+
+use super::differential::compare_backend;
+use super::ir_test::ALL_STATEMENTS;
+use super::{application, collatz, program_output, source};
+
+/// C source is compiled with the same compiler that builds the assembly
+fn run_c(application: ApplicationType, file_name: &str) -> (String, i32) {
+    let mut source_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+
+    source_file_name.push(format!("{}.c", file_name));
+    executable_name.push(format!("{}.exe", file_name));
+    fs::write(&source_file_name, application.build::<CSourceGenerator>().unwrap()).unwrap();
+
+    let compiler = TBCompiler::get_compiler().unwrap();
+    compiler.compile(&source_file_name, &executable_name, Default::default()).unwrap();
+
+    program_output(Command::new(&executable_name).output().unwrap())
+}
+
+#[test]
+fn c_collatz() {
+    assert_eq!(include_str!("c/collatz.c"), source::<CSourceGenerator>(application(collatz())));
+}
+
+#[test]
+fn c_all_statements() {
+    assert_eq!(include_str!("c/all_statements.c"), source::<CSourceGenerator>(application(ir::parse(ALL_STATEMENTS).unwrap())));
+}
+
+#[test]
+fn c_collatz_output() {
+    assert_eq!(("27 111\n".to_owned(), 0), run_c(application(collatz()), "c_collatz"));
+}
+
+#[test]
+fn c_variables() {
+    let definitions = ir::parse(r#"fn show(int: u8) {
+    print "%d\n", int;
+}
+
+fn main() {
+    let a: i32 = 1;
+    if a > 0 {
+        let a: u8 = 255u8;
+        let b: i16 = -2i16;
+        a = inc(a);
+        call show(a);
+    }
+    print "%d %d %s\n", a, b, message;
+    return b;
+}
+"#).unwrap();
+
+    let mut application = application(definitions);
+    application.add_string_data("message", "done");
+
+    // Variables outlive their blocks, shadowing ones and the keywords get another name
+    let source = source::<CSourceGenerator>(application.clone());
+    assert!(source.contains("static const char message[] = \"done\";\n"), "{}", source);
+    assert!(source.contains("void show(uint8_t int_1) {\n    printf(\"%d\\n\", int_1);\n"), "{}", source);
+    assert!(source.contains("    int32_t a;\n    uint8_t a_1;\n    int16_t b;\n"), "{}", source);
+    assert!(source.contains("        a_1 = (uint8_t)(int32_t)((uint32_t)(int32_t)a_1 + 1u);\n"), "{}", source);
+
+    assert_eq!(("0\n0 -2 done\n".to_owned(), 254), run_c(application, "c_variables"));
+}
+
+/// The same program should print the same output with the C compiler and with the assembly backend
+#[test]
+fn c_differential() {
+    compare_backend("c_differential", run_c);
+}
@@ -0,0 +1,227 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ConditionType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_builder::IfBlockType;
+use tb_builder::WhileBlockType;
+use tb_core::types::NumberType;
+use tb_core::types::Value;
+
+use super::check_output;
+use super::get_exit_code;
+
+fn variable(name: &str) -> Value {
+    Value::Variable(name.to_owned())
+}
+
+#[test]
+fn condition_value() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I32, Some(ExpressionType::value(Value::Number((-5).into()))));
+    main_func_block.add_declare("b", NumberType::I32, Some(ExpressionType::value(Value::Number(3.into()))));
+    main_func_block.add_declare("less", NumberType::I32, Some(ExpressionType::condition(ConditionType::ls(variable("a"), variable("b")))));
+    main_func_block.add_declare("greater", NumberType::I32, Some(ExpressionType::condition(ConditionType::gr(variable("a"), variable("b")))));
+    main_func_block.add_declare("equal", NumberType::I64, Some(ExpressionType::condition(ConditionType::eq(variable("a"), Value::Number((-5).into())))));
+    main_func_block.add_print("%d %d %lld".to_owned(), vec![variable("less"), variable("greater"), variable("equal")]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "condition_value", "1 0 1");
+}
+
+#[test]
+fn condition_value_arithmetic() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::U8, Some(ExpressionType::value(Value::Number(200_u8.into()))));
+    main_func_block.add_declare("b", NumberType::U8, Some(ExpressionType::value(Value::Number(100_u8.into()))));
+
+    // (a > b) + (a != b) * 2 + (a <= b) * 4
+    let total = ExpressionType::add(
+        ExpressionType::mul(Value::Number(4.into()), ConditionType::le(variable("a"), variable("b"))),
+        ExpressionType::add(
+            ExpressionType::mul(Value::Number(2.into()), ConditionType::ne(variable("a"), variable("b"))),
+            ConditionType::gr(variable("a"), variable("b"))));
+
+    main_func_block.add_declare("total", NumberType::I32, Some(total));
+    main_func_block.add_return_variable("total");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "condition_value_arithmetic", 3);
+}
+
+#[test]
+fn short_circuit_and() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+    main_func_block.add_declare("b", NumberType::I32, Some(ExpressionType::value(Value::Number(12.into()))));
+    main_func_block.add_declare("result", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+
+    // Division by zero is never executed
+    let mut if_condition_true_block = BlockType::default();
+    if_condition_true_block.add_assign("result", ExpressionType::add(Value::Number(1.into()), variable("result")));
+
+    let mut if_condition = IfBlockType::default();
+    if_condition.set_condition(ConditionType::and(
+        ConditionType::gr(variable("a"), Value::Number(0.into())),
+        ConditionType::gr(ExpressionType::div(variable("a"), variable("b")), Value::Number(2.into()))));
+    if_condition.set_true_block(if_condition_true_block.clone());
+    main_func_block.add_if(if_condition);
+
+    main_func_block.add_assign("a", ExpressionType::value(Value::Number(4.into())));
+
+    let mut if_condition = IfBlockType::default();
+    if_condition.set_condition(ConditionType::and(
+        ConditionType::gr(variable("a"), Value::Number(0.into())),
+        ConditionType::gr(ExpressionType::div(variable("a"), variable("b")), Value::Number(2.into()))));
+    if_condition.set_true_block(if_condition_true_block);
+    main_func_block.add_if(if_condition);
+
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "short_circuit_and", 1);
+}
+
+#[test]
+fn short_circuit_or() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+    main_func_block.add_declare("b", NumberType::I32, Some(ExpressionType::value(Value::Number(7.into()))));
+
+    let mut if_condition_true_block = BlockType::default();
+    if_condition_true_block.add_assign("result", ExpressionType::value(Value::Number(1.into())));
+
+    let mut if_condition_false_block = BlockType::default();
+    if_condition_false_block.add_assign("result", ExpressionType::value(Value::Number(2.into())));
+
+    let mut if_condition = IfBlockType::default();
+    if_condition.set_condition(ConditionType::or(
+        ConditionType::eq(variable("a"), Value::Number(0.into())),
+        ConditionType::eq(ExpressionType::modulo(variable("a"), variable("b")), Value::Number(0.into()))));
+    if_condition.set_true_block(if_condition_true_block);
+    if_condition.set_false_block(if_condition_false_block);
+
+    main_func_block.add_if(if_condition);
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "short_circuit_or", 1);
+}
+
+#[test]
+fn not_in_loop() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("i", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+    main_func_block.add_declare("count", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+
+    // Counts the numbers in [0, 20) that are not in [5, 15] or are equal to 10
+    let mut if_condition_true_block = BlockType::default();
+    if_condition_true_block.add_assign("count", ExpressionType::add(Value::Number(1.into()), variable("count")));
+
+    let mut if_condition = IfBlockType::default();
+    if_condition.set_condition(ConditionType::or(
+        !ConditionType::and(ConditionType::ge(variable("i"), Value::Number(5.into())), ConditionType::le(variable("i"), Value::Number(15.into()))),
+        ConditionType::eq(variable("i"), Value::Number(10.into()))));
+    if_condition.set_true_block(if_condition_true_block);
+
+    let mut loop_block = BlockType::default();
+    loop_block.add_if(if_condition);
+    loop_block.add_assign("i", ExpressionType::add(Value::Number(1.into()), variable("i")));
+
+    let mut while_block = WhileBlockType::default();
+    while_block.set_condition(!ConditionType::ge(variable("i"), Value::Number(20.into())));
+    while_block.set_block(loop_block);
+
+    main_func_block.add_while(while_block);
+    main_func_block.add_return_variable("count");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "not_in_loop", 10);
+}
+
+#[test]
+fn logical_condition_value() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I64, Some(ExpressionType::value(Value::Number(5.into()))));
+    main_func_block.add_declare("b", NumberType::I64, Some(ExpressionType::value(Value::Number((-5).into()))));
+    main_func_block.add_declare("both", NumberType::I32, Some(ExpressionType::condition(ConditionType::and(
+        ConditionType::gr(variable("a"), Value::Number(0.into())),
+        ConditionType::gr(variable("b"), Value::Number(0.into()))))));
+    main_func_block.add_declare("any", NumberType::I32, Some(ExpressionType::condition(ConditionType::or(
+        ConditionType::gr(variable("a"), Value::Number(0.into())),
+        ConditionType::gr(variable("b"), Value::Number(0.into()))))));
+    main_func_block.add_declare("none", NumberType::I32, Some(ExpressionType::condition(!ConditionType::or(
+        ConditionType::ls(variable("a"), Value::Number(0.into())),
+        ConditionType::ls(variable("b"), Value::Number(0.into()))))));
+    main_func_block.add_print("%d %d %d".to_owned(), vec![variable("both"), variable("any"), variable("none")]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "logical_condition_value", "0 1 0");
+}
+
+#[test]
+fn double_condition_value_nan() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("nan", NumberType::Double, Some(ExpressionType::div(Value::Number(0.0.into()), Value::Number(0.0.into()))));
+    main_func_block.add_declare("x", NumberType::Double, Some(ExpressionType::value(1.5.into())));
+
+    let conditions = [
+        ("eq", ConditionType::eq(variable("nan"), variable("nan"))),
+        ("ne", ConditionType::ne(variable("nan"), variable("nan"))),
+        ("gr", ConditionType::gr(variable("nan"), variable("x"))),
+        ("ge", ConditionType::ge(variable("nan"), variable("x"))),
+        ("ls", ConditionType::ls(variable("nan"), variable("x"))),
+        ("le", ConditionType::le(variable("nan"), variable("x"))),
+        ("x_gr", ConditionType::gr(variable("x"), Value::Number(1.0.into()))),
+        ("x_eq", ConditionType::eq(variable("x"), Value::Number(1.5.into()))),
+    ];
+
+    let mut arguments = Vec::new();
+    for (name, condition) in conditions.into_iter() {
+        main_func_block.add_declare(name, NumberType::I32, Some(ExpressionType::condition(condition)));
+        arguments.push(variable(name));
+    }
+
+    main_func_block.add_print("%d %d %d %d %d %d %d %d".to_owned(), arguments);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "double_condition_value_nan", "0 1 0 0 0 0 1 1");
+}
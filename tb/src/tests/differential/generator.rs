@@ -0,0 +1,405 @@
+use tb_core::types::{Block, Condition, Definition, Expression, Number, NumberType, Parameter, Statement, Value};
+
+const INTEGER_TYPES: [NumberType; 8] = [NumberType::I8, NumberType::U8, NumberType::I16, NumberType::U16, NumberType::I32, NumberType::U32, NumberType::I64, NumberType::U64];
+
+/// More parameters than the argument registers, the others are passed on the stack
+const MAX_PARAMETERS: usize = 10;
+
+/// Variadic arguments of the print also fill the registers and go to the stack
+const MAX_PRINT_ARGUMENTS: usize = 10;
+
+/// xorshift64*, the same seed always generates the same program
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Zero is a fixed point of the xorshift
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    pub fn below(&mut self, limit: usize) -> usize {
+        (self.next() % limit as u64) as usize
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+#[derive(Clone)]
+struct Variable {
+    name: String,
+    var_type: NumberType,
+
+    // Loop counters are only read, the loops always terminate
+    counter: bool
+}
+
+struct Function {
+    name: String,
+    parameters: Vec<NumberType>,
+    returns_float: bool
+}
+
+/// Random well-formed programs. Generated code avoids the cases that C leaves undefined and the
+/// generator does not model: variables are visible only in the block that declares them and the names
+/// are unique, float values are never converted to integers and loops are bounded by counters.
+pub struct Generator {
+    random: Random,
+    scopes: Vec<Vec<Variable>>,
+    functions: Vec<Function>,
+    variable_index: usize,
+    loop_depth: usize,
+    returns_float: bool
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self { random: Random::new(seed), scopes: Vec::new(), functions: Vec::new(), variable_index: 0, loop_depth: 0, returns_float: false }
+    }
+
+    pub fn generate(mut self) -> Vec<Definition> {
+        let mut definitions = Vec::new();
+
+        for index in 0..self.random.below(3) {
+            definitions.push(self.function(format!("f{}", index)));
+        }
+
+        self.returns_float = false;
+        self.scopes.push(Vec::new());
+        let mut items = self.statements(0);
+
+        // Everything that is still visible is printed at the end
+        let variables = self.scopes[0].clone();
+        let mut remaining = variables.as_slice();
+        while !remaining.is_empty() {
+            let (printed, rest) = remaining.split_at((self.random.below(MAX_PRINT_ARGUMENTS) + 1).min(remaining.len()));
+            items.push(Self::print(printed));
+            remaining = rest;
+        }
+
+        let exit_code = self.variable(false).map(|variable| Value::Variable(variable.name));
+        items.push(Statement::Return(Some(exit_code.unwrap_or(Value::Number(Number::I32(0))))));
+        self.scopes.pop();
+
+        definitions.push(Definition::Function { name: "main".to_owned(), parameters: Vec::new(), block: Block { items } });
+        definitions
+    }
+
+    fn function(&mut self, name: String) -> Definition {
+        let mut scope = Vec::new();
+        let mut parameters = Vec::new();
+
+        for _ in 0..self.random.below(MAX_PARAMETERS + 1) {
+            let param_type = self.parameter_type();
+            let name = self.new_name();
+            scope.push(Variable { name: name.clone(), var_type: param_type, counter: false });
+            parameters.push(Parameter { name, param_type });
+        }
+
+        let returns_float = self.random.chance(25);
+        self.returns_float = returns_float;
+
+        self.scopes.push(scope);
+        let mut items = self.statements(1);
+
+        let result = match self.variable(returns_float) {
+            Some(variable) => variable.name,
+            None => {
+                let result = self.declare(returns_float);
+                let name = match &result {
+                    Statement::Declare { name, .. } => name.clone(),
+                    _ => unreachable!()
+                };
+                items.push(result);
+                name
+            }
+        };
+        items.push(Statement::Return(Some(Value::Variable(result))));
+        self.scopes.pop();
+
+        self.functions.push(Function { name: name.clone(), parameters: parameters.iter().map(|parameter| parameter.param_type).collect(), returns_float });
+        Definition::Function { name, parameters, block: Block { items } }
+    }
+
+    fn statements(&mut self, depth: usize) -> Vec<Statement> {
+        let mut items = Vec::new();
+        for _ in 0..self.random.below(6) + 1 {
+            items.extend(self.statement(depth));
+        }
+        items
+    }
+
+    fn block(&mut self, depth: usize) -> Block {
+        self.scopes.push(Vec::new());
+        let items = self.statements(depth + 1);
+        self.scopes.pop();
+        Block { items }
+    }
+
+    fn statement(&mut self, depth: usize) -> Vec<Statement> {
+        match self.random.below(100) {
+            0..=29 => {
+                let float = self.random.chance(20);
+                vec![self.declare(float)]
+            },
+            30..=54 => match self.assignable() {
+                Some(variable) => vec![Statement::Assign { assigne: self.expression(variable.var_type.is_float(), 3), name: variable.name }],
+                None => vec![self.declare(false)]
+            },
+            55..=66 if depth < 3 => {
+                let condition = self.condition(2);
+                let true_block = self.block(depth);
+                let false_block = match self.random.chance(50) {
+                    true => Some(self.block(depth)),
+                    false => None
+                };
+                vec![Statement::If { condition, true_block, false_block }]
+            },
+            67..=76 if depth < 3 && self.loop_depth < 2 => self.bounded_loop(depth),
+            77..=82 if self.loop_depth > 0 => {
+                let statement = match self.random.chance(50) {
+                    true => Statement::Break,
+                    false => Statement::Continue
+                };
+                vec![Statement::If { condition: self.condition(1), true_block: Block { items: vec![statement] }, false_block: None }]
+            },
+            83..=89 => self.call(),
+            90..=92 if depth > 0 => {
+                let value = self.value(self.returns_float);
+                vec![Statement::If { condition: self.condition(1), true_block: Block { items: vec![Statement::Return(Some(value))] }, false_block: None }]
+            },
+            _ => {
+                let variables = (0..self.random.below(MAX_PRINT_ARGUMENTS) + 1).filter_map(|_| {
+                    let float = self.random.chance(30);
+                    self.variable(float)
+                }).collect::<Vec<_>>();
+
+                match variables.is_empty() {
+                    true => vec![self.declare(false)],
+                    false => vec![Self::print(&variables)]
+                }
+            }
+        }
+    }
+
+    /// The counter is increased first, so continue could not skip it
+    fn bounded_loop(&mut self, depth: usize) -> Vec<Statement> {
+        let name = self.new_name();
+        let limit = Value::Number(Number::I32(self.random.below(6) as i32));
+        let counter = Expression::Value(Value::Variable(name.clone()));
+        let increase = Statement::Assign { name: name.clone(), assigne: Expression::Add { target: Box::new(counter.clone()), source: Box::new(Value::Number(Number::I32(1)).into()) } };
+
+        let declare = Statement::Declare { name: name.clone(), ty: NumberType::I32, init: Some(Value::Number(Number::I32(0)).into()) };
+        self.scopes.last_mut().unwrap().push(Variable { name, var_type: NumberType::I32, counter: true });
+
+        self.loop_depth += 1;
+        let mut block = self.block(depth);
+        self.loop_depth -= 1;
+
+        let statement = match self.random.chance(50) {
+            true => {
+                block.items.insert(0, increase);
+                Statement::While { condition: Condition::Ls { left: counter, right: limit.into() }, block }
+            },
+            false => {
+                let exit = Statement::If { condition: Condition::Gr { left: counter, right: limit.into() }, true_block: Block { items: vec![Statement::Break] }, false_block: None };
+                block.items.insert(0, exit);
+                block.items.insert(0, increase);
+                Statement::Loop { block }
+            }
+        };
+
+        vec![declare, statement]
+    }
+
+    /// Call result is assigned to a variable of the same kind, integers are converted like C does
+    fn call(&mut self) -> Vec<Statement> {
+        if self.functions.is_empty() {
+            return vec![self.declare(false)];
+        }
+
+        let index = self.random.below(self.functions.len());
+        let parameters = self.functions[index].parameters.clone();
+        let returns_float = self.functions[index].returns_float;
+        let name = self.functions[index].name.clone();
+
+        // Floats are passed with their exact type
+        let arguments = parameters.iter().map(|param_type| match param_type.is_float() {
+            true => match self.variable_of(|variable_type| variable_type == *param_type) {
+                Some(variable) => Value::Variable(variable.name),
+                None => Value::Number(self.float_number())
+            },
+            false => self.value(false)
+        }).collect::<Vec<_>>();
+
+        let mut items = Vec::new();
+        let assign = match self.variable_of(|variable_type| variable_type.is_float() == returns_float) {
+            Some(variable) if !variable.counter => variable.name,
+            _ => {
+                let declare = self.declare(returns_float);
+                let Statement::Declare { name, .. } = &declare else { unreachable!() };
+                let name = name.clone();
+                items.push(declare);
+                name
+            }
+        };
+
+        items.push(Statement::Call { name, arguments, assign: Some(assign), is_variadic: false });
+        items
+    }
+
+    fn declare(&mut self, float: bool) -> Statement {
+        let ty = match float {
+            true => NumberType::Double,
+            false => self.random.pick(&INTEGER_TYPES)
+        };
+
+        let init = self.expression(float, 3);
+        let name = self.new_name();
+        self.scopes.last_mut().unwrap().push(Variable { name: name.clone(), var_type: ty, counter: false });
+        Statement::Declare { name, ty, init: Some(init) }
+    }
+
+    fn print(variables: &[Variable]) -> Statement {
+        let format = variables.iter().map(|variable| {
+            let format = match variable.var_type {
+                NumberType::I64 => "%lld",
+                NumberType::U64 => "%llu",
+                NumberType::U32 => "%u",
+                NumberType::Double => "%.17g",
+                _ => "%d"
+            };
+            format!("{} = {}", variable.name, format)
+        }).collect::<Vec<_>>();
+
+        let arguments = variables.iter().map(|variable| Value::Variable(variable.name.clone())).collect();
+        Statement::Print { format: format!("{}\n", format.join(" ")), arguments }
+    }
+
+    /// Integer expressions never contain a float value, float expressions could contain integers
+    fn expression(&mut self, float: bool, depth: usize) -> Expression {
+        if depth == 0 || self.random.chance(30) {
+            return self.value(float).into();
+        }
+
+        let left = Box::new(self.expression(float, depth - 1));
+        let mixed = float && self.random.chance(80);
+        let right = Box::new(self.expression(mixed, depth - 1));
+
+        match (float, self.random.below(17)) {
+            (_, 0..=1) => Expression::Add { target: left, source: right },
+            (_, 2..=3) => Expression::Sub { target: left, source: right },
+            (_, 4..=5) => Expression::Mul { target: left, source: right },
+            (_, 6) => Expression::Div { divider: left, divided: right },
+            (_, 7) => Expression::Inc { source: left },
+            (_, 8) => Expression::Dec { source: left },
+            (_, 9) => Expression::BitwiseNeg { source: left },
+            (false, 10) => Expression::Modulo { divider: left, divided: right },
+            (false, 11) => Expression::ShiftLeft { target: left, source: right },
+            (false, 12) => Expression::ShiftRight { target: left, source: right },
+            (false, 13) => Expression::BitwiseAnd { target: left, source: right },
+            (false, 14) => Expression::BitwiseOr { target: left, source: right },
+            (false, 15) => Expression::BitwiseXor { target: left, source: right },
+            (false, 16) => Expression::BitwiseNot { source: left },
+            (_, _) => Expression::Condition(Box::new(self.condition(1)))
+        }
+    }
+
+    fn condition(&mut self, depth: usize) -> Condition {
+        if depth > 0 {
+            match self.random.below(10) {
+                0 => return Condition::And { left: Box::new(self.condition(depth - 1)), right: Box::new(self.condition(depth - 1)) },
+                1 => return Condition::Or { left: Box::new(self.condition(depth - 1)), right: Box::new(self.condition(depth - 1)) },
+                2 => return Condition::Not { source: Box::new(self.condition(depth - 1)) },
+                _ => ()
+            }
+        }
+
+        let (left_float, right_float) = (self.random.chance(20), self.random.chance(20));
+        let left = self.expression(left_float, 2);
+        let right = self.expression(right_float, 2);
+
+        match self.random.below(6) {
+            0 => Condition::Eq { left, right },
+            1 => Condition::Ne { left, right },
+            2 => Condition::Gr { left, right },
+            3 => Condition::Ge { left, right },
+            4 => Condition::Ls { left, right },
+            _ => Condition::Le { left, right }
+        }
+    }
+
+    fn value(&mut self, float: bool) -> Value {
+        if self.random.chance(60) {
+            if let Some(variable) = self.variable(float) {
+                return Value::Variable(variable.name);
+            }
+        }
+
+        match float {
+            true => Value::Number(self.float_number()),
+            false => Value::Number(self.integer_number())
+        }
+    }
+
+    /// Mostly small numbers, limits of the types are more likely than the others
+    fn integer_number(&mut self) -> Number {
+        let number_type = self.random.pick(&INTEGER_TYPES);
+        let value = match self.random.below(4) {
+            0 | 1 => self.random.below(21) as i64 - 10,
+            2 => self.random.pick(&[0, 1, -1, i64::MIN, i64::MAX, i32::MIN as i64, i32::MAX as i64, u32::MAX as i64, 255, 128, 65535]),
+            _ => self.random.next() as i64
+        };
+
+        Number::I64(value).convert(number_type)
+    }
+
+    fn float_number(&mut self) -> Number {
+        Number::Double((self.random.below(401) as f64 - 200.0) / 8.0)
+    }
+
+    /// Half of the parameters are floats, so both register kinds could be used up
+    fn parameter_type(&mut self) -> NumberType {
+        match self.random.chance(50) {
+            true => NumberType::Double,
+            false => self.random.pick(&INTEGER_TYPES)
+        }
+    }
+
+    fn variable(&mut self, float: bool) -> Option<Variable> {
+        self.variable_of(|variable_type| variable_type.is_float() == float)
+    }
+
+    fn variable_of(&mut self, filter: impl Fn(NumberType) -> bool) -> Option<Variable> {
+        let variables = self.scopes.iter().flatten().filter(|variable| filter(variable.var_type)).cloned().collect::<Vec<_>>();
+        match variables.is_empty() {
+            true => None,
+            false => Some(variables[self.random.below(variables.len())].clone())
+        }
+    }
+
+    fn assignable(&mut self) -> Option<Variable> {
+        let variables = self.scopes.iter().flatten().filter(|variable| !variable.counter).cloned().collect::<Vec<_>>();
+        match variables.is_empty() {
+            true => None,
+            false => Some(variables[self.random.below(variables.len())].clone())
+        }
+    }
+
+    fn new_name(&mut self) -> String {
+        self.variable_index += 1;
+        format!("v{}", self.variable_index)
+    }
+}
@@ -0,0 +1,156 @@
+//! Random programs are executed natively and with the interpreter, both of them should print the same
+//! output and exit with the same code. Failing programs are reduced before they are reported.
+//!
+//! TB_DIFFERENTIAL_SEED and TB_DIFFERENTIAL_COUNT change the generated programs for longer runs.
+
+use std::{io::Read, process::{Command, Stdio}, thread, time::{Duration, Instant}};
+
+use tb_builder::ApplicationType;
+use tb_core::{error::TBError, interpreter::{Execution, Interpreter}, ir, target::Target, types::{DataItemCollection, Definition}};
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use generator::Generator;
+
+use super::{application, build_executable};
+
+pub mod generator;
+mod shrink;
+
+/// Generated loops are small, programs that run longer than this are not terminating
+const STEP_LIMIT: usize = 100_000;
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+enum Outcome {
+    /// Program is not valid for the reference, it divides by zero for example
+    Invalid,
+    Same,
+    Different { expected: Execution, found: Execution },
+    BuildFailed(String)
+}
+
+fn interpret(definitions: &[Definition]) -> Option<Execution> {
+    let mut interpreter = Interpreter::new(definitions.to_vec(), DataItemCollection::default());
+    interpreter.set_step_limit(STEP_LIMIT);
+    interpreter.execute().ok()
+}
+
+/// Exit code is -1 if the process is killed by a signal or does not finish in time
+fn run_native(definitions: &[Definition], file_name: &str) -> Result<Execution, TBError> {
+    let executable_name = build_executable(application(definitions.to_vec()), file_name)?;
+    let mut child = Command::new(executable_name).stdout(Stdio::piped()).spawn().unwrap();
+
+    let start = Instant::now();
+    let exit_code = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status.code().unwrap_or(-1);
+        }
+
+        if start.elapsed() > TIMEOUT {
+            child.kill().unwrap();
+            child.wait().unwrap();
+            break -1;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    let mut output = String::new();
+    child.stdout.take().unwrap().read_to_string(&mut output).unwrap();
+    Ok(Execution { exit_code, output })
+}
+
+fn compare(definitions: &[Definition], file_name: &str, run: &impl Fn(&[Definition], &str) -> Result<Execution, TBError>) -> Outcome {
+    let expected = match interpret(definitions) {
+        Some(expected) => expected,
+        None => return Outcome::Invalid
+    };
+
+    let found = match run(definitions, file_name) {
+        Ok(found) => found,
+        Err(error) => return Outcome::BuildFailed(error.to_string())
+    };
+
+    match expected == found {
+        true => Outcome::Same,
+        false => Outcome::Different { expected, found }
+    }
+}
+
+/// Generated programs are executed with the runner and the interpreter, the failing ones are reduced before they are reported
+fn compare_programs(name: &str, run: impl Fn(&[Definition], &str) -> Result<Execution, TBError>) {
+    let first_seed = environment("TB_DIFFERENTIAL_SEED", 0);
+    let count = environment("TB_DIFFERENTIAL_COUNT", 40);
+
+    let mut checked = 0;
+    for seed in first_seed..first_seed + count {
+        let file_name = format!("{}_{}", name, seed);
+        let definitions = Generator::new(seed).generate();
+
+        match compare(&definitions, &file_name, &run) {
+            Outcome::Invalid => continue,
+            Outcome::Same => checked += 1,
+            Outcome::BuildFailed(error) => panic!("Seed {} is not compiled\n{}\n{}", seed, ir::print(&definitions), error),
+            Outcome::Different { .. } => {
+                let file_name = format!("{}_shrink", file_name);
+                let definitions = shrink::shrink(definitions, |candidate| matches!(compare(candidate, &file_name, &run), Outcome::Different { .. }));
+                let Outcome::Different { expected, found } = compare(&definitions, &file_name, &run) else { unreachable!() };
+
+                panic!("Seed {} is failing\n{}\nexpected: {:?}\nfound: {:?}", seed, ir::print(&definitions), expected, found);
+            }
+        }
+    }
+
+    // Most of the programs should be valid, otherwise the generator does not test much
+    assert!(checked * 2 > count, "only {} programs of {} are valid", checked, count);
+}
+
+/// Programs of another backend should print the same output with the interpreter, the runner builds and executes the application.
+/// The same programs are compared with the x86 backend in differential_programs.
+pub fn compare_backend(name: &str, run: impl Fn(ApplicationType, &str) -> (String, i32)) {
+    compare_programs(name, |definitions, file_name| {
+        let (output, exit_code) = run(application(definitions.to_vec()), file_name);
+        Ok(Execution { exit_code, output })
+    });
+}
+
+fn environment(name: &str, default: u64) -> u64 {
+    std::env::var(name).ok().and_then(|value| value.parse().ok()).unwrap_or(default)
+}
+
+#[test]
+fn differential_programs() {
+    compare_programs("differential", run_native);
+}
+
+#[test]
+fn microsoft_programs() {
+    // Programs could not be executed, the shadow space and the positional arguments are only generated
+    let first_seed = environment("TB_DIFFERENTIAL_SEED", 0);
+    let count = environment("TB_DIFFERENTIAL_COUNT", 40);
+
+    for seed in first_seed..first_seed + count {
+        let definitions = Generator::new(seed).generate();
+        let mut application = application(definitions.clone());
+        application.set_target(Target::X86_64_WINDOWS);
+
+        if let Err(error) = application.build::<X86AssemblyGenerator>() {
+            panic!("Seed {} is not compiled\n{}\n{}", seed, ir::print(&definitions), error);
+        }
+    }
+}
+
+#[test]
+fn shrink_to_minimal_program() {
+    // Every program that prints something is "failing", the smallest one is a single print
+    let definitions = (0..).map(|seed| Generator::new(seed).generate())
+        .find(|definitions| interpret(definitions).map(|execution| execution.output.lines().count() > 3).unwrap_or_default())
+        .unwrap();
+
+    let minimal = shrink::shrink(definitions, |candidate| interpret(candidate).map(|execution| !execution.output.is_empty()).unwrap_or_default());
+    let Definition::Function { name, block, .. } = minimal.last().unwrap();
+
+    assert_eq!(1, minimal.len());
+    assert_eq!("main", name);
+    assert_eq!(1, interpret(&minimal).unwrap().output.lines().count());
+    assert!(block.items.len() <= 3, "{}", ir::print(&minimal));
+}
@@ -0,0 +1,204 @@
+use tb_core::types::{Block, Condition, Definition, Expression, Number, Statement, Value};
+
+type Rebuild<'a, T> = &'a dyn Fn(T) -> Vec<Definition>;
+
+/// Greedy reduction, the first smaller program that is still failing replaces the current one.
+/// Search continues from the last successful reduction, the reductions before it are checked at the end.
+pub fn shrink(mut definitions: Vec<Definition>, mut failing: impl FnMut(&[Definition]) -> bool) -> Vec<Definition> {
+    let mut start = 0;
+
+    loop {
+        let candidates = candidates(&definitions);
+        let count = candidates.len();
+        let mut candidates = candidates.into_iter().enumerate().collect::<Vec<_>>();
+        candidates.rotate_left(std::cmp::min(start, count));
+
+        match candidates.into_iter().find(|(_, candidate)| failing(candidate)) {
+            Some((index, candidate)) => {
+                definitions = candidate;
+                start = index;
+            },
+            None => return definitions
+        }
+    }
+}
+
+/// Programs with one reduction applied, bigger reductions come first
+fn candidates(definitions: &[Definition]) -> Vec<Vec<Definition>> {
+    let mut candidates = Vec::new();
+
+    // Calls to the removed functions are not linked, the calls are removed first
+    for index in 0..definitions.len() {
+        let Definition::Function { name, .. } = &definitions[index];
+        if name != "main" && !definitions.iter().any(|Definition::Function { block, .. }| calls(block, name)) {
+            let mut candidate = definitions.to_vec();
+            candidate.remove(index);
+            candidates.push(candidate);
+        }
+    }
+
+    for index in 0..definitions.len() {
+        let Definition::Function { name, parameters, block } = &definitions[index];
+        let rebuild = |block: Block| {
+            let mut candidate = definitions.to_vec();
+            candidate[index] = Definition::Function { name: name.clone(), parameters: parameters.clone(), block };
+            candidate
+        };
+        block_candidates(block, &rebuild, &mut candidates);
+    }
+
+    candidates
+}
+
+fn calls(block: &Block, function: &str) -> bool {
+    block.items.iter().any(|statement| match statement {
+        Statement::Call { name, .. } => name == function,
+        Statement::If { true_block, false_block, .. } => calls(true_block, function) || false_block.as_ref().is_some_and(|false_block| calls(false_block, function)),
+        Statement::While { block, .. } | Statement::Loop { block } => calls(block, function),
+        _ => false
+    })
+}
+
+fn block_candidates(block: &Block, rebuild: Rebuild<Block>, candidates: &mut Vec<Vec<Definition>>) {
+    let replace = |index: usize, statements: Vec<Statement>| {
+        let mut items = block.items.clone();
+        items.splice(index..index + 1, statements);
+        rebuild(Block { items })
+    };
+
+    for index in 0..block.items.len() {
+        candidates.push(replace(index, Vec::new()));
+    }
+
+    for (index, statement) in block.items.iter().enumerate() {
+        statement_candidates(statement, &|statements| replace(index, statements), candidates);
+    }
+}
+
+fn statement_candidates(statement: &Statement, rebuild: Rebuild<Vec<Statement>>, candidates: &mut Vec<Vec<Definition>>) {
+    match statement {
+        Statement::Declare { name, ty, init: Some(init) } => {
+            expression_candidates(init, &|init| rebuild(vec![Statement::Declare { name: name.clone(), ty: *ty, init: Some(init) }]), candidates);
+        },
+        Statement::Assign { name, assigne } => {
+            expression_candidates(assigne, &|assigne| rebuild(vec![Statement::Assign { name: name.clone(), assigne }]), candidates);
+        },
+        Statement::If { condition, true_block, false_block } => {
+            candidates.push(rebuild(true_block.items.clone()));
+            if let Some(false_block) = false_block {
+                candidates.push(rebuild(false_block.items.clone()));
+                candidates.push(rebuild(vec![Statement::If { condition: condition.clone(), true_block: true_block.clone(), false_block: None }]));
+                block_candidates(false_block, &|false_block| rebuild(vec![Statement::If { condition: condition.clone(), true_block: true_block.clone(), false_block: Some(false_block) }]), candidates);
+            }
+
+            block_candidates(true_block, &|true_block| rebuild(vec![Statement::If { condition: condition.clone(), true_block, false_block: false_block.clone() }]), candidates);
+            condition_candidates(condition, &|condition| rebuild(vec![Statement::If { condition, true_block: true_block.clone(), false_block: false_block.clone() }]), candidates);
+        },
+        Statement::While { condition, block } => {
+            block_candidates(block, &|block| rebuild(vec![Statement::While { condition: condition.clone(), block }]), candidates);
+            condition_candidates(condition, &|condition| rebuild(vec![Statement::While { condition, block: block.clone() }]), candidates);
+        },
+        Statement::Loop { block } => {
+            block_candidates(block, &|block| rebuild(vec![Statement::Loop { block }]), candidates);
+        },
+        _ => ()
+    }
+}
+
+fn condition_candidates(condition: &Condition, rebuild: Rebuild<Condition>, candidates: &mut Vec<Vec<Definition>>) {
+    match condition {
+        Condition::And { left, right } | Condition::Or { left, right } => {
+            candidates.push(rebuild(*left.clone()));
+            candidates.push(rebuild(*right.clone()));
+
+            let combine = |left: Condition, right: Condition| match condition {
+                Condition::And { .. } => Condition::And { left: Box::new(left), right: Box::new(right) },
+                _ => Condition::Or { left: Box::new(left), right: Box::new(right) }
+            };
+
+            condition_candidates(left, &|left| rebuild(combine(left, *right.clone())), candidates);
+            condition_candidates(right, &|right| rebuild(combine(*left.clone(), right)), candidates);
+        },
+        Condition::Not { source } => {
+            candidates.push(rebuild(*source.clone()));
+            condition_candidates(source, &|source| rebuild(Condition::Not { source: Box::new(source) }), candidates);
+        },
+        Condition::Eq { left, right } | Condition::Ne { left, right } | Condition::Gr { left, right } |
+        Condition::Ge { left, right } | Condition::Ls { left, right } | Condition::Le { left, right } => {
+            let combine = |left: Expression, right: Expression| match condition {
+                Condition::Eq { .. } => Condition::Eq { left, right },
+                Condition::Ne { .. } => Condition::Ne { left, right },
+                Condition::Gr { .. } => Condition::Gr { left, right },
+                Condition::Ge { .. } => Condition::Ge { left, right },
+                Condition::Ls { .. } => Condition::Ls { left, right },
+                _ => Condition::Le { left, right }
+            };
+
+            expression_candidates(left, &|left| rebuild(combine(left, right.clone())), candidates);
+            expression_candidates(right, &|right| rebuild(combine(left.clone(), right)), candidates);
+        }
+    }
+}
+
+/// Expression is replaced with one of its operands or with a small number
+fn expression_candidates(expression: &Expression, rebuild: Rebuild<Expression>, candidates: &mut Vec<Vec<Definition>>) {
+    match expression {
+        Expression::Value(Value::Number(Number::I32(0 | 1))) => return,
+        Expression::Value(Value::Number(_)) => {
+            candidates.push(rebuild(Value::Number(Number::I32(0)).into()));
+            candidates.push(rebuild(Value::Number(Number::I32(1)).into()));
+            return;
+        },
+        Expression::Value(_) => return,
+        _ => ()
+    };
+
+    match expression {
+        Expression::Add { target, source } | Expression::Sub { target, source } | Expression::Mul { target, source } |
+        Expression::ShiftLeft { target, source } | Expression::ShiftRight { target, source } | Expression::BitwiseAnd { target, source } |
+        Expression::BitwiseOr { target, source } | Expression::BitwiseXor { target, source } |
+        Expression::Div { divider: target, divided: source } | Expression::Modulo { divider: target, divided: source } => {
+            candidates.push(rebuild(*target.clone()));
+            candidates.push(rebuild(*source.clone()));
+
+            let combine = |target: Expression, source: Expression| {
+                let (target, source) = (Box::new(target), Box::new(source));
+                match expression {
+                    Expression::Add { .. } => Expression::Add { target, source },
+                    Expression::Sub { .. } => Expression::Sub { target, source },
+                    Expression::Mul { .. } => Expression::Mul { target, source },
+                    Expression::ShiftLeft { .. } => Expression::ShiftLeft { target, source },
+                    Expression::ShiftRight { .. } => Expression::ShiftRight { target, source },
+                    Expression::BitwiseAnd { .. } => Expression::BitwiseAnd { target, source },
+                    Expression::BitwiseOr { .. } => Expression::BitwiseOr { target, source },
+                    Expression::BitwiseXor { .. } => Expression::BitwiseXor { target, source },
+                    Expression::Div { .. } => Expression::Div { divider: target, divided: source },
+                    _ => Expression::Modulo { divider: target, divided: source }
+                }
+            };
+
+            expression_candidates(target, &|target| rebuild(combine(target, *source.clone())), candidates);
+            expression_candidates(source, &|source| rebuild(combine(*target.clone(), source)), candidates);
+        },
+        Expression::BitwiseNot { source } | Expression::BitwiseNeg { source } | Expression::Inc { source } | Expression::Dec { source } => {
+            candidates.push(rebuild(*source.clone()));
+
+            let combine = |source: Expression| {
+                let source = Box::new(source);
+                match expression {
+                    Expression::BitwiseNot { .. } => Expression::BitwiseNot { source },
+                    Expression::BitwiseNeg { .. } => Expression::BitwiseNeg { source },
+                    Expression::Inc { .. } => Expression::Inc { source },
+                    _ => Expression::Dec { source }
+                }
+            };
+
+            expression_candidates(source, &|source| rebuild(combine(source)), candidates);
+        },
+        Expression::Condition(condition) => {
+            candidates.push(rebuild(Value::Number(Number::I32(0)).into()));
+            condition_candidates(condition, &|condition| rebuild(Expression::Condition(Box::new(condition))), candidates);
+        },
+        Expression::Value(_) => ()
+    }
+}
@@ -0,0 +1,225 @@
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_core::syntax::{SyntaxGeneratorTrait, TBSyntaxGenerator};
+use tb_core::types::{DataItemCollection, Definition, Number};
+use tb_target_x86_64::encoder::X86Encoder;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+use tb_target_x86_64::instruction::X86Instruction;
+use tb_target_x86_64::register::Register;
+use tb_target_x86_64::{X86AddressingMode, X86ApplicationContext, X86Location};
+
+use super::differential::generator::Generator;
+
+/// Text section of the assembled AT&T output and the offsets of its relocations
+fn assemble(context: &mut X86ApplicationContext, file_name: &str) -> (Vec<u8>, Vec<usize>) {
+    let mut source_file_name = temp_dir();
+    let mut object_file_name = temp_dir();
+    let mut binary_file_name = temp_dir();
+
+    source_file_name.push(format!("{}.s", file_name));
+    object_file_name.push(format!("{}.o", file_name));
+    binary_file_name.push(format!("{}.bin", file_name));
+
+    let syntax_generator = TBSyntaxGenerator::get_generator::<X86Instruction>().unwrap();
+    fs::write(&source_file_name, syntax_generator.generate(context)).unwrap();
+
+    let status = Command::new("as").arg("-o").arg(&object_file_name).arg(&source_file_name).status().unwrap();
+    assert!(status.success(), "{} is not assembled", source_file_name.display());
+
+    let status = Command::new("objcopy").args(["-O", "binary", "--only-section=.text"]).arg(&object_file_name).arg(&binary_file_name).status().unwrap();
+    assert!(status.success());
+
+    // Relocation lines start with the offset, like "0000000000000013 R_X86_64_PC32 .text-0x0000000000000004"
+    let output = Command::new("objdump").args(["-r", "-j", ".text"]).arg(&object_file_name).output().unwrap();
+    let relocations = String::from_utf8(output.stdout).unwrap().lines()
+        .filter(|line| line.contains("R_X86_64"))
+        .map(|line| usize::from_str_radix(line.split_whitespace().next().unwrap(), 16).unwrap())
+        .collect();
+
+    (fs::read(binary_file_name).unwrap(), relocations)
+}
+
+/// Calls between the functions are resolved by the encoder, the assembler leaves them to the linker
+fn check_encoding(mut context: X86ApplicationContext, file_name: &str) {
+    let machine_code = X86Encoder::encode(&context.instructions.items).unwrap();
+    let (mut expected, relocations) = assemble(&mut context, file_name);
+    let mut found = machine_code.code;
+
+    for relocation in machine_code.relocations.iter() {
+        assert!(relocations.contains(&relocation.offset), "{:?} is not in {:?}", relocation, relocations);
+    }
+
+    for offset in relocations {
+        expected[offset..offset + 4].fill(0);
+        found[offset..offset + 4].fill(0);
+    }
+
+    assert_eq!(expected, found);
+}
+
+fn compile(definitions: Vec<Definition>) -> X86ApplicationContext {
+    X86AssemblyGenerator::default().compile(definitions, DataItemCollection::default()).unwrap()
+}
+
+fn register(register: Register) -> X86Location {
+    X86Location::Register(X86AddressingMode::Direct(register))
+}
+
+fn memory(offset: i32, register: Register) -> X86Location {
+    X86Location::Register(X86AddressingMode::Based(offset, register))
+}
+
+fn imm<N: Into<Number>>(number: N) -> X86Location {
+    X86Location::Imm(number.into())
+}
+
+#[test]
+fn encode_instructions() {
+    use Register::*;
+
+    let mov = |source, target| X86Instruction::Mov { source, target, comment: None };
+    let add = |source, target| X86Instruction::Add { source, target, comment: None };
+
+    let instructions = vec![
+        // Immediate sizes and the short forms
+        mov(imm(5), register(EAX)),
+        mov(imm(Number::I64(-1)), register(RAX)),
+        mov(imm(Number::I64(4000000000)), register(RBX)),
+        mov(imm(Number::U32(4294967295)), register(R9D)),
+        mov(imm(Number::U8(5)), register(AL)),
+        mov(imm(Number::I16(500)), register(AX)),
+        mov(imm(3), memory(-16, RBP)),
+        add(imm(200), register(EAX)),
+        add(imm(200), register(ECX)),
+        add(imm(Number::U8(5)), register(AL)),
+        add(imm(Number::U8(5)), register(CL)),
+        add(imm(Number::I16(5)), register(AX)),
+        add(imm(Number::I16(500)), register(AX)),
+        add(imm(Number::U32(4294967295)), register(EAX)),
+        add(imm(1), memory(-8, RBP)),
+
+        // Registers that need REX and the special addressing modes
+        mov(register(RAX), register(RBX)),
+        mov(register(R12), register(R13)),
+        mov(register(SIL), register(AL)),
+        mov(register(ECX), register(R10D)),
+        mov(register(RAX), memory(-8, RBP)),
+        mov(register(EAX), X86Location::Register(X86AddressingMode::Indirect(RSP))),
+        mov(register(R8), memory(0, R13)),
+        mov(register(CL), memory(16, R12)),
+        mov(register(AX), memory(-300, RBP)),
+        mov(memory(0, RBP), register(RCX)),
+        mov(X86Location::Label("number".to_owned()), register(RAX)),
+        X86Instruction::Movsx { source: register(EDX), target: register(RDX), comment: None },
+        X86Instruction::Movsx { source: register(AL), target: register(ECX), comment: None },
+        X86Instruction::Movsx { source: register(AX), target: register(RBX), comment: None },
+        X86Instruction::Movzx { source: register(CL), target: register(EAX), comment: None },
+        X86Instruction::Movzx { source: register(R9W), target: register(R10D), comment: None },
+        X86Instruction::Lea { source: memory(-8, RBP), target: register(RAX), comment: None },
+        X86Instruction::Lea { source: X86Location::Label("number".to_owned()), target: register(RDI), comment: None },
+
+        // Arithmetic
+        X86Instruction::Sub { source: register(RBX), target: register(RAX), comment: None },
+        X86Instruction::And { source: memory(-8, RBP), target: register(R11), comment: None },
+        X86Instruction::Or { source: register(EDX), target: memory(-4, RBP), comment: None },
+        X86Instruction::Xor { source: register(R14B), target: register(BL), comment: None },
+        X86Instruction::Cmp { left: imm(0), right: register(RAX), comment: None },
+        X86Instruction::Cmp { left: register(RBX), right: register(RCX), comment: None },
+        X86Instruction::IMul { source: register(RBX), target: register(RAX), comment: None },
+        X86Instruction::IMul { source: imm(10), target: register(ECX), comment: None },
+        X86Instruction::IMul { source: imm(1000), target: register(R15), comment: None },
+        X86Instruction::Not { source: register(RAX), comment: None },
+        X86Instruction::Neg { source: register(DL), comment: None },
+        X86Instruction::IDiv { target: register(RCX), comment: None },
+        X86Instruction::Div { target: register(R8D), comment: None },
+        X86Instruction::Inc { source: memory(-8, RBP), comment: None },
+        X86Instruction::Dec { source: register(AX), comment: None },
+        X86Instruction::Shl { source: imm(1), target: register(EAX), comment: None },
+        X86Instruction::Shr { source: imm(3), target: register(RBX), comment: None },
+        X86Instruction::Sar { source: register(CL), target: register(R8), comment: None },
+        X86Instruction::Shl { source: register(CL), target: register(AL), comment: None },
+        X86Instruction::Cdq,
+        X86Instruction::Cqo,
+        X86Instruction::Sete(register(DIL)),
+        X86Instruction::Setne(register(AL)),
+        X86Instruction::Setp(register(CL)),
+        X86Instruction::Setbe(register(R9B)),
+        X86Instruction::Push(register(RBX)),
+        X86Instruction::Push(register(R12)),
+        X86Instruction::Pop(register(R12)),
+        X86Instruction::Pop(register(RBX)),
+
+        // Floating point
+        X86Instruction::Movsd { source: memory(-8, RBP), target: register(XMM0), comment: None },
+        X86Instruction::Movsd { source: register(XMM0), target: memory(-16, RBP), comment: None },
+        X86Instruction::Movsd { source: register(XMM1), target: register(XMM2), comment: None },
+        X86Instruction::Movss { source: X86Location::Label("number".to_owned()), target: register(XMM3), comment: None },
+        X86Instruction::Addsd { source: register(XMM1), target: register(XMM0), comment: None },
+        X86Instruction::Subss { source: memory(-4, RBP), target: register(XMM4), comment: None },
+        X86Instruction::Mulsd { source: register(XMM8), target: register(XMM9), comment: None },
+        X86Instruction::Divsd { source: register(XMM2), target: register(XMM15), comment: None },
+        X86Instruction::Xorps { source: register(XMM10), target: register(XMM10), comment: None },
+        X86Instruction::Cvtsi2sd { source: register(RAX), target: register(XMM0), comment: None },
+        X86Instruction::Cvtsi2ss { source: register(EAX), target: register(XMM1), comment: None },
+        X86Instruction::Cvttsd2si { source: register(XMM0), target: register(RAX), comment: None },
+        X86Instruction::Cvttss2si { source: register(XMM1), target: register(ECX), comment: None },
+        X86Instruction::Cvtss2sd { source: register(XMM1), target: register(XMM11), comment: None },
+        X86Instruction::Cvtsd2ss { source: register(XMM12), target: register(XMM1), comment: None },
+        X86Instruction::Ucomisd { left: register(XMM0), right: register(XMM1), comment: None },
+        X86Instruction::Ucomiss { left: register(XMM9), right: register(XMM2), comment: None },
+        X86Instruction::Movq { source: register(XMM1), target: register(RDX), comment: None },
+        X86Instruction::Movq { source: register(R9), target: register(XMM11), comment: None },
+        X86Instruction::Movups { source: register(XMM8), target: memory(-64, RBP), comment: None },
+        X86Instruction::Movups { source: memory(-80, RBP), target: register(XMM14), comment: None },
+        X86Instruction::Call("printf".to_owned()),
+    ];
+
+    let mut context = X86ApplicationContext::default();
+    context.instructions.add_branch("encoding".to_owned());
+    for instruction in instructions {
+        context.instructions.add_instruction(instruction);
+    }
+    context.instructions.add_instruction(X86Instruction::Ret);
+    context.instructions.add_close_branch();
+
+    check_encoding(context, "encode_instructions");
+}
+
+#[test]
+fn encode_jumps() {
+    let mut context = X86ApplicationContext::default();
+    context.instructions.add_branch("jumps".to_owned());
+    context.instructions.add_branch("jumps_begin".to_owned());
+    context.instructions.add_instruction(X86Instruction::Je("jumps_near".to_owned()));
+    context.instructions.add_instruction(X86Instruction::Jmp("jumps_far".to_owned()));
+
+    // Short jump over the filler does not fit, it has to be relaxed to rel32
+    context.instructions.add_branch("jumps_near".to_owned());
+    for _ in 0..40 {
+        context.instructions.add_instruction(X86Instruction::Add { source: imm(1), target: register(Register::RAX), comment: None });
+    }
+    context.instructions.add_instruction(X86Instruction::Jl("jumps_near".to_owned()));
+    context.instructions.add_instruction(X86Instruction::Jne("jumps_begin".to_owned()));
+
+    context.instructions.add_branch("jumps_far".to_owned());
+    context.instructions.add_instruction(X86Instruction::Call("jumps".to_owned()));
+    context.instructions.add_instruction(X86Instruction::Ret);
+    context.instructions.add_close_branch();
+
+    check_encoding(context, "encode_jumps");
+}
+
+#[test]
+fn encode_ir_program() {
+    let definitions = tb_core::ir::parse(include_str!("ir/collatz.tb")).unwrap();
+    check_encoding(compile(definitions), "encode_ir_program");
+}
+
+#[test]
+fn encode_generated_programs() {
+    for seed in 0..20 {
+        check_encoding(compile(Generator::new(seed).generate()), &format!("encode_generated_{}", seed));
+    }
+}
@@ -0,0 +1,90 @@
+use std::error::Error;
+
+use tb_builder::ApplicationType;
+use tb_core::error::{SourceError, TBError};
+use tb_core::ir;
+use tb_core::types::AssemblyGenerator;
+use tb_target_aarch64::compiler::error::AArch64Error;
+use tb_target_aarch64::generator::AArch64AssemblyGenerator;
+use tb_target_c::generator::CSourceGenerator;
+use tb_target_llvm::generator::LlvmIrGenerator;
+use tb_target_wasm::generator::WasmTextGenerator;
+use tb_target_x86_64::compiler::error::X86Error;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+fn application(source: &str) -> ApplicationType {
+    let mut application = ApplicationType::default();
+    ir::parse(source).unwrap().into_iter().for_each(|definition| application.add_definition(definition));
+    application
+}
+
+/// Function, statement index and the error of the target
+fn failure<T: AssemblyGenerator>(source: &str) -> (String, usize, String) {
+    match application(source).build::<T>() {
+        Err(TBError::CodeGenerationFailed(error)) => (error.function.clone(), error.statement, error.source().unwrap().to_string()),
+        result => panic!("Code generation did not fail ({:?})", result)
+    }
+}
+
+/// Second statement of main uses an unknown variable
+const UNKNOWN_IN_MAIN: &str = r#"fn main() {
+    let a: i32 = 1;
+    a = b;
+    return a;
+}
+"#;
+
+/// Statement inside of the first statement of sum uses an unknown variable, the error has the index of the outer one
+const UNKNOWN_IN_NESTED: &str = r#"fn sum(a: i32) {
+    if a > 0 {
+        print "%d\n", a;
+        return c;
+    }
+    return a;
+}
+
+fn main() {
+    let a: i32 = 0;
+    a = call sum(1);
+    return a;
+}
+"#;
+
+#[test]
+fn error_x86_variable_not_found() {
+    let Err(TBError::CodeGenerationFailed(error)) = application(UNKNOWN_IN_MAIN).build::<X86AssemblyGenerator>() else { panic!("Code generation did not fail") };
+    assert_eq!("main", error.function);
+    assert_eq!(1, error.statement);
+    assert!(matches!(error.error.downcast_ref::<X86Error>(), Some(X86Error::VariableNotFound(variable)) if variable == "b"));
+    assert_eq!("Code generation failed (main:1: Variable not found (b))", TBError::CodeGenerationFailed(error).to_string());
+
+    assert_eq!(("sum".to_owned(), 0, "Variable not found (c)".to_owned()), failure::<X86AssemblyGenerator>(UNKNOWN_IN_NESTED));
+}
+
+#[test]
+fn error_x86_object() {
+    assert!(matches!(application(UNKNOWN_IN_MAIN).build_object::<X86AssemblyGenerator>(), Err(TBError::CodeGenerationFailed(_))));
+}
+
+#[test]
+fn error_other_targets() {
+    for (function, statement, message) in [failure::<AArch64AssemblyGenerator>(UNKNOWN_IN_MAIN), failure::<CSourceGenerator>(UNKNOWN_IN_MAIN), failure::<LlvmIrGenerator>(UNKNOWN_IN_MAIN), failure::<WasmTextGenerator>(UNKNOWN_IN_MAIN)] {
+        assert_eq!(("main", 1, "Variable not found (b)"), (function.as_str(), statement, message.as_str()));
+    }
+
+    for (function, statement, message) in [failure::<AArch64AssemblyGenerator>(UNKNOWN_IN_NESTED), failure::<CSourceGenerator>(UNKNOWN_IN_NESTED), failure::<LlvmIrGenerator>(UNKNOWN_IN_NESTED), failure::<WasmTextGenerator>(UNKNOWN_IN_NESTED)] {
+        assert_eq!(("sum", 0, "Variable not found (c)"), (function.as_str(), statement, message.as_str()));
+    }
+
+    let Err(TBError::CodeGenerationFailed(error)) = application(UNKNOWN_IN_MAIN).build::<AArch64AssemblyGenerator>() else { panic!("Code generation did not fail") };
+    assert!(matches!(error.error.downcast_ref::<AArch64Error>(), Some(AArch64Error::VariableNotFound(_))));
+
+    let Err(TBError::CodeGenerationFailed(error)) = application(UNKNOWN_IN_MAIN).build::<CSourceGenerator>() else { panic!("Code generation did not fail") };
+    assert!(matches!(error.error.downcast_ref::<SourceError>(), Some(SourceError::VariableNotFound(_))));
+
+    let Err(TBError::CodeGenerationFailed(error)) = application(UNKNOWN_IN_MAIN).build::<LlvmIrGenerator>() else { panic!("Code generation did not fail") };
+    assert!(matches!(error.error.downcast_ref::<SourceError>(), Some(SourceError::VariableNotFound(_))));
+
+    let Err(TBError::CodeGenerationFailed(error)) = application(UNKNOWN_IN_MAIN).build::<WasmTextGenerator>() else { panic!("Code generation did not fail") };
+    assert!(matches!(error.error.downcast_ref::<SourceError>(), Some(SourceError::VariableNotFound(_))));
+}
@@ -0,0 +1,113 @@
+use std::env::temp_dir;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use tb_builder::ApplicationType;
+use tb_core::ir;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use super::differential::generator::Generator;
+
+/// Executable is written directly, there is no assembler or linker
+fn build_static_executable(application: ApplicationType, file_name: &str) -> PathBuf {
+    let mut executable_name = temp_dir();
+    executable_name.push(format!("{}.exe", file_name));
+
+    fs::write(&executable_name, application.build_static_executable::<X86AssemblyGenerator>().unwrap()).unwrap();
+    fs::set_permissions(&executable_name, fs::Permissions::from_mode(0o755)).unwrap();
+    executable_name
+}
+
+fn run(executable_name: PathBuf) -> Output {
+    Command::new(executable_name).output().unwrap()
+}
+
+/// Output and the exit code should be same with the interpreter
+fn check_with_interpreter(application: ApplicationType, file_name: &str) {
+    let expected = application.clone().interpret().unwrap();
+    let found = run(build_static_executable(application, file_name));
+
+    assert_eq!(expected.output, String::from_utf8(found.stdout).unwrap(), "{}", file_name);
+    assert_eq!(Some(expected.exit_code), found.status.code(), "{}", file_name);
+}
+
+#[test]
+fn static_ir_program() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+    let executable_name = build_static_executable(application_type, "static_ir_program");
+
+    let output = run(executable_name.clone());
+    assert_eq!("27 111\n", String::from_utf8(output.stdout).unwrap());
+    assert_eq!(Some(0), output.status.code());
+
+    // No interpreter and no shared library
+    let program_headers = String::from_utf8(Command::new("readelf").arg("-l").arg(&executable_name).output().unwrap().stdout).unwrap();
+    assert!(program_headers.contains("LOAD"), "{}", program_headers);
+    assert!(!program_headers.contains("INTERP"), "{}", program_headers);
+    assert!(!program_headers.contains("DYNAMIC"), "{}", program_headers);
+}
+
+#[test]
+fn static_print_formats() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    let negative: i32 = -1234;
+    let unsigned: u32 = 4000000000u32;
+    let long: i64 = -9000000000i64;
+    let small: i8 = -5i8;
+    let hex: i32 = 48879;
+    let letter: i32 = 65;
+    let value: f64 = 3.14159;
+    let round: f64 = -2.5;
+    print "%d %u %ld %hhd\n", negative, unsigned, long, small;
+    print "%x %X %o %c\n", hex, hex, hex, letter;
+    print "%s 100%%\n", "text";
+    print "%.2f %f %.0f %.3f\n", value, value, value, round;
+    return 7;
+}"#).unwrap();
+
+    check_with_interpreter(application_type, "static_print_formats");
+}
+
+#[test]
+fn static_unsupported_format() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    let value: i32 = 1;
+    print "%5d\n", value;
+    return 0;
+}"#).unwrap();
+
+    assert!(application_type.build_static_executable::<X86AssemblyGenerator>().is_err());
+}
+
+#[test]
+fn static_unknown_function() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    call puts("text");
+    return 0;
+}"#).unwrap();
+
+    assert!(application_type.build_static_executable::<X86AssemblyGenerator>().is_err());
+}
+
+#[test]
+fn static_generated_programs() {
+    for seed in 0..20 {
+        let definitions = Generator::new(seed).generate();
+
+        // %.17g is not in the built-in formatter
+        if ir::print(&definitions).contains("%.17g") {
+            continue;
+        }
+
+        let mut application_type = ApplicationType::default();
+        definitions.into_iter().for_each(|definition| application_type.add_definition(definition));
+
+        if application_type.clone().interpret().is_err() {
+            continue;
+        }
+
+        check_with_interpreter(application_type, &format!("static_generated_{}", seed));
+    }
+}
@@ -0,0 +1,198 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ConditionType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_builder::WhileBlockType;
+use tb_core::types::Number;
+use tb_core::types::NumberType;
+use tb_core::types::Value;
+
+use super::check_output;
+use super::get_exit_code;
+
+#[test]
+fn double_arithmetic() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::Double, Some(ExpressionType::value(1.5.into())));
+    main_func_block.add_declare("b", NumberType::Double, Some(ExpressionType::value(2.25.into())));
+    main_func_block.add_declare("c", NumberType::Double, Some(ExpressionType::div(Value::Number(0.5.into()), ExpressionType::mul(Value::Number(2.0.into()), ExpressionType::add(Value::Variable("b".to_owned()), Value::Variable("a".to_owned()))))));
+    main_func_block.add_print("%.2f".to_owned(), vec![Value::Variable("c".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "double_arithmetic", "15.00");
+}
+
+#[test]
+fn float_arithmetic() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("f", NumberType::Float, Some(ExpressionType::value(Value::Number(Number::Float(1.25)))));
+    main_func_block.add_declare("g", NumberType::Float, Some(ExpressionType::mul(Value::Number(4.into()), Value::Variable("f".to_owned()))));
+    main_func_block.add_declare("h", NumberType::Float, Some(ExpressionType::sub(Value::Number(Number::Float(0.5)), Value::Variable("g".to_owned()))));
+    main_func_block.add_print("%.2f %.2f".to_owned(), vec![Value::Variable("g".to_owned()), Value::Variable("h".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "float_arithmetic", "5.00 4.50");
+}
+
+#[test]
+fn integer_to_double() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("i", NumberType::I32, Some(ExpressionType::value(Value::Number((-7).into()))));
+    main_func_block.add_declare("d", NumberType::Double, Some(ExpressionType::div(Value::Number(2.0.into()), Value::Variable("i".to_owned()))));
+    main_func_block.add_print("%.1f".to_owned(), vec![Value::Variable("d".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "integer_to_double", "-3.5");
+}
+
+#[test]
+fn double_to_integer() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("d", NumberType::Double, Some(ExpressionType::value(9.75.into())));
+    main_func_block.add_declare("i", NumberType::I32, Some(ExpressionType::value(Value::Variable("d".to_owned()))));
+    main_func_block.add_return_variable("i");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "double_to_integer", 9);
+}
+
+#[test]
+fn double_negate_and_increment() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::Double, Some(ExpressionType::value(2.5.into())));
+    main_func_block.add_declare("b", NumberType::Double, Some(ExpressionType::bitwise_neg(Value::Variable("a".to_owned()))));
+    main_func_block.add_declare("c", NumberType::Double, Some(ExpressionType::inc("a".to_owned())));
+    main_func_block.add_print("%.1f %.1f".to_owned(), vec![Value::Variable("b".to_owned()), Value::Variable("c".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "double_negate_and_increment", "-2.5 3.5");
+}
+
+#[test]
+fn double_compare() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("x", NumberType::Double, Some(ExpressionType::value(0.0.into())));
+    main_func_block.add_declare("count", NumberType::I32, Some(ExpressionType::value(Value::Number(0.into()))));
+
+    let mut loop_block = BlockType::default();
+    loop_block.add_assign("x", ExpressionType::add(Value::Number(0.25.into()), Value::Variable("x".to_owned())));
+    loop_block.add_assign("count", ExpressionType::add(Value::Number(1.into()), Value::Variable("count".to_owned())));
+
+    let mut while_block = WhileBlockType::default();
+    while_block.set_condition(ConditionType::ls(Value::Variable("x".to_owned()), Value::Number(1.into())));
+    while_block.set_block(loop_block);
+
+    main_func_block.add_while(while_block);
+    main_func_block.add_return_variable("count");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "double_compare", 4);
+}
+
+#[test]
+fn double_parameters_and_return() {
+    let mut scale_func = FunctionType::main();
+    scale_func.set_name("scale");
+    scale_func.add_parameter("x", NumberType::Double);
+    scale_func.add_parameter("n", NumberType::I64);
+    scale_func.add_parameter("y", NumberType::Double);
+
+    let mut scale_func_block = BlockType::default();
+    scale_func_block.add_declare("result", NumberType::Double, Some(ExpressionType::add(Value::Variable("n".to_owned()), ExpressionType::mul(Value::Variable("y".to_owned()), Value::Variable("x".to_owned())))));
+    scale_func_block.add_return_variable("result");
+    scale_func.set_body(scale_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("total", NumberType::Double, None);
+    main_func_block.add_call_and_assign("scale".to_owned(), vec![1.5.into(), 2.into(), 4.0.into()], "total".to_owned());
+    main_func_block.add_print("Total: %.1f".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(scale_func);
+
+    check_output(application_type, "double_parameters_and_return", "Total: 8.0");
+}
+
+#[test]
+fn large_unsigned_to_float() {
+    let source = r#"
+fn main() {
+    let a: u64 = 18446744073709551615u64;
+    let b: u64 = 9223372036854775809u64;
+    let c: u64 = 12345u64;
+    let d: f64 = a;
+    let e: f64 = b;
+    let f: f32 = b;
+    let g: f64 = c;
+    print "%.17g %.17g %.17g %.17g", d, e, f, g;
+    return 0;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(source).unwrap();
+    let expected = "1.8446744073709552e+19 9.2233720368547758e+18 9.2233720368547758e+18 12345";
+
+    assert_eq!(expected, application_type.clone().interpret().unwrap().output);
+    check_output(application_type, "large_unsigned_to_float", expected);
+}
+
+#[test]
+fn large_unsigned_to_float_rounding() {
+    // Values are just above the half of the gap, halving without the lowest bit makes them a tie and they are rounded down
+    let source = r#"
+fn main() {
+    let a: u64 = 9223372586610589697u64;
+    let b: u64 = 9223372036854776833u64;
+    let c: f32 = a;
+    let d: f64 = b;
+    print "%.17g %.17g", c, d;
+    return 0;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(source).unwrap();
+    let expected = "9.2233731363664036e+18 9.2233720368547779e+18";
+
+    assert_eq!(expected, application_type.clone().interpret().unwrap().output);
+    check_output(application_type, "large_unsigned_to_float_rounding", expected);
+}
@@ -5,8 +5,10 @@ use tb_builder::FunctionType;
 use tb_core::types::Number;
 use tb_core::types::NumberType;
 use tb_core::types::Value;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
 
 use super::check_output;
+use super::check_output_with_c;
 
 #[test]
 fn func_call_1() {
@@ -76,3 +78,177 @@ fn func_call_2() {
 
     check_output(application_type, "func_call_2", "Total value: 32");
 }
+
+#[test]
+fn func_call_stack_arguments() {
+    let mut mix_func = FunctionType::main();
+    mix_func.set_name("mix");
+
+    for name in ["a", "b", "c", "d", "e", "f", "g", "h"] {
+        mix_func.add_parameter(name, NumberType::I64);
+    }
+
+    for name in ["x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9"] {
+        mix_func.add_parameter(name, NumberType::Double);
+    }
+
+    mix_func.add_parameter("small", NumberType::I16);
+
+    // (g + h + small) + (x8 + x9)
+    let mut mix_func_block = BlockType::default();
+    let integers = ExpressionType::add(Value::Variable("small".to_owned()), ExpressionType::add(Value::Variable("h".to_owned()), Value::Variable("g".to_owned())));
+    let doubles = ExpressionType::add(Value::Variable("x9".to_owned()), Value::Variable("x8".to_owned()));
+    mix_func_block.add_declare("result", NumberType::Double, Some(ExpressionType::add(doubles, integers)));
+    mix_func_block.add_return_variable("result");
+    mix_func.set_body(mix_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("total", NumberType::Double, None);
+    main_func_block.add_call_and_assign("mix".to_owned(), vec![
+        1.into(), 2.into(), 3.into(), 4.into(), 5.into(), 6.into(), 70.into(), 800.into(),
+        0.5.into(), 1.5.into(), 2.5.into(), 3.5.into(), 4.5.into(), 5.5.into(), 6.5.into(), 0.25.into(), 0.125.into(),
+        Value::Number(Number::I16(-9000))
+    ], "total".to_owned());
+    main_func_block.add_print("Total: %.3f".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(mix_func);
+
+    check_output(application_type, "func_call_stack_arguments", "Total: -8129.625");
+}
+
+#[test]
+fn func_call_stack_before_parameters() {
+    let mut sum_func = FunctionType::main();
+    sum_func.set_name("sum");
+
+    let names = (0..20).map(|index| format!("p{}", index)).collect::<Vec<_>>();
+    for name in names.iter() {
+        sum_func.add_parameter(name, NumberType::I64);
+    }
+
+    let mut sum_func_block = BlockType::default();
+    sum_func_block.add_declare("result", NumberType::I64, Some(ExpressionType::add(Value::Variable("p19".to_owned()), Value::Variable("p0".to_owned()))));
+    sum_func_block.add_return_variable("result");
+    sum_func.set_body(sum_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+    main_func_block.add_declare("total", NumberType::I64, None);
+    main_func_block.add_call_and_assign("sum".to_owned(), (1..=20).map(|value: i32| value.into()).collect(), "total".to_owned());
+    main_func_block.add_print("Total: %d".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(sum_func);
+
+    // Parameters are stored below the red zone, the stack is allocated before them
+    let source = application_type.clone().build::<X86AssemblyGenerator>().unwrap();
+    let function = &source[source.find("\nsum:").unwrap()..];
+    assert!(function.find("subq $").unwrap() < function.find("%rdi, -8(%rbp)").unwrap(), "{}", source);
+
+    check_output(application_type, "func_call_stack_before_parameters", "Total: 21");
+}
+
+#[test]
+fn func_call_argument_conversion() {
+    // Arguments are converted to the parameter types, the last float and integer arguments are on the stack
+    let source = r#"
+fn convert(a: f64, b: i64, c: f32, d: i32, e: f64, f: f64, g: f64, h: f64, i: f64, j: f64, k: f32, l: i64, m: i64, n: i64, o: i64, p: i16) {
+    print "%.2f %lld %.2f %d %.2f %.2f %lld %lld %d\n", a, b, c, d, i, k, l, o, p;
+    return 0;
+}
+
+fn main() {
+    let half: f32 = 0.5f32;
+    let small: i32 = -7;
+    let wide: f64 = 9.75;
+    let unsigned: u8 = 200u8;
+    let result: i32;
+    result = call convert(half, small, small, wide, 1.0, 2.0, 3.0, 4.0, small, half, unsigned, unsigned, small, 1, wide, 70000);
+    return 0;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(source).unwrap();
+    let execution = application_type.clone().interpret().unwrap();
+    assert_eq!("0.50 -7 -7.00 9 -7.00 200.00 200 9 4464\n", execution.output);
+    check_output(application_type, "func_call_argument_conversion", &execution.output);
+}
+
+#[test]
+fn func_call_variadic_stack_arguments() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // Format and five integers fill the registers, the last double, two integers, a string and a variable are pushed
+    main_func_block.add_declare("last", NumberType::U8, Some(ExpressionType::value(Value::Number(Number::U8(200)))));
+    main_func_block.add_print("%d %d %d %d %d %.1f %.1f %.1f %.1f %.1f %.1f %.1f %.1f %.1f %d %d %s %d".to_owned(), vec![
+        1.into(), 2.into(), 3.into(), 4.into(), 5.into(),
+        0.5.into(), 1.5.into(), 2.5.into(), 3.5.into(), 4.5.into(), 5.5.into(), 6.5.into(), 7.5.into(), 8.5.into(),
+        6.into(), 7.into(), Value::String("text".to_owned()), Value::Variable("last".to_owned())
+    ]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "func_call_variadic_stack_arguments", "1 2 3 4 5 0.5 1.5 2.5 3.5 4.5 5.5 6.5 7.5 8.5 6 7 text 200");
+}
+
+#[test]
+fn func_call_c_interop() {
+    let c_source = r#"
+long tb_weighted(long a, long b, long c, long d, long e, long f, long g, long h);
+
+double c_mix(long a, long b, long c, long d, long e, long f, long g, double x1, double x2, double x3, double x4, double x5, double x6, double x7, double x8, double x9, char *text) {
+    return a + b + c + d + e + f + g * 10 + x1 + x9 * 100 + text[0];
+}
+
+long c_call_back(void) {
+    return tb_weighted(1, 2, 3, 4, 5, 6, 7, 8);
+}
+"#;
+
+    let mut weighted_func = FunctionType::main();
+    weighted_func.set_name("tb_weighted");
+
+    for name in ["a", "b", "c", "d", "e", "f", "g", "h"] {
+        weighted_func.add_parameter(name, NumberType::I64);
+    }
+
+    // h * 100 + g * 10 + a
+    let mut weighted_func_block = BlockType::default();
+    let tens = ExpressionType::add(Value::Variable("a".to_owned()), ExpressionType::mul(Value::Number(10.into()), Value::Variable("g".to_owned())));
+    weighted_func_block.add_assign("result", ExpressionType::add(tens, ExpressionType::mul(Value::Number(100.into()), Value::Variable("h".to_owned()))));
+    weighted_func_block.add_return_variable("result");
+    weighted_func.set_body(weighted_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("mixed", NumberType::Double, None);
+    main_func_block.add_call_and_assign("c_mix".to_owned(), vec![
+        1.into(), 2.into(), 3.into(), 4.into(), 5.into(), 6.into(), 7.into(),
+        0.5.into(), 0.0.into(), 0.0.into(), 0.0.into(), 0.0.into(), 0.0.into(), 0.0.into(), 0.0.into(), 2.0.into(),
+        Value::String("A".to_owned())
+    ], "mixed".to_owned());
+    main_func_block.add_call_and_assign("c_call_back".to_owned(), Vec::new(), "weighted".to_owned());
+    main_func_block.add_print("%.1f %ld".to_owned(), vec![Value::Variable("mixed".to_owned()), Value::Variable("weighted".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(weighted_func);
+
+    check_output_with_c(application_type, "func_call_c_interop", c_source, "356.5 871");
+}
@@ -5,6 +5,7 @@ use tb_builder::ExpressionType;
 use tb_builder::FunctionType;
 use tb_builder::IfBlockType;
 use tb_core::types::Number;
+use tb_core::types::NumberType;
 use tb_core::types::Value;
 
 use super::get_exit_code;
@@ -470,3 +471,122 @@ fn no_else_2() {
 
     get_exit_code(application_type, "no_else_2", 1);
 }
+
+/// Set the result to 1 if the condition is true, the result is the exit code
+fn condition_result(main_func_block: &mut BlockType, condition: ConditionType) {
+    let mut if_condition_true_block = BlockType::default();
+    if_condition_true_block.add_assign("result", ExpressionType::value(Value::Number(1.into())));
+
+    let mut if_condition_false_block = BlockType::default();
+    if_condition_false_block.add_assign("result", ExpressionType::value(Value::Number(0.into())));
+
+    let mut if_condition = IfBlockType::default();
+    if_condition.set_condition(condition);
+    if_condition.set_true_block(if_condition_true_block);
+    if_condition.set_false_block(if_condition_false_block);
+
+    main_func_block.add_if(if_condition);
+}
+
+#[test]
+fn signed_less_negative() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I32, Some(ExpressionType::value(Value::Number((-5).into()))));
+    condition_result(&mut main_func_block, ConditionType::ls(Value::Variable("a".to_owned()), Value::Number(3.into())));
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "signed_less_negative", 1);
+}
+
+#[test]
+fn signed_greater_equal_negative() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I64, Some(ExpressionType::value(Value::Number((-1).into()))));
+    main_func_block.add_declare("b", NumberType::I64, Some(ExpressionType::value(Value::Number((-2).into()))));
+    condition_result(&mut main_func_block, ConditionType::ge(Value::Variable("a".to_owned()), Value::Variable("b".to_owned())));
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "signed_greater_equal_negative", 1);
+}
+
+#[test]
+fn unsigned_greater() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // Same bits are negative as a signed number
+    main_func_block.add_declare("a", NumberType::U32, Some(ExpressionType::value(Value::Number(Number::U32(4_000_000_000)))));
+    condition_result(&mut main_func_block, ConditionType::gr(Value::Variable("a".to_owned()), Value::Number(Number::U32(5))));
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "unsigned_greater", 1);
+}
+
+#[test]
+fn double_less_negative() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::Double, Some(ExpressionType::value((-0.5).into())));
+    condition_result(&mut main_func_block, ConditionType::le(Value::Variable("a".to_owned()), Value::Number(0.25.into())));
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "double_less_negative", 1);
+}
+
+#[test]
+fn double_nan_comparisons() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // Only "not equal" is true for NaN, every true condition adds a different bit
+    main_func_block.add_declare("nan", NumberType::Double, Some(ExpressionType::div(Value::Number(0.0.into()), Value::Number(0.0.into()))));
+    main_func_block.add_assign("total", ExpressionType::value(Value::Number(0.into())));
+
+    let conditions = [
+        ConditionType::eq(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+        ConditionType::ne(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+        ConditionType::gr(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+        ConditionType::ge(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+        ConditionType::ls(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+        ConditionType::le(Value::Variable("nan".to_owned()), Value::Number(1.0.into())),
+    ];
+
+    for (index, condition) in conditions.into_iter().enumerate() {
+        let mut if_condition_true_block = BlockType::default();
+        if_condition_true_block.add_assign("total", ExpressionType::add(Value::Number((1 << index).into()), Value::Variable("total".to_owned())));
+
+        let mut if_condition = IfBlockType::default();
+        if_condition.set_condition(condition);
+        if_condition.set_true_block(if_condition_true_block);
+        main_func_block.add_if(if_condition);
+    }
+
+    main_func_block.add_return_variable("total");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "double_nan_comparisons", 2);
+}
@@ -0,0 +1,100 @@
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::ApplicationType;
+use tb_core::compiler::{CompilerTrait, TBCompiler};
+use tb_core::syntax::Syntax;
+use tb_core::target::Target;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use super::differential::generator::Generator;
+
+/// Text section of the assembled source
+fn assemble(source: &str, file_name: &str) -> Vec<u8> {
+    let mut source_file_name = temp_dir();
+    let mut object_file_name = temp_dir();
+    let mut binary_file_name = temp_dir();
+
+    source_file_name.push(format!("{}.s", file_name));
+    object_file_name.push(format!("{}.o", file_name));
+    binary_file_name.push(format!("{}.bin", file_name));
+
+    fs::write(&source_file_name, source).unwrap();
+
+    let status = Command::new("as").arg("-o").arg(&object_file_name).arg(&source_file_name).status().unwrap();
+    assert!(status.success(), "{} is not assembled", source_file_name.display());
+
+    let status = Command::new("objcopy").args(["-O", "binary", "--only-section=.text"]).arg(&object_file_name).arg(&binary_file_name).status().unwrap();
+    assert!(status.success());
+
+    fs::read(binary_file_name).unwrap()
+}
+
+#[test]
+fn intel_ir_program() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+
+    let mut source_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+    source_file_name.push("intel_ir_program.s");
+    executable_name.push("intel_ir_program.exe");
+
+    fs::write(&source_file_name, application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Intel).unwrap()).unwrap();
+
+    let compiler = TBCompiler::get_compiler().unwrap();
+    compiler.compile(&source_file_name, &executable_name, Default::default()).unwrap();
+
+    let output = Command::new(executable_name).output().unwrap();
+    assert_eq!("27 111\n", String::from_utf8(output.stdout).unwrap());
+}
+
+#[test]
+fn intel_operands() {
+    let application_type = ApplicationType::from_ir(r#"fn scale(value: i64, small: i8, ratio: f64) {
+    let result: f64 = ratio * 2.5;
+    return value;
+}"#).unwrap();
+
+    let source = application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Intel).unwrap();
+    assert!(source.starts_with(".intel_syntax noprefix\r\n"), "{}", source);
+    assert!(source.contains("mov rbp, rsp\r\n"), "{}", source);
+    assert!(source.contains("mov qword ptr [rbp-8], rdi"), "{}", source);
+    assert!(source.contains("mov byte ptr [rbp-9], sil"), "{}", source);
+    assert!(source.contains("movsd qword ptr [rbp-24], xmm0"), "{}", source);
+    assert!(source.contains("mulsd xmm"), "{}", source);
+    assert!(source.contains("qword ptr [rip+.LC"), "{}", source);
+    assert!(!source.contains('%'), "{}", source);
+}
+
+#[test]
+fn intel_generated_programs() {
+    for seed in 0..20 {
+        let definitions = Generator::new(seed).generate();
+
+        let mut application_type = ApplicationType::default();
+        definitions.into_iter().for_each(|definition| application_type.add_definition(definition));
+
+        // Both syntaxes should be assembled to the same machine code
+        let att = assemble(&application_type.clone().build::<X86AssemblyGenerator>().unwrap(), &format!("intel_att_{}", seed));
+        let intel = assemble(&application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Intel).unwrap(), &format!("intel_generated_{}", seed));
+        assert_eq!(att, intel, "seed {}", seed);
+    }
+}
+
+#[test]
+fn intel_windows_programs() {
+    for seed in 0..10 {
+        let definitions = Generator::new(seed).generate();
+
+        let mut application_type = ApplicationType::default();
+        application_type.set_target(Target::X86_64_WINDOWS);
+        definitions.into_iter().for_each(|definition| application_type.add_definition(definition));
+
+        // GNU assembler for ELF does not know the COFF section flags
+        let att = application_type.clone().build::<X86AssemblyGenerator>().unwrap().replace(".rdata,\"dr\"", ".rodata");
+        let intel = application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Intel).unwrap().replace(".rdata,\"dr\"", ".rodata");
+        assert!(att.contains("# shadow space"), "seed {}", seed);
+        assert_eq!(assemble(&att, &format!("intel_windows_att_{}", seed)), assemble(&intel, &format!("intel_windows_{}", seed)), "seed {}", seed);
+    }
+}
@@ -0,0 +1,225 @@
+use tb_builder::ApplicationType;
+use tb_core::interpreter::{Interpreter, InterpreterError};
+use tb_core::ir;
+use tb_core::types::DataItemCollection;
+
+use super::check_output;
+use super::get_exit_code;
+
+/// Interpreter and the native code should print the same output
+fn check_both(application: ApplicationType, file_name: &str, expected_message: &str) {
+    let execution = application.clone().interpret().unwrap();
+    assert_eq!(expected_message, execution.output);
+    check_output(application, file_name, expected_message);
+}
+
+#[test]
+fn interpret_ir_file() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+    let execution = application_type.interpret().unwrap();
+
+    assert_eq!("27 111\n", execution.output);
+    assert_eq!(0, execution.exit_code);
+}
+
+#[test]
+fn interpret_integer_widths() {
+    let source = r#"
+fn main() {
+    let a: u8 = 250u8;
+    let b: u8 = a + 10u8;
+    let c: i8 = -128i8;
+    let d: i8 = c - 1;
+    let e: i32 = 2147483647;
+    let f: i32 = e + 1;
+    let g: u32 = 4000000000u32;
+    let h: u32 = g / 3u32;
+    let i: i32 = -7 / 2;
+    let j: i32 = -7 % 3;
+    let k: i32 = -16 >> 2;
+    let l: u32 = 4294967280u32 >> 2;
+    let m: i32 = 1 << 33;
+    let n: i64 = 1i64 << 33;
+    let o: u16 = ~0u16;
+    let p: i64 = a * 1000000000000i64;
+    print "%d %d %d %u %d %d %d %u %d %lld %d %lld\n", b, d, f, h, i, j, k, l, m, n, o, p;
+    return 0;
+}
+"#;
+
+    check_both(ApplicationType::from_ir(source).unwrap(), "interpret_integer_widths", "4 127 -2147483648 1333333333 -3 -1 -4 1073741820 2 8589934592 65535 250000000000000\n");
+}
+
+#[test]
+fn interpret_printf_formats() {
+    let source = r#"
+fn main() {
+    let a: i32 = -42;
+    let b: f64 = 3.14159;
+    let c: f32 = 0.5f32;
+    let d: u64 = 255u64;
+    print "[%5d][%-5d][%05d][%+d][% d][%.3d]\n", a, a, a, 42, 42, 7;
+    print "[%x][%X][%#x][%o][%#o][%lu][%hhd]\n", d, d, d, d, d, d, d;
+    print "[%f][%.2f][%10.3f][%-10.1f][%e][%.0e][%E]\n", b, b, b, b, b, b, 0.000123;
+    print "[%g][%g][%g][%g][%.3g][%#g][%G]\n", b, 100000.0, 1000000.0, 0.0001, 1234.5, 1.0, 0.00001;
+    print "[%s][%8s][%-8s][%.2s][%c][%%][%f]\n", "text", "text", "text", "text", 65, c;
+    return 0;
+}
+"#;
+
+    check_both(ApplicationType::from_ir(source).unwrap(), "interpret_printf_formats", concat!(
+        "[  -42][-42  ][-0042][+42][ 42][007]\n",
+        "[ff][FF][0xff][377][0377][255][-1]\n",
+        "[3.141590][3.14][     3.142][3.1       ][3.141590e+00][3e+00][1.230000E-04]\n",
+        "[3.14159][100000][1e+06][0.0001][1.23e+03][1.00000][1E-05]\n",
+        "[text][    text][text    ][te][A][%][0.500000]\n"));
+}
+
+#[test]
+fn interpret_function_calls() {
+    let source = r#"
+fn fib(n: i64) {
+    let result: i64 = n;
+    if n > 1i64 {
+        let a: i64 = n - 1;
+        let b: i64 = n - 2;
+        let x: i64;
+        let y: i64;
+        x = call fib(a);
+        y = call fib(b);
+        result = x + y;
+    }
+    return result;
+}
+
+fn scale(value: f64, count: i32) {
+    let result: f64 = value * count;
+    return result;
+}
+
+fn main() {
+    let total: i64;
+    let scaled: f64;
+    total = call fib(15);
+    scaled = call scale(1.25, 3);
+    print "%lld %.2f\n", total, scaled;
+    return total;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(source).unwrap();
+    let execution = application_type.clone().interpret().unwrap();
+
+    // Exit code is the lowest byte of the return value
+    assert_eq!("610 3.75\n", execution.output);
+    assert_eq!(610 & 0xff, execution.exit_code);
+
+    check_both(application_type.clone(), "interpret_function_calls", "610 3.75\n");
+    get_exit_code(application_type, "interpret_function_calls_exit", 610 & 0xff);
+}
+
+#[test]
+fn interpret_loops() {
+    let source = r#"
+fn main() {
+    let total: u8 = 0u8;
+    let i: i32 = 0;
+    loop {
+        i = i + 1;
+        if (i % 3) == 0 {
+            continue;
+        }
+        if i > 40 {
+            break;
+        }
+        total = total + (i * 7);
+    }
+    while !(i < 0) && (i < 100) {
+        i = i + 20;
+    }
+    print "%d %d\n", total, i;
+    return total;
+}
+"#;
+
+    check_both(ApplicationType::from_ir(source).unwrap(), "interpret_loops", "245 101\n");
+}
+
+#[test]
+fn interpret_integer_to_float() {
+    // Just above the half of the float gap, rounding through double makes them a tie and they are rounded down
+    let source = r#"
+fn main() {
+    let a: u64 = 9223372586610589697u64;
+    let b: i64 = 4611686293305294849i64;
+    let c: f32 = a;
+    let d: f32 = b;
+    print "%.17g %.17g\n", c, d;
+    return 0;
+}
+"#;
+
+    let execution = ApplicationType::from_ir(source).unwrap().interpret().unwrap();
+    assert_eq!("9.2233731363664036e+18 4.6116865681832018e+18\n", execution.output);
+}
+
+#[test]
+fn interpret_errors() {
+    let cases = [
+        ("fn main() {\n    let a: i32 = 0;\n    let b: i32 = 10 / a;\n}", InterpreterError::DivisionByZero),
+        ("fn main() {\n    let a: i64 = -9223372036854775808i64;\n    let b: i64 = a / -1i64;\n}", InterpreterError::DivisionOverflow),
+        ("fn main() {\n    let a: i32;\n    let b: i32 = a + 1;\n}", InterpreterError::UninitializedVariable("a".to_owned())),
+        ("fn main() {\n    b = c;\n}", InterpreterError::VariableNotFound("c".to_owned())),
+        ("fn main() {\n    call missing(1);\n}", InterpreterError::FunctionNotFound("missing".to_owned())),
+        ("fn f(a: i32) {\n    return;\n}\nfn main() {\n    call f(1, 2);\n}", InterpreterError::ArgumentCount { name: "f".to_owned(), expected: 1, found: 2 }),
+        ("fn f() {\n    call f();\n}\nfn main() {\n    call f();\n}", InterpreterError::CallDepthExceeded),
+        ("fn main() {\n    let a: f64 = 1.5 % 2.0;\n}", InterpreterError::UnsupportedFloatOperation),
+    ];
+
+    for (source, error) in cases.into_iter() {
+        assert_eq!(Err(error), ApplicationType::from_ir(source).unwrap().interpret(), "{}", source);
+    }
+}
+
+#[test]
+fn interpret_step_limit() {
+    // Loops without a statement are also stopped
+    let sources = [
+        "fn main() {\n    let a: i32 = 0;\n    while a < 10 {\n        a = a + 0;\n    }\n}",
+        "fn main() {\n    let a: i32 = 0;\n    while a < 10 {\n    }\n}",
+        "fn main() {\n    loop {\n    }\n}"
+    ];
+
+    for source in sources.into_iter() {
+        let mut interpreter = Interpreter::new(ir::parse(source).unwrap(), DataItemCollection::default());
+        interpreter.set_step_limit(1000);
+        assert_eq!(Err(InterpreterError::StepLimitExceeded), interpreter.execute().map(|_| ()), "{}", source);
+    }
+}
+
+#[test]
+fn interpret_loop_declarations() {
+    // Reads before the declaration use the outer variable in every iteration like the native code, the last one stays visible after the loop
+    let source = r#"
+fn main() {
+    let a: i32 = 1;
+    let i: i32 = 0;
+    while i < 3 {
+        print "%d ", a;
+        let a: i32 = 10;
+        i = i + 1;
+    }
+    loop {
+        print "%d ", a;
+        let a: i32 = 20;
+        i = i + 1;
+        if i > 5 {
+            break;
+        }
+    }
+    return 0;
+}
+"#;
+
+    check_both(ApplicationType::from_ir(source).unwrap(), "interpret_loop_declarations", "1 1 1 10 10 10 ");
+}
@@ -0,0 +1,124 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::BuilderGenerate;
+use tb_builder::ConditionType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_builder::IfBlockType;
+use tb_core::ir;
+use tb_core::types::Number;
+use tb_core::types::NumberType;
+use tb_core::types::Value;
+
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use super::check_output;
+use super::source;
+
+pub const ALL_STATEMENTS: &str = r#"fn calculate(a: i64, b: f64, c: u8) {
+    let x: i32 = (a + 1) * (b - 2.5);
+    let y: u8;
+    let flag: bool = (x < 3) && !(c == 200u8);
+    y = ((c << 2) | (c >> 1)) ^ (~c & 15u8);
+    x = (x / -3) % (-(7i8) + -x);
+    x = inc(x) + dec(a);
+    flag = x >= (a <= b);
+    if (x > 0) || ((a != b) && !!(c == 0u8)) {
+        print "x: %d\n", x;
+    } else if x == -1 {
+        call printf("tab\t\"quote\" \\ \x01", x, 1.5f32, -2i16, true, ...);
+    } else {
+        y = call pick(a, -1e300, "text");
+    }
+    while !(x > 10) {
+        x = x + 1;
+        loop {
+            break;
+        }
+        continue;
+    }
+    return x;
+}
+
+fn empty() {
+    return;
+}
+"#;
+
+#[test]
+fn print_round_trip() {
+    let definitions = ir::parse(ALL_STATEMENTS).unwrap();
+    assert_eq!(ALL_STATEMENTS, ir::print(&definitions));
+    assert_eq!(definitions, ir::parse(&ir::print(&definitions)).unwrap());
+}
+
+#[test]
+fn builder_round_trip() {
+    let mut func = FunctionType::main();
+    func.set_name("build");
+    func.add_parameter("a", NumberType::I16);
+
+    let mut true_block = BlockType::default();
+    true_block.add_assign("b", ExpressionType::div(Value::Variable("a".to_owned()), ExpressionType::sub(Value::Number(Number::U64(u64::MAX)), Value::Number((-5).into()))));
+    true_block.add_call("show".to_owned(), vec![Value::Number(Number::Float(0.1)), Value::Number(Number::I8(i8::MIN))]);
+
+    let mut if_block = IfBlockType::default();
+    if_block.set_condition(ConditionType::or(!ConditionType::ge(Value::Variable("a".to_owned()), Value::Number(Number::Double(-0.0))), ConditionType::eq(ExpressionType::bitwise_neg(Value::Number(3.into())), Value::Number(i64::MIN.into()))));
+    if_block.set_true_block(true_block);
+
+    let mut block = BlockType::default();
+    block.add_declare("b", NumberType::Double, Some(ExpressionType::condition(ConditionType::ls(ExpressionType::shift_left(Value::Number(2.into()), Value::Variable("a".to_owned())), Value::Number(Number::U32(7))))));
+    block.add_if(if_block);
+    block.add_return_variable("b");
+    func.set_body(block);
+
+    let definition = func.convert();
+    let text = ir::print(std::slice::from_ref(&definition));
+    assert_eq!(vec![definition], ir::parse(&text).unwrap());
+}
+
+#[test]
+fn parse_errors() {
+    let cases = [
+        ("fn main() {\n    let x: i33 = 1;\n}", 2, 12, "unknown type 'i33'"),
+        ("fn main() {\n    x = 1\n}", 3, 1, "expected ';', found '}'"),
+        ("fn main() {\n    if x + 1 {\n    }\n}", 2, 8, "expected a condition"),
+        ("fn main() {\n    x = 300u8;\n}", 2, 9, "number '300' is out of range"),
+        ("fn main() {\n    x = 1.5i32;\n}", 2, 9, "fraction can not be i32"),
+        ("fn main() {\n    print \"abc;\n}", 2, 11, "unterminated string"),
+        ("fn main() {\n    x = a # b;\n}", 2, 11, "unexpected character '#'"),
+        ("fn main() {\n    return 1;\n", 3, 1, "expected '}', found end of input"),
+        ("main() {}", 1, 1, "expected 'fn', found 'main'"),
+    ];
+
+    for (source, line, column, message) in cases.into_iter() {
+        let error = ir::parse(source).unwrap_err();
+        assert_eq!((line, column, message), (error.line, error.column, error.message.as_str()), "{}", source);
+    }
+}
+
+#[test]
+fn ir_file_program() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+    check_output(application_type, "ir_file_program", "27 111\n");
+}
+
+#[test]
+fn ir_string_escapes() {
+    let ir = r#"
+fn main() {
+    print "tab\t\"quote\" \\ \x01\n";
+    return 0;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(ir).unwrap();
+    let expected = "tab\t\"quote\" \\ \x01\n";
+    assert_eq!(expected, application_type.clone().interpret().unwrap().output);
+
+    // Assembler strings could not have a line break, control characters are octal escapes
+    let assembly = source::<X86AssemblyGenerator>(application_type.clone());
+    assert!(assembly.contains(r#"    .string "tab\011\"quote\" \\ \001\012""#), "{}", assembly);
+
+    check_output(application_type, "ir_string_escapes", expected);
+}
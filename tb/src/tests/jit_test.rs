@@ -0,0 +1,82 @@
+use tb_builder::ApplicationType;
+use tb_core::jit::JitError;
+use tb_target_x86_64::compiler::error::X86Error;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+#[test]
+fn jit_sum() {
+    let application_type = ApplicationType::from_ir(r#"fn sum(a: i64, b: i64) {
+    let result: i64 = a + b;
+    return result;
+}"#).unwrap();
+
+    let jit = application_type.jit::<X86AssemblyGenerator>().unwrap();
+    let sum = unsafe { jit.get::<extern "C" fn(i64, i64) -> i64>("sum") }.unwrap();
+
+    assert_eq!(42, sum(40, 2));
+    assert_eq!(-5, sum(-10, 5));
+    assert!(unsafe { jit.get::<extern "C" fn() -> i64>("missing") }.is_none());
+}
+
+#[test]
+fn jit_loops_and_calls() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+
+    let jit = application_type.jit::<X86AssemblyGenerator>().unwrap();
+    let steps = unsafe { jit.get::<extern "C" fn(i64) -> i32>("steps") }.unwrap();
+
+    assert_eq!(111, steps(27));
+    assert_eq!(0, steps(1));
+}
+
+#[test]
+fn jit_double_constants() {
+    let application_type = ApplicationType::from_ir(r#"fn scale(value: f64) {
+    let result: f64 = (value * 2.5) - 0.25;
+    return result;
+}"#).unwrap();
+
+    let jit = application_type.jit::<X86AssemblyGenerator>().unwrap();
+    let scale = unsafe { jit.get::<extern "C" fn(f64) -> f64>("scale") }.unwrap();
+
+    assert_eq!(9.75, scale(4.0));
+}
+
+#[test]
+fn jit_external_function() {
+    let application_type = ApplicationType::from_ir(r#"fn greet(value: i32) {
+    let count: i32;
+    count = call printf("jit %d\n", value, ...);
+    return count;
+}"#).unwrap();
+
+    // printf returns the number of the written characters
+    let jit = application_type.jit::<X86AssemblyGenerator>().unwrap();
+    let greet = unsafe { jit.get::<extern "C" fn(i32) -> i32>("greet") }.unwrap();
+
+    assert_eq!(7, greet(42));
+}
+
+#[test]
+fn jit_unknown_symbol() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    call tb_missing_function();
+    return 0;
+}"#).unwrap();
+
+    let error = application_type.jit::<X86AssemblyGenerator>().unwrap_err();
+    assert!(matches!(error, JitError::SymbolNotFound(name) if name == "tb_missing_function"));
+}
+
+#[test]
+fn jit_code_generation_error() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    let a: i32 = b;
+    return a;
+}"#).unwrap();
+
+    // Error of the target is kept, it is not turned into a message
+    let Err(JitError::CodeGenerationFailed(error)) = application_type.jit::<X86AssemblyGenerator>() else { panic!("Code generation did not fail") };
+    assert_eq!(("main", 0), (error.function.as_str(), error.statement));
+    assert!(matches!(error.error.downcast_ref::<X86Error>(), Some(X86Error::VariableNotFound(name)) if name == "b"));
+}
@@ -0,0 +1,143 @@
+target triple = "x86_64-pc-linux-gnu"
+
+@.str.1 = private unnamed_addr constant [7 x i8] c"x: %d\0A\00"
+@.str.2 = private unnamed_addr constant [16 x i8] c"tab\09\22quote\22 \5C \01\00"
+@.str.3 = private unnamed_addr constant [5 x i8] c"text\00"
+
+declare i32 @printf(ptr, ...)
+declare i64 @pick(...)
+
+define i64 @calculate(i64 %a.param, double %b.param, i8 %c.param) {
+entry:
+  %a = alloca i64
+  %b = alloca double
+  %c = alloca i8
+  %x = alloca i32
+  %y = alloca i8
+  %flag = alloca i1
+  store i64 %a.param, ptr %a
+  store double %b.param, ptr %b
+  store i8 %c.param, ptr %c
+  %.t1 = load i64, ptr %a
+  %.t2 = add i64 %.t1, 1
+  %.t3 = sitofp i64 %.t2 to double
+  %.t4 = load double, ptr %b
+  %.t5 = fsub double %.t4, 2.5
+  %.t6 = fmul double %.t3, %.t5
+  %.t7 = fptosi double %.t6 to i32
+  store i32 %.t7, ptr %x
+  %.t8 = load i32, ptr %x
+  %.t9 = icmp slt i32 %.t8, 3
+  br i1 %.t9, label %.L4, label %.L2
+.L4:
+  %.t10 = load i8, ptr %c
+  %.t11 = zext i8 %.t10 to i32
+  %.t12 = icmp eq i32 %.t11, 200
+  br i1 %.t12, label %.L2, label %.L1
+.L1:
+  br label %.L3
+.L2:
+  br label %.L3
+.L3:
+  %.t13 = phi i1 [ true, %.L1 ], [ false, %.L2 ]
+  store i1 %.t13, ptr %flag
+  %.t14 = load i8, ptr %c
+  %.t15 = zext i8 %.t14 to i32
+  %.t16 = and i32 2, 31
+  %.t17 = shl i32 %.t15, %.t16
+  %.t18 = load i8, ptr %c
+  %.t19 = zext i8 %.t18 to i32
+  %.t20 = and i32 1, 31
+  %.t21 = ashr i32 %.t19, %.t20
+  %.t22 = or i32 %.t17, %.t21
+  %.t23 = load i8, ptr %c
+  %.t24 = zext i8 %.t23 to i32
+  %.t25 = xor i32 %.t24, -1
+  %.t26 = and i32 %.t25, 15
+  %.t27 = xor i32 %.t22, %.t26
+  %.t28 = trunc i32 %.t27 to i8
+  store i8 %.t28, ptr %y
+  %.t29 = load i32, ptr %x
+  %.t30 = sdiv i32 %.t29, -3
+  %.t31 = sub i32 0, 7
+  %.t32 = load i32, ptr %x
+  %.t33 = sub i32 0, %.t32
+  %.t34 = add i32 %.t31, %.t33
+  %.t35 = srem i32 %.t30, %.t34
+  store i32 %.t35, ptr %x
+  %.t36 = load i32, ptr %x
+  %.t37 = add i32 %.t36, 1
+  %.t38 = sext i32 %.t37 to i64
+  %.t39 = load i64, ptr %a
+  %.t40 = sub i64 %.t39, 1
+  %.t41 = add i64 %.t38, %.t40
+  %.t42 = trunc i64 %.t41 to i32
+  store i32 %.t42, ptr %x
+  %.t43 = load i32, ptr %x
+  %.t44 = load i64, ptr %a
+  %.t45 = sitofp i64 %.t44 to double
+  %.t46 = load double, ptr %b
+  %.t47 = fcmp ole double %.t45, %.t46
+  %.t48 = zext i1 %.t47 to i32
+  %.t49 = icmp sge i32 %.t43, %.t48
+  store i1 %.t49, ptr %flag
+  %.t50 = load i32, ptr %x
+  %.t51 = icmp sgt i32 %.t50, 0
+  br i1 %.t51, label %.L5, label %.L7
+.L7:
+  %.t52 = load i64, ptr %a
+  %.t53 = sitofp i64 %.t52 to double
+  %.t54 = load double, ptr %b
+  %.t55 = fcmp une double %.t53, %.t54
+  br i1 %.t55, label %.L8, label %.L6
+.L8:
+  %.t56 = load i8, ptr %c
+  %.t57 = zext i8 %.t56 to i32
+  %.t58 = icmp eq i32 %.t57, 0
+  br i1 %.t58, label %.L5, label %.L6
+.L5:
+  %.t59 = load i32, ptr %x
+  %.t60 = call i32 (ptr, ...) @printf(ptr @.str.1, i32 %.t59)
+  br label %.L9
+.L6:
+  %.t61 = load i32, ptr %x
+  %.t62 = icmp eq i32 %.t61, -1
+  br i1 %.t62, label %.L10, label %.L11
+.L10:
+  %.t63 = load i32, ptr %x
+  %.t64 = call i32 (ptr, ...) @printf(ptr @.str.2, i32 %.t63, double 1.5, i32 -2, i32 1)
+  br label %.L12
+.L11:
+  %.t65 = load i64, ptr %a
+  %.t66 = call i64 (...) @pick(i64 %.t65, double 0xFE37E43C8800759C, ptr @.str.3)
+  %.t67 = trunc i64 %.t66 to i8
+  store i8 %.t67, ptr %y
+  br label %.L12
+.L12:
+  br label %.L9
+.L9:
+  br label %.L13
+.L13:
+  %.t68 = load i32, ptr %x
+  %.t69 = icmp sgt i32 %.t68, 10
+  br i1 %.t69, label %.L14, label %.L15
+.L15:
+  %.t70 = load i32, ptr %x
+  %.t71 = add i32 %.t70, 1
+  store i32 %.t71, ptr %x
+  br label %.L16
+.L16:
+  br label %.L17
+.L17:
+  br label %.L13
+.L14:
+  %.t72 = load i32, ptr %x
+  %.t73 = sext i32 %.t72 to i64
+  ret i64 %.t73
+}
+
+define void @empty() {
+entry:
+  ret void
+}
+
@@ -0,0 +1,88 @@
+target triple = "x86_64-pc-linux-gnu"
+
+@.str.1 = private unnamed_addr constant [9 x i8] c"%lld %d\0A\00"
+
+declare i32 @printf(ptr, ...)
+
+define i64 @steps(i64 %start.param) {
+entry:
+  %start = alloca i64
+  %value = alloca i64
+  %count = alloca i32
+  store i64 %start.param, ptr %start
+  %.t1 = load i64, ptr %start
+  store i64 %.t1, ptr %value
+  store i32 0, ptr %count
+  br label %.L1
+.L1:
+  %.t2 = load i64, ptr %value
+  %.t3 = icmp ne i64 %.t2, 1
+  br i1 %.t3, label %.L3, label %.L2
+.L3:
+  %.t4 = load i64, ptr %value
+  %.t5 = srem i64 %.t4, 2
+  %.t6 = icmp eq i64 %.t5, 0
+  br i1 %.t6, label %.L4, label %.L5
+.L4:
+  %.t7 = load i64, ptr %value
+  %.t8 = sdiv i64 %.t7, 2
+  store i64 %.t8, ptr %value
+  br label %.L6
+.L5:
+  %.t9 = load i64, ptr %value
+  %.t10 = mul i64 %.t9, 3
+  %.t11 = add i64 %.t10, 1
+  store i64 %.t11, ptr %value
+  br label %.L6
+.L6:
+  %.t12 = load i32, ptr %count
+  %.t13 = add i32 %.t12, 1
+  store i32 %.t13, ptr %count
+  br label %.L1
+.L2:
+  %.t14 = load i32, ptr %count
+  %.t15 = sext i32 %.t14 to i64
+  ret i64 %.t15
+}
+
+define i32 @main() {
+entry:
+  %best = alloca i32
+  %best_start = alloca i64
+  %start = alloca i64
+  %count = alloca i32
+  store i32 0, ptr %best
+  store i64 0, ptr %best_start
+  store i64 1, ptr %start
+  br label %.L1
+.L1:
+  %.t1 = load i64, ptr %start
+  %.t2 = icmp slt i64 %.t1, 30
+  br i1 %.t2, label %.L3, label %.L2
+.L3:
+  %.t3 = load i64, ptr %start
+  %.t4 = call i64 @steps(i64 %.t3)
+  %.t5 = trunc i64 %.t4 to i32
+  store i32 %.t5, ptr %count
+  %.t6 = load i32, ptr %count
+  %.t7 = load i32, ptr %best
+  %.t8 = icmp sgt i32 %.t6, %.t7
+  br i1 %.t8, label %.L4, label %.L5
+.L4:
+  %.t9 = load i32, ptr %count
+  store i32 %.t9, ptr %best
+  %.t10 = load i64, ptr %start
+  store i64 %.t10, ptr %best_start
+  br label %.L5
+.L5:
+  %.t11 = load i64, ptr %start
+  %.t12 = add i64 %.t11, 1
+  store i64 %.t12, ptr %start
+  br label %.L1
+.L2:
+  %.t13 = load i64, ptr %best_start
+  %.t14 = load i32, ptr %best
+  %.t15 = call i32 (ptr, ...) @printf(ptr @.str.1, i64 %.t13, i32 %.t14)
+  ret i32 0
+}
+
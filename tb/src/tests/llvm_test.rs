@@ -0,0 +1,122 @@
+use core::str;
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::ApplicationType;
+use tb_core::ir;
+use tb_core::target::Target;
+use tb_core::types::Definition;
+use tb_target_llvm::generator::LlvmIrGenerator;
+
+use super::differential::compare_backend;
+use super::ir_test::ALL_STATEMENTS;
+use super::{application, collatz, program_output, source};
+
+/// Golden files have the target triple of Linux
+fn linux_application(definitions: Vec<Definition>) -> ApplicationType {
+    let mut application = application(definitions);
+    application.set_target(Target::X86_64_LINUX);
+    application
+}
+
+fn variables() -> ApplicationType {
+    let definitions = ir::parse(r#"fn show(value: u8) {
+    print "%d\n", value;
+}
+
+fn main() {
+    let a: i32 = 1;
+    if a > 0 {
+        let a: u8 = 255u8;
+        let b: f32 = 0.1f32;
+        a = inc(a);
+        call show(a);
+    }
+    print "%d %.9g %s\n", a, b, message;
+    a = a - 2;
+    return a;
+}
+"#).unwrap();
+
+    let mut application = linux_application(definitions);
+    application.add_string_data("message", "done");
+    application
+}
+
+#[test]
+fn llvm_collatz() {
+    assert_eq!(include_str!("llvm/collatz.ll"), source::<LlvmIrGenerator>(linux_application(collatz())));
+}
+
+#[test]
+fn llvm_all_statements() {
+    assert_eq!(include_str!("llvm/all_statements.ll"), source::<LlvmIrGenerator>(linux_application(ir::parse(ALL_STATEMENTS).unwrap())));
+}
+
+#[test]
+fn llvm_variables() {
+    let source = source::<LlvmIrGenerator>(variables());
+
+    // Every declaration has its own stack slot, float constants are written with their exact double value
+    assert!(source.contains("@message = private unnamed_addr constant [5 x i8] c\"done\\00\"\n"), "{}", source);
+    assert!(source.contains("define void @show(i8 %value.param) {\n"), "{}", source);
+    assert!(source.contains("  %a = alloca i32\n  %a.1 = alloca i8\n  %b = alloca float\n"), "{}", source);
+    assert!(source.contains("  store float 0.10000000149011612, ptr %b\n"), "{}", source);
+    assert!(source.contains("  call void @show(i8 %.t7)\n"), "{}", source);
+    assert!(source.contains("call i32 (ptr, ...) @printf(ptr @.str.2, i32 %.t9, double %.t11, ptr @message)\n"), "{}", source);
+}
+
+#[test]
+fn llvm_target_triple() {
+    for (target, triple) in [(Target::X86_64_MACOS, "x86_64-apple-macosx"), (Target::X86_64_WINDOWS, "x86_64-pc-windows-msvc"), (Target::AARCH64_LINUX, "aarch64-unknown-linux-gnu"), (Target::AARCH64_MACOS, "arm64-apple-macosx")] {
+        let mut application = application(collatz());
+        application.set_target(target);
+        assert!(source::<LlvmIrGenerator>(application).starts_with(&format!("target triple = \"{}\"\n", triple)));
+    }
+}
+
+/// Major version of the installed clang, "Debian clang version 14.0.6" is 14. Unknown versions are taken as the newest one.
+fn clang_version() -> u32 {
+    let output = Command::new("clang").arg("--version").output().expect("clang is not found");
+    let version = str::from_utf8(&output.stdout).unwrap();
+    version.split("version ").nth(1).and_then(|version| version.split('.').next()).and_then(|major| major.parse().ok()).unwrap_or(u32::MAX)
+}
+
+/// clang is not installed everywhere, tests using it are ignored by default
+fn run_with_clang(application: ApplicationType, file_name: &str) -> (String, i32) {
+    let mut source_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+
+    source_file_name.push(format!("{}.ll", file_name));
+    executable_name.push(format!("{}.exe", file_name));
+    fs::write(&source_file_name, application.build::<LlvmIrGenerator>().unwrap()).unwrap();
+
+    // Generated IR uses the opaque pointers, they are the default since LLVM 15 and LLVM 14 needs the flag
+    let version = clang_version();
+    assert!(version >= 14, "LLVM IR tests need clang 14 or newer, installed one is {}", version);
+
+    let mut command = Command::new("clang");
+    if version == 14 {
+        command.args(["-mllvm", "-opaque-pointers"]);
+    }
+
+    let output = command.arg(&source_file_name).arg("-o").arg(&executable_name).output().expect("clang is not found");
+    assert!(output.status.success(), "{}", str::from_utf8(&output.stderr).unwrap());
+
+    program_output(Command::new(&executable_name).output().unwrap())
+}
+
+#[test]
+#[ignore = "needs clang 14 or newer"]
+fn llvm_clang_collatz() {
+    assert_eq!(("27 111\n".to_owned(), 0), run_with_clang(linux_application(collatz()), "llvm_collatz"));
+    assert_eq!(("0\n0 0.100000001 done\n".to_owned(), 254), run_with_clang(variables(), "llvm_variables"));
+}
+
+/// LLVM and the x86 backend should print the same output for the same program
+#[test]
+#[ignore = "needs clang 14 or newer"]
+fn llvm_clang_differential() {
+    compare_backend("llvm_differential", run_with_clang);
+}
@@ -0,0 +1,165 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ConditionType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_builder::IfBlockType;
+use tb_builder::LoopBlockType;
+use tb_builder::WhileBlockType;
+use tb_core::types::Value;
+
+use super::check_output;
+use super::get_exit_code;
+
+#[test]
+fn while_factorial() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_assign("n", ExpressionType::value(Value::Number(5.into())));
+    main_func_block.add_assign("result", ExpressionType::value(Value::Number(1.into())));
+
+    let mut loop_block = BlockType::default();
+    loop_block.add_assign("result", ExpressionType::mul(Value::Variable("n".to_owned()), Value::Variable("result".to_owned())));
+    loop_block.add_assign("n", ExpressionType::sub(Value::Number(1.into()), Value::Variable("n".to_owned())));
+
+    let mut while_block = WhileBlockType::default();
+    while_block.set_condition(ConditionType::gr(Value::Variable("n".to_owned()), Value::Number(1.into())));
+    while_block.set_block(loop_block);
+
+    main_func_block.add_while(while_block);
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "while_factorial", 120);
+}
+
+#[test]
+fn while_never_executed() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_assign("n", ExpressionType::value(Value::Number(3.into())));
+
+    let mut loop_block = BlockType::default();
+    loop_block.add_assign("n", ExpressionType::value(Value::Number(100.into())));
+
+    let mut while_block = WhileBlockType::default();
+    while_block.set_condition(ConditionType::ls(Value::Variable("n".to_owned()), Value::Number(1.into())));
+    while_block.set_block(loop_block);
+
+    main_func_block.add_while(while_block);
+    main_func_block.add_return_variable("n");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "while_never_executed", 3);
+}
+
+#[test]
+fn loop_break() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_assign("i", ExpressionType::value(Value::Number(0.into())));
+
+    let mut break_block = BlockType::default();
+    break_block.add_break();
+
+    let mut if_block = IfBlockType::default();
+    if_block.set_condition(ConditionType::eq(Value::Variable("i".to_owned()), Value::Number(7.into())));
+    if_block.set_true_block(break_block);
+
+    let mut body = BlockType::default();
+    body.add_assign("i", ExpressionType::add(Value::Number(1.into()), Value::Variable("i".to_owned())));
+    body.add_if(if_block);
+
+    let mut loop_block = LoopBlockType::default();
+    loop_block.set_block(body);
+
+    main_func_block.add_loop(loop_block);
+    main_func_block.add_return_variable("i");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "loop_break", 7);
+}
+
+#[test]
+fn while_continue() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // Sum of the odd numbers between 1 and 10
+    main_func_block.add_assign("i", ExpressionType::value(Value::Number(0.into())));
+    main_func_block.add_assign("sum", ExpressionType::value(Value::Number(0.into())));
+
+    let mut continue_block = BlockType::default();
+    continue_block.add_continue();
+
+    let mut if_block = IfBlockType::default();
+    if_block.set_condition(ConditionType::eq(Value::Variable("rem".to_owned()), Value::Number(0.into())));
+    if_block.set_true_block(continue_block);
+
+    let mut body = BlockType::default();
+    body.add_assign("i", ExpressionType::add(Value::Number(1.into()), Value::Variable("i".to_owned())));
+    body.add_assign("rem", ExpressionType::modulo(Value::Number(2.into()), Value::Variable("i".to_owned())));
+    body.add_if(if_block);
+    body.add_assign("sum", ExpressionType::add(Value::Variable("i".to_owned()), Value::Variable("sum".to_owned())));
+
+    let mut while_block = WhileBlockType::default();
+    while_block.set_condition(ConditionType::ls(Value::Variable("i".to_owned()), Value::Number(10.into())));
+    while_block.set_block(body);
+
+    main_func_block.add_while(while_block);
+    main_func_block.add_return_variable("sum");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "while_continue", 25);
+}
+
+#[test]
+fn nested_loops() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_assign("i", ExpressionType::value(Value::Number(0.into())));
+    main_func_block.add_assign("total", ExpressionType::value(Value::Number(0.into())));
+
+    let mut inner_body = BlockType::default();
+    inner_body.add_assign("total", ExpressionType::add(Value::Number(1.into()), Value::Variable("total".to_owned())));
+    inner_body.add_assign("j", ExpressionType::add(Value::Number(1.into()), Value::Variable("j".to_owned())));
+
+    let mut inner_while = WhileBlockType::default();
+    inner_while.set_condition(ConditionType::ls(Value::Variable("j".to_owned()), Value::Variable("i".to_owned())));
+    inner_while.set_block(inner_body);
+
+    let mut outer_body = BlockType::default();
+    outer_body.add_assign("j", ExpressionType::value(Value::Number(0.into())));
+    outer_body.add_while(inner_while);
+    outer_body.add_assign("i", ExpressionType::add(Value::Number(1.into()), Value::Variable("i".to_owned())));
+
+    let mut outer_while = WhileBlockType::default();
+    outer_while.set_condition(ConditionType::le(Value::Variable("i".to_owned()), Value::Number(5.into())));
+    outer_while.set_block(outer_body);
+
+    main_func_block.add_while(outer_while);
+    main_func_block.add_print("total: %d".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_variable("total");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "nested_loops", "total: 15");
+}
@@ -0,0 +1,149 @@
+use core::str;
+use std::collections::HashMap;
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::{ApplicationType, BlockType, ExpressionType, FunctionType};
+use tb_core::compiler::{CompilerTrait, TBCompiler};
+use tb_core::target::Target;
+use tb_core::types::{NumberType, Value};
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+fn scale_function() -> FunctionType {
+    let mut scale_func = FunctionType::main();
+    scale_func.set_name("scale");
+    scale_func.add_parameter("x", NumberType::Double);
+    scale_func.add_parameter("n", NumberType::I64);
+    scale_func.add_parameter("y", NumberType::Double);
+    scale_func.add_parameter("a", NumberType::I64);
+    scale_func.add_parameter("b", NumberType::I64);
+
+    let mut scale_func_block = BlockType::default();
+    scale_func_block.add_declare("result", NumberType::Double, Some(ExpressionType::add(Value::Variable("b".to_owned()), ExpressionType::add(Value::Variable("n".to_owned()), ExpressionType::mul(Value::Variable("y".to_owned()), Value::Variable("x".to_owned()))))));
+    scale_func_block.add_return_variable("result");
+    scale_func.set_body(scale_func_block);
+    scale_func
+}
+
+fn scale_application() -> ApplicationType {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+    main_func_block.add_declare("total", NumberType::Double, None);
+    main_func_block.add_call_and_assign("scale".to_owned(), vec![1.5.into(), 2.into(), 4.0.into(), 3.into(), 5.into()], "total".to_owned());
+    main_func_block.add_print("Total: %.1f".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application = ApplicationType::default();
+    application.add_function(main_func);
+    application.add_function(scale_function());
+    application
+}
+
+fn windows_source(application: ApplicationType) -> String {
+    let mut application = application;
+    application.set_target(Target::X86_64_WINDOWS);
+    application.build::<X86AssemblyGenerator>().unwrap()
+}
+
+#[test]
+fn microsoft_parameters() {
+    let source = windows_source(scale_application());
+
+    // Arguments are positional, the double at the second place is in the third register pair
+    assert!(source.contains("    movsd %xmm0, -8(%rbp)\r\n    mov %rdx, -16(%rbp)\r\n    movsd %xmm2, -24(%rbp)\r\n    mov %r9, -32(%rbp)\r\n"), "{}", source);
+
+    // Fifth argument is after the return address, the saved RBP and the shadow space
+    assert!(source.contains("    mov 48(%rbp), %rax # parameter b\r\n"), "{}", source);
+
+    // There is no red zone, parameters are stored after the stack is allocated
+    assert!(source.contains("scale:\r\n    pushq %rbp\r\n    mov %rsp, %rbp\r\n    subq $80, %rsp\r\n    movups %xmm8, -64(%rbp)\r\n    movups %xmm9, -80(%rbp)\r\n    movsd %xmm0, -8(%rbp)\r\n"), "{}", source);
+}
+
+#[test]
+fn microsoft_calls() {
+    let source = windows_source(scale_application());
+
+    assert!(source.contains("    push $5\r\n    movq $3, %r9\r\n    movsd .LC1(%rip), %xmm2\r\n    movq $2, %rdx\r\n    movsd .LC2(%rip), %xmm0\r\n    subq $32, %rsp # shadow space\r\n    call scale\r\n    addq $48, %rsp\r\n"), "{}", source);
+
+    // Variadic doubles are also in the integer register, there is no vector count in RAX
+    assert!(source.contains("    movq %xmm1, %rdx\r\n    leaq .LC3(%rip), %rcx\r\n    subq $32, %rsp # shadow space\r\n    call printf\r\n    addq $32, %rsp\r\n"), "{}", source);
+    assert!(!source.contains("%rax # vector"), "{}", source);
+    assert!(source.contains(".section    .rdata,\"dr\"\r\n.LC3:"), "{}", source);
+}
+
+#[test]
+fn microsoft_callee_saved() {
+    let source = windows_source(scale_application());
+
+    // Whole vector registers are preserved
+    assert!(source.contains("    movups %xmm8, -64(%rbp)\r\n    movups %xmm9, -80(%rbp)\r\n"), "{}", source);
+    assert!(source.contains("    movups -64(%rbp), %xmm8\r\n    movups -80(%rbp), %xmm9\r\n    mov %rbp, %rsp\r\n"), "{}", source);
+
+    let mut application = scale_application();
+    application.set_target(Target::X86_64_LINUX);
+    let source = application.build::<X86AssemblyGenerator>().unwrap();
+    assert!(!source.contains("movups"), "{}", source);
+}
+
+/// Functions with the Microsoft convention are called from C with the ms_abi attribute
+#[test]
+fn microsoft_c_interop() {
+    let c_source = r#"
+#include <stdio.h>
+
+double __attribute__((ms_abi)) scale(double x, long n, double y, long a, long b);
+long __attribute__((ms_abi)) tb_caller(long value);
+
+long __attribute__((ms_abi)) c_sum6(long a, long b, long c, long d, long e, long f) {
+    return a + b * 10 + c * 100 + d * 1000 + e * 10000 + f * 100000;
+}
+
+long __attribute__((ms_abi)) c_weight(long a, double b) {
+    return a * 2 + (long)(b * 10);
+}
+
+int main(void) {
+    printf("%.2f %ld", scale(1.5, 2, 4.0, 3, 5), tb_caller(7));
+    return 0;
+}
+"#;
+
+    let mut caller_func = FunctionType::main();
+    caller_func.set_name("tb_caller");
+    caller_func.add_parameter("value", NumberType::I64);
+
+    let mut caller_func_block = BlockType::default();
+    caller_func_block.add_call_and_assign("c_sum6".to_owned(), vec![Value::Variable("value".to_owned()), 1.into(), 2.into(), 3.into(), 4.into(), 5.into()], "total".to_owned());
+    caller_func_block.add_call_and_assign("c_weight".to_owned(), vec![Value::Variable("total".to_owned()), 2.5.into()], "weighted".to_owned());
+    caller_func_block.add_return_variable("weighted");
+    caller_func.set_body(caller_func_block);
+
+    let mut application = ApplicationType::default();
+    application.add_function(scale_function());
+    application.add_function(caller_func);
+
+    // Only the section of the read only data is different on ELF
+    let source = windows_source(application).replace(".section    .rdata,\"dr\"", ".section .rodata");
+
+    let mut source_file_name = temp_dir();
+    let mut c_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+
+    source_file_name.push("microsoft_c_interop.s");
+    c_file_name.push("microsoft_c_interop.c");
+    executable_name.push("microsoft_c_interop.exe");
+
+    fs::write(&source_file_name, source).unwrap();
+    fs::write(&c_file_name, c_source).unwrap();
+
+    let mut arguments = HashMap::new();
+    arguments.insert("-xc".to_owned(), c_file_name.display().to_string());
+
+    let compiler = TBCompiler::get_compiler().unwrap();
+    compiler.compile(&source_file_name, &executable_name, arguments).unwrap();
+
+    let command = Command::new(executable_name).output().unwrap();
+    assert_eq!("13.00 1086459", str::from_utf8(&command.stdout).unwrap());
+}
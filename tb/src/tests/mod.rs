@@ -1,10 +1,16 @@
 use core::str;
+use std::collections::HashMap;
 use std::env::temp_dir;
+use std::path::PathBuf;
+use std::process::Output;
 use std::{fs::File, io::Write, process::Command};
 
 use tb_builder::ApplicationType;
 use tb_core::compiler::TBCompiler;
 use tb_core::compiler::CompilerTrait;
+use tb_core::error::TBError;
+use tb_core::ir;
+use tb_core::types::{AssemblyGenerator, Definition};
 use tb_target_x86_64::generator::X86AssemblyGenerator;
 
 mod return_test;
@@ -23,9 +29,32 @@ mod shift_right_test;
 mod inc_test;
 mod dec_test;
 mod if_test;
+mod condition_test;
+mod ir_test;
+mod interpreter_test;
+mod differential;
+mod encoder_test;
+mod jit_test;
+mod object_test;
+mod executable_test;
+mod intel_syntax_test;
+mod nasm_syntax_test;
+mod target_test;
+mod microsoft_abi_test;
 mod func_call;
+mod nested_expression_test;
+mod loop_test;
+mod typed_variable_test;
+mod float_test;
+mod register_allocator_test;
+mod aarch64_test;
+mod c_test;
+mod llvm_test;
+mod wasm_test;
+mod error_test;
 
-pub fn get_exit_code(application: ApplicationType, file_name: &str, exit_code: i32) {
+/// Generate the assembly of the application and build the executable, returns the path of the executable
+pub fn build_executable(application: ApplicationType, file_name: &str) -> Result<PathBuf, TBError> {
     let mut source_file_name = temp_dir();
     let mut executable_name = temp_dir();
 
@@ -35,12 +64,37 @@ pub fn get_exit_code(application: ApplicationType, file_name: &str, exit_code: i
     println!("Source: {}", source_file_name.display());
     println!("Exe: {}", executable_name.display());
 
-    let buffer = application.build::<X86AssemblyGenerator>();
+    let buffer = application.build::<X86AssemblyGenerator>()?;
     let mut file = File::create(&source_file_name).unwrap();
     file.write_all(buffer.as_bytes()).unwrap();
 
-    let compiler = TBCompiler::get_compiler().unwrap();
-    compiler.compile(&source_file_name, &executable_name, Default::default()).unwrap();
+    let compiler = TBCompiler::get_compiler()?;
+    compiler.compile(&source_file_name, &executable_name, Default::default())?;
+    Ok(executable_name)
+}
+
+pub fn application(definitions: Vec<Definition>) -> ApplicationType {
+    let mut application = ApplicationType::default();
+    definitions.into_iter().for_each(|definition| application.add_definition(definition));
+    application
+}
+
+/// Golden files are compared without the carriage returns
+pub fn source<A: AssemblyGenerator>(application: ApplicationType) -> String {
+    application.build::<A>().unwrap().replace("\r\n", "\n")
+}
+
+pub fn collatz() -> Vec<Definition> {
+    ir::parse(include_str!("ir/collatz.tb")).unwrap()
+}
+
+/// Printed text and the exit code of the program, exit code is -1 if the process is killed by a signal
+pub fn program_output(output: Output) -> (String, i32) {
+    (str::from_utf8(&output.stdout).unwrap().to_owned(), output.status.code().unwrap_or(-1))
+}
+
+pub fn get_exit_code(application: ApplicationType, file_name: &str, exit_code: i32) {
+    let executable_name = build_executable(application, file_name).unwrap();
 
     // to test
     let command = Command::new(executable_name).output().unwrap();
@@ -48,21 +102,40 @@ pub fn get_exit_code(application: ApplicationType, file_name: &str, exit_code: i
 }
 
 pub fn check_output(application: ApplicationType, file_name: &str, expected_message: &str) {
+    let executable_name = build_executable(application, file_name).unwrap();
+
+    // to test
+    let command = Command::new(executable_name).output().unwrap();
+    assert_eq!(expected_message, str::from_utf8(&command.stdout).unwrap());
+}
+
+/// Same as check_output, but the C source is compiled and linked together with the generated code
+pub fn check_output_with_c(application: ApplicationType, file_name: &str, c_source: &str, expected_message: &str) {
     let mut source_file_name = temp_dir();
+    let mut c_file_name = temp_dir();
     let mut executable_name = temp_dir();
 
     source_file_name.push(format!("{}.s", &file_name));
+    c_file_name.push(format!("{}.c", &file_name));
     executable_name.push(format!("{}.exe", &file_name));
 
     println!("Source: {}", source_file_name.display());
+    println!("C source: {}", c_file_name.display());
     println!("Exe: {}", executable_name.display());
 
-    let buffer = application.build::<X86AssemblyGenerator>();
+    let buffer = application.build::<X86AssemblyGenerator>().unwrap();
     let mut file = File::create(&source_file_name).unwrap();
     file.write_all(buffer.as_bytes()).unwrap();
 
+    let mut file = File::create(&c_file_name).unwrap();
+    file.write_all(c_source.as_bytes()).unwrap();
+
+    // Files after "-xc" are compiled as C
+    let mut arguments = HashMap::new();
+    arguments.insert("-xc".to_owned(), c_file_name.display().to_string());
+
     let compiler = TBCompiler::get_compiler().unwrap();
-    compiler.compile(&source_file_name, &executable_name, Default::default()).unwrap();
+    compiler.compile(&source_file_name, &executable_name, arguments).unwrap();
 
     // to test
     let command = Command::new(executable_name).output().unwrap();
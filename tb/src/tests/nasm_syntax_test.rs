@@ -0,0 +1,88 @@
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::ApplicationType;
+use tb_core::syntax::Syntax;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+#[test]
+fn nasm_directives() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+    let source = application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Nasm).unwrap();
+
+    assert!(source.starts_with("default rel\r\n"), "{}", source);
+    assert!(source.contains("global steps\r\n"), "{}", source);
+    assert!(source.contains("global main\r\n"), "{}", source);
+    assert!(source.contains("extern printf\r\n"), "{}", source);
+    assert!(!source.contains("extern steps"), "{}", source);
+    assert!(source.contains("section .rodata\r\n"), "{}", source);
+    assert!(source.contains("section .text\r\n"), "{}", source);
+    assert!(source.contains(r#"    db "%lld %d", 10, 0"#), "{}", source);
+    assert!(source.contains("lea rdi, [..@LC"), "{}", source);
+    assert!(source.contains("call steps"), "{}", source);
+
+    // GAS directives
+    assert!(!source.contains(".string"), "{}", source);
+    assert!(!source.contains(".globl"), "{}", source);
+}
+
+#[test]
+fn nasm_operands() {
+    let application_type = ApplicationType::from_ir(r#"fn scale(value: i64, small: i8, ratio: f64, count: i32) {
+    let wide: i64 = count;
+    let result: f64 = ratio * 2.5;
+    return value;
+}"#).unwrap();
+
+    let source = application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Nasm).unwrap();
+    assert!(source.contains("mov qword [rbp-8], rdi"), "{}", source);
+    assert!(source.contains("mov byte [rbp-9], sil"), "{}", source);
+    assert!(source.contains("movsd qword [rbp-24], xmm0"), "{}", source);
+    assert!(source.contains("movsd xmm"), "{}", source);
+    assert!(source.contains("movsxd r"), "{}", source);
+    assert!(source.contains("qword [..@LC"), "{}", source);
+    assert!(source.contains("dq 0x4004000000000000 ; 2.5"), "{}", source);
+    assert!(!source.contains("ptr"), "{}", source);
+}
+
+#[test]
+fn nasm_strings() {
+    let mut application_type = ApplicationType::from_ir(r#"fn main() {
+    print "quote \" tab \t end\n";
+    return 0;
+}"#).unwrap();
+    application_type.add_string_data("text", "");
+
+    let source = application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Nasm).unwrap();
+    assert!(source.contains(r#"    db "quote ", 34, " tab ", 9, " end", 10, 0"#), "{}", source);
+    assert!(source.contains("..@text:\r\n    db 0\r\n"), "{}", source);
+}
+
+/// nasm is not a dependency of the other tests, the source is only assembled when it is installed
+#[test]
+fn nasm_ir_program() {
+    if Command::new("nasm").arg("-v").output().is_err() {
+        return;
+    }
+
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+
+    let mut source_file_name = temp_dir();
+    let mut object_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+    source_file_name.push("nasm_ir_program.asm");
+    object_file_name.push("nasm_ir_program.o");
+    executable_name.push("nasm_ir_program.exe");
+
+    fs::write(&source_file_name, application_type.build_with_syntax::<X86AssemblyGenerator>(Syntax::Nasm).unwrap()).unwrap();
+
+    let status = Command::new("nasm").args(["-f", "elf64", "-o"]).arg(&object_file_name).arg(&source_file_name).status().unwrap();
+    assert!(status.success(), "{} is not assembled", source_file_name.display());
+
+    let status = Command::new("cc").arg("-no-pie").arg("-o").arg(&executable_name).arg(&object_file_name).status().unwrap();
+    assert!(status.success());
+
+    let output = Command::new(executable_name).output().unwrap();
+    assert_eq!("27 111\n", String::from_utf8(output.stdout).unwrap());
+}
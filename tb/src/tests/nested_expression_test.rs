@@ -0,0 +1,123 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_core::types::Value;
+
+use super::get_exit_code;
+
+#[test]
+fn nested_expression_test_1() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // a + b * c
+    main_func_block.add_assign("a", ExpressionType::value(Value::Number(2.into())));
+    main_func_block.add_assign("b", ExpressionType::value(Value::Number(3.into())));
+    main_func_block.add_assign("c", ExpressionType::value(Value::Number(4.into())));
+    main_func_block.add_assign("actual", ExpressionType::add(ExpressionType::mul(Value::Variable("c".to_owned()), Value::Variable("b".to_owned())), Value::Variable("a".to_owned())));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test1", 14);
+}
+
+#[test]
+fn nested_expression_test_2() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // (((1 + 2) * (3 + 4)) - (10 / 2)) % 7
+    let left = ExpressionType::mul(ExpressionType::add(Value::Number(4.into()), Value::Number(3.into())), ExpressionType::add(Value::Number(2.into()), Value::Number(1.into())));
+    let right = ExpressionType::div(Value::Number(2.into()), Value::Number(10.into()));
+    main_func_block.add_assign("actual", ExpressionType::modulo(Value::Number(7.into()), ExpressionType::sub(right, left)));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test2", 2);
+}
+
+#[test]
+fn nested_expression_test_3() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // (1 << x) + (x << x)
+    main_func_block.add_assign("x", ExpressionType::value(Value::Number(3.into())));
+    let left = ExpressionType::shift_left(Value::Variable("x".to_owned()), Value::Number(1.into()));
+    let right = ExpressionType::shift_left(Value::Variable("x".to_owned()), Value::Variable("x".to_owned()));
+    main_func_block.add_assign("actual", ExpressionType::add(right, left));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test3", 32);
+}
+
+#[test]
+fn nested_expression_test_4() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // (100 / (a % 7)) + (b / 3)
+    main_func_block.add_assign("a", ExpressionType::value(Value::Number(12.into())));
+    main_func_block.add_assign("b", ExpressionType::value(Value::Number(9.into())));
+    let left = ExpressionType::div(ExpressionType::modulo(Value::Number(7.into()), Value::Variable("a".to_owned())), Value::Number(100.into()));
+    let right = ExpressionType::div(Value::Number(3.into()), Value::Variable("b".to_owned()));
+    main_func_block.add_assign("actual", ExpressionType::add(right, left));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test4", 23);
+}
+
+#[test]
+fn nested_expression_test_5() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // Balanced tree with 16 leaves: 1 + 2 + ... + 16
+    let mut items: Vec<ExpressionType> = (1..=16).map(|item| ExpressionType::value(Value::Number(item.into()))).collect();
+    while items.len() > 1 {
+        items = items.chunks(2).map(|pair| ExpressionType::add(pair[1].clone(), pair[0].clone())).collect();
+    }
+
+    main_func_block.add_assign("actual", items.remove(0));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test5", 136);
+}
+
+#[test]
+fn nested_expression_test_6() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    // -(~(a - 10)) ^ (a & 6)
+    main_func_block.add_assign("a", ExpressionType::value(Value::Number(15.into())));
+    let left = ExpressionType::bitwise_neg(ExpressionType::bitwise_not(ExpressionType::sub(Value::Number(10.into()), Value::Variable("a".to_owned()))));
+    let right = ExpressionType::bitwise_and(Value::Number(6.into()), Value::Variable("a".to_owned()));
+    main_func_block.add_assign("actual", ExpressionType::bitwise_xor(right, left));
+    main_func_block.add_return_variable("actual");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "nested_expression_test6", 0);
+}
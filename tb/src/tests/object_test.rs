@@ -0,0 +1,77 @@
+use std::env::temp_dir;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use tb_builder::ApplicationType;
+use tb_core::compiler::{CompilerTrait, TBCompiler};
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use super::build_executable;
+use super::differential::generator::Generator;
+
+/// Object file is written without the assembler, the compiler only links it
+fn build_object_executable(application: ApplicationType, file_name: &str) -> (PathBuf, PathBuf) {
+    let mut object_file_name = temp_dir();
+    let mut executable_name = temp_dir();
+
+    object_file_name.push(format!("{}.o", file_name));
+    executable_name.push(format!("{}.exe", file_name));
+
+    fs::write(&object_file_name, application.build_object::<X86AssemblyGenerator>().unwrap()).unwrap();
+
+    let compiler = TBCompiler::get_compiler().unwrap();
+    compiler.compile(&object_file_name, &executable_name, Default::default()).unwrap();
+    (object_file_name, executable_name)
+}
+
+fn run(executable_name: PathBuf) -> Output {
+    Command::new(executable_name).output().unwrap()
+}
+
+#[test]
+fn object_ir_program() {
+    let application_type = ApplicationType::from_ir(include_str!("ir/collatz.tb")).unwrap();
+    let (_, executable_name) = build_object_executable(application_type, "object_ir_program");
+
+    let output = run(executable_name);
+    assert_eq!("27 111\n", String::from_utf8(output.stdout).unwrap());
+    assert_eq!(Some(0), output.status.code());
+}
+
+#[test]
+fn object_relocations() {
+    let application_type = ApplicationType::from_ir(r#"fn main() {
+    let value: f64 = 1.5;
+    print "%.2f\n", value;
+    return 3;
+}"#).unwrap();
+    let (object_file_name, executable_name) = build_object_executable(application_type, "object_relocations");
+
+    let output = run(executable_name);
+    assert_eq!("1.50\n", String::from_utf8(output.stdout).unwrap());
+    assert_eq!(Some(3), output.status.code());
+
+    // Calls go through the PLT, data is PC relative
+    let relocations = String::from_utf8(Command::new("readelf").arg("-r").arg(&object_file_name).output().unwrap().stdout).unwrap();
+    assert!(relocations.lines().any(|line| line.contains("R_X86_64_PLT32") && line.contains("printf")), "{}", relocations);
+    assert!(relocations.lines().any(|line| line.contains("R_X86_64_PC32") && line.contains(".LC")), "{}", relocations);
+
+    let symbols = String::from_utf8(Command::new("readelf").arg("-s").arg(&object_file_name).output().unwrap().stdout).unwrap();
+    assert!(symbols.lines().any(|line| line.contains("FUNC") && line.contains("GLOBAL") && line.ends_with(" main")), "{}", symbols);
+}
+
+#[test]
+fn object_generated_programs() {
+    for seed in 0..10 {
+        let mut application_type = ApplicationType::default();
+        Generator::new(seed).generate().into_iter().for_each(|definition| application_type.add_definition(definition));
+
+        let expected = run(build_executable(application_type.clone(), &format!("object_assembly_{}", seed)).unwrap());
+        let (_, executable_name) = build_object_executable(application_type, &format!("object_generated_{}", seed));
+        let found = run(executable_name);
+
+        assert_eq!(expected.stdout, found.stdout, "seed {}", seed);
+        assert_eq!(expected.status.code(), found.status.code(), "seed {}", seed);
+    }
+}
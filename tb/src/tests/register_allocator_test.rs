@@ -0,0 +1,245 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_core::register_allocator::{Allocation, FixedRanges, LinearScanAllocator, LiveInterval};
+use tb_core::types::{NumberType, RegisterSize, RegisterTrait, Value};
+
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+use super::check_output;
+use super::source;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TestRegister {
+    A,
+    B
+}
+
+impl std::fmt::Display for TestRegister {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl RegisterTrait for TestRegister {
+    fn get_register_size(&self) -> RegisterSize {
+        RegisterSize::_64Bit
+    }
+
+    fn get_sized(self, _: RegisterSize) -> Self {
+        self
+    }
+}
+
+fn interval(register: usize, start: usize, end: usize) -> LiveInterval {
+    LiveInterval { register, vector: false, start, end }
+}
+
+#[test]
+fn reuse_expired_register() {
+    let allocator = LinearScanAllocator::new(vec![TestRegister::A, TestRegister::B]);
+    let allocations = allocator.allocate(vec![interval(0, 0, 2), interval(1, 3, 4)], &FixedRanges::default());
+
+    assert_eq!(allocations[&0], Allocation::Register(TestRegister::A));
+    assert_eq!(allocations[&1], Allocation::Register(TestRegister::A));
+}
+
+#[test]
+fn spill_longest_interval() {
+    let allocator = LinearScanAllocator::new(vec![TestRegister::A, TestRegister::B]);
+    let allocations = allocator.allocate(vec![interval(0, 0, 10), interval(1, 1, 3), interval(2, 2, 4)], &FixedRanges::default());
+
+    assert_eq!(allocations[&0], Allocation::Spill);
+    assert_eq!(allocations[&1], Allocation::Register(TestRegister::B));
+    assert_eq!(allocations[&2], Allocation::Register(TestRegister::A));
+}
+
+#[test]
+fn fixed_register_is_not_used() {
+    let allocator = LinearScanAllocator::new(vec![TestRegister::A, TestRegister::B]);
+    let mut fixed_ranges = FixedRanges::default();
+    fixed_ranges.add(TestRegister::A, 1, 2);
+
+    let allocations = allocator.allocate(vec![interval(0, 0, 3), interval(1, 2, 3)], &fixed_ranges);
+
+    assert_eq!(allocations[&0], Allocation::Register(TestRegister::B));
+    assert_eq!(allocations[&1], Allocation::Register(TestRegister::A));
+}
+
+/// Balanced tree of additions, every level keeps one more value alive
+fn build_sum_tree(depth: usize, leaf: &mut i64) -> (ExpressionType, i64) {
+    if depth == 0 {
+        *leaf += 1;
+        return (ExpressionType::add(Value::Number((*leaf).into()), Value::Variable("x".to_owned())), *leaf + 1);
+    }
+
+    let (left, left_value) = build_sum_tree(depth - 1, leaf);
+    let (right, right_value) = build_sum_tree(depth - 1, leaf);
+    (ExpressionType::add(right, left), left_value + right_value)
+}
+
+/// (left / two) + (right << x), division and shift need fixed registers while the other values are alive
+fn build_fixed_tree(depth: usize, leaf: &mut i64) -> (ExpressionType, i64) {
+    if depth == 0 {
+        *leaf += 1;
+        return (ExpressionType::add(Value::Number((*leaf).into()), Value::Variable("x".to_owned())), *leaf + 1);
+    }
+
+    let (left, left_value) = build_fixed_tree(depth - 1, leaf);
+    let (right, right_value) = build_fixed_tree(depth - 1, leaf);
+    let left = ExpressionType::div(Value::Variable("two".to_owned()), left);
+    let right = ExpressionType::shift_left(Value::Variable("x".to_owned()), right);
+    (ExpressionType::add(right, left), left_value / 2 + (right_value << 1))
+}
+
+#[test]
+fn spill_deep_expression() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    let (expression, expected) = build_sum_tree(13, &mut 0);
+    main_func_block.add_assign("x", ExpressionType::value(Value::Number(1.into())));
+    main_func_block.add_assign("actual", expression);
+    main_func_block.add_print("%ld".to_owned(), vec![Value::Variable("actual".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "spill_deep_expression", &expected.to_string());
+}
+
+#[test]
+fn spill_with_fixed_registers() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    let (expression, expected) = build_fixed_tree(13, &mut 0);
+    main_func_block.add_assign("x", ExpressionType::value(Value::Number(1.into())));
+    main_func_block.add_assign("two", ExpressionType::value(Value::Number(2.into())));
+    main_func_block.add_assign("actual", expression);
+    main_func_block.add_print("%ld".to_owned(), vec![Value::Variable("actual".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "spill_with_fixed_registers", &expected.to_string());
+}
+
+#[test]
+fn callee_saved_registers() {
+    // Tree needs more registers than the caller-saved ones
+    let (expression, expected) = build_sum_tree(10, &mut 0);
+
+    let mut tree_func = FunctionType::main();
+    tree_func.set_name("tree");
+    tree_func.add_parameter("x", NumberType::I64);
+
+    let mut tree_func_block = BlockType::default();
+    tree_func_block.add_assign("result", expression);
+    tree_func_block.add_return_variable("result");
+    tree_func.set_body(tree_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_call_and_assign("tree".to_owned(), vec![1.into()], "first".to_owned());
+    main_func_block.add_call_and_assign("tree".to_owned(), vec![1.into()], "second".to_owned());
+    main_func_block.add_print("%ld %ld".to_owned(), vec![Value::Variable("first".to_owned()), Value::Variable("second".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(tree_func);
+
+    check_output(application_type, "callee_saved_registers", &format!("{} {}", expected, expected));
+}
+
+/// 64bit name of the caller-saved register, RAX and XMM0 have the return value
+fn caller_saved_register(name: &str) -> Option<String> {
+    let name = match name {
+        "rcx" | "ecx" | "cx" | "cl" => "rcx",
+        "rdx" | "edx" | "dx" | "dl" => "rdx",
+        "rsi" | "esi" | "si" | "sil" => "rsi",
+        "rdi" | "edi" | "di" | "dil" => "rdi",
+        name if name.starts_with("xmm") && name != "xmm0" => name,
+        name => match name.trim_end_matches(['d', 'w', 'b']) {
+            register @ ("r8" | "r9" | "r10" | "r11") => register,
+            _ => return None
+        }
+    };
+    Some(name.to_owned())
+}
+
+/// Caller-saved registers should be written after the call before they are read
+fn check_registers_after_calls(source: &str) {
+    let mut written: Option<Vec<String>> = None;
+
+    for line in source.lines().map(|line| line.split('#').next().unwrap().trim()) {
+        if line.starts_with("call ") {
+            written = Some(Vec::new());
+            continue;
+        }
+
+        if line.ends_with(':') {
+            written = None;
+        }
+
+        let (Some(written), Some((instruction, operands))) = (written.as_mut(), line.split_once(' ')) else { continue };
+        let operands = operands.split(", ").collect::<Vec<_>>();
+        let only_write = instruction.starts_with("mov") || instruction.starts_with("lea") || instruction.starts_with("cvt");
+
+        for (index, operand) in operands.iter().enumerate() {
+            let registers = operand.split('%').skip(1).filter_map(|name| caller_saved_register(name.trim_end_matches(|item: char| !item.is_alphanumeric())));
+            let is_target = index == operands.len() - 1 && operand.starts_with('%');
+
+            for register in registers {
+                match is_target && only_write {
+                    true => written.push(register),
+                    false => assert!(written.contains(&register), "{} is read after the call before it is written: {}\n{}", register, line, source)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn values_live_across_nested_calls() {
+    // IR has no call in the argument position, so the inner call results are passed through the variables.
+    // Narrow arguments are converted after the earlier argument registers are loaded, they stay alive until the call.
+    let ir = r#"
+fn mix(a: i64, b: f64, c: i64, d: f64, e: i64, f: i64, g: i64, h: i64) {
+    let first: i64 = (a * 3i64) + (c * 5i64);
+    let second: i64 = (e * 7i64) + (f * 11i64);
+    let third: i64 = (g * 13i64) + (h * 17i64);
+    let scale: f64 = b + d;
+    let result: i64 = first + second + third;
+    print "%lld %lld %lld %.1f\n", first, second, third, scale;
+    return result;
+}
+
+fn main() {
+    let a: i32 = 1;
+    let b: i32 = 2;
+    let c: i32 = 3;
+    let x: f32 = 0.5f32;
+    let inner: i64;
+    let outer: i64;
+    inner = call mix(a, x, b, 1.5, c, a, b, c);
+    outer = call mix(inner, x, inner, x, a, b, c, inner);
+    print "%d %d %d %lld %lld\n", a, b, c, inner, outer;
+    return 0;
+}
+"#;
+
+    let application_type = ApplicationType::from_ir(ir).unwrap();
+    let execution = application_type.clone().interpret().unwrap();
+    assert_eq!("13 32 77 2.0\n976 29 2113 1.0\n1 2 3 122 3118\n", execution.output);
+    check_registers_after_calls(&source::<X86AssemblyGenerator>(application_type.clone()));
+    check_output(application_type, "values_live_across_nested_calls", &execution.output);
+}
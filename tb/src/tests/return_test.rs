@@ -4,6 +4,7 @@ use tb_builder::ExpressionType;
 use tb_builder::FunctionType;
 use tb_core::types::Value;
 
+use super::check_output;
 use super::get_exit_code;
 
 #[test]
@@ -63,3 +64,67 @@ fn basic_return_test_4() {
 
     get_exit_code(application_type, "return_test4", 10);
 }
+
+#[test]
+fn early_return_test() {
+    let source = r#"
+fn find(limit: i32) {
+    let i: i32 = 0;
+    loop {
+        i = i + 1;
+        if (i * i) > limit {
+            return i;
+        }
+    }
+    return -1;
+}
+
+fn main() {
+    let result: i32;
+    result = call find(50);
+    if result == 8 {
+        return 20;
+    }
+    return 30;
+}
+"#;
+
+    get_exit_code(ApplicationType::from_ir(source).unwrap(), "early_return_test", 20);
+}
+
+#[test]
+fn main_without_return_test() {
+    let source = "fn main() {\n    let a: i64 = 12345;\n}";
+    get_exit_code(ApplicationType::from_ir(source).unwrap(), "main_without_return_test", 0);
+}
+
+#[test]
+fn early_return_skips_rest_test() {
+    // Return jumps to the function end, the statements after it are not executed
+    let source = r#"
+fn check(value: i32) {
+    if value > 10 {
+        print "big ";
+        return 1;
+    }
+    print "small ";
+    return 0;
+}
+
+fn main() {
+    let result: i32;
+    result = call check(20);
+    result = call check(5);
+    return 0;
+}
+"#;
+
+    check_output(ApplicationType::from_ir(source).unwrap(), "early_return_skips_rest_test", "big small ");
+}
+
+#[test]
+fn main_without_return_after_print_test() {
+    // RAX has the printed byte count at the end, main should still return zero
+    let source = "fn main() {\n    print \"abc\";\n}";
+    get_exit_code(ApplicationType::from_ir(source).unwrap(), "main_without_return_after_print_test", 0);
+}
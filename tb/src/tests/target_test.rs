@@ -0,0 +1,75 @@
+use tb_builder::{ApplicationType, BlockType, FunctionType};
+use tb_core::error::TBError;
+use tb_core::target::{Architecture, ObjectFormat, OperatingSystem, Target};
+use tb_core::types::CallingConventions;
+use tb_target_x86_64::generator::X86AssemblyGenerator;
+
+fn application(target: Target) -> ApplicationType {
+    let mut block = BlockType::default();
+    block.add_print("value %d\n".to_owned(), vec![10.into()]);
+    block.add_return_number(0.into());
+
+    let mut main = FunctionType::main();
+    main.set_body(block);
+
+    let mut application = ApplicationType::default();
+    application.set_target(target);
+    application.add_function(main);
+    application
+}
+
+#[test]
+fn target_triples() {
+    let target = "x86_64-unknown-linux-gnu".parse::<Target>().unwrap();
+    assert_eq!(Target::X86_64_LINUX, target);
+    assert_eq!(Architecture::X86_64, target.architecture);
+    assert_eq!(ObjectFormat::Elf, target.object_format);
+    assert_eq!(CallingConventions::Systemv, target.calling_convention);
+
+    let target = "x86_64-apple-darwin".parse::<Target>().unwrap();
+    assert_eq!(OperatingSystem::MacOS, target.operating_system);
+    assert_eq!(ObjectFormat::MachO, target.object_format);
+
+    let target = "x86_64-pc-windows-msvc".parse::<Target>().unwrap();
+    assert_eq!(OperatingSystem::Windows, target.operating_system);
+    assert_eq!(ObjectFormat::Coff, target.object_format);
+    assert_eq!(CallingConventions::Microsoft, target.calling_convention);
+
+    let target = "aarch64-unknown-linux-gnu".parse::<Target>().unwrap();
+    assert_eq!(Target::AARCH64_LINUX, target);
+    assert_eq!(Architecture::Aarch64, target.architecture);
+    assert_eq!(CallingConventions::Arm, target.calling_convention);
+    assert_eq!(Target::AARCH64_MACOS, "arm64-apple-darwin".parse::<Target>().unwrap());
+
+    for target in [Target::X86_64_LINUX, Target::X86_64_MACOS, Target::X86_64_WINDOWS, Target::AARCH64_LINUX, Target::AARCH64_MACOS] {
+        assert_eq!(target, target.to_string().parse::<Target>().unwrap());
+    }
+
+    assert!(matches!("riscv64-unknown-linux-gnu".parse::<Target>(), Err(TBError::UnknownTarget(_))));
+    assert!(matches!("x86_64-unknown-none".parse::<Target>(), Err(TBError::UnknownTarget(_))));
+}
+
+#[test]
+fn target_linux_assembly() {
+    let source = application(Target::X86_64_LINUX).build::<X86AssemblyGenerator>().unwrap();
+    assert!(source.contains(".globl main\r\nmain:"), "{}", source);
+    assert!(source.contains(".section\t.rodata"), "{}", source);
+    assert!(source.contains("call printf\r\n"), "{}", source);
+}
+
+#[test]
+fn target_macos_assembly() {
+    let source = application(Target::X86_64_MACOS).build::<X86AssemblyGenerator>().unwrap();
+    assert!(source.contains(".globl _main\r\n_main:"), "{}", source);
+    assert!(source.contains("__TEXT,__cstring,cstring_literals"), "{}", source);
+    assert!(source.contains("call _printf\r\n"), "{}", source);
+}
+
+#[test]
+fn target_unsupported_object() {
+    let result = application(Target::X86_64_MACOS).build_object::<X86AssemblyGenerator>();
+    assert!(matches!(result, Err(TBError::UnsupportedTarget(_))));
+
+    let result = application(Target::X86_64_WINDOWS).build_static_executable::<X86AssemblyGenerator>();
+    assert!(matches!(result, Err(TBError::UnsupportedTarget(_))));
+}
@@ -0,0 +1,148 @@
+use tb_builder::ApplicationType;
+use tb_builder::BlockType;
+use tb_builder::ConditionType;
+use tb_builder::ExpressionType;
+use tb_builder::FunctionType;
+use tb_builder::IfBlockType;
+use tb_core::types::Number;
+use tb_core::types::NumberType;
+use tb_core::types::Value;
+
+use super::check_output;
+use super::get_exit_code;
+
+#[test]
+fn u8_wraparound() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::U8, Some(ExpressionType::value(Value::Number(Number::U8(200)))));
+    main_func_block.add_assign("a", ExpressionType::add(Value::Number(100.into()), Value::Variable("a".to_owned())));
+    main_func_block.add_return_variable("a");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "u8_wraparound", 44);
+}
+
+#[test]
+fn i8_sign_extension() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I8, Some(ExpressionType::value(Value::Number(Number::I8(-1)))));
+    main_func_block.add_declare("b", NumberType::I64, Some(ExpressionType::add(Value::Number(10.into()), Value::Variable("a".to_owned()))));
+    main_func_block.add_return_variable("b");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "i8_sign_extension", 9);
+}
+
+#[test]
+fn u16_overflow_compare() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::U16, None);
+    main_func_block.add_assign("a", ExpressionType::value(Value::Number(Number::U16(65535))));
+    main_func_block.add_assign("a", ExpressionType::add(Value::Number(1.into()), Value::Variable("a".to_owned())));
+
+    main_func_block.add_assign("result", ExpressionType::value(Value::Number(2.into())));
+
+    let mut true_block = BlockType::default();
+    true_block.add_assign("result", ExpressionType::value(Value::Number(1.into())));
+
+    let mut if_block = IfBlockType::default();
+    if_block.set_condition(ConditionType::eq(Value::Variable("a".to_owned()), Value::Number(0.into())));
+    if_block.set_true_block(true_block);
+
+    main_func_block.add_if(if_block);
+    main_func_block.add_return_variable("result");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "u16_overflow_compare", 1);
+}
+
+#[test]
+fn signed_division() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I32, Some(ExpressionType::value(Value::Number((-20).into()))));
+    main_func_block.add_declare("b", NumberType::I32, Some(ExpressionType::div(Value::Number(4.into()), Value::Variable("a".to_owned()))));
+    main_func_block.add_print("%d".to_owned(), vec![Value::Variable("b".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "signed_division", "-5");
+}
+
+#[test]
+fn signed_shift_right() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("a", NumberType::I16, Some(ExpressionType::value(Value::Number(Number::I16(-64)))));
+    main_func_block.add_declare("b", NumberType::I64, Some(ExpressionType::shift_right(Value::Number(2.into()), Value::Variable("a".to_owned()))));
+    main_func_block.add_print("%ld".to_owned(), vec![Value::Variable("b".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    check_output(application_type, "signed_shift_right", "-16");
+}
+
+#[test]
+fn i16_parameter() {
+    let mut double_func = FunctionType::main();
+    double_func.set_name("double_it");
+    double_func.add_parameter("x", NumberType::I16);
+
+    let mut double_func_block = BlockType::default();
+    double_func_block.add_declare("result", NumberType::I16, Some(ExpressionType::mul(Value::Number(2.into()), Value::Variable("x".to_owned()))));
+    double_func_block.add_return_variable("result");
+    double_func.set_body(double_func_block);
+
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_call_and_assign("double_it".to_owned(), vec![Value::Number(Number::I16(-300))], "total".to_owned());
+    main_func_block.add_print("Total: %ld".to_owned(), vec![Value::Variable("total".to_owned())]);
+    main_func_block.add_return_number(0.into());
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+    application_type.add_function(double_func);
+
+    check_output(application_type, "i16_parameter", "Total: -600");
+}
+
+#[test]
+fn shadowed_declaration() {
+    let mut main_func = FunctionType::main();
+    let mut main_func_block = BlockType::default();
+
+    main_func_block.add_declare("x", NumberType::I32, Some(ExpressionType::value(Value::Number(5.into()))));
+    main_func_block.add_declare("x", NumberType::U8, Some(ExpressionType::add(Value::Number(250.into()), Value::Variable("x".to_owned()))));
+    main_func_block.add_return_variable("x");
+    main_func.set_body(main_func_block);
+
+    let mut application_type = ApplicationType::default();
+    application_type.add_function(main_func);
+
+    get_exit_code(application_type, "shadowed_declaration", 255);
+}
@@ -0,0 +1,178 @@
+(module
+  (import "env" "printf" (func $printf (param i32 i32) (result i32)))
+  (import "env" "pick" (func $pick (param i64 f64 i64) (result i64)))
+
+  (memory (export "memory") 1)
+  (global $.str.1 i32 (i32.const 8))
+  (global $.str.2 i32 (i32.const 15))
+  (global $.str.3 i32 (i32.const 31))
+  (global $.arguments i32 (i32.const 40))
+  (data (i32.const 8) "x: %d\0a\00")
+  (data (i32.const 15) "tab\09\22quote\22 \5c \01\00")
+  (data (i32.const 31) "text\00")
+
+  (func $calculate (param $a i64) (param $b f64) (param $c i32) (result i64)
+    (local $x i32)
+    (local $y i32)
+    (local $flag i32)
+    local.get $a
+    i64.const 1
+    i64.add
+    f64.convert_i64_s
+    local.get $b
+    f64.const 2.5
+    f64.sub
+    f64.mul
+    i32.trunc_sat_f64_s
+    local.set $x
+    local.get $x
+    i32.const 3
+    i32.lt_s
+    if (result i32)
+      local.get $c
+      i32.const 200
+      i32.eq
+      i32.eqz
+    else
+      i32.const 0
+    end
+    local.set $flag
+    local.get $c
+    i32.const 2
+    i32.shl
+    local.get $c
+    i32.const 1
+    i32.shr_s
+    i32.or
+    local.get $c
+    i32.const -1
+    i32.xor
+    i32.const 15
+    i32.and
+    i32.xor
+    i32.const 255
+    i32.and
+    local.set $y
+    local.get $x
+    i32.const -3
+    i32.div_s
+    i32.const 0
+    i32.const 7
+    i32.sub
+    i32.const 0
+    local.get $x
+    i32.sub
+    i32.add
+    i32.rem_s
+    local.set $x
+    local.get $x
+    i32.const 1
+    i32.add
+    i64.extend_i32_s
+    local.get $a
+    i64.const 1
+    i64.sub
+    i64.add
+    i32.wrap_i64
+    local.set $x
+    local.get $x
+    local.get $a
+    f64.convert_i64_s
+    local.get $b
+    f64.le
+    i32.ge_s
+    local.set $flag
+    local.get $x
+    i32.const 0
+    i32.gt_s
+    if (result i32)
+      i32.const 1
+    else
+      local.get $a
+      f64.convert_i64_s
+      local.get $b
+      f64.ne
+      if (result i32)
+        local.get $c
+        i32.const 0
+        i32.eq
+        i32.eqz
+        i32.eqz
+      else
+        i32.const 0
+      end
+    end
+    if
+      global.get $.arguments
+      local.get $x
+      i64.extend_i32_s
+      i64.store
+      global.get $.str.1
+      global.get $.arguments
+      call $printf
+      drop
+    else
+      local.get $x
+      i32.const -1
+      i32.eq
+      if
+        global.get $.arguments
+        local.get $x
+        i64.extend_i32_s
+        i64.store
+        global.get $.arguments
+        f64.const 1.5
+        f64.store offset=8
+        global.get $.arguments
+        i64.const -2
+        i64.store offset=16
+        global.get $.arguments
+        i64.const 1
+        i64.store offset=24
+        global.get $.str.2
+        global.get $.arguments
+        call $printf
+        drop
+      else
+        local.get $a
+        f64.const -1e300
+        global.get $.str.3
+        i64.extend_i32_u
+        call $pick
+        i32.wrap_i64
+        i32.const 255
+        i32.and
+        local.set $y
+      end
+    end
+    block $.L2
+      loop $.L1
+        local.get $x
+        i32.const 10
+        i32.gt_s
+        i32.eqz
+        i32.eqz
+        br_if $.L2
+        local.get $x
+        i32.const 1
+        i32.add
+        local.set $x
+        block $.L4
+          loop $.L3
+            br $.L4
+            br $.L3
+          end
+        end
+        br $.L1
+        br $.L1
+      end
+    end
+    local.get $x
+    i64.extend_i32_s
+    return
+  )
+
+  (func $empty
+    return
+  )
+)
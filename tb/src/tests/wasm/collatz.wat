@@ -0,0 +1,105 @@
+(module
+  (import "env" "printf" (func $printf (param i32 i32) (result i32)))
+
+  (memory (export "memory") 1)
+  (global $.str.1 i32 (i32.const 8))
+  (global $.arguments i32 (i32.const 24))
+  (data (i32.const 8) "%lld %d\0a\00")
+
+  (func $steps (param $start i64) (result i64)
+    (local $value i64)
+    (local $count i32)
+    local.get $start
+    local.set $value
+    i32.const 0
+    local.set $count
+    block $.L2
+      loop $.L1
+        local.get $value
+        i64.const 1
+        i64.ne
+        i32.eqz
+        br_if $.L2
+        local.get $value
+        i64.const 2
+        i64.rem_s
+        i64.const 0
+        i64.eq
+        if
+          local.get $value
+          i64.const 2
+          i64.div_s
+          local.set $value
+        else
+          local.get $value
+          i64.const 3
+          i64.mul
+          i64.const 1
+          i64.add
+          local.set $value
+        end
+        local.get $count
+        i32.const 1
+        i32.add
+        local.set $count
+        br $.L1
+      end
+    end
+    local.get $count
+    i64.extend_i32_s
+    return
+  )
+
+  (func $main (export "main") (result i32)
+    (local $best i32)
+    (local $best_start i64)
+    (local $start i64)
+    (local $count i32)
+    i32.const 0
+    local.set $best
+    i64.const 0
+    local.set $best_start
+    i64.const 1
+    local.set $start
+    block $.L2
+      loop $.L1
+        local.get $start
+        i64.const 30
+        i64.lt_s
+        i32.eqz
+        br_if $.L2
+        local.get $start
+        call $steps
+        i32.wrap_i64
+        local.set $count
+        local.get $count
+        local.get $best
+        i32.gt_s
+        if
+          local.get $count
+          local.set $best
+          local.get $start
+          local.set $best_start
+        end
+        local.get $start
+        i64.const 1
+        i64.add
+        local.set $start
+        br $.L1
+      end
+    end
+    global.get $.arguments
+    local.get $best_start
+    i64.store
+    global.get $.arguments
+    local.get $best
+    i64.extend_i32_s
+    i64.store offset=8
+    global.get $.str.1
+    global.get $.arguments
+    call $printf
+    drop
+    i32.const 0
+    return
+  )
+)
@@ -0,0 +1,206 @@
+// Host of the generated modules: node host.js module.wasm
+// printf gets the address of the format and the address of the arguments, every argument uses an 8 byte slot.
+const fs = require("fs");
+
+let memory;
+
+function cString(address) {
+    const bytes = new Uint8Array(memory.buffer);
+    let end = address;
+    while (bytes[end] !== 0) {
+        end++;
+    }
+    return Buffer.from(bytes.subarray(address, end)).toString("latin1");
+}
+
+function signed(value, length) {
+    switch (length) {
+        case "hh": return BigInt.asIntN(8, value);
+        case "h": return BigInt.asIntN(16, value);
+        case "": return BigInt.asIntN(32, value);
+        default: return BigInt.asIntN(64, value);
+    }
+}
+
+function unsigned(value, length) {
+    switch (length) {
+        case "hh": return BigInt.asUintN(8, value);
+        case "h": return BigInt.asUintN(16, value);
+        case "": return BigInt.asUintN(32, value);
+        default: return BigInt.asUintN(64, value);
+    }
+}
+
+// C writes the exponent with a sign and at least two digits
+function exponentDigits(value, precision) {
+    const [mantissa, exponent] = value.toExponential(precision).split("e");
+    const number = parseInt(exponent, 10);
+    return mantissa + "e" + (number < 0 ? "-" : "+") + String(Math.abs(number)).padStart(2, "0");
+}
+
+// toFixed uses the exponent format for the big numbers
+function fixedDigits(value, precision) {
+    if (value < 1e21) {
+        return value.toFixed(precision);
+    }
+    return BigInt(value).toString() + (precision > 0 ? "." + "0".repeat(precision) : "");
+}
+
+function removeTrailingZeros(text) {
+    const position = text.indexOf("e");
+    let mantissa = position === -1 ? text : text.substring(0, position);
+    const exponent = position === -1 ? "" : text.substring(position);
+    if (mantissa.includes(".")) {
+        mantissa = mantissa.replace(/0+$/, "").replace(/\.$/, "");
+    }
+    return mantissa + exponent;
+}
+
+function floatDigits(value, conversion, spec) {
+    const precision = spec.precision === null ? 6 : spec.precision;
+    let text;
+    switch (conversion.toLowerCase()) {
+        case "f":
+            text = fixedDigits(value, precision);
+            break;
+        case "e":
+            text = exponentDigits(value, precision);
+            break;
+        default: {
+            const significant = Math.max(precision, 1);
+            const exponent = parseInt(value.toExponential(significant - 1).split("e")[1], 10);
+            text = exponent < -4 || exponent >= significant ? exponentDigits(value, significant - 1) : fixedDigits(value, significant - 1 - exponent);
+            if (!spec.alternate) {
+                text = removeTrailingZeros(text);
+            }
+        }
+    }
+
+    if (spec.alternate && !text.includes(".")) {
+        const position = text.indexOf("e");
+        text = position === -1 ? text + "." : text.substring(0, position) + "." + text.substring(position);
+    }
+    return conversion === conversion.toUpperCase() ? text.toUpperCase() : text;
+}
+
+function integerDigits(value, radix, spec) {
+    let digits = value.toString(radix);
+    if (spec.precision !== null) {
+        if (spec.precision === 0 && value === 0n) {
+            digits = "";
+        }
+        digits = digits.padStart(spec.precision, "0");
+    }
+    return digits;
+}
+
+function pad(sign, body, spec, conversion) {
+    const length = sign.length + body.length;
+    if (length >= spec.width) {
+        return sign + body;
+    }
+
+    const padding = spec.width - length;
+    const zero = spec.zero && !spec.left && !("diouxX".includes(conversion) && spec.precision !== null);
+    if (spec.left) {
+        return sign + body + " ".repeat(padding);
+    }
+    if (zero) {
+        const prefix = body.startsWith("0x") || body.startsWith("0X") ? 2 : 0;
+        return sign + body.substring(0, prefix) + "0".repeat(padding) + body.substring(prefix);
+    }
+    return " ".repeat(padding) + sign + body;
+}
+
+function format(text, argumentsAddress) {
+    const view = new DataView(memory.buffer);
+    const pattern = /%([-+ #0]*)(\d*)(?:\.(\d*))?([hlLqjzt]*)([%a-zA-Z])/g;
+    let index = 0;
+
+    return text.replace(pattern, (_, flags, width, precision, length, conversion) => {
+        if (conversion === "%") {
+            return "%";
+        }
+
+        const spec = {
+            left: flags.includes("-"), plus: flags.includes("+"), space: flags.includes(" "), alternate: flags.includes("#"), zero: flags.includes("0"),
+            width: width === "" ? 0 : parseInt(width, 10),
+            precision: precision === undefined ? null : (precision === "" ? 0 : parseInt(precision, 10))
+        };
+
+        const address = argumentsAddress + index * 8;
+        index++;
+
+        const signOf = negative => negative ? "-" : (spec.plus ? "+" : (spec.space ? " " : ""));
+        let sign = "";
+        let body;
+
+        switch (conversion) {
+            case "d":
+            case "i": {
+                const value = signed(view.getBigUint64(address, true), length);
+                sign = signOf(value < 0n);
+                body = integerDigits(value < 0n ? -value : value, 10, spec);
+                break;
+            }
+            case "u":
+            case "o":
+            case "x":
+            case "X": {
+                const value = unsigned(view.getBigUint64(address, true), length);
+                const radix = { u: 10, o: 8, x: 16, X: 16 }[conversion];
+                body = integerDigits(value, radix, spec);
+                if (conversion === "X") {
+                    body = body.toUpperCase();
+                }
+                if (spec.alternate && conversion === "o" && !body.startsWith("0")) {
+                    body = "0" + body;
+                } else if (spec.alternate && conversion !== "o" && conversion !== "u" && value !== 0n) {
+                    body = (conversion === "x" ? "0x" : "0X") + body;
+                }
+                break;
+            }
+            case "c":
+                spec.zero = false;
+                body = String.fromCharCode(view.getUint8(address));
+                break;
+            case "s":
+                spec.zero = false;
+                body = cString(view.getUint32(address, true));
+                if (spec.precision !== null) {
+                    body = body.substring(0, spec.precision);
+                }
+                break;
+            default: {
+                const value = view.getFloat64(address, true);
+                sign = signOf((view.getUint8(address + 7) & 0x80) !== 0);
+                if (Number.isFinite(value)) {
+                    body = floatDigits(Math.abs(value), conversion, spec);
+                } else {
+                    spec.zero = false;
+                    body = Number.isNaN(value) ? "nan" : "inf";
+                    if (conversion === conversion.toUpperCase()) {
+                        body = body.toUpperCase();
+                    }
+                }
+            }
+        }
+
+        return pad(sign, body, spec, conversion);
+    });
+}
+
+const imports = {
+    env: {
+        printf(formatAddress, argumentsAddress) {
+            const text = format(cString(formatAddress), argumentsAddress);
+            process.stdout.write(Buffer.from(text, "latin1"));
+            return text.length;
+        }
+    }
+};
+
+WebAssembly.instantiate(fs.readFileSync(process.argv[2]), imports).then(({ instance }) => {
+    memory = instance.exports.memory;
+    process.exitCode = instance.exports.main() & 255;
+});
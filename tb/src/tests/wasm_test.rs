@@ -0,0 +1,95 @@
+use core::str;
+use std::env::temp_dir;
+use std::fs;
+use std::process::Command;
+
+use tb_builder::ApplicationType;
+use tb_core::ir;
+use tb_target_wasm::generator::WasmTextGenerator;
+
+use super::differential::compare_backend;
+use super::ir_test::ALL_STATEMENTS;
+use super::{application, collatz, program_output, source};
+
+fn variables() -> ApplicationType {
+    let definitions = ir::parse(r#"fn show(value: u8) {
+    print "%d\n", value;
+}
+
+fn main() {
+    let a: i32 = 1;
+    if a > 0 {
+        let a: u8 = 255u8;
+        let b: f32 = 0.1f32;
+        a = inc(a);
+        call show(a);
+    }
+    print "%d %.9g %s\n", a, b, message;
+    a = a - 2;
+    return a;
+}
+"#).unwrap();
+
+    let mut application = application(definitions);
+    application.add_string_data("message", "done");
+    application
+}
+
+#[test]
+fn wasm_collatz() {
+    assert_eq!(include_str!("wasm/collatz.wat"), source::<WasmTextGenerator>(application(collatz())));
+}
+
+#[test]
+fn wasm_all_statements() {
+    assert_eq!(include_str!("wasm/all_statements.wat"), source::<WasmTextGenerator>(application(ir::parse(ALL_STATEMENTS).unwrap())));
+}
+
+#[test]
+fn wasm_variables() {
+    let source = source::<WasmTextGenerator>(variables());
+
+    // Strings and the data items are in the data segment, print arguments are written to the argument area after them
+    assert!(source.contains("  (global $.str.1 i32 (i32.const 8))\n  (global $.str.2 i32 (i32.const 12))\n  (global $message i32 (i32.const 24))\n  (global $.arguments i32 (i32.const 32))\n"), "{}", source);
+    assert!(source.contains("  (data (i32.const 24) \"done\\00\")\n"), "{}", source);
+
+    // Every declaration has its own local, small integers are cut after the arithmetic
+    assert!(source.contains("  (func $show (param $value i32)\n"), "{}", source);
+    assert!(source.contains("  (func $main (export \"main\") (result i32)\n    (local $a i32)\n    (local $a.1 i32)\n    (local $b f32)\n"), "{}", source);
+    assert!(source.contains("      local.get $a.1\n      i32.const 1\n      i32.add\n      i32.const 255\n      i32.and\n      local.set $a.1\n"), "{}", source);
+    assert!(source.contains("    global.get $.arguments\n    local.get $b\n    f64.promote_f32\n    f64.store offset=8\n"), "{}", source);
+    assert!(source.contains("    global.get $message\n    i64.extend_i32_u\n    i64.store offset=16\n    global.get $.str.2\n    global.get $.arguments\n    call $printf\n    drop\n"), "{}", source);
+}
+
+/// Module is executed by node, the host script implements printf. Tools are not installed everywhere, tests using them are ignored by default
+fn run_with_node(application: ApplicationType, file_name: &str) -> (String, i32) {
+    let mut source_file_name = temp_dir();
+    let mut module_name = temp_dir();
+    let mut host_name = temp_dir();
+
+    source_file_name.push(format!("{}.wat", file_name));
+    module_name.push(format!("{}.wasm", file_name));
+    host_name.push("tb_wasm_host.js");
+    fs::write(&source_file_name, application.build::<WasmTextGenerator>().unwrap()).unwrap();
+    fs::write(&host_name, include_str!("wasm/host.js")).unwrap();
+
+    let output = Command::new("wat2wasm").arg(&source_file_name).arg("-o").arg(&module_name).output().expect("wat2wasm is not found");
+    assert!(output.status.success(), "{}", str::from_utf8(&output.stderr).unwrap());
+
+    let output = Command::new("node").arg(&host_name).arg(&module_name).output().expect("node is not found");
+    assert!(output.stderr.is_empty(), "{}", str::from_utf8(&output.stderr).unwrap());
+    program_output(output)
+}
+
+#[test]
+#[ignore = "needs wat2wasm and node"]
+fn wasm_node_collatz() {
+    assert_eq!(("27 111\n".to_owned(), 0), run_with_node(application(collatz()), "wasm_collatz"));
+    assert_eq!(("0\n0 0.100000001 done\n".to_owned(), 254), run_with_node(variables(), "wasm_variables"));
+}
+
+#[test]
+#[ignore = "needs wat2wasm and node"]
+fn wasm_node_differential() {
+    compare_backend("wasm_differential", run_with_node);
+}